@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+/// Errors produced while generating bindings
+#[derive(Debug, Error)]
+pub enum CodegenError {
+    #[error("Invalid IDL: {0}")]
+    InvalidIdl(String),
+    #[error("Unsupported IDL type: {0}")]
+    UnsupportedType(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Result type for codegen operations
+pub type Result<T> = std::result::Result<T, CodegenError>;
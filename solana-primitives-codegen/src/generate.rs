@@ -0,0 +1,668 @@
+use crate::error::{CodegenError, Result};
+use crate::idl::{Idl, IdlAccount, IdlAccountDef, IdlField, IdlInstruction, IdlType};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+const READER: &str = r#"struct BindingReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+#[allow(dead_code)]
+impl<'a> BindingReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.offset
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.remaining() {
+            return Err(SolanaError::DeserializationError("account data too short".to_string()));
+        }
+        let bytes = &self.data[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_len(&mut self) -> Result<usize> {
+        Ok(u32::from_le_bytes(self.read_array()?) as usize)
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let len = self.read_len()?;
+        String::from_utf8(self.read_bytes(len)?.to_vec())
+            .map_err(|e| SolanaError::DeserializationError(e.to_string()))
+    }
+}
+"#;
+
+/// Characters of a base58 program address
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Names the generated instruction builders use for their own parameters and locals
+const RESERVED_ARG_NAMES: &[&str] = &["program_id", "accounts", "data"];
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro",
+    "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+struct Arg {
+    ident: String,
+    ty: IdlType,
+}
+
+struct AccountField {
+    ident: String,
+    ty: IdlType,
+}
+
+/// Generate Rust bindings for an Anchor IDL
+pub fn generate(idl: &Idl) -> Result<String> {
+    let program_name = idl.program_name().unwrap_or("program");
+    let mut instructions = String::new();
+    let mut accounts = String::new();
+    let mut uses_account_meta = false;
+
+    for instruction in &idl.instructions {
+        match instruction_args(instruction) {
+            Ok(args) => {
+                let flattened = instruction.flat_accounts();
+                uses_account_meta |= !flattened.is_empty();
+                write_instruction(
+                    &mut instructions,
+                    instruction,
+                    &flattened,
+                    &args,
+                    idl.program_address().is_some(),
+                );
+            }
+            Err(err) => {
+                writeln!(
+                    instructions,
+                    "// Skipped instruction `{}`: {err}\n",
+                    instruction.name
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    for account in &idl.accounts {
+        match account_fields(idl, account) {
+            Ok(fields) => write_account(&mut accounts, account, &fields),
+            Err(err) => {
+                writeln!(accounts, "// Skipped account `{}`: {err}\n", account.name).unwrap();
+            }
+        }
+    }
+
+    let has_account_types = accounts.contains("impl ");
+    let mut imports = Vec::new();
+    if uses_account_meta {
+        imports.push("AccountMeta");
+    }
+    if !instructions.is_empty() {
+        imports.push("Instruction");
+    }
+    imports.push("Pubkey");
+    if has_account_types {
+        imports.extend(["Result", "SolanaError"]);
+    }
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// @generated by solana-primitives-codegen from the `{program_name}` IDL. Do not edit.\n"
+    )
+    .unwrap();
+    writeln!(out, "#[allow(unused_imports)]").unwrap();
+    writeln!(out, "use solana_primitives::{{{}}};\n", imports.join(", ")).unwrap();
+
+    if let Some(address) = idl.program_address() {
+        if !(32..=44).contains(&address.len())
+            || !address.chars().all(|c| BASE58_ALPHABET.contains(c))
+        {
+            return Err(CodegenError::InvalidIdl(format!(
+                "invalid program address: {address}"
+            )));
+        }
+        writeln!(out, "/// Address of the `{program_name}` program").unwrap();
+        writeln!(
+            out,
+            "pub const PROGRAM_ID: Pubkey = solana_primitives::pubkey!(\"{address}\");\n"
+        )
+        .unwrap();
+        writeln!(out, "/// Address of the `{program_name}` program").unwrap();
+        writeln!(out, "pub const fn program_id() -> Pubkey {{").unwrap();
+        writeln!(out, "    PROGRAM_ID").unwrap();
+        writeln!(out, "}}\n").unwrap();
+    }
+
+    out.push_str(&instructions);
+    out.push_str(&accounts);
+    if has_account_types {
+        out.push_str(READER);
+    }
+
+    Ok(out.trim_end().to_string() + "\n")
+}
+
+fn instruction_args(instruction: &IdlInstruction) -> Result<Vec<Arg>> {
+    instruction
+        .args
+        .iter()
+        .map(|field| {
+            let mut ident = to_snake_case(&field.name);
+            if RESERVED_ARG_NAMES.contains(&ident.as_str()) {
+                ident.push_str("_arg");
+            }
+            Ok(Arg {
+                ident: escape_keyword(ident),
+                ty: IdlType::parse(&field.ty)?,
+            })
+        })
+        .collect()
+}
+
+fn account_fields(idl: &Idl, account: &IdlAccountDef) -> Result<Vec<AccountField>> {
+    let layout = idl
+        .account_layout(account)
+        .ok_or_else(|| CodegenError::InvalidIdl("missing account layout".to_string()))?;
+    if layout.kind != "struct" {
+        return Err(CodegenError::UnsupportedType(format!(
+            "{} account",
+            layout.kind
+        )));
+    }
+    layout
+        .fields
+        .iter()
+        .map(|value| {
+            let field: IdlField = serde_json::from_value(value.clone())
+                .map_err(|_| CodegenError::UnsupportedType(value.to_string()))?;
+            Ok(AccountField {
+                ident: escape_keyword(to_snake_case(&field.name)),
+                ty: IdlType::parse(&field.ty)?,
+            })
+        })
+        .collect()
+}
+
+fn write_instruction(
+    out: &mut String,
+    instruction: &IdlInstruction,
+    accounts: &[IdlAccount],
+    args: &[Arg],
+    has_program_id: bool,
+) {
+    let fn_name = escape_keyword(to_snake_case(&instruction.name));
+    let pascal = to_pascal_case(&instruction.name);
+    let const_name = format!(
+        "{}_DISCRIMINATOR",
+        to_snake_case(&instruction.name).to_uppercase()
+    );
+    let accounts_struct = format!("{pascal}Accounts");
+    let discriminator = instruction.discriminator();
+
+    writeln!(
+        out,
+        "/// Discriminator of the `{}` instruction",
+        instruction.name
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "pub const {const_name}: [u8; {}] = {discriminator:?};\n",
+        discriminator.len()
+    )
+    .unwrap();
+
+    if !accounts.is_empty() {
+        writeln!(
+            out,
+            "/// Accounts of the `{}` instruction",
+            instruction.name
+        )
+        .unwrap();
+        writeln!(out, "#[derive(Debug, Clone, Copy, PartialEq, Eq)]").unwrap();
+        writeln!(out, "pub struct {accounts_struct} {{").unwrap();
+        for account in accounts {
+            write_docs(out, "    ", &account.docs);
+            let ty = if account.optional {
+                "Option<Pubkey>"
+            } else {
+                "Pubkey"
+            };
+            writeln!(out, "    pub {}: {ty},", account_ident(account)).unwrap();
+        }
+        writeln!(out, "}}\n").unwrap();
+    }
+
+    let mut params = Vec::new();
+    let mut call_args = Vec::new();
+    if !accounts.is_empty() {
+        params.push(format!("accounts: &{accounts_struct}"));
+        call_args.push("accounts".to_string());
+    }
+    for arg in args {
+        params.push(format!("{}: {}", arg.ident, rust_type(&arg.ty)));
+        call_args.push(arg.ident.clone());
+    }
+
+    let with_program_id = if has_program_id {
+        let signature = params.join(", ");
+        let call = std::iter::once("&program_id()".to_string())
+            .chain(call_args)
+            .collect::<Vec<_>>()
+            .join(", ");
+        write_docs(out, "", &instruction.docs);
+        if instruction.docs.is_empty() {
+            writeln!(out, "/// Build the `{}` instruction", instruction.name).unwrap();
+        }
+        if params.len() > 6 {
+            writeln!(out, "#[allow(clippy::too_many_arguments)]").unwrap();
+        }
+        writeln!(out, "pub fn {fn_name}({signature}) -> Instruction {{").unwrap();
+        writeln!(out, "    {fn_name}_with_program_id({call})").unwrap();
+        writeln!(out, "}}\n").unwrap();
+        writeln!(
+            out,
+            "/// Build the `{}` instruction for the given program deployment",
+            instruction.name
+        )
+        .unwrap();
+        format!("{fn_name}_with_program_id")
+    } else {
+        write_docs(out, "", &instruction.docs);
+        if instruction.docs.is_empty() {
+            writeln!(out, "/// Build the `{}` instruction", instruction.name).unwrap();
+        }
+        fn_name
+    };
+
+    params.insert(0, "program_id: &Pubkey".to_string());
+    if params.len() > 7 {
+        writeln!(out, "#[allow(clippy::too_many_arguments)]").unwrap();
+    }
+    writeln!(
+        out,
+        "pub fn {with_program_id}({}) -> Instruction {{",
+        params.join(", ")
+    )
+    .unwrap();
+    if args.is_empty() {
+        writeln!(out, "    let data = {const_name}.to_vec();").unwrap();
+    } else {
+        writeln!(out, "    let mut data = {const_name}.to_vec();").unwrap();
+        for arg in args {
+            write_encode(out, 1, &arg.ty, &arg.ident, false, 0);
+        }
+    }
+    writeln!(out, "    Instruction {{").unwrap();
+    writeln!(out, "        program_id: *program_id,").unwrap();
+    if accounts.is_empty() {
+        writeln!(out, "        accounts: Vec::new(),").unwrap();
+    } else {
+        writeln!(out, "        accounts: vec![").unwrap();
+        for account in accounts {
+            let ident = account_ident(account);
+            let meta = |pubkey: &str| {
+                format!(
+                    "AccountMeta::new({pubkey}, {}, {})",
+                    account.signer, account.writable
+                )
+            };
+            if account.optional {
+                writeln!(
+                    out,
+                    "            accounts.{ident}.map_or(AccountMeta::new_readonly(*program_id), |pubkey| {}),",
+                    meta("pubkey")
+                )
+                .unwrap();
+            } else {
+                writeln!(out, "            {},", meta(&format!("accounts.{ident}"))).unwrap();
+            }
+        }
+        writeln!(out, "        ],").unwrap();
+    }
+    writeln!(out, "        data,").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+}
+
+fn write_account(out: &mut String, account: &IdlAccountDef, fields: &[AccountField]) {
+    let name = to_pascal_case(&account.name);
+    let discriminator = account.discriminator();
+
+    writeln!(out, "/// `{}` account data", account.name).unwrap();
+    writeln!(out, "#[derive(Debug, Clone, PartialEq, Eq)]").unwrap();
+    writeln!(out, "pub struct {name} {{").unwrap();
+    for field in fields {
+        writeln!(out, "    pub {}: {},", field.ident, rust_type(&field.ty)).unwrap();
+    }
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "impl {name} {{").unwrap();
+    writeln!(
+        out,
+        "    /// Discriminator prefixing `{}` account data",
+        account.name
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    pub const DISCRIMINATOR: [u8; {}] = {discriminator:?};\n",
+        discriminator.len()
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    /// Decode account data, checking the discriminator"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    pub fn try_from_account_data(data: &[u8]) -> Result<Self> {{"
+    )
+    .unwrap();
+    writeln!(out, "        let mut reader = BindingReader::new(data);").unwrap();
+    writeln!(
+        out,
+        "        if reader.read_array::<{}>()? != Self::DISCRIMINATOR {{",
+        discriminator.len()
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "            return Err(SolanaError::DeserializationError(\"account discriminator mismatch\".to_string()));"
+    )
+    .unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "        Ok(Self {{").unwrap();
+    for field in fields {
+        writeln!(
+            out,
+            "            {}: {},",
+            field.ident,
+            decode_expr(&field.ty, 0)
+        )
+        .unwrap();
+    }
+    writeln!(out, "        }})").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}\n").unwrap();
+}
+
+fn write_docs(out: &mut String, indent: &str, docs: &[String]) {
+    for line in docs {
+        writeln!(out, "{indent}/// {line}").unwrap();
+    }
+}
+
+fn account_ident(account: &IdlAccount) -> String {
+    escape_keyword(to_snake_case(&account.name))
+}
+
+fn rust_type(ty: &IdlType) -> String {
+    match ty {
+        IdlType::Bool => "bool".to_string(),
+        IdlType::U8 => "u8".to_string(),
+        IdlType::U16 => "u16".to_string(),
+        IdlType::U32 => "u32".to_string(),
+        IdlType::U64 => "u64".to_string(),
+        IdlType::U128 => "u128".to_string(),
+        IdlType::I8 => "i8".to_string(),
+        IdlType::I16 => "i16".to_string(),
+        IdlType::I32 => "i32".to_string(),
+        IdlType::I64 => "i64".to_string(),
+        IdlType::I128 => "i128".to_string(),
+        IdlType::Pubkey => "Pubkey".to_string(),
+        IdlType::String => "String".to_string(),
+        IdlType::Bytes => "Vec<u8>".to_string(),
+        IdlType::Vec(inner) => format!("Vec<{}>", rust_type(inner)),
+        IdlType::Option(inner) => format!("Option<{}>", rust_type(inner)),
+        IdlType::Array(inner, len) => format!("[{}; {len}]", rust_type(inner)),
+    }
+}
+
+/// Emit statements appending the Borsh encoding of `expr` to `data`.
+///
+/// `is_ref` tells whether `expr` is a reference (loop items, option payloads) or an owned
+/// argument, which decides where a borrow or dereference is needed.
+fn write_encode(
+    out: &mut String,
+    level: usize,
+    ty: &IdlType,
+    expr: &str,
+    is_ref: bool,
+    depth: usize,
+) {
+    let indent = "    ".repeat(level);
+    let borrowed = if is_ref {
+        expr.to_string()
+    } else {
+        format!("&{expr}")
+    };
+    match ty {
+        IdlType::Bool => {
+            let value = if is_ref {
+                format!("*{expr}")
+            } else {
+                expr.to_string()
+            };
+            writeln!(out, "{indent}data.push({value} as u8);").unwrap();
+        }
+        IdlType::Pubkey => {
+            writeln!(out, "{indent}data.extend_from_slice({expr}.as_bytes());").unwrap();
+        }
+        IdlType::String | IdlType::Bytes => {
+            writeln!(
+                out,
+                "{indent}data.extend_from_slice(&({expr}.len() as u32).to_le_bytes());"
+            )
+            .unwrap();
+            let bytes = if *ty == IdlType::String {
+                format!("{expr}.as_bytes()")
+            } else {
+                borrowed
+            };
+            writeln!(out, "{indent}data.extend_from_slice({bytes});").unwrap();
+        }
+        IdlType::Array(inner, _) if **inner == IdlType::U8 => {
+            writeln!(out, "{indent}data.extend_from_slice({borrowed});").unwrap();
+        }
+        IdlType::Vec(inner) | IdlType::Array(inner, _) => {
+            if matches!(ty, IdlType::Vec(_)) {
+                writeln!(
+                    out,
+                    "{indent}data.extend_from_slice(&({expr}.len() as u32).to_le_bytes());"
+                )
+                .unwrap();
+            }
+            let item = format!("item{depth}");
+            writeln!(out, "{indent}for {item} in {expr}.iter() {{").unwrap();
+            write_encode(out, level + 1, inner, &item, true, depth + 1);
+            writeln!(out, "{indent}}}").unwrap();
+        }
+        IdlType::Option(inner) => {
+            let value = format!("value{depth}");
+            writeln!(out, "{indent}match {borrowed} {{").unwrap();
+            writeln!(out, "{indent}    Some({value}) => {{").unwrap();
+            writeln!(out, "{indent}        data.push(1);").unwrap();
+            write_encode(out, level + 2, inner, &value, true, depth + 1);
+            writeln!(out, "{indent}    }}").unwrap();
+            writeln!(out, "{indent}    None => data.push(0),").unwrap();
+            writeln!(out, "{indent}}}").unwrap();
+        }
+        _ => {
+            writeln!(
+                out,
+                "{indent}data.extend_from_slice(&{expr}.to_le_bytes());"
+            )
+            .unwrap();
+        }
+    }
+}
+
+/// Expression decoding a value of `ty` from `reader`
+fn decode_expr(ty: &IdlType, depth: usize) -> String {
+    match ty {
+        IdlType::Bool => "reader.read_u8()? != 0".to_string(),
+        IdlType::U8 => "reader.read_u8()?".to_string(),
+        IdlType::Pubkey => "Pubkey::new(reader.read_array()?)".to_string(),
+        IdlType::String => "reader.read_string()?".to_string(),
+        IdlType::Bytes => format!(
+            "{{ let len{depth} = reader.read_len()?; reader.read_bytes(len{depth})?.to_vec() }}"
+        ),
+        IdlType::Array(inner, _) if **inner == IdlType::U8 => "reader.read_array()?".to_string(),
+        IdlType::Vec(inner) => format!(
+            "{{ let len{depth} = reader.read_len()?; let mut items{depth} = Vec::with_capacity(len{depth}.min(reader.remaining())); for _ in 0..len{depth} {{ items{depth}.push({}); }} items{depth} }}",
+            decode_expr(inner, depth + 1)
+        ),
+        IdlType::Array(inner, len) => format!(
+            "{{ let mut items{depth} = Vec::with_capacity({len}); for _ in 0..{len} {{ items{depth}.push({}); }} items{depth}.try_into().map_err(|_| SolanaError::DeserializationError(\"array length mismatch\".to_string()))? }}",
+            decode_expr(inner, depth + 1)
+        ),
+        IdlType::Option(inner) => format!(
+            "if reader.read_u8()? != 0 {{ Some({}) }} else {{ None }}",
+            decode_expr(inner, depth + 1)
+        ),
+        _ => format!("{}::from_le_bytes(reader.read_array()?)", rust_type(ty)),
+    }
+}
+
+/// First 8 bytes of `sha256("{namespace}:{name}")`, Anchor's default discriminator
+pub(crate) fn sighash(namespace: &str, name: &str) -> Vec<u8> {
+    Sha256::digest(format!("{namespace}:{name}").as_bytes())[..8].to_vec()
+}
+
+pub(crate) fn to_snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if prev_lower {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+            prev_lower = false;
+        } else {
+            out.push(c);
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        }
+    }
+    out
+}
+
+fn to_pascal_case(name: &str) -> String {
+    to_snake_case(name)
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn escape_keyword(ident: String) -> String {
+    if RUST_KEYWORDS.contains(&ident.as_str()) {
+        format!("r#{ident}")
+    } else {
+        ident
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_conversion() {
+        assert_eq!(to_snake_case("initializeMint"), "initialize_mint");
+        assert_eq!(to_snake_case("set_authority"), "set_authority");
+        assert_eq!(to_snake_case("tokenProgram2022"), "token_program2022");
+        assert_eq!(to_pascal_case("initialize_mint"), "InitializeMint");
+        assert_eq!(to_pascal_case("CounterState"), "CounterState");
+        assert_eq!(escape_keyword("type".to_string()), "r#type");
+    }
+
+    #[test]
+    fn test_sighash_matches_anchor() {
+        // `global:initialize` is the discriminator every Anchor `initialize` handler uses
+        assert_eq!(
+            sighash("global", "initialize"),
+            vec![175, 175, 109, 31, 13, 152, 155, 237]
+        );
+    }
+
+    #[test]
+    fn test_legacy_idl() {
+        let idl = Idl::from_json(
+            r#"{
+                "name": "legacy",
+                "metadata": { "address": "11111111111111111111111111111111" },
+                "instructions": [{
+                    "name": "setValue",
+                    "accounts": [
+                        { "name": "state", "isMut": true, "isSigner": false },
+                        { "name": "admin", "accounts": [
+                            { "name": "authority", "isMut": false, "isSigner": true }
+                        ]}
+                    ],
+                    "args": [
+                        { "name": "value", "type": { "option": "u64" } },
+                        { "name": "data", "type": "bytes" },
+                        { "name": "choice", "type": { "defined": "Choice" } }
+                    ]
+                }],
+                "accounts": [{
+                    "name": "State",
+                    "type": { "kind": "struct", "fields": [
+                        { "name": "authority", "type": "publicKey" }
+                    ]}
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let generated = generate(&idl).unwrap();
+        assert!(generated.contains("// Skipped instruction `setValue`: Unsupported IDL type"));
+        assert!(generated.contains(
+            "pub const PROGRAM_ID: Pubkey = solana_primitives::pubkey!(\"11111111111111111111111111111111\");"
+        ));
+        assert!(generated.contains("pub struct State {"));
+        assert!(generated.contains("pub const DISCRIMINATOR: [u8; 8]"));
+        assert!(!generated.contains("AccountMeta"));
+
+        let mut idl = idl;
+        idl.instructions[0].args.pop();
+        let generated = generate(&idl).unwrap();
+        assert!(generated.contains("pub admin_authority: Pubkey,"));
+        assert!(generated.contains("AccountMeta::new(accounts.admin_authority, true, false),"));
+        assert!(generated.contains("data_arg: Vec<u8>"));
+
+        idl.address = Some("1111111111111111111111111111111\")".to_string());
+        assert!(generate(&idl).is_err());
+    }
+}
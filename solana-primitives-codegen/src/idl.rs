@@ -0,0 +1,283 @@
+use crate::error::{CodegenError, Result};
+use crate::generate::{sighash, to_snake_case};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Anchor IDL, accepting both the legacy (< 0.30) and the current spec layout
+#[derive(Debug, Clone, Deserialize)]
+pub struct Idl {
+    /// Program address (0.30+)
+    #[serde(default)]
+    pub address: Option<String>,
+    /// Program name (legacy)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Program metadata
+    #[serde(default)]
+    pub metadata: Option<IdlMetadata>,
+    /// Program instructions
+    #[serde(default)]
+    pub instructions: Vec<IdlInstruction>,
+    /// Program account types
+    #[serde(default)]
+    pub accounts: Vec<IdlAccountDef>,
+    /// User-defined types
+    #[serde(default)]
+    pub types: Vec<IdlTypeDef>,
+}
+
+/// IDL metadata block
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IdlMetadata {
+    /// Program name (0.30+)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Program address (legacy)
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
+/// An instruction in the IDL
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlInstruction {
+    /// Instruction name
+    pub name: String,
+    /// Doc lines
+    #[serde(default)]
+    pub docs: Vec<String>,
+    /// Explicit discriminator (0.30+); derived from the name when absent
+    #[serde(default)]
+    pub discriminator: Option<Vec<u8>>,
+    /// Accounts, possibly nested in groups
+    #[serde(default)]
+    pub accounts: Vec<IdlAccountItem>,
+    /// Arguments, Borsh-encoded after the discriminator
+    #[serde(default)]
+    pub args: Vec<IdlField>,
+}
+
+/// Either a single account or a named group of accounts
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum IdlAccountItem {
+    /// Composite account group
+    Group(IdlAccountGroup),
+    /// Single account
+    Single(IdlAccount),
+}
+
+/// Composite account group
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlAccountGroup {
+    /// Group name
+    pub name: String,
+    /// Accounts in the group
+    pub accounts: Vec<IdlAccountItem>,
+}
+
+/// A single instruction account
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlAccount {
+    /// Account name
+    pub name: String,
+    /// Doc lines
+    #[serde(default)]
+    pub docs: Vec<String>,
+    /// Whether the account is writable
+    #[serde(default, alias = "isMut")]
+    pub writable: bool,
+    /// Whether the account must sign
+    #[serde(default, alias = "isSigner")]
+    pub signer: bool,
+    /// Whether the account may be omitted
+    #[serde(default, alias = "isOptional")]
+    pub optional: bool,
+}
+
+/// A named, typed field
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlField {
+    /// Field name
+    pub name: String,
+    /// Raw IDL type, see [`IdlType::parse`]
+    #[serde(rename = "type")]
+    pub ty: Value,
+}
+
+/// An account type in the IDL
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlAccountDef {
+    /// Account type name
+    pub name: String,
+    /// Explicit discriminator (0.30+); derived from the name when absent
+    #[serde(default)]
+    pub discriminator: Option<Vec<u8>>,
+    /// Inline layout (legacy); 0.30+ IDLs put it in `types`
+    #[serde(default, rename = "type")]
+    pub ty: Option<IdlTypeDefTy>,
+}
+
+/// A user-defined type in the IDL
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlTypeDef {
+    /// Type name
+    pub name: String,
+    /// Type layout
+    #[serde(rename = "type")]
+    pub ty: IdlTypeDefTy,
+}
+
+/// Layout of a user-defined type
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlTypeDefTy {
+    /// `struct` or `enum`
+    pub kind: String,
+    /// Struct fields; tuple structs and enums are kept raw
+    #[serde(default)]
+    pub fields: Vec<Value>,
+}
+
+/// Borsh types the generator knows how to encode and decode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdlType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Pubkey,
+    String,
+    Bytes,
+    Vec(Box<IdlType>),
+    Option(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+}
+
+impl Idl {
+    /// Parse an IDL from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| CodegenError::InvalidIdl(e.to_string()))
+    }
+
+    /// Program name, from whichever location the IDL version uses
+    pub fn program_name(&self) -> Option<&str> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.name.as_deref())
+            .or(self.name.as_deref())
+    }
+
+    /// Program address, from whichever location the IDL version uses
+    pub fn program_address(&self) -> Option<&str> {
+        self.address.as_deref().or(self
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.address.as_deref()))
+    }
+
+    /// Layout of an account type, looking in `types` when it isn't inline
+    pub fn account_layout<'a>(&'a self, account: &'a IdlAccountDef) -> Option<&'a IdlTypeDefTy> {
+        account.ty.as_ref().or_else(|| {
+            self.types
+                .iter()
+                .find(|def| def.name == account.name)
+                .map(|def| &def.ty)
+        })
+    }
+}
+
+impl IdlInstruction {
+    /// Discriminator prefixed to the instruction data
+    pub fn discriminator(&self) -> Vec<u8> {
+        self.discriminator
+            .clone()
+            .unwrap_or_else(|| sighash("global", &to_snake_case(&self.name)))
+    }
+
+    /// Accounts in order, with nested groups flattened into `group_member` names
+    pub fn flat_accounts(&self) -> Vec<IdlAccount> {
+        let mut accounts = Vec::new();
+        for item in &self.accounts {
+            item.flatten("", &mut accounts);
+        }
+        accounts
+    }
+}
+
+impl IdlAccountDef {
+    /// Discriminator at the start of the account data
+    pub fn discriminator(&self) -> Vec<u8> {
+        self.discriminator
+            .clone()
+            .unwrap_or_else(|| sighash("account", &self.name))
+    }
+}
+
+impl IdlAccountItem {
+    /// Flatten nested groups, prefixing member names with the group name
+    pub fn flatten(&self, prefix: &str, out: &mut Vec<IdlAccount>) {
+        match self {
+            IdlAccountItem::Single(account) => {
+                let mut account = account.clone();
+                account.name = format!("{prefix}{}", account.name);
+                out.push(account);
+            }
+            IdlAccountItem::Group(group) => {
+                let prefix = format!("{prefix}{}_", group.name);
+                for item in &group.accounts {
+                    item.flatten(&prefix, out);
+                }
+            }
+        }
+    }
+}
+
+impl IdlType {
+    /// Interpret a raw IDL type, in either spec layout
+    pub fn parse(value: &Value) -> Result<Self> {
+        let unsupported = || CodegenError::UnsupportedType(value.to_string());
+        match value {
+            Value::String(name) => match name.as_str() {
+                "bool" => Ok(Self::Bool),
+                "u8" => Ok(Self::U8),
+                "u16" => Ok(Self::U16),
+                "u32" => Ok(Self::U32),
+                "u64" => Ok(Self::U64),
+                "u128" => Ok(Self::U128),
+                "i8" => Ok(Self::I8),
+                "i16" => Ok(Self::I16),
+                "i32" => Ok(Self::I32),
+                "i64" => Ok(Self::I64),
+                "i128" => Ok(Self::I128),
+                "publicKey" | "pubkey" => Ok(Self::Pubkey),
+                "string" => Ok(Self::String),
+                "bytes" => Ok(Self::Bytes),
+                _ => Err(unsupported()),
+            },
+            Value::Object(map) => {
+                if let Some(inner) = map.get("vec") {
+                    Ok(Self::Vec(Box::new(Self::parse(inner)?)))
+                } else if let Some(inner) = map.get("option") {
+                    Ok(Self::Option(Box::new(Self::parse(inner)?)))
+                } else if let Some(Value::Array(parts)) = map.get("array") {
+                    match parts.as_slice() {
+                        [inner, Value::Number(len)] => {
+                            let len = len.as_u64().ok_or_else(unsupported)? as usize;
+                            Ok(Self::Array(Box::new(Self::parse(inner)?), len))
+                        }
+                        _ => Err(unsupported()),
+                    }
+                } else {
+                    Err(unsupported())
+                }
+            }
+            _ => Err(unsupported()),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Generate `solana-primitives` bindings from Anchor IDLs.
+//!
+//! Intended for build scripts: each IDL instruction becomes an accounts struct plus a function
+//! returning an [`Instruction`](https://docs.rs/solana-primitives), and each IDL account type
+//! becomes a struct that decodes its on-chain data.
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("counter.rs");
+//!     solana_primitives_codegen::generate_file("idl/counter.json", &out).unwrap();
+//!     println!("cargo:rerun-if-changed=idl/counter.json");
+//! }
+//!
+//! // src/lib.rs
+//! pub mod counter {
+//!     include!(concat!(env!("OUT_DIR"), "/counter.rs"));
+//! }
+//! ```
+//!
+//! Both the legacy (< 0.30) and the current IDL layouts are accepted. Instructions and
+//! accounts using types the generator can't encode (user-defined types, floats) are skipped
+//! with a comment in the output rather than failing the build.
+
+pub mod error;
+mod generate;
+pub mod idl;
+
+pub use error::{CodegenError, Result};
+pub use idl::Idl;
+
+use std::path::Path;
+
+/// Generate bindings from IDL JSON
+pub fn generate(idl_json: &str) -> Result<String> {
+    generate::generate(&Idl::from_json(idl_json)?)
+}
+
+/// Generate bindings from the IDL at `idl_path` and write them to `out_path`
+pub fn generate_file(idl_path: impl AsRef<Path>, out_path: impl AsRef<Path>) -> Result<()> {
+    let idl_json = std::fs::read_to_string(idl_path)?;
+    std::fs::write(out_path, generate(&idl_json)?)?;
+    Ok(())
+}
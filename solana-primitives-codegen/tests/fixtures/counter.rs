@@ -0,0 +1,169 @@
+// @generated by solana-primitives-codegen from the `counter` IDL. Do not edit.
+
+#[allow(unused_imports)]
+use solana_primitives::{AccountMeta, Instruction, Pubkey, Result, SolanaError};
+
+/// Address of the `counter` program
+pub const PROGRAM_ID: Pubkey = solana_primitives::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Address of the `counter` program
+pub const fn program_id() -> Pubkey {
+    PROGRAM_ID
+}
+
+/// Discriminator of the `initialize` instruction
+pub const INITIALIZE_DISCRIMINATOR: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+
+/// Accounts of the `initialize` instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InitializeAccounts {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub system_program: Pubkey,
+}
+
+/// Build the `initialize` instruction
+pub fn initialize(accounts: &InitializeAccounts, label: String, start: u64) -> Instruction {
+    initialize_with_program_id(&program_id(), accounts, label, start)
+}
+
+/// Build the `initialize` instruction for the given program deployment
+pub fn initialize_with_program_id(program_id: &Pubkey, accounts: &InitializeAccounts, label: String, start: u64) -> Instruction {
+    let mut data = INITIALIZE_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&(label.len() as u32).to_le_bytes());
+    data.extend_from_slice(label.as_bytes());
+    data.extend_from_slice(&start.to_le_bytes());
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(accounts.counter, true, true),
+            AccountMeta::new(accounts.authority, true, true),
+            AccountMeta::new(accounts.system_program, false, false),
+        ],
+        data,
+    }
+}
+
+/// Discriminator of the `increment` instruction
+pub const INCREMENT_DISCRIMINATOR: [u8; 8] = [11, 18, 104, 9, 104, 174, 59, 33];
+
+/// Accounts of the `increment` instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IncrementAccounts {
+    pub counter: Pubkey,
+    pub admin_authority: Pubkey,
+    pub admin_delegate: Option<Pubkey>,
+}
+
+/// Add `by` to the counter, optionally recording a memo
+pub fn increment(accounts: &IncrementAccounts, by: u32, memo: Option<String>, tags: Vec<Pubkey>, seed: [u8; 4], flag: bool) -> Instruction {
+    increment_with_program_id(&program_id(), accounts, by, memo, tags, seed, flag)
+}
+
+/// Build the `increment` instruction for the given program deployment
+pub fn increment_with_program_id(program_id: &Pubkey, accounts: &IncrementAccounts, by: u32, memo: Option<String>, tags: Vec<Pubkey>, seed: [u8; 4], flag: bool) -> Instruction {
+    let mut data = INCREMENT_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&by.to_le_bytes());
+    match &memo {
+        Some(value0) => {
+            data.push(1);
+            data.extend_from_slice(&(value0.len() as u32).to_le_bytes());
+            data.extend_from_slice(value0.as_bytes());
+        }
+        None => data.push(0),
+    }
+    data.extend_from_slice(&(tags.len() as u32).to_le_bytes());
+    for item0 in tags.iter() {
+        data.extend_from_slice(item0.as_bytes());
+    }
+    data.extend_from_slice(&seed);
+    data.push(flag as u8);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(accounts.counter, false, true),
+            AccountMeta::new(accounts.admin_authority, true, false),
+            accounts.admin_delegate.map_or(AccountMeta::new_readonly(*program_id), |pubkey| AccountMeta::new(pubkey, false, false)),
+        ],
+        data,
+    }
+}
+
+// Skipped instruction `configure`: Unsupported IDL type: {"defined":{"name":"Settings"}}
+
+/// `Counter` account data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter {
+    pub authority: Pubkey,
+    pub count: u64,
+    pub label: String,
+    pub delegate: Option<Pubkey>,
+    pub history: Vec<i64>,
+    pub bump: u8,
+}
+
+impl Counter {
+    /// Discriminator prefixing `Counter` account data
+    pub const DISCRIMINATOR: [u8; 8] = [255, 176, 4, 245, 188, 253, 124, 25];
+
+    /// Decode account data, checking the discriminator
+    pub fn try_from_account_data(data: &[u8]) -> Result<Self> {
+        let mut reader = BindingReader::new(data);
+        if reader.read_array::<8>()? != Self::DISCRIMINATOR {
+            return Err(SolanaError::DeserializationError("account discriminator mismatch".to_string()));
+        }
+        Ok(Self {
+            authority: Pubkey::new(reader.read_array()?),
+            count: u64::from_le_bytes(reader.read_array()?),
+            label: reader.read_string()?,
+            delegate: if reader.read_u8()? != 0 { Some(Pubkey::new(reader.read_array()?)) } else { None },
+            history: { let len0 = reader.read_len()?; let mut items0 = Vec::with_capacity(len0.min(reader.remaining())); for _ in 0..len0 { items0.push(i64::from_le_bytes(reader.read_array()?)); } items0 },
+            bump: reader.read_u8()?,
+        })
+    }
+}
+
+struct BindingReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+#[allow(dead_code)]
+impl<'a> BindingReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len() - self.offset
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.remaining() {
+            return Err(SolanaError::DeserializationError("account data too short".to_string()));
+        }
+        let bytes = &self.data[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_len(&mut self) -> Result<usize> {
+        Ok(u32::from_le_bytes(self.read_array()?) as usize)
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let len = self.read_len()?;
+        String::from_utf8(self.read_bytes(len)?.to_vec())
+            .map_err(|e| SolanaError::DeserializationError(e.to_string()))
+    }
+}
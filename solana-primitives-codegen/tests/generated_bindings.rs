@@ -0,0 +1,88 @@
+use solana_primitives::Pubkey;
+
+pub mod counter {
+    include!("fixtures/counter.rs");
+}
+
+#[test]
+fn test_generated_bindings_are_up_to_date() {
+    let generated =
+        solana_primitives_codegen::generate(include_str!("fixtures/counter.json")).unwrap();
+    assert_eq!(generated, include_str!("fixtures/counter.rs"));
+}
+
+#[test]
+fn test_generated_instruction() {
+    let counter = Pubkey::new([1u8; 32]);
+    let authority = Pubkey::new([2u8; 32]);
+    let tag = Pubkey::new([3u8; 32]);
+    let accounts = counter::IncrementAccounts {
+        counter,
+        admin_authority: authority,
+        admin_delegate: None,
+    };
+
+    let instruction = counter::increment(
+        &accounts,
+        5,
+        Some("hi".to_string()),
+        vec![tag],
+        [9; 4],
+        true,
+    );
+
+    assert_eq!(instruction.program_id, counter::program_id());
+    let metas: Vec<_> = instruction
+        .accounts
+        .iter()
+        .map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable))
+        .collect();
+    assert_eq!(
+        metas,
+        vec![
+            (counter, false, true),
+            (authority, true, false),
+            (counter::program_id(), false, false),
+        ]
+    );
+
+    let mut expected = counter::INCREMENT_DISCRIMINATOR.to_vec();
+    expected.extend_from_slice(&5u32.to_le_bytes());
+    expected.extend_from_slice(&[1, 2, 0, 0, 0, b'h', b'i']);
+    expected.extend_from_slice(&1u32.to_le_bytes());
+    expected.extend_from_slice(tag.as_bytes());
+    expected.extend_from_slice(&[9; 4]);
+    expected.push(1);
+    assert_eq!(instruction.data, expected);
+}
+
+#[test]
+fn test_generated_account_decoding() {
+    let authority = Pubkey::new([4u8; 32]);
+    let mut data = counter::Counter::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(authority.as_bytes());
+    data.extend_from_slice(&7u64.to_le_bytes());
+    data.extend_from_slice(&[3, 0, 0, 0, b'a', b'b', b'c']);
+    data.push(0);
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(&(-1i64).to_le_bytes());
+    data.extend_from_slice(&2i64.to_le_bytes());
+    data.push(254);
+
+    let account = counter::Counter::try_from_account_data(&data).unwrap();
+    assert_eq!(
+        account,
+        counter::Counter {
+            authority,
+            count: 7,
+            label: "abc".to_string(),
+            delegate: None,
+            history: vec![-1, 2],
+            bump: 254,
+        }
+    );
+
+    assert!(counter::Counter::try_from_account_data(&data[..data.len() - 1]).is_err());
+    data[0] ^= 1;
+    assert!(counter::Counter::try_from_account_data(&data).is_err());
+}
@@ -0,0 +1,131 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+use criterion::{BatchSize, Criterion, Throughput, criterion_group, criterion_main};
+use solana_primitives::instructions::program_ids::{associated_token_program, token_program};
+use solana_primitives::instructions::system::transfer;
+use solana_primitives::{
+    Keypair, Pubkey, TransactionBuilder, VersionedTransaction, find_program_address,
+    find_program_address_many,
+};
+use std::hint::black_box;
+
+/// Transactions per batch in the encode and decode benchmarks
+const BATCH: usize = 1_000;
+
+/// Mainnet transactions from the fixture corpus
+const FIXTURES: [&str; 2] = [
+    include_str!("../tests/fixtures/transactions/mayan_swap_v0.b64"),
+    include_str!("../tests/fixtures/transactions/legacy_compute_budget.b64"),
+];
+
+/// A batch of serialized mainnet transactions, cycling through the corpus
+fn mainnet_batch() -> Vec<Vec<u8>> {
+    let fixtures: Vec<Vec<u8>> = FIXTURES
+        .iter()
+        .map(|text| {
+            let encoded: String = text.lines().filter(|line| !line.starts_with('#')).collect();
+            STANDARD.decode(encoded).unwrap()
+        })
+        .collect();
+    fixtures.into_iter().cycle().take(BATCH).collect()
+}
+
+fn bench_wire(c: &mut Criterion) {
+    let batch = mainnet_batch();
+    let decoded: Vec<VersionedTransaction> = batch
+        .iter()
+        .map(|bytes| VersionedTransaction::deserialize_with_version(bytes).unwrap())
+        .collect();
+
+    let mut group = c.benchmark_group("mainnet_1k");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    group.bench_function("decode", |b| {
+        b.iter(|| {
+            for bytes in &batch {
+                black_box(
+                    VersionedTransaction::deserialize_with_version(black_box(bytes)).unwrap(),
+                );
+            }
+        })
+    });
+    group.bench_function("encode", |b| {
+        b.iter(|| {
+            for transaction in &decoded {
+                black_box(black_box(transaction).serialize().unwrap());
+            }
+        })
+    });
+    group.finish();
+}
+
+fn bench_sign(c: &mut Criterion) {
+    let keypair = Keypair::from_seed(&[1u8; 32]).unwrap();
+    let mut builder = TransactionBuilder::new(keypair.pubkey(), [7u8; 32]);
+    builder.add_instruction(transfer(&keypair.pubkey(), &Pubkey::new([2u8; 32]), 1_000));
+    let transaction = builder.build_v0(&[]).unwrap();
+
+    c.bench_function("sign/v0_transfer", |b| {
+        b.iter_batched(
+            || transaction.clone(),
+            |mut transaction| transaction.sign(&[&keypair]).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn bench_pda(c: &mut Criterion) {
+    let (wallet, mint) = (Pubkey::new([3u8; 32]), Pubkey::new([4u8; 32]));
+    let token_program = token_program();
+    let program = associated_token_program();
+    c.bench_function("find_program_address/ata", |b| {
+        b.iter(|| {
+            find_program_address(
+                black_box(&program),
+                &[wallet.as_bytes(), token_program.as_bytes(), mint.as_bytes()],
+            )
+            .unwrap()
+        })
+    });
+
+    let wallets: Vec<Pubkey> = (0..BATCH as u32)
+        .map(|i| {
+            let mut bytes = [0u8; 32];
+            bytes[..4].copy_from_slice(&i.to_le_bytes());
+            Pubkey::new(bytes)
+        })
+        .collect();
+    let seeds: Vec<[&[u8]; 3]> = wallets
+        .iter()
+        .map(|wallet| -> [&[u8]; 3] {
+            [wallet.as_bytes(), token_program.as_bytes(), mint.as_bytes()]
+        })
+        .collect();
+    let mut group = c.benchmark_group("find_program_address_1k");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    group.bench_function("ata", |b| {
+        b.iter(|| find_program_address_many(black_box(&program), &seeds))
+    });
+    group.finish();
+}
+
+fn bench_instruction_builders(c: &mut Criterion) {
+    let (from, to) = (Pubkey::new([5u8; 32]), Pubkey::new([6u8; 32]));
+    let mut group = c.benchmark_group("builders_1k");
+    group.throughput(Throughput::Elements(BATCH as u64));
+    group.bench_function("system_transfer", |b| {
+        b.iter(|| {
+            for lamports in 0..BATCH as u64 {
+                black_box(transfer(black_box(&from), black_box(&to), lamports));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_wire,
+    bench_sign,
+    bench_pda,
+    bench_instruction_builders
+);
+criterion_main!(benches);
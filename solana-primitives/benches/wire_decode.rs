@@ -0,0 +1,44 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use solana_primitives::instructions::compute_budget::{
+    set_compute_unit_limit, set_compute_unit_price,
+};
+use solana_primitives::instructions::system::transfer;
+use solana_primitives::{
+    AddressLookupTableAccount, ComputeUnits, MicroLamports, Pubkey, SignatureBytes,
+    TransactionBuilder, VersionedTransaction,
+};
+use std::hint::black_box;
+
+/// A V0 transfer fan-out with three signers, roughly the shape indexers see most
+fn sample_transaction() -> Vec<u8> {
+    let payer = Pubkey::new([1u8; 32]);
+    let mut builder = TransactionBuilder::new(payer, [7u8; 32]);
+    builder.add_instruction(set_compute_unit_limit(ComputeUnits(200_000)));
+    builder.add_instruction(set_compute_unit_price(MicroLamports(1_000)));
+    for i in 2..5u8 {
+        builder.add_instruction(transfer(&Pubkey::new([i; 32]), &payer, 1_000));
+    }
+    for i in 10..20u8 {
+        builder.add_instruction(transfer(&payer, &Pubkey::new([i; 32]), 1_000));
+    }
+    let table = AddressLookupTableAccount::new(
+        Pubkey::new([9u8; 32]),
+        (10..20u8).map(|i| Pubkey::new([i; 32])).collect(),
+    );
+
+    let mut transaction = builder.build_v0(&[table]).unwrap();
+    for signature in transaction.signatures_mut() {
+        *signature = SignatureBytes::new([0xAB; 64]);
+    }
+    transaction.serialize().unwrap()
+}
+
+fn bench_wire_decode(c: &mut Criterion) {
+    let bytes = sample_transaction();
+    c.bench_function("deserialize_with_version/v0", |b| {
+        b.iter(|| VersionedTransaction::deserialize_with_version(black_box(&bytes)).unwrap())
+    });
+}
+
+criterion_group!(benches, bench_wire_decode);
+criterion_main!(benches);
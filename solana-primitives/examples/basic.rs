@@ -1,7 +1,5 @@
 use base64::Engine;
-use solana_primitives::{
-    Pubkey, TransactionBuilder, get_public_key, instructions::system::transfer,
-};
+use solana_primitives::{Keypair, Pubkey, TransactionBuilder, instructions::system::transfer};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🚀 Solana Primitives - Transaction Signing Example");
@@ -11,11 +9,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // ⚠️  WARNING: This is for demonstration only!
     // In production, use proper key generation and secure storage
     let private_key_base58 = "3KSeAx7jkVrjJ2PXjhzVMnJfV3zsyT4ADWtpnHxks5eD";
-    let private_key = bs58::decode(private_key_base58).into_vec()?;
-
-    // Generate corresponding public key
-    let fee_payer_pubkey_bytes = get_public_key(&private_key)?;
-    let fee_payer = Pubkey::new(fee_payer_pubkey_bytes);
+    let private_key: [u8; 32] = bs58::decode(private_key_base58)
+        .into_vec()?
+        .try_into()
+        .map_err(|_| "private key must be 32 bytes")?;
+    let keypair = Keypair::from_secret_key(&private_key);
+    let fee_payer = keypair.pubkey();
 
     let recipient = Pubkey::from_base58("4fYNw3dojWmQ4dXtSGE9epjRGy9uFrCRgbvGgQBNZCQF")?;
 
@@ -56,9 +55,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("\n🔐 Signing Transaction:");
 
-    // Method 1: Sign the entire transaction at once
-    let private_keys = [private_key.as_ref()]; // References to private keys
-    transaction.sign(&private_keys)?;
+    // Method 1: Sign the entire transaction at once, with any `Signer` (keypair, hardware wallet...)
+    transaction.sign(&[&keypair])?;
 
     println!("✅ Transaction signed successfully!");
     println!("   - Is signed: {}", transaction.is_signed());
@@ -91,10 +89,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         partial_tx.is_signed()
     );
 
-    // Partial sign with specific keys and their corresponding public keys
-    let private_keys_for_partial = [private_key.as_ref()];
-    let public_keys_for_partial = [fee_payer];
-    partial_tx.partial_sign(&private_keys_for_partial, &public_keys_for_partial)?;
+    // Partial sign with a subset of the signers; each lands in its own signature slot
+    partial_tx.partial_sign(&[&keypair])?;
 
     println!("✅ Partial signing completed!");
     println!(
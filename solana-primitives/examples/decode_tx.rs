@@ -57,10 +57,7 @@ fn print_versioned_transaction(tx: &VersionedTransaction) {
                 println!("  Account {}: {}", i, key.to_base58());
             }
 
-            println!(
-                "\nRecent blockhash: {}",
-                bs58::encode(&message.recent_blockhash).into_string()
-            );
+            println!("\nRecent blockhash: {}", message.recent_blockhash);
 
             println!("\nInstructions: {}", message.instructions.len());
             for (i, instruction) in message.instructions.iter().enumerate() {
@@ -106,10 +103,7 @@ fn print_versioned_transaction(tx: &VersionedTransaction) {
                 println!("  Account {}: {}", i, key.to_base58());
             }
 
-            println!(
-                "\nRecent blockhash: {}",
-                bs58::encode(&message.recent_blockhash).into_string()
-            );
+            println!("\nRecent blockhash: {}", message.recent_blockhash);
 
             println!("\nInstructions: {}", message.instructions.len());
             for (i, instruction) in message.instructions.iter().enumerate() {
@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    solana_primitives::fuzz::system_instruction(data);
+    solana_primitives::fuzz::token_instruction(data);
+    solana_primitives::fuzz::known_instructions(data);
+});
@@ -0,0 +1,353 @@
+//! Batch-level analysis of transactions before submission.
+
+use crate::error::{Result, SolanaError};
+use crate::instructions::program_ids::{token_2022_program, token_program};
+use crate::state::{TOKEN_ACCOUNT_LENGTH, TokenAccountState};
+use crate::types::{Account, AddressLookupTableAccount, Pubkey, VersionedTransaction};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// How many transactions in a batch write-lock each account
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteHeatmap {
+    counts: BTreeMap<Pubkey, usize>,
+    transactions: usize,
+}
+
+impl WriteHeatmap {
+    /// Number of transactions in the batch
+    pub fn transactions(&self) -> usize {
+        self.transactions
+    }
+
+    /// Number of transactions that write-lock `pubkey`
+    pub fn count(&self, pubkey: &Pubkey) -> usize {
+        self.counts.get(pubkey).copied().unwrap_or(0)
+    }
+
+    /// Every write-locked account with its count, most contended first
+    pub fn hottest(&self) -> Vec<(Pubkey, usize)> {
+        let mut entries: Vec<(Pubkey, usize)> = self.counts.iter().map(|(k, v)| (*k, *v)).collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        entries
+    }
+
+    /// Accounts write-locked by at least `min_writes` transactions, most contended first.
+    ///
+    /// Transactions that write the same account cannot execute in parallel, so these
+    /// accounts serialize the batch.
+    pub fn hot_accounts(&self, min_writes: usize) -> Vec<(Pubkey, usize)> {
+        self.hottest()
+            .into_iter()
+            .filter(|(_, count)| *count >= min_writes)
+            .collect()
+    }
+
+    fn record(&mut self, writable: BTreeSet<Pubkey>) {
+        self.transactions += 1;
+        for pubkey in writable {
+            *self.counts.entry(pubkey).or_default() += 1;
+        }
+    }
+}
+
+/// Count write locks on static account keys across a batch.
+///
+/// Addresses loaded from lookup tables are not known without the tables; use
+/// [`write_heatmap_with_tables`] to include them.
+pub fn write_heatmap(transactions: &[VersionedTransaction]) -> WriteHeatmap {
+    let mut heatmap = WriteHeatmap::default();
+    for transaction in transactions {
+        let keys = transaction.account_keys();
+        heatmap.record(
+            (0..keys.len())
+                .filter(|index| is_writable_index(transaction, *index))
+                .map(|index| keys[index])
+                .collect(),
+        );
+    }
+    heatmap
+}
+
+/// Count write locks across a batch, including writable addresses loaded from lookup tables
+pub fn write_heatmap_with_tables(
+    transactions: &[VersionedTransaction],
+    address_lookup_tables: &[AddressLookupTableAccount],
+) -> Result<WriteHeatmap> {
+    let mut heatmap = WriteHeatmap::default();
+    for transaction in transactions {
+        let addresses = transaction.resolve_addresses(address_lookup_tables)?;
+        heatmap.record(
+            addresses
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| is_writable_index(transaction, *index))
+                .map(|(_, pubkey)| *pubkey)
+                .collect(),
+        );
+    }
+    Ok(heatmap)
+}
+
+/// Whether the account at `index` of the resolved account list is write-locked
+pub(crate) fn is_writable_index(transaction: &VersionedTransaction, index: usize) -> bool {
+    let num_static = transaction.account_keys().len();
+    let num_required = transaction.num_required_signatures() as usize;
+    if index < num_required {
+        return index
+            < num_required.saturating_sub(transaction.num_readonly_signed_accounts() as usize);
+    }
+    if index < num_static {
+        return index
+            < num_static.saturating_sub(transaction.num_readonly_unsigned_accounts() as usize);
+    }
+
+    let num_writable_lookups: usize = match transaction {
+        VersionedTransaction::Legacy { .. } => 0,
+        VersionedTransaction::V0 { message, .. } => message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.writable_indexes.len())
+            .sum(),
+    };
+    index < num_static + num_writable_lookups
+}
+
+/// Batched source of account state, e.g. backed by `getMultipleAccounts`
+pub trait AccountResolver {
+    /// Fetch `pubkeys` in one batch, with `None` for accounts that do not exist
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>>;
+}
+
+#[cfg(feature = "std")]
+impl AccountResolver for HashMap<Pubkey, Account> {
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        Ok(pubkeys
+            .iter()
+            .map(|pubkey| self.get(pubkey).cloned())
+            .collect())
+    }
+}
+
+impl AccountResolver for BTreeMap<Pubkey, Account> {
+    fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        Ok(pubkeys
+            .iter()
+            .map(|pubkey| self.get(pubkey).cloned())
+            .collect())
+    }
+}
+
+/// Balance of an SPL token account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenHolding {
+    /// The token mint
+    pub mint: Pubkey,
+    /// The wallet that owns the token account
+    pub owner: Pubkey,
+    /// Raw token amount, not adjusted for decimals
+    pub amount: u64,
+}
+
+/// One account of a transaction with its role and current on-chain state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountRoleRow {
+    /// The account
+    pub pubkey: Pubkey,
+    /// Whether the account signs the transaction
+    pub is_signer: bool,
+    /// Whether the account is write-locked
+    pub is_writable: bool,
+    /// Whether the account pays the fee
+    pub is_fee_payer: bool,
+    /// Whether an instruction invokes the account as a program
+    pub is_program: bool,
+    /// Whether the address was loaded from a lookup table
+    pub is_looked_up: bool,
+    /// Program that owns the account, if it exists
+    pub owner: Option<Pubkey>,
+    /// Balance in lamports, if the account exists
+    pub lamports: Option<u64>,
+    /// Token balance, if the account is an SPL token account
+    pub token: Option<TokenHolding>,
+}
+
+/// Build the rows a wallet preview needs for every account in a transaction.
+///
+/// Lookup table addresses are resolved with `address_lookup_tables` and all accounts are
+/// fetched from `resolver` in a single batch.
+pub fn account_roles_table(
+    transaction: &VersionedTransaction,
+    address_lookup_tables: &[AddressLookupTableAccount],
+    resolver: &impl AccountResolver,
+) -> Result<Vec<AccountRoleRow>> {
+    let addresses = transaction.resolve_addresses(address_lookup_tables)?;
+    let accounts = resolver.get_multiple_accounts(&addresses)?;
+    if accounts.len() != addresses.len() {
+        return Err(SolanaError::GenericError(format!(
+            "resolver returned {} accounts for {} addresses",
+            accounts.len(),
+            addresses.len()
+        )));
+    }
+
+    let num_static = transaction.account_keys().len();
+    let num_signers = transaction.num_required_signatures() as usize;
+    let program_indexes: BTreeSet<usize> = transaction
+        .instructions()
+        .iter()
+        .map(|ix| ix.program_id_index as usize)
+        .collect();
+
+    Ok(addresses
+        .into_iter()
+        .zip(accounts)
+        .enumerate()
+        .map(|(index, (pubkey, account))| AccountRoleRow {
+            pubkey,
+            is_signer: index < num_signers,
+            is_writable: is_writable_index(transaction, index),
+            is_fee_payer: index == 0,
+            is_program: program_indexes.contains(&index),
+            is_looked_up: index >= num_static,
+            owner: account.as_ref().map(|account| account.owner),
+            lamports: account.as_ref().map(|account| account.lamports),
+            token: account.as_ref().and_then(token_holding),
+        })
+        .collect())
+}
+
+/// Parse the mint, owner and amount of an SPL Token or Token-2022 account
+fn token_holding(account: &Account) -> Option<TokenHolding> {
+    let is_token_program = account.owner == token_program();
+    if !is_token_program && account.owner != token_2022_program() {
+        return None;
+    }
+    if is_token_program && account.data.len() != TOKEN_ACCOUNT_LENGTH {
+        return None;
+    }
+
+    let state = TokenAccountState::unpack(&account.data).ok()?;
+    Some(TokenHolding {
+        mint: state.mint,
+        owner: state.owner,
+        amount: state.amount,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::TransactionBuilder;
+    use crate::instructions::system::transfer;
+
+    fn transfer_tx(
+        payer: Pubkey,
+        recipient: Pubkey,
+        tables: &[AddressLookupTableAccount],
+    ) -> VersionedTransaction {
+        let mut builder = TransactionBuilder::new(payer, [0u8; 32]);
+        builder.add_instruction(transfer(&payer, &recipient, 1));
+        builder.build_v0(tables).unwrap()
+    }
+
+    #[test]
+    fn test_write_heatmap_flags_shared_accounts() {
+        let vault = Pubkey::new([9u8; 32]);
+        let transactions: Vec<_> = (1..=3)
+            .map(|n| transfer_tx(Pubkey::new([n; 32]), vault, &[]))
+            .collect();
+
+        let heatmap = write_heatmap(&transactions);
+        assert_eq!(heatmap.transactions(), 3);
+        assert_eq!(heatmap.count(&vault), 3);
+        assert_eq!(heatmap.count(&Pubkey::new([1u8; 32])), 1);
+        // The system program is read-only
+        assert_eq!(heatmap.count(&Pubkey::new([0u8; 32])), 0);
+        assert_eq!(heatmap.hot_accounts(2), vec![(vault, 3)]);
+        assert_eq!(heatmap.hottest().len(), 4);
+    }
+
+    #[test]
+    fn test_write_heatmap_with_tables() {
+        let vault = Pubkey::new([9u8; 32]);
+        let tables = [AddressLookupTableAccount::new(
+            Pubkey::new([8u8; 32]),
+            vec![vault],
+        )];
+        let transactions = vec![
+            transfer_tx(Pubkey::new([1u8; 32]), vault, &tables),
+            transfer_tx(Pubkey::new([2u8; 32]), vault, &[]),
+        ];
+
+        // The looked-up write lock is invisible without the tables
+        assert_eq!(write_heatmap(&transactions).count(&vault), 1);
+
+        let heatmap = write_heatmap_with_tables(&transactions, &tables).unwrap();
+        assert_eq!(heatmap.count(&vault), 2);
+        assert!(write_heatmap_with_tables(&transactions, &[]).is_err());
+    }
+
+    #[test]
+    fn test_account_roles_table() {
+        let payer = Pubkey::new([1u8; 32]);
+        let token_account = Pubkey::new([2u8; 32]);
+        let mint = Pubkey::new([3u8; 32]);
+        let tables = [AddressLookupTableAccount::new(
+            Pubkey::new([8u8; 32]),
+            vec![token_account],
+        )];
+        let transaction = transfer_tx(payer, token_account, &tables);
+
+        let mut token_data = vec![0u8; TOKEN_ACCOUNT_LENGTH];
+        token_data[..32].copy_from_slice(mint.as_bytes());
+        token_data[32..64].copy_from_slice(payer.as_bytes());
+        token_data[64..72].copy_from_slice(&500u64.to_le_bytes());
+        let resolver = HashMap::from([
+            (
+                payer,
+                Account {
+                    lamports: 10,
+                    owner: Pubkey::new([0u8; 32]),
+                    data: vec![],
+                    executable: false,
+                },
+            ),
+            (
+                token_account,
+                Account {
+                    lamports: 2_039_280,
+                    owner: token_program(),
+                    data: token_data,
+                    executable: false,
+                },
+            ),
+        ]);
+
+        let rows = account_roles_table(&transaction, &tables, &resolver).unwrap();
+        assert_eq!(rows.len(), 3);
+
+        assert_eq!(rows[0].pubkey, payer);
+        assert!(rows[0].is_fee_payer && rows[0].is_signer && rows[0].is_writable);
+        assert_eq!(rows[0].lamports, Some(10));
+        assert_eq!(rows[0].token, None);
+
+        let system = rows.iter().find(|row| row.is_program).unwrap();
+        assert!(!system.is_writable && !system.is_looked_up);
+        assert_eq!(system.owner, None);
+
+        let token_row = rows.iter().find(|row| row.pubkey == token_account).unwrap();
+        assert!(token_row.is_looked_up && token_row.is_writable && !token_row.is_signer);
+        assert_eq!(
+            token_row.token,
+            Some(TokenHolding {
+                mint,
+                owner: payer,
+                amount: 500
+            })
+        );
+    }
+}
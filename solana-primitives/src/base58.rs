@@ -0,0 +1,204 @@
+//! Hardened base58 decoding for untrusted input.
+//!
+//! Decodes straight into fixed-size arrays without heap allocation, rejects oversized input
+//! before doing any big-number work, and reports the byte offset of the first invalid character.
+
+use thiserror::Error;
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Scratch space for decoding; large enough for any 64-byte value
+const SCRATCH_LEN: usize = 128;
+
+/// Errors produced by the hardened base58 decoder
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum Base58Error {
+    #[error("failed to decode base58: input is empty")]
+    Empty,
+    #[error("failed to decode base58: input length {len} exceeds maximum {max}")]
+    TooLong { len: usize, max: usize },
+    /// `position` is the byte offset of `character` in the input
+    #[error("failed to decode base58: invalid character {character:?} at byte offset {position}")]
+    InvalidCharacter { character: char, position: usize },
+    #[error("invalid length: {len}, expected: {expected}")]
+    InvalidLength { len: usize, expected: usize },
+    #[error("failed to decode base58: {0}")]
+    Decode(bs58::decode::Error),
+}
+
+/// Maximum base58 length of an `n`-byte value
+pub const fn max_encoded_len(n: usize) -> usize {
+    // log58(256) ~= 1.3657
+    n * 1366 / 1000 + 1
+}
+
+/// Decode base58 into exactly `N` bytes
+pub fn decode_array<const N: usize>(s: &str) -> Result<[u8; N], Base58Error> {
+    let max = max_encoded_len(N).min(SCRATCH_LEN);
+    if s.is_empty() {
+        return Err(Base58Error::Empty);
+    }
+    if s.len() > max {
+        return Err(Base58Error::TooLong { len: s.len(), max });
+    }
+    if let Some((position, character)) = s
+        .char_indices()
+        .find(|(_, c)| !c.is_ascii() || !ALPHABET.contains(&(*c as u8)))
+    {
+        return Err(Base58Error::InvalidCharacter {
+            character,
+            position,
+        });
+    }
+
+    // Every base58 character decodes to at most one byte, so the scratch buffer can't overflow
+    let mut scratch = [0u8; SCRATCH_LEN];
+    let len = bs58::decode(s)
+        .onto(&mut scratch[..])
+        .map_err(Base58Error::Decode)?;
+    if len != N {
+        return Err(Base58Error::InvalidLength { len, expected: N });
+    }
+
+    let mut bytes = [0u8; N];
+    bytes.copy_from_slice(&scratch[..N]);
+    Ok(bytes)
+}
+
+/// Decode base58 into exactly `N` bytes in a const context.
+///
+/// Panics on invalid input, which fails compilation when evaluated in a `const`. Use
+/// [`decode_array`] for untrusted input.
+pub const fn decode_array_const<const N: usize>(s: &str) -> [u8; N] {
+    let input = s.as_bytes();
+    assert!(!input.is_empty(), "base58 input is empty");
+    let mut bytes = [0u8; N];
+    let mut i = 0;
+    while i < input.len() {
+        let mut digit = 0;
+        while digit < ALPHABET.len() && ALPHABET[digit] != input[i] {
+            digit += 1;
+        }
+        assert!(digit < ALPHABET.len(), "invalid base58 character");
+
+        let mut carry = digit as u32;
+        let mut j = N;
+        while j > 0 {
+            j -= 1;
+            carry += bytes[j] as u32 * 58;
+            bytes[j] = carry as u8;
+            carry >>= 8;
+        }
+        assert!(carry == 0, "base58 value is too long");
+        i += 1;
+    }
+
+    // Each leading '1' encodes a leading zero byte, so the counts must match exactly
+    let mut leading_ones = 0;
+    while leading_ones < input.len() && input[leading_ones] == b'1' {
+        leading_ones += 1;
+    }
+    let mut leading_zeros = 0;
+    while leading_zeros < N && bytes[leading_zeros] == 0 {
+        leading_zeros += 1;
+    }
+    assert!(leading_ones == leading_zeros, "base58 value is too short");
+    bytes
+}
+
+/// Compare two byte strings in time independent of where they differ
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    core::hint::black_box(diff) == 0
+}
+
+/// Check that `s` decodes to `expected`, comparing in constant time.
+///
+/// Useful when confirming a user-supplied address matches a known value, where an early-exit
+/// comparison would leak how many leading bytes were right.
+pub fn matches_expected<const N: usize>(s: &str, expected: &[u8; N]) -> bool {
+    decode_array::<N>(s).is_ok_and(|bytes| ct_eq(&bytes, expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+    const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+    #[test]
+    fn test_decode_array() {
+        assert_eq!(decode_array::<32>(SYSTEM_PROGRAM).unwrap(), [0u8; 32]);
+        assert_eq!(
+            decode_array::<32>(TOKEN_PROGRAM).unwrap().to_vec(),
+            bs58::decode(TOKEN_PROGRAM).into_vec().unwrap()
+        );
+        assert_eq!(max_encoded_len(32), 44);
+        assert_eq!(max_encoded_len(64), 88);
+    }
+
+    #[test]
+    fn test_decode_array_errors() {
+        assert_eq!(decode_array::<32>(""), Err(Base58Error::Empty));
+        assert_eq!(
+            decode_array::<32>(&"2".repeat(45)),
+            Err(Base58Error::TooLong { len: 45, max: 44 })
+        );
+        assert_eq!(
+            decode_array::<32>("Tokenkeg0"),
+            Err(Base58Error::InvalidCharacter {
+                character: '0',
+                position: 8
+            })
+        );
+        assert_eq!(
+            decode_array::<32>("Tökenkeg"),
+            Err(Base58Error::InvalidCharacter {
+                character: 'ö',
+                position: 1
+            })
+        );
+        assert_eq!(
+            decode_array::<32>("Tokenkeg0").unwrap_err().to_string(),
+            "failed to decode base58: invalid character '0' at byte offset 8"
+        );
+        assert_eq!(
+            decode_array::<32>("1111"),
+            Err(Base58Error::InvalidLength {
+                len: 4,
+                expected: 32
+            })
+        );
+        // 44 characters that decode to more than 32 bytes
+        assert!(matches!(
+            decode_array::<32>(&"z".repeat(44)),
+            Err(Base58Error::InvalidLength { expected: 32, .. })
+        ));
+    }
+
+    #[test]
+    fn test_decode_array_const() {
+        const TOKEN: [u8; 32] = decode_array_const(TOKEN_PROGRAM);
+        assert_eq!(TOKEN, decode_array::<32>(TOKEN_PROGRAM).unwrap());
+        assert_eq!(decode_array_const::<32>(SYSTEM_PROGRAM), [0u8; 32]);
+        for invalid in ["", "1111", "Tokenkeg0", &"z".repeat(44)] {
+            assert!(
+                std::panic::catch_unwind(|| decode_array_const::<32>(invalid)).is_err(),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches_expected() {
+        let expected = decode_array::<32>(TOKEN_PROGRAM).unwrap();
+        assert!(matches_expected(TOKEN_PROGRAM, &expected));
+        assert!(!matches_expected(SYSTEM_PROGRAM, &expected));
+        assert!(!matches_expected("not base58!", &expected));
+        assert!(!ct_eq(&[1, 2], &[1, 2, 3]));
+    }
+}
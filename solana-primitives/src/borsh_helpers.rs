@@ -1,4 +1,6 @@
 use crate::error::{Result, SolanaError};
+use alloc::string::ToString;
+use alloc::vec::Vec;
 use borsh::{BorshDeserialize, BorshSerialize};
 
 /// Helper function to convert a compact array to bytes
@@ -0,0 +1,93 @@
+use crate::instructions::anchor::global_discriminator;
+use crate::{AccountMeta, Instruction, Pubkey, Result, SolanaError};
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use borsh::BorshSerialize;
+
+/// A builder for instructions of Anchor programs.
+///
+/// Instruction data starts with the `global:<method>` discriminator, followed by each
+/// argument borsh-serialized in declaration order.
+#[derive(Debug)]
+pub struct AnchorInstructionBuilder {
+    /// The Anchor program
+    program_id: Pubkey,
+    /// The accounts in the order of the method's `Accounts` struct
+    accounts: Vec<AccountMeta>,
+    /// Discriminator followed by serialized arguments
+    data: Vec<u8>,
+}
+
+impl AnchorInstructionBuilder {
+    /// Start an instruction calling `method`, the snake_case name of the program entrypoint
+    pub fn new(program_id: Pubkey, method: &str) -> Self {
+        Self {
+            program_id,
+            accounts: Vec::new(),
+            data: global_discriminator(method).to_vec(),
+        }
+    }
+
+    /// Add an account to the instruction
+    pub fn account(mut self, pubkey: Pubkey, is_signer: bool, is_writable: bool) -> Self {
+        self.accounts
+            .push(AccountMeta::new(pubkey, is_signer, is_writable));
+        self
+    }
+
+    /// Add an AccountMeta directly
+    pub fn account_meta(mut self, account_meta: AccountMeta) -> Self {
+        self.accounts.push(account_meta);
+        self
+    }
+
+    /// Add multiple accounts at once
+    pub fn accounts(mut self, accounts: Vec<AccountMeta>) -> Self {
+        self.accounts.extend(accounts);
+        self
+    }
+
+    /// Append the next argument, borsh-serialized
+    pub fn arg<T: BorshSerialize>(mut self, value: &T) -> Result<Self> {
+        value
+            .serialize(&mut self.data)
+            .map_err(|e| SolanaError::SerializationError(e.to_string()))?;
+        Ok(self)
+    }
+
+    /// Build the instruction
+    pub fn build(self) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: self.accounts,
+            data: self.data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AnchorInstructionBuilder;
+    use crate::Pubkey;
+    use crate::instructions::anchor::global_discriminator;
+
+    #[test]
+    fn test_anchor_instruction_builder() {
+        let program_id = Pubkey::new([7u8; 32]);
+        let authority = Pubkey::new([1u8; 32]);
+
+        let ix = AnchorInstructionBuilder::new(program_id, "init_order")
+            .account(authority, true, true)
+            .arg(&42u64)
+            .unwrap()
+            .arg(&Some("memo".to_string()))
+            .unwrap()
+            .build();
+
+        assert_eq!(ix.program_id, program_id);
+        assert_eq!(ix.accounts.len(), 1);
+        assert_eq!(ix.data[..8], global_discriminator("init_order"));
+        assert_eq!(ix.data[8..16], 42u64.to_le_bytes());
+        assert_eq!(ix.data[16..], [1, 4, 0, 0, 0, b'm', b'e', b'm', b'o']);
+    }
+}
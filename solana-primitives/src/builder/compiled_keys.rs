@@ -0,0 +1,287 @@
+use crate::{Instruction, MessageHeader, Pubkey, Result, SolanaError};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+/// Roles of one account, merged across every use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct KeyMeta {
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub is_invoked: bool,
+}
+
+/// The accounts of a message being compiled, following the Solana SDK's `CompiledKeys`.
+///
+/// The fee payer is always the first key and a writable signer, whatever roles instructions
+/// give it. Every other key takes the union of its roles and lands in one of four groups:
+/// writable signers, readonly signers, writable non-signers, then readonly non-signers.
+#[derive(Debug, Clone)]
+pub(super) struct CompiledKeys {
+    payer: Pubkey,
+    /// Keys other than the payer, in the order they are emitted within their group
+    keys: Vec<(Pubkey, KeyMeta)>,
+}
+
+impl CompiledKeys {
+    /// Collect the accounts of `instructions`.
+    ///
+    /// With `sorted` each group is ordered by pubkey as `Message::new_with_payer` does,
+    /// otherwise keys keep the order of their first use.
+    pub(super) fn compile(payer: Pubkey, instructions: &[Instruction], sorted: bool) -> Self {
+        let mut metas: BTreeMap<Pubkey, KeyMeta> = BTreeMap::new();
+        let mut order = Vec::new();
+        let mut merge = |pubkey: Pubkey, update: &dyn Fn(&mut KeyMeta)| {
+            let meta = metas.entry(pubkey).or_insert_with(|| {
+                order.push(pubkey);
+                KeyMeta::default()
+            });
+            update(meta);
+        };
+
+        for instruction in instructions {
+            merge(instruction.program_id, &|meta| meta.is_invoked = true);
+            for account in &instruction.accounts {
+                merge(account.pubkey, &|meta| {
+                    meta.is_signer |= account.is_signer;
+                    meta.is_writable |= account.is_writable;
+                });
+            }
+        }
+
+        if sorted {
+            order.sort();
+        }
+        let keys = order
+            .into_iter()
+            .filter(|pubkey| *pubkey != payer)
+            .map(|pubkey| (pubkey, metas[&pubkey]))
+            .collect();
+        Self { payer, keys }
+    }
+
+    /// Number of keys, fee payer included
+    pub(super) fn num_keys(&self) -> usize {
+        1 + self.keys.len()
+    }
+
+    /// Number of signers, fee payer included
+    pub(super) fn num_signers(&self) -> usize {
+        1 + self.keys.iter().filter(|(_, meta)| meta.is_signer).count()
+    }
+
+    /// Remove the writable and readonly non-signers that `lookup` resolves, in key order.
+    ///
+    /// Signers and invoked programs always stay static, as the runtime requires.
+    pub(super) fn drain_lookups<T>(
+        &mut self,
+        mut lookup: impl FnMut(&Pubkey) -> Option<T>,
+    ) -> Vec<(Pubkey, KeyMeta, T)> {
+        let mut drained = Vec::new();
+        self.keys.retain(|(pubkey, meta)| {
+            if meta.is_signer || meta.is_invoked {
+                return true;
+            }
+            match lookup(pubkey) {
+                Some(entry) => {
+                    drained.push((*pubkey, *meta, entry));
+                    false
+                }
+                None => true,
+            }
+        });
+        drained
+    }
+
+    /// The header and static account keys, fee payer first
+    pub(super) fn into_message_components(self) -> Result<(MessageHeader, Vec<Pubkey>)> {
+        let mut groups: [Vec<Pubkey>; 4] = Default::default();
+        for (pubkey, meta) in self.keys {
+            let group = match (meta.is_signer, meta.is_writable) {
+                (true, true) => 0,
+                (true, false) => 1,
+                (false, true) => 2,
+                (false, false) => 3,
+            };
+            groups[group].push(pubkey);
+        }
+
+        let count = |len: usize| u8::try_from(len).map_err(|_| SolanaError::InvalidMessage);
+        let header = MessageHeader {
+            num_required_signatures: count(1 + groups[0].len() + groups[1].len())?,
+            num_readonly_signed_accounts: count(groups[1].len())?,
+            num_readonly_unsigned_accounts: count(groups[3].len())?,
+        };
+
+        let mut account_keys = Vec::with_capacity(1 + groups.iter().map(Vec::len).sum::<usize>());
+        account_keys.push(self.payer);
+        for group in groups {
+            account_keys.extend(group);
+        }
+        Ok((header, account_keys))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompiledKeys, KeyMeta};
+    use crate::{AccountMeta, Instruction, Pubkey};
+    use alloc::collections::BTreeMap;
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new([byte; 32])
+    }
+
+    fn instruction(program_id: Pubkey, accounts: &[(Pubkey, bool, bool)]) -> Instruction {
+        Instruction {
+            program_id,
+            accounts: accounts
+                .iter()
+                .map(|&(pubkey, is_signer, is_writable)| {
+                    AccountMeta::new(pubkey, is_signer, is_writable)
+                })
+                .collect(),
+            data: vec![],
+        }
+    }
+
+    #[test]
+    fn test_compile_fixture() {
+        let (payer, a, b, c, d, x, y) = (key(9), key(1), key(2), key(3), key(4), key(5), key(0));
+        let instructions = vec![
+            // The fee payer used as a readonly non-signer stays a writable signer
+            instruction(
+                x,
+                &[(payer, false, false), (d, false, true), (b, true, false)],
+            ),
+            // A program id used as a writable account is a writable non-signer
+            instruction(
+                y,
+                &[
+                    (c, false, false),
+                    (b, false, false),
+                    (a, true, true),
+                    (x, false, true),
+                ],
+            ),
+            instruction(x, &[(c, false, true), (d, false, false)]),
+        ];
+
+        let (header, keys) = CompiledKeys::compile(payer, &instructions, true)
+            .into_message_components()
+            .unwrap();
+        assert_eq!(keys, vec![payer, a, b, c, d, x, y]);
+        assert_eq!(
+            (
+                header.num_required_signatures,
+                header.num_readonly_signed_accounts,
+                header.num_readonly_unsigned_accounts
+            ),
+            (3, 1, 1)
+        );
+
+        let (_, keys) = CompiledKeys::compile(payer, &instructions, false)
+            .into_message_components()
+            .unwrap();
+        assert_eq!(keys, vec![payer, a, b, x, d, c, y]);
+    }
+
+    #[test]
+    fn test_drain_lookups_keeps_signers_and_programs_static() {
+        let (payer, signer, program, writable, readonly) = (key(1), key(2), key(3), key(4), key(5));
+        let instructions = vec![instruction(
+            program,
+            &[
+                (signer, true, false),
+                (writable, false, true),
+                (readonly, false, false),
+            ],
+        )];
+
+        let mut compiled = CompiledKeys::compile(payer, &instructions, true);
+        let drained = compiled.drain_lookups(|_| Some(()));
+        let drained: Vec<_> = drained
+            .into_iter()
+            .map(|(pubkey, meta, _)| (pubkey, meta))
+            .collect();
+        assert_eq!(
+            drained,
+            vec![
+                (
+                    writable,
+                    KeyMeta {
+                        is_writable: true,
+                        ..KeyMeta::default()
+                    }
+                ),
+                (readonly, KeyMeta::default()),
+            ]
+        );
+        let (_, keys) = compiled.into_message_components().unwrap();
+        assert_eq!(keys, vec![payer, signer, program]);
+    }
+
+    #[test]
+    fn test_compile_ordering_properties() {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move |bound: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % bound
+        };
+
+        for _ in 0..500 {
+            let payer = key(next(8) as u8);
+            let instructions: Vec<_> = (0..1 + next(4))
+                .map(|_| {
+                    let accounts: Vec<_> = (0..next(6))
+                        .map(|_| (key(next(8) as u8), next(2) == 0, next(2) == 0))
+                        .collect();
+                    instruction(key(next(8) as u8), &accounts)
+                })
+                .collect();
+
+            let mut roles: BTreeMap<Pubkey, (bool, bool)> = BTreeMap::new();
+            roles.insert(payer, (true, true));
+            for instruction in &instructions {
+                roles.entry(instruction.program_id).or_default();
+                for account in &instruction.accounts {
+                    let role = roles.entry(account.pubkey).or_default();
+                    role.0 |= account.is_signer || account.pubkey == payer;
+                    role.1 |= account.is_writable || account.pubkey == payer;
+                }
+            }
+
+            let (header, keys) = CompiledKeys::compile(payer, &instructions, true)
+                .into_message_components()
+                .unwrap();
+            assert_eq!(keys[0], payer);
+            assert_eq!(keys.len(), roles.len());
+
+            let group = |pubkey: &Pubkey| match roles[pubkey] {
+                (true, true) => 0,
+                (true, false) => 1,
+                (false, true) => 2,
+                (false, false) => 3,
+            };
+            for pair in keys[1..].windows(2) {
+                let (left, right) = (group(&pair[0]), group(&pair[1]));
+                assert!(left < right || left == right && pair[0] < pair[1]);
+            }
+
+            let signers = keys.iter().filter(|pubkey| roles[pubkey].0).count();
+            let readonly_signers = keys.iter().filter(|pubkey| group(pubkey) == 1).count();
+            let readonly_unsigned = keys.iter().filter(|pubkey| group(pubkey) == 3).count();
+            assert_eq!(header.num_required_signatures as usize, signers);
+            assert_eq!(
+                header.num_readonly_signed_accounts as usize,
+                readonly_signers
+            );
+            assert_eq!(
+                header.num_readonly_unsigned_accounts as usize,
+                readonly_unsigned
+            );
+            assert!(keys[..signers].iter().all(|pubkey| roles[pubkey].0));
+        }
+    }
+}
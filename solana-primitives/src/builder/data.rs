@@ -1,6 +1,7 @@
 //! Instruction data builder for encoding instruction parameters
 
 use crate::types::Pubkey;
+use alloc::vec::Vec;
 
 /// Builder for encoding instruction data
 pub struct InstructionDataBuilder {
@@ -0,0 +1,219 @@
+use crate::instructions::program_ids::compute_budget_program;
+use crate::types::Instruction;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// How [`TransactionBuilder`](super::TransactionBuilder) handles identical instructions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateInstructionPolicy {
+    /// Keep every instruction as added
+    #[default]
+    Allow,
+    /// Drop later copies of an instruction at build time
+    Merge,
+    /// Fail the build if any instruction was added twice
+    Error,
+}
+
+/// An instruction identical to an earlier one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuplicateInstruction {
+    /// Index of the repeated instruction
+    pub instruction_index: usize,
+    /// Index of the first identical instruction
+    pub original_index: usize,
+}
+
+impl fmt::Display for DuplicateInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "instruction {} duplicates instruction {}",
+            self.instruction_index, self.original_index
+        )
+    }
+}
+
+/// Same program, accounts with the same roles, and the same data
+fn is_identical(a: &Instruction, b: &Instruction) -> bool {
+    a.program_id == b.program_id
+        && a.data == b.data
+        && a.accounts.len() == b.accounts.len()
+        && a.accounts.iter().zip(&b.accounts).all(|(a, b)| {
+            a.pubkey == b.pubkey && a.is_signer == b.is_signer && a.is_writable == b.is_writable
+        })
+}
+
+/// Find every instruction identical to an earlier one, in order
+pub(super) fn find_duplicate_instructions(
+    instructions: &[Instruction],
+) -> Vec<DuplicateInstruction> {
+    instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(instruction_index, instruction)| {
+            instructions[..instruction_index]
+                .iter()
+                .position(|earlier| is_identical(earlier, instruction))
+                .map(|original_index| DuplicateInstruction {
+                    instruction_index,
+                    original_index,
+                })
+        })
+        .collect()
+}
+
+/// Drop `duplicates` from `instructions`.
+///
+/// Returns the new index of every instruction; a dropped one maps to the instruction it
+/// duplicates.
+pub(super) fn remove_duplicate_instructions(
+    instructions: &mut Vec<Instruction>,
+    duplicates: &[DuplicateInstruction],
+) -> Vec<usize> {
+    let mut new_indexes: Vec<usize> = Vec::with_capacity(instructions.len());
+    let mut kept = 0;
+    for index in 0..instructions.len() {
+        match duplicates
+            .iter()
+            .find(|duplicate| duplicate.instruction_index == index)
+        {
+            Some(duplicate) => new_indexes.push(new_indexes[duplicate.original_index]),
+            None => {
+                new_indexes.push(kept);
+                kept += 1;
+            }
+        }
+    }
+    let mut index = 0;
+    instructions.retain(|_| {
+        let keep = !duplicates
+            .iter()
+            .any(|duplicate| duplicate.instruction_index == index);
+        index += 1;
+        keep
+    });
+    new_indexes
+}
+
+/// Keep one compute budget instruction per kind, at the first position with the last value.
+///
+/// The runtime rejects a transaction that repeats a compute budget instruction kind. Returns
+/// the new index of every instruction; a dropped one maps to the instruction that took its
+/// value.
+pub(super) fn dedupe_compute_budget(instructions: &mut Vec<Instruction>) -> Vec<usize> {
+    let program_id = compute_budget_program();
+    let mut first_of_kind: Vec<(u8, usize)> = Vec::new();
+    let mut kept = Vec::with_capacity(instructions.len());
+    let mut new_indexes = Vec::with_capacity(instructions.len());
+    for instruction in instructions.drain(..) {
+        let kind = match instruction.data.first() {
+            Some(kind) if instruction.program_id == program_id => *kind,
+            _ => {
+                new_indexes.push(kept.len());
+                kept.push(instruction);
+                continue;
+            }
+        };
+        match first_of_kind.iter().find(|(seen, _)| *seen == kind) {
+            Some((_, index)) => {
+                new_indexes.push(*index);
+                kept[*index] = instruction;
+            }
+            None => {
+                first_of_kind.push((kind, kept.len()));
+                new_indexes.push(kept.len());
+                kept.push(instruction);
+            }
+        }
+    }
+    *instructions = kept;
+    new_indexes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DuplicateInstruction, dedupe_compute_budget, find_duplicate_instructions,
+        remove_duplicate_instructions,
+    };
+    use crate::Pubkey;
+    use crate::instructions::compute_budget::{
+        parse_compute_unit_limit_data, parse_compute_unit_price_data, set_compute_unit_limit,
+        set_compute_unit_price,
+    };
+    use crate::instructions::system::transfer;
+    use crate::types::{ComputeUnits, MicroLamports};
+
+    #[test]
+    fn test_find_duplicate_instructions() {
+        let (from, to) = (Pubkey::new([1u8; 32]), Pubkey::new([2u8; 32]));
+        let instructions = vec![
+            transfer(&from, &to, 1),
+            transfer(&from, &to, 2),
+            transfer(&from, &to, 1),
+            transfer(&to, &from, 1),
+            transfer(&from, &to, 1),
+        ];
+
+        assert_eq!(
+            find_duplicate_instructions(&instructions),
+            vec![
+                DuplicateInstruction {
+                    instruction_index: 2,
+                    original_index: 0
+                },
+                DuplicateInstruction {
+                    instruction_index: 4,
+                    original_index: 0
+                },
+            ]
+        );
+        assert_eq!(
+            find_duplicate_instructions(&instructions)[0].to_string(),
+            "instruction 2 duplicates instruction 0"
+        );
+
+        let mut deduped = instructions.clone();
+        let duplicates = find_duplicate_instructions(&instructions);
+        assert_eq!(
+            remove_duplicate_instructions(&mut deduped, &duplicates),
+            vec![0, 1, 0, 2, 0]
+        );
+        let data: Vec<_> = deduped
+            .iter()
+            .map(|instruction| &instruction.data)
+            .collect();
+        assert_eq!(
+            data,
+            vec![
+                &instructions[0].data,
+                &instructions[1].data,
+                &instructions[3].data
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dedupe_compute_budget() {
+        let (from, to) = (Pubkey::new([1u8; 32]), Pubkey::new([2u8; 32]));
+        let mut instructions = vec![
+            set_compute_unit_price(MicroLamports(1)),
+            set_compute_unit_limit(ComputeUnits(10_000)),
+            transfer(&from, &to, 1),
+            set_compute_unit_price(MicroLamports(5)),
+        ];
+
+        assert_eq!(dedupe_compute_budget(&mut instructions), vec![0, 1, 2, 0]);
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(
+            parse_compute_unit_price_data(&instructions[0].data),
+            Some(MicroLamports(5))
+        );
+        assert_eq!(
+            parse_compute_unit_limit_data(&instructions[1].data),
+            Some(ComputeUnits(10_000))
+        );
+        assert_eq!(instructions[2].data, transfer(&from, &to, 1).data);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{AccountMeta, Instruction, Pubkey};
+use alloc::vec::Vec;
 
 /// A builder for constructing Solana instructions
 #[derive(Debug)]
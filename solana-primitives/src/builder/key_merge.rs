@@ -0,0 +1,139 @@
+use crate::types::{Instruction, Pubkey};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// How [`TransactionBuilder`](super::TransactionBuilder) handles a pubkey used with conflicting roles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateKeyPolicy {
+    /// Merge roles silently, widening to the union of every use
+    #[default]
+    Merge,
+    /// Merge roles and record each widening, see `TransactionBuilder::build_with_key_merges`
+    Report,
+    /// Fail the build if any roles had to be widened
+    Error,
+}
+
+/// The kind of role conflict that was merged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyMergeKind {
+    /// The pubkey is both a program id and an instruction account
+    ProgramIdAsAccount,
+    /// The pubkey is a signer in one use and not in another
+    SignerWidened,
+    /// The pubkey is writable in one use and read-only in another
+    WritableWidened,
+}
+
+/// A role conflict the builder resolved by merging
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyMerge {
+    /// The affected pubkey
+    pub pubkey: Pubkey,
+    /// Index of the instruction that introduced the conflict
+    pub instruction_index: usize,
+    /// What was merged
+    pub kind: KeyMergeKind,
+}
+
+impl fmt::Display for KeyMerge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            KeyMergeKind::ProgramIdAsAccount => "used as both program id and account",
+            KeyMergeKind::SignerWidened => "signer role widened",
+            KeyMergeKind::WritableWidened => "writable role widened",
+        };
+        write!(
+            f,
+            "{} {} in instruction {}",
+            self.pubkey, kind, self.instruction_index
+        )
+    }
+}
+
+/// Point each merge at the new index of its instruction, given the new index of every
+/// instruction, dropping merges that become identical
+pub(super) fn reindex_key_merges(merges: &mut Vec<KeyMerge>, new_indexes: &[usize]) {
+    let mut reindexed: Vec<KeyMerge> = Vec::with_capacity(merges.len());
+    for mut merge in merges.drain(..) {
+        if let Some(&index) = new_indexes.get(merge.instruction_index) {
+            merge.instruction_index = index;
+        }
+        if !reindexed.contains(&merge) {
+            reindexed.push(merge);
+        }
+    }
+    *merges = reindexed;
+}
+
+/// Find the role conflicts of `instructions` in their final order, as a built transaction
+/// compiles them
+pub(super) fn collect_key_merges(
+    fee_payer: &Pubkey,
+    instructions: &[Instruction],
+) -> Vec<KeyMerge> {
+    instructions
+        .iter()
+        .enumerate()
+        .flat_map(|(index, instruction)| {
+            find_key_merges(fee_payer, &instructions[..index], instruction)
+        })
+        .collect()
+}
+
+/// Find the role conflicts `instruction` introduces on top of `prior` instructions.
+///
+/// The fee payer is always a writable signer, so differing uses of it are not reported.
+pub(super) fn find_key_merges(
+    fee_payer: &Pubkey,
+    prior: &[Instruction],
+    instruction: &Instruction,
+) -> Vec<KeyMerge> {
+    let instruction_index = prior.len();
+    let mut program_ids: BTreeSet<Pubkey> = prior.iter().map(|ix| ix.program_id).collect();
+    let mut roles: BTreeMap<Pubkey, (bool, bool)> = BTreeMap::new();
+    for meta in prior.iter().flat_map(|ix| &ix.accounts) {
+        let role = roles.entry(meta.pubkey).or_default();
+        role.0 |= meta.is_signer;
+        role.1 |= meta.is_writable;
+    }
+
+    let mut merges = Vec::new();
+    let mut push = |pubkey: Pubkey, kind: KeyMergeKind| {
+        let merge = KeyMerge {
+            pubkey,
+            instruction_index,
+            kind,
+        };
+        if !merges.contains(&merge) {
+            merges.push(merge);
+        }
+    };
+
+    if roles.contains_key(&instruction.program_id) {
+        push(instruction.program_id, KeyMergeKind::ProgramIdAsAccount);
+    }
+    program_ids.insert(instruction.program_id);
+
+    for meta in &instruction.accounts {
+        if program_ids.contains(&meta.pubkey) {
+            push(meta.pubkey, KeyMergeKind::ProgramIdAsAccount);
+        }
+        if meta.pubkey != *fee_payer
+            && let Some((is_signer, is_writable)) = roles.get(&meta.pubkey)
+        {
+            if *is_signer != meta.is_signer {
+                push(meta.pubkey, KeyMergeKind::SignerWidened);
+            }
+            if *is_writable != meta.is_writable {
+                push(meta.pubkey, KeyMergeKind::WritableWidened);
+            }
+        }
+        let role = roles.entry(meta.pubkey).or_default();
+        role.0 |= meta.is_signer;
+        role.1 |= meta.is_writable;
+    }
+
+    merges
+}
@@ -1,9 +1,18 @@
 //! Builder utilities for constructing Solana transactions and instructions
 
+mod anchor;
+mod compiled_keys;
 mod data;
+mod duplicates;
 mod instruction;
+mod key_merge;
+mod packer;
 mod transaction;
 
+pub use anchor::AnchorInstructionBuilder;
 pub use data::InstructionDataBuilder;
+pub use duplicates::{DuplicateInstruction, DuplicateInstructionPolicy};
 pub use instruction::InstructionBuilder;
-pub use transaction::TransactionBuilder;
+pub use key_merge::{DuplicateKeyPolicy, KeyMerge, KeyMergeKind};
+pub use packer::TransactionPacker;
+pub use transaction::{BUILDER_LAYOUT_VERSION, BuildMetadata, TransactionBuilder};
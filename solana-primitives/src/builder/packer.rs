@@ -0,0 +1,168 @@
+use super::TransactionBuilder;
+use crate::types::{
+    Blockhash, Instruction, MAX_TRANSACTION_ACCOUNTS, MAX_TRANSACTION_SIZE, Pubkey,
+};
+use crate::{Result, SolanaError};
+use alloc::format;
+use alloc::vec::Vec;
+
+/// Splits a batch of instructions across as few transactions as fit.
+///
+/// Instructions are packed greedily and in order, so a transaction is closed as soon as the
+/// next instruction would push it over [`MAX_TRANSACTION_SIZE`] or the account limit. Useful
+/// for batch transfers or associated token account creation loops.
+#[derive(Debug, Clone)]
+pub struct TransactionPacker {
+    fee_payer: Pubkey,
+    recent_blockhash: Blockhash,
+    max_accounts: usize,
+}
+
+impl TransactionPacker {
+    /// Create a packer whose transactions share a fee payer and blockhash
+    pub fn new(fee_payer: Pubkey, recent_blockhash: impl Into<Blockhash>) -> Self {
+        Self {
+            fee_payer,
+            recent_blockhash: recent_blockhash.into(),
+            max_accounts: MAX_TRANSACTION_ACCOUNTS,
+        }
+    }
+
+    /// Cap the distinct accounts per transaction, defaults to [`MAX_TRANSACTION_ACCOUNTS`]
+    pub fn with_max_accounts(&mut self, max_accounts: usize) -> &mut Self {
+        self.max_accounts = max_accounts;
+        self
+    }
+
+    /// Pack `instructions` into builders, keeping their order.
+    ///
+    /// Fails if a single instruction does not fit in a transaction on its own.
+    pub fn pack<I>(&self, instructions: I) -> Result<Vec<TransactionBuilder>>
+    where
+        I: IntoIterator<Item = Instruction>,
+    {
+        let mut builders = Vec::new();
+        let mut current = self.new_builder();
+        let mut current_len = 0;
+
+        for instruction in instructions {
+            let mut candidate = current.clone();
+            candidate.add_instruction(instruction.clone());
+            if self.fits(&candidate) {
+                current = candidate;
+                current_len += 1;
+                continue;
+            }
+            if current_len == 0 {
+                return Err(self.too_large(&candidate));
+            }
+
+            builders.push(core::mem::replace(&mut current, self.new_builder()));
+            current.add_instruction(instruction);
+            current_len = 1;
+            if !self.fits(&current) {
+                return Err(self.too_large(&current));
+            }
+        }
+
+        if current_len > 0 {
+            builders.push(current);
+        }
+        Ok(builders)
+    }
+
+    fn new_builder(&self) -> TransactionBuilder {
+        TransactionBuilder::new(self.fee_payer, self.recent_blockhash)
+    }
+
+    fn fits(&self, builder: &TransactionBuilder) -> bool {
+        builder.estimated_size() <= MAX_TRANSACTION_SIZE
+            && builder.num_accounts() <= self.max_accounts
+    }
+
+    fn too_large(&self, builder: &TransactionBuilder) -> SolanaError {
+        SolanaError::SerializationError(format!(
+            "Instruction does not fit in a transaction: {} bytes of {MAX_TRANSACTION_SIZE}, {} accounts of {}",
+            builder.estimated_size(),
+            builder.num_accounts(),
+            self.max_accounts
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransactionPacker;
+    use crate::instructions::program_ids::system_program;
+    use crate::instructions::system::transfer;
+    use crate::types::{Instruction, MAX_TRANSACTION_SIZE, Pubkey};
+
+    fn recipients(count: u8) -> Vec<Pubkey> {
+        (0..count).map(|i| Pubkey::new([i + 2; 32])).collect()
+    }
+
+    #[test]
+    fn test_pack_respects_size_limit_and_order() {
+        let payer = Pubkey::new([1u8; 32]);
+        let recipients = recipients(50);
+        let instructions: Vec<_> = recipients
+            .iter()
+            .map(|recipient| transfer(&payer, recipient, 1_000))
+            .collect();
+
+        let builders = TransactionPacker::new(payer, [0u8; 32])
+            .pack(instructions)
+            .unwrap();
+        assert!(builders.len() > 1);
+
+        let mut packed = Vec::new();
+        for builder in builders {
+            assert!(builder.estimated_size() <= MAX_TRANSACTION_SIZE);
+            let transaction = builder.build().unwrap();
+            assert!(transaction.serialize_legacy().unwrap().len() <= MAX_TRANSACTION_SIZE);
+            for instruction in &transaction.message.instructions {
+                packed.push(transaction.message.account_keys[instruction.accounts[1] as usize]);
+            }
+        }
+        assert_eq!(packed, recipients);
+    }
+
+    #[test]
+    fn test_pack_respects_max_accounts() {
+        let payer = Pubkey::new([1u8; 32]);
+        let instructions: Vec<_> = recipients(7)
+            .iter()
+            .map(|recipient| transfer(&payer, recipient, 1_000))
+            .collect();
+
+        // Fee payer and system program leave room for three recipients per transaction
+        let builders = TransactionPacker::new(payer, [0u8; 32])
+            .with_max_accounts(5)
+            .pack(instructions)
+            .unwrap();
+        let accounts: Vec<_> = builders.iter().map(|b| b.num_accounts()).collect();
+        assert_eq!(accounts, vec![5, 5, 3]);
+    }
+
+    #[test]
+    fn test_pack_rejects_oversized_instruction() {
+        let payer = Pubkey::new([1u8; 32]);
+        let oversized = Instruction {
+            program_id: system_program(),
+            accounts: vec![],
+            data: vec![0u8; MAX_TRANSACTION_SIZE],
+        };
+
+        let packer = TransactionPacker::new(payer, [0u8; 32]);
+        assert!(packer.pack(vec![oversized.clone()]).is_err());
+        assert!(
+            packer
+                .pack(vec![
+                    transfer(&payer, &Pubkey::new([2u8; 32]), 1),
+                    oversized
+                ])
+                .is_err()
+        );
+        assert!(packer.pack(Vec::new()).unwrap().is_empty());
+    }
+}
@@ -1,66 +1,131 @@
+use super::compiled_keys::CompiledKeys;
+use super::duplicates::{
+    DuplicateInstructionPolicy, dedupe_compute_budget, find_duplicate_instructions,
+    remove_duplicate_instructions,
+};
+use super::key_merge::{
+    DuplicateKeyPolicy, KeyMerge, collect_key_merges, find_key_merges, reindex_key_merges,
+};
+use crate::fee::{PriorityFeeEstimate, PriorityLevel};
+use crate::instructions::compute_budget::{
+    ensure_compute_unit_price, parse_compute_unit_price_data, set_compute_unit_price,
+};
+use crate::instructions::program_ids::compute_budget_program;
+use crate::instructions::system::advance_nonce_account;
+use crate::types::{check_size, message_body_size, signatures_size};
 use crate::{
-    AccountMeta, AddressLookupTableAccount, CompiledInstruction, Instruction, Message,
-    MessageAddressTableLookup, MessageHeader, Pubkey, Result, SignatureBytes, SolanaError,
-    Transaction, VersionedMessageV0, VersionedTransaction,
+    AddressLookupTableAccount, Blockhash, CompiledInstruction, Instruction, Message,
+    MessageAddressTableLookup, MicroLamports, PrioritizationFee, Pubkey, Result, SignatureBytes,
+    SolanaError, Transaction, VersionedMessageV0, VersionedTransaction,
 };
-use std::collections::{HashMap, HashSet};
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use serde::{Deserialize, Serialize};
+
+/// Version of the account ordering and compilation rules used by [`TransactionBuilder`].
+///
+/// Bumped whenever the builder would produce different bytes for the same inputs, so
+/// systems that persist expected transaction bytes can pin the layout they were built with.
+///
+/// 1. Fee payer first, then writable signers, readonly signers, writable and readonly
+///    non-signers. Legacy groups are sorted by pubkey as in the Solana SDK, V0 groups keep
+///    first-use order as in web3.js; V0 lookups take the first table holding a key.
+pub const BUILDER_LAYOUT_VERSION: u32 = 1;
+
+/// Metadata describing how a transaction was built
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildMetadata {
+    /// Layout version the builder used, see [`BUILDER_LAYOUT_VERSION`]
+    pub layout_version: u32,
+}
 
 /// A builder for constructing Solana transactions
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TransactionBuilder {
     /// The fee payer for the transaction
     fee_payer: Pubkey,
     /// The instructions to include in the transaction
     instructions: Vec<Instruction>,
     /// The recent blockhash
-    recent_blockhash: [u8; 32],
-    /// A map of account public keys to their metadata, including the fee payer
-    account_metas: HashMap<Pubkey, AccountMeta>,
+    recent_blockhash: Blockhash,
+    /// How conflicting roles for the same pubkey are handled
+    duplicate_key_policy: DuplicateKeyPolicy,
+    /// Role conflicts merged so far, recorded unless the policy is `Merge`
+    key_merges: Vec<KeyMerge>,
+    /// How identical instructions are handled at build time
+    duplicate_instruction_policy: DuplicateInstructionPolicy,
+    /// Account ordering and compilation rules to build with
+    layout_version: u32,
 }
 
 impl TransactionBuilder {
     /// Create a new transaction builder
-    pub fn new(fee_payer: Pubkey, recent_blockhash: [u8; 32]) -> Self {
-        let mut account_metas = HashMap::new();
-        account_metas.insert(
-            fee_payer,
-            AccountMeta {
-                pubkey: fee_payer,
-                is_signer: true,
-                is_writable: true,
-            },
-        );
-
+    pub fn new(fee_payer: Pubkey, recent_blockhash: impl Into<Blockhash>) -> Self {
         Self {
             fee_payer, // Store the fee_payer
             instructions: Vec::new(),
-            recent_blockhash,
-            account_metas,
+            recent_blockhash: recent_blockhash.into(),
+            duplicate_key_policy: DuplicateKeyPolicy::default(),
+            key_merges: Vec::new(),
+            duplicate_instruction_policy: DuplicateInstructionPolicy::default(),
+            layout_version: BUILDER_LAYOUT_VERSION,
+        }
+    }
+
+    /// Build with the rules of an older layout version; unsupported versions fail at build time
+    pub fn with_layout_version(&mut self, version: u32) -> &mut Self {
+        self.layout_version = version;
+        self
+    }
+
+    /// Metadata to persist alongside the built transaction
+    pub fn metadata(&self) -> BuildMetadata {
+        BuildMetadata {
+            layout_version: self.layout_version,
         }
     }
 
+    /// Set how conflicting roles for the same pubkey are handled
+    pub fn set_duplicate_key_policy(&mut self, policy: DuplicateKeyPolicy) -> &mut Self {
+        self.duplicate_key_policy = policy;
+        self
+    }
+
+    /// Role conflicts merged so far; always empty under [`DuplicateKeyPolicy::Merge`].
+    ///
+    /// Indexes refer to the instructions added so far. Use [`Self::build_with_key_merges`] or
+    /// [`Self::build_v0_with_key_merges`] for the conflicts of the transaction actually built.
+    pub fn key_merges(&self) -> &[KeyMerge] {
+        &self.key_merges
+    }
+
+    /// Set how identical instructions are handled at build time
+    pub fn set_duplicate_instruction_policy(
+        &mut self,
+        policy: DuplicateInstructionPolicy,
+    ) -> &mut Self {
+        self.duplicate_instruction_policy = policy;
+        self
+    }
+
+    /// Keep one compute budget instruction per kind, e.g. after a price was added twice.
+    ///
+    /// Each kind stays at its first position and takes the last value that was added.
+    pub fn dedupe_compute_budget(&mut self) -> &mut Self {
+        let new_indexes = dedupe_compute_budget(&mut self.instructions);
+        reindex_key_merges(&mut self.key_merges, &new_indexes);
+        self
+    }
+
     /// Add an instruction to the transaction
     pub fn add_instruction(&mut self, instruction: Instruction) -> &mut Self {
-        // Add program ID to account metas. Program IDs are typically not signers and are read-only (executable).
-        self.account_metas
-            .entry(instruction.program_id)
-            .or_insert_with(|| AccountMeta {
-                pubkey: instruction.program_id,
-                is_signer: false,
-                is_writable: false,
-            });
-
-        // Add all accounts from the instruction to our account_metas, merging properties.
-        // If an account is used in multiple instructions, its signer/writable status is the OR of all uses.
-        for account_meta in &instruction.accounts {
-            self.account_metas
-                .entry(account_meta.pubkey)
-                .and_modify(|existing_meta| {
-                    existing_meta.is_signer = existing_meta.is_signer || account_meta.is_signer;
-                    existing_meta.is_writable =
-                        existing_meta.is_writable || account_meta.is_writable;
-                })
-                .or_insert_with(|| account_meta.clone());
+        if self.duplicate_key_policy != DuplicateKeyPolicy::Merge {
+            self.key_merges.extend(find_key_merges(
+                &self.fee_payer,
+                &self.instructions,
+                &instruction,
+            ));
         }
         self.instructions.push(instruction);
         self
@@ -77,71 +142,188 @@ impl TransactionBuilder {
         self
     }
 
-    /// Build the transaction
-    pub fn build(self) -> Result<Transaction> {
-        let mut final_account_keys = Vec::new();
-        // HashSet to track keys already added to final_account_keys to prevent duplicates,
-        // though the categorization should handle distinct roles.
-        let mut processed_keys = std::collections::HashSet::new();
-
-        // 1. Fee payer first
-        final_account_keys.push(self.fee_payer);
-        processed_keys.insert(self.fee_payer);
-
-        let mut writable_signers = Vec::new();
-        let mut readonly_signers = Vec::new();
-        let mut writable_non_signers = Vec::new();
-        let mut readonly_non_signers = Vec::new();
-
-        // Categorize all other accounts from account_metas
-        for (pubkey, meta) in &self.account_metas {
-            if *pubkey == self.fee_payer {
-                // Already added
-                continue;
-            }
-            if meta.is_signer {
-                if meta.is_writable {
-                    writable_signers.push(*pubkey);
-                } else {
-                    readonly_signers.push(*pubkey);
-                }
-            } else if meta.is_writable {
-                writable_non_signers.push(*pubkey);
+    /// Replace the recent blockhash
+    pub fn set_recent_blockhash(&mut self, recent_blockhash: impl Into<Blockhash>) -> &mut Self {
+        self.recent_blockhash = recent_blockhash.into();
+        self
+    }
+
+    /// Use a durable nonce instead of a recent blockhash.
+    ///
+    /// Prepends `AdvanceNonceAccount` signed by `authority`, which the runtime requires as
+    /// the first instruction, and sets `nonce` (see [`NonceState`](crate::nonce::NonceState))
+    /// as the recent blockhash. Recorded [`KeyMerge`]s are re-indexed to follow the shift.
+    /// Call once per builder.
+    pub fn with_durable_nonce(
+        &mut self,
+        nonce_account: Pubkey,
+        authority: Pubkey,
+        nonce: impl Into<Blockhash>,
+    ) -> &mut Self {
+        let nonce_index = self.instructions.len();
+        self.add_instruction(advance_nonce_account(&nonce_account, &authority));
+        self.instructions.rotate_right(1);
+        for merge in &mut self.key_merges {
+            merge.instruction_index = if merge.instruction_index == nonce_index {
+                0
             } else {
-                readonly_non_signers.push(*pubkey);
-            }
+                merge.instruction_index + 1
+            };
         }
+        self.set_recent_blockhash(nonce)
+    }
 
-        // Sort within categories for deterministic output
-        writable_signers.sort();
-        readonly_signers.sort();
-        writable_non_signers.sort();
-        readonly_non_signers.sort();
-
-        // Append categorized keys to final_account_keys, ensuring no duplicates from previous categories
-        for key in writable_signers {
-            if processed_keys.insert(key) {
-                // insert returns true if value was newly inserted
-                final_account_keys.push(key);
-            }
+    /// Set the compute unit price, replacing an existing price instruction or inserting one
+    pub fn set_compute_unit_price(&mut self, price: MicroLamports) -> &mut Self {
+        let compute_budget_program_id = compute_budget_program();
+        if let Some(instruction) = self.instructions.iter_mut().find(|instruction| {
+            instruction.program_id == compute_budget_program_id
+                && parse_compute_unit_price_data(&instruction.data).is_some()
+        }) {
+            instruction.data = set_compute_unit_price(price).data;
+            return self;
         }
-        for key in readonly_signers {
-            if processed_keys.insert(key) {
-                final_account_keys.push(key);
+
+        if ensure_compute_unit_price(&mut self.instructions, price) {
+            let inserted = self
+                .instructions
+                .iter()
+                .position(|instruction| {
+                    instruction.program_id == compute_budget_program_id
+                        && parse_compute_unit_price_data(&instruction.data).is_some()
+                })
+                .unwrap_or_default();
+            for merge in &mut self.key_merges {
+                if merge.instruction_index >= inserted {
+                    merge.instruction_index += 1;
+                }
             }
         }
-        for key in writable_non_signers {
-            if processed_keys.insert(key) {
-                final_account_keys.push(key);
+        self
+    }
+
+    /// Set the compute unit price to the `percentile` (0-100) of recent prioritization fees,
+    /// e.g. the result of `getRecentPrioritizationFees` for the accounts this transaction writes
+    pub fn set_compute_unit_price_from_fees(
+        &mut self,
+        fees: &[PrioritizationFee],
+        percentile: u8,
+    ) -> &mut Self {
+        self.set_compute_unit_price(MicroLamports::percentile(fees, percentile))
+    }
+
+    /// Set the compute unit price recommended for `level` by a [`PriorityFeeEstimate`]
+    pub fn set_compute_unit_price_from_estimate(
+        &mut self,
+        estimate: &PriorityFeeEstimate,
+        level: PriorityLevel,
+    ) -> &mut Self {
+        self.set_compute_unit_price(estimate.price(level))
+    }
+
+    /// Build the same transaction against a fresh blockhash, keeping this builder for later retries
+    pub fn rebuild_with_blockhash(
+        &self,
+        recent_blockhash: impl Into<Blockhash>,
+    ) -> Result<Transaction> {
+        let mut builder = self.clone();
+        builder.set_recent_blockhash(recent_blockhash);
+        builder.build()
+    }
+
+    fn resolve_duplicate_instructions(&mut self) -> Result<()> {
+        let new_indexes = Self::apply_duplicate_policy(
+            self.duplicate_instruction_policy,
+            &mut self.instructions,
+        )?;
+        reindex_key_merges(&mut self.key_merges, &new_indexes);
+        Ok(())
+    }
+
+    /// Apply `policy`, returning the new index of every instruction
+    fn apply_duplicate_policy(
+        policy: DuplicateInstructionPolicy,
+        instructions: &mut Vec<Instruction>,
+    ) -> Result<Vec<usize>> {
+        let duplicates = find_duplicate_instructions(instructions);
+        match (policy, duplicates.first()) {
+            (DuplicateInstructionPolicy::Error, Some(duplicate)) => Err(SolanaError::GenericError(
+                format!("duplicate instruction: {duplicate}"),
+            )),
+            (DuplicateInstructionPolicy::Merge, Some(_)) => {
+                Ok(remove_duplicate_instructions(instructions, &duplicates))
             }
+            _ => Ok((0..instructions.len()).collect()),
         }
-        for key in readonly_non_signers {
-            if processed_keys.insert(key) {
-                final_account_keys.push(key);
-            }
+    }
+
+    fn check_key_merges(&self) -> Result<()> {
+        match (self.duplicate_key_policy, self.key_merges.first()) {
+            (DuplicateKeyPolicy::Error, Some(merge)) => Err(SolanaError::GenericError(format!(
+                "conflicting account roles: {merge}"
+            ))),
+            _ => Ok(()),
         }
+    }
 
-        let account_keys: Vec<Pubkey> = final_account_keys;
+    fn check_layout_version(&self) -> Result<()> {
+        if (1..=BUILDER_LAYOUT_VERSION).contains(&self.layout_version) {
+            Ok(())
+        } else {
+            Err(SolanaError::GenericError(format!(
+                "unsupported builder layout version {}, latest is {BUILDER_LAYOUT_VERSION}",
+                self.layout_version
+            )))
+        }
+    }
+
+    /// The instructions and keys [`Self::build`] would compile, without consuming the builder.
+    ///
+    /// When the duplicate policy would reject the instructions, they are compiled as they are.
+    fn preview(&self) -> (Vec<Instruction>, CompiledKeys) {
+        let mut instructions = self.instructions.clone();
+        if Self::apply_duplicate_policy(self.duplicate_instruction_policy, &mut instructions)
+            .is_err()
+        {
+            instructions.clone_from(&self.instructions);
+        }
+        let keys = CompiledKeys::compile(self.fee_payer, &instructions, true);
+        (instructions, keys)
+    }
+
+    /// Number of distinct accounts [`Self::build`] would compile, including the fee payer and
+    /// program ids
+    pub fn num_accounts(&self) -> usize {
+        self.preview().1.num_keys()
+    }
+
+    /// Wire size of the legacy transaction [`Self::build`] would produce, without building it.
+    ///
+    /// Building as V0 against lookup tables can only make the transaction smaller.
+    pub fn estimated_size(&self) -> usize {
+        let (instructions, keys) = self.preview();
+        signatures_size(keys.num_signers())
+            + message_body_size(
+                keys.num_keys(),
+                instructions
+                    .iter()
+                    .map(|instruction| (instruction.accounts.len(), instruction.data.len())),
+            )
+    }
+
+    /// Check that the built transaction will fit in [`MAX_TRANSACTION_SIZE`](crate::MAX_TRANSACTION_SIZE)
+    pub fn check_size(&self) -> Result<()> {
+        check_size(self.estimated_size())
+    }
+
+    /// Build the transaction
+    pub fn build(mut self) -> Result<Transaction> {
+        self.check_layout_version()?;
+        self.check_key_merges()?;
+        self.resolve_duplicate_instructions()?;
+        let (header, account_keys) =
+            CompiledKeys::compile(self.fee_payer, &self.instructions, true)
+                .into_message_components()?;
 
         // Legacy messages address accounts with a single `u8` index (max 256 accounts).
         if account_keys.len() > u8::MAX as usize + 1 {
@@ -149,7 +331,7 @@ impl TransactionBuilder {
         }
 
         // Create a map of pubkey to index for quick lookups
-        let key_to_index: HashMap<Pubkey, u8> = account_keys
+        let key_to_index: BTreeMap<Pubkey, u8> = account_keys
             .iter()
             .enumerate()
             .map(|(i, &key)| (key, i as u8))
@@ -175,37 +357,9 @@ impl TransactionBuilder {
             })
             .collect();
 
-        // Each count below can independently reach 256 and wrap when cast to u8.
-        let num_required_signatures = self
-            .account_metas
-            .values()
-            .filter(|meta| meta.is_signer)
-            .count();
-
-        let num_readonly_signed_accounts = self
-            .account_metas
-            .values()
-            .filter(|meta| meta.is_signer && !meta.is_writable)
-            .count();
-
-        let num_readonly_unsigned_accounts = self
-            .account_metas
-            .values()
-            .filter(|meta| !meta.is_signer && !meta.is_writable)
-            .count();
-
-        if num_required_signatures > u8::MAX as usize
-            || num_readonly_signed_accounts > u8::MAX as usize
-            || num_readonly_unsigned_accounts > u8::MAX as usize
-        {
-            return Err(SolanaError::InvalidMessage);
-        }
-
-        let header = MessageHeader {
-            num_required_signatures: num_required_signatures as u8,
-            num_readonly_signed_accounts: num_readonly_signed_accounts as u8,
-            num_readonly_unsigned_accounts: num_readonly_unsigned_accounts as u8,
-        };
+        // Create empty signatures vector
+        let signatures =
+            vec![SignatureBytes::new([0u8; 64]); header.num_required_signatures as usize];
 
         // Create message
         let message = Message {
@@ -215,21 +369,40 @@ impl TransactionBuilder {
             instructions: compiled_instructions,
         };
 
-        // Create empty signatures vector
-        let signatures = vec![SignatureBytes::new([0u8; 64]); num_required_signatures];
-
         Ok(Transaction {
             signatures,
             message,
         })
     }
 
+    /// Build the transaction along with the role conflicts merged into it.
+    ///
+    /// The conflicts are found in the final instruction order, after durable nonce, compute
+    /// budget and duplicate instruction handling, whatever the [`DuplicateKeyPolicy`].
+    pub fn build_with_key_merges(self) -> Result<(Transaction, Vec<KeyMerge>)> {
+        let key_merges = collect_key_merges(&self.fee_payer, &self.preview().0);
+        Ok((self.build()?, key_merges))
+    }
+
+    /// Build a V0 versioned transaction along with the role conflicts merged into it, see
+    /// [`Self::build_with_key_merges`]
+    pub fn build_v0_with_key_merges(
+        self,
+        address_lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<(VersionedTransaction, Vec<KeyMerge>)> {
+        let key_merges = collect_key_merges(&self.fee_payer, &self.preview().0);
+        Ok((self.build_v0(address_lookup_tables)?, key_merges))
+    }
+
     /// Build a V0 versioned transaction.
     pub fn build_v0(
-        self,
+        mut self,
         address_lookup_tables: &[AddressLookupTableAccount],
     ) -> Result<VersionedTransaction> {
-        let mut lookup_map: HashMap<Pubkey, (usize, u8)> = HashMap::new();
+        self.check_layout_version()?;
+        self.check_key_merges()?;
+        self.resolve_duplicate_instructions()?;
+        let mut lookup_map: BTreeMap<Pubkey, (usize, u8)> = BTreeMap::new();
         for (table_index, table) in address_lookup_tables.iter().enumerate().rev() {
             for (entry_index, address) in table.addresses.iter().enumerate() {
                 if let Ok(entry_index_u8) = u8::try_from(entry_index) {
@@ -240,97 +413,28 @@ impl TransactionBuilder {
             }
         }
 
-        let program_ids: HashSet<Pubkey> = self
-            .instructions
-            .iter()
-            .map(|instruction| instruction.program_id)
-            .collect();
-
-        let mut flags: HashMap<Pubkey, (bool, bool)> = HashMap::new();
-        let mut order: Vec<Pubkey> = Vec::new();
-        let mut merge = |pubkey: Pubkey, is_signer: bool, is_writable: bool| {
-            flags
-                .entry(pubkey)
-                .and_modify(|(existing_signer, existing_writable)| {
-                    *existing_signer |= is_signer;
-                    *existing_writable |= is_writable;
-                })
-                .or_insert_with(|| {
-                    order.push(pubkey);
-                    (is_signer, is_writable)
-                });
-        };
-
-        merge(self.fee_payer, true, true);
-        for instruction in &self.instructions {
-            merge(instruction.program_id, false, false);
-            for account_meta in &instruction.accounts {
-                merge(
-                    account_meta.pubkey,
-                    account_meta.is_signer,
-                    account_meta.is_writable,
-                );
-            }
-        }
-
-        let mut static_keys: [Vec<Pubkey>; 4] = Default::default();
+        // V0 keeps first-use order within each group, as web3.js compiles it
+        let mut compiled_keys = CompiledKeys::compile(self.fee_payer, &self.instructions, false);
         let mut lookup_writable: Vec<Vec<(Pubkey, u8)>> =
             vec![Vec::new(); address_lookup_tables.len()];
         let mut lookup_readonly: Vec<Vec<(Pubkey, u8)>> =
             vec![Vec::new(); address_lookup_tables.len()];
-
-        for pubkey in &order {
-            let (is_signer, is_writable) = flags
-                .get(pubkey)
-                .copied()
-                .ok_or(SolanaError::InvalidMessage)?;
-
-            if is_signer || program_ids.contains(pubkey) || !lookup_map.contains_key(pubkey) {
-                let bucket = match (is_signer, is_writable) {
-                    (true, true) => 0,
-                    (true, false) => 1,
-                    (false, true) => 2,
-                    (false, false) => 3,
-                };
-                static_keys[bucket].push(*pubkey);
+        for (pubkey, meta, (table_index, entry_index)) in
+            compiled_keys.drain_lookups(|pubkey| lookup_map.get(pubkey).copied())
+        {
+            if meta.is_writable {
+                lookup_writable[table_index].push((pubkey, entry_index));
             } else {
-                let (table_index, entry_index) = lookup_map
-                    .get(pubkey)
-                    .copied()
-                    .ok_or(SolanaError::InvalidMessage)?;
-                if is_writable {
-                    lookup_writable[table_index].push((*pubkey, entry_index));
-                } else {
-                    lookup_readonly[table_index].push((*pubkey, entry_index));
-                }
+                lookup_readonly[table_index].push((pubkey, entry_index));
             }
         }
 
-        let mut account_keys = Vec::with_capacity(static_keys.iter().map(Vec::len).sum());
-        account_keys.push(self.fee_payer);
-
-        account_keys.extend(
-            static_keys[0]
-                .iter()
-                .copied()
-                .filter(|pubkey| *pubkey != self.fee_payer),
-        );
-
-        for bucket in &static_keys[1..] {
-            account_keys.extend(bucket.iter().copied());
-        }
-
+        let (header, account_keys) = compiled_keys.into_message_components()?;
         if account_keys.len() > u8::MAX as usize {
             return Err(SolanaError::InvalidMessage);
         }
 
-        let header = MessageHeader {
-            num_required_signatures: (static_keys[0].len() + static_keys[1].len()) as u8,
-            num_readonly_signed_accounts: static_keys[1].len() as u8,
-            num_readonly_unsigned_accounts: static_keys[3].len() as u8,
-        };
-
-        let mut virtual_index_map: HashMap<Pubkey, u8> = HashMap::new();
+        let mut virtual_index_map: BTreeMap<Pubkey, u8> = BTreeMap::new();
         for (next_virtual_index, (pubkey, _)) in (account_keys.len()..).zip(
             lookup_writable
                 .iter()
@@ -367,7 +471,7 @@ impl TransactionBuilder {
             })
             .collect();
 
-        let static_index_map: HashMap<Pubkey, u8> = account_keys
+        let static_index_map: BTreeMap<Pubkey, u8> = account_keys
             .iter()
             .enumerate()
             .map(|(index, pubkey)| (*pubkey, index as u8))
@@ -431,18 +535,21 @@ impl TransactionBuilder {
 
 #[cfg(test)]
 mod tests {
-    use super::TransactionBuilder;
+    use super::{BUILDER_LAYOUT_VERSION, BuildMetadata, TransactionBuilder};
     use crate::Pubkey;
     use crate::SolanaError;
     use crate::builder::InstructionBuilder;
+    use crate::builder::{DuplicateInstructionPolicy, DuplicateKeyPolicy, KeyMergeKind};
+    use crate::decode::ParsedInstruction;
     use crate::instructions::{
         program_ids::{system_program, token_program},
-        system::{create_account, transfer},
+        system::{SystemInstruction, create_account, transfer},
         token::transfer_checked,
     };
     use crate::types::instruction::AccountMeta;
     use crate::types::{
-        AddressLookupTableAccount, Instruction, SignatureBytes, VersionedTransaction,
+        AddressLookupTableAccount, Blockhash, Instruction, MicroLamports, PrioritizationFee,
+        SignatureBytes, VersionedTransaction,
     };
     use base64::Engine;
     use base64::engine::general_purpose::STANDARD;
@@ -533,7 +640,6 @@ mod tests {
     #[test]
     fn test_transaction_builder() {
         let recent_blockhash = "9U2ogLjDt479wubHbEtPLGBF84DijmWggA4KoXSwcivd";
-        let recent_blockhash_bytes = bs58::decode(recent_blockhash).into_vec().unwrap();
         let fee_payer: Pubkey = "A21o4asMbFHYadqXdLusT9Bvx9xaC5YV9gcaidjqtdXC"
             .parse()
             .unwrap();
@@ -572,7 +678,7 @@ mod tests {
             ]);
 
         let mut tx_builder =
-            TransactionBuilder::new(fee_payer, recent_blockhash_bytes.try_into().unwrap());
+            TransactionBuilder::new(fee_payer, recent_blockhash.parse::<Blockhash>().unwrap());
         tx_builder.add_instruction(instruction.build());
 
         let transaction = tx_builder.build().unwrap();
@@ -934,4 +1040,336 @@ mod tests {
             "expected build() to reject 256 required signers with InvalidMessage, got {result:?}"
         );
     }
+
+    #[test]
+    fn test_rebuild_with_blockhash() {
+        let fee_payer = Pubkey::new([1u8; 32]);
+        let recipient = Pubkey::new([2u8; 32]);
+        let mut builder = TransactionBuilder::new(fee_payer, [3u8; 32]);
+        builder.add_instruction(transfer(&fee_payer, &recipient, 10));
+
+        let first = builder.rebuild_with_blockhash([4u8; 32]).unwrap();
+        let second = builder.rebuild_with_blockhash([5u8; 32]).unwrap();
+
+        assert_eq!(first.message.recent_blockhash, Blockhash::new([4u8; 32]));
+        assert_eq!(second.message.recent_blockhash, Blockhash::new([5u8; 32]));
+        assert_eq!(first.message.account_keys, second.message.account_keys);
+        assert_eq!(second.signatures.len(), 1);
+        assert_eq!(
+            second.serialize_legacy().unwrap(),
+            first.rebase([5u8; 32]).serialize_legacy().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_duplicate_key_policy() {
+        let fee_payer = Pubkey::new([1u8; 32]);
+        let owner = Pubkey::new([2u8; 32]);
+        let recipient = Pubkey::new([3u8; 32]);
+
+        let read_owner = InstructionBuilder::new(token_program())
+            .account(owner, false, false)
+            .build();
+        let instructions = [
+            read_owner.clone(),
+            transfer(&owner, &recipient, 1),
+            transfer(&fee_payer, &token_program(), 1),
+        ];
+
+        let mut merged = TransactionBuilder::new(fee_payer, [0u8; 32]);
+        merged.add_instructions(instructions.clone());
+        assert!(merged.key_merges().is_empty());
+        assert!(merged.build().is_ok());
+
+        let mut reported = TransactionBuilder::new(fee_payer, [0u8; 32]);
+        reported.set_duplicate_key_policy(DuplicateKeyPolicy::Report);
+        reported.add_instructions(instructions.clone());
+        let merges: Vec<_> = reported
+            .key_merges()
+            .iter()
+            .map(|merge| (merge.pubkey, merge.instruction_index, merge.kind))
+            .collect();
+        assert_eq!(
+            merges,
+            vec![
+                (owner, 1, KeyMergeKind::SignerWidened),
+                (owner, 1, KeyMergeKind::WritableWidened),
+                (token_program(), 2, KeyMergeKind::ProgramIdAsAccount),
+            ]
+        );
+        assert!(reported.build().is_ok());
+
+        let mut strict = TransactionBuilder::new(fee_payer, [0u8; 32]);
+        strict.set_duplicate_key_policy(DuplicateKeyPolicy::Error);
+        strict.add_instruction(read_owner);
+        assert!(strict.clone().build().is_ok());
+        strict.add_instruction(transfer(&owner, &recipient, 1));
+        assert!(strict.clone().build().is_err());
+        assert!(strict.build_v0(&[]).is_err());
+    }
+
+    #[test]
+    fn test_duplicate_instruction_policy() {
+        let payer = Pubkey::new([1u8; 32]);
+        let recipient = Pubkey::new([2u8; 32]);
+        let mut builder = TransactionBuilder::new(payer, test_blockhash());
+        builder.add_instruction(transfer(&payer, &recipient, 1));
+        builder.add_instruction(transfer(&payer, &recipient, 1));
+        builder.set_compute_unit_price(MicroLamports(1));
+        builder.add_instruction(crate::instructions::compute_budget::set_compute_unit_price(
+            MicroLamports(7),
+        ));
+        builder.dedupe_compute_budget();
+        assert_eq!(
+            builder.clone().build().unwrap().message.instructions.len(),
+            3
+        );
+
+        builder.set_duplicate_instruction_policy(DuplicateInstructionPolicy::Merge);
+        let transaction = builder.clone().build_v0(&[]).unwrap();
+        assert_eq!(transaction.instructions().len(), 2);
+        assert_eq!(transaction.get_compute_unit_price(), Some(MicroLamports(7)));
+
+        builder.set_duplicate_instruction_policy(DuplicateInstructionPolicy::Error);
+        let error = builder.clone().build().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "duplicate instruction: instruction 2 duplicates instruction 1"
+        );
+        assert!(builder.build_v0(&[]).is_err());
+    }
+
+    #[test]
+    fn test_dedupe_compute_budget_reindexes_key_merges() {
+        let payer = Pubkey::new([1u8; 32]);
+        let owner = Pubkey::new([2u8; 32]);
+        let mut builder = TransactionBuilder::new(payer, test_blockhash());
+        builder.set_duplicate_key_policy(DuplicateKeyPolicy::Report);
+        builder.add_instructions([
+            crate::instructions::compute_budget::set_compute_unit_price(MicroLamports(1)),
+            crate::instructions::compute_budget::set_compute_unit_price(MicroLamports(7)),
+            InstructionBuilder::new(token_program())
+                .account(owner, false, false)
+                .build(),
+            transfer(&owner, &payer, 1),
+        ]);
+        assert_eq!(builder.key_merges()[0].instruction_index, 3);
+
+        builder.dedupe_compute_budget();
+        assert_eq!(builder.key_merges().len(), 2);
+        assert!(
+            builder
+                .key_merges()
+                .iter()
+                .all(|merge| merge.instruction_index == 2)
+        );
+    }
+
+    #[test]
+    fn test_set_compute_unit_price_from_fees() {
+        let payer = payer_pubkey();
+        let fees: Vec<_> = [10u64, 500, 2_000]
+            .into_iter()
+            .map(|fee| PrioritizationFee {
+                slot: fee,
+                prioritization_fee: MicroLamports(fee),
+            })
+            .collect();
+
+        let mut builder = TransactionBuilder::new(payer, [0u8; 32]);
+        builder.add_instruction(transfer(&payer, &authority_pubkey(), 1));
+        builder.set_compute_unit_price_from_fees(&fees, 50);
+        builder.set_compute_unit_price_from_fees(&fees, 90);
+
+        let transaction = builder.build_v0(&[]).unwrap();
+        assert_eq!(transaction.instructions().len(), 2);
+        assert_eq!(
+            transaction.get_compute_unit_price(),
+            Some(MicroLamports(2_000))
+        );
+    }
+
+    #[test]
+    fn test_layout_version() {
+        let payer = payer_pubkey();
+        let mut builder = TransactionBuilder::new(payer, [0u8; 32]);
+        builder.add_instruction(transfer(&payer, &authority_pubkey(), 1));
+        assert_eq!(
+            builder.metadata(),
+            BuildMetadata {
+                layout_version: BUILDER_LAYOUT_VERSION
+            }
+        );
+
+        let latest = builder.clone().build().unwrap().serialize_legacy().unwrap();
+        builder.with_layout_version(1);
+        assert_eq!(builder.metadata().layout_version, 1);
+        assert_eq!(
+            builder.clone().build().unwrap().serialize_legacy().unwrap(),
+            latest
+        );
+
+        builder.with_layout_version(BUILDER_LAYOUT_VERSION + 1);
+        assert!(builder.clone().build().is_err());
+        assert!(builder.build_v0(&[]).is_err());
+    }
+
+    #[test]
+    fn test_with_durable_nonce() {
+        let payer = payer_pubkey();
+        let nonce_account = Pubkey::new([5u8; 32]);
+        let nonce = [6u8; 32];
+
+        let mut builder = TransactionBuilder::new(payer, [0u8; 32]);
+        builder.add_instruction(transfer(&payer, &authority_pubkey(), 1));
+        builder.with_durable_nonce(nonce_account, payer, nonce);
+        builder.set_compute_unit_price(MicroLamports(100));
+
+        let transaction = builder.build_v0(&[]).unwrap();
+        assert_eq!(transaction.recent_blockhash(), &Blockhash::new(nonce));
+        let parsed = transaction.decode_instructions().unwrap();
+        assert!(matches!(
+            parsed[0],
+            ParsedInstruction::System(SystemInstruction::AdvanceNonceAccount { authorized })
+                if authorized == payer
+        ));
+        assert_eq!(
+            transaction.account_keys()[transaction.instructions()[0].accounts[0] as usize],
+            nonce_account
+        );
+        assert_eq!(
+            transaction.get_compute_unit_price(),
+            Some(MicroLamports(100))
+        );
+    }
+
+    #[test]
+    fn test_durable_nonce_keeps_key_merge_indexes() {
+        let payer = payer_pubkey();
+        let owner = Pubkey::new([2u8; 32]);
+        let recipient = Pubkey::new([3u8; 32]);
+
+        let mut builder = TransactionBuilder::new(payer, [0u8; 32]);
+        builder.set_duplicate_key_policy(DuplicateKeyPolicy::Report);
+        builder.add_instruction(
+            InstructionBuilder::new(token_program())
+                .account(owner, false, false)
+                .build(),
+        );
+        builder.add_instruction(transfer(&owner, &recipient, 1));
+        builder.with_durable_nonce(Pubkey::new([5u8; 32]), recipient, [6u8; 32]);
+        builder.set_compute_unit_price(MicroLamports(100));
+
+        let merges: Vec<_> = builder
+            .key_merges()
+            .iter()
+            .map(|merge| (merge.pubkey, merge.instruction_index, merge.kind))
+            .collect();
+        assert_eq!(
+            merges,
+            vec![
+                (owner, 3, KeyMergeKind::SignerWidened),
+                (owner, 3, KeyMergeKind::WritableWidened),
+                (recipient, 0, KeyMergeKind::SignerWidened),
+                (recipient, 0, KeyMergeKind::WritableWidened),
+            ]
+        );
+        let transaction = builder.build_v0(&[]).unwrap();
+        let keys = transaction.account_keys();
+        let transfer_ix = &transaction.instructions()[3];
+        assert_eq!(keys[transfer_ix.accounts[0] as usize], owner);
+    }
+
+    #[test]
+    fn test_build_with_key_merges_follows_final_order() {
+        let payer = payer_pubkey();
+        let owner = Pubkey::new([2u8; 32]);
+        let recipient = Pubkey::new([3u8; 32]);
+
+        let mut builder = TransactionBuilder::new(payer, [0u8; 32]);
+        builder.set_duplicate_instruction_policy(DuplicateInstructionPolicy::Merge);
+        builder.add_instructions([
+            transfer(&payer, &recipient, 1),
+            transfer(&payer, &recipient, 1),
+            InstructionBuilder::new(token_program())
+                .account(owner, false, false)
+                .build(),
+            transfer(&owner, &recipient, 1),
+        ]);
+        builder.with_durable_nonce(Pubkey::new([5u8; 32]), recipient, [6u8; 32]);
+
+        let (transaction, merges) = builder.clone().build_v0_with_key_merges(&[]).unwrap();
+        let merges: Vec<_> = merges
+            .iter()
+            .map(|merge| (merge.pubkey, merge.instruction_index, merge.kind))
+            .collect();
+        assert_eq!(
+            merges,
+            vec![
+                (recipient, 1, KeyMergeKind::SignerWidened),
+                (recipient, 1, KeyMergeKind::WritableWidened),
+                (owner, 3, KeyMergeKind::SignerWidened),
+                (owner, 3, KeyMergeKind::WritableWidened),
+                (recipient, 3, KeyMergeKind::SignerWidened),
+            ]
+        );
+        assert_eq!(transaction.instructions().len(), 4);
+        let keys = transaction.account_keys();
+        let transfer_ix = &transaction.instructions()[3];
+        assert_eq!(keys[transfer_ix.accounts[0] as usize], owner);
+
+        let (legacy, legacy_merges) = builder.build_with_key_merges().unwrap();
+        assert_eq!(legacy.message.instructions.len(), 4);
+        assert_eq!(legacy_merges.len(), 5);
+    }
+
+    #[test]
+    fn test_estimated_size_matches_serialized_size() {
+        let payer = Pubkey::new([1u8; 32]);
+        let recipient = Pubkey::new([2u8; 32]);
+        let mut builder = TransactionBuilder::new(payer, test_blockhash());
+        builder.add_instruction(transfer(&payer, &recipient, 1_000));
+        builder.add_instruction(Instruction {
+            program_id: token_program(),
+            accounts: vec![AccountMeta::new(recipient, false, true)],
+            data: vec![7u8; 200],
+        });
+        builder.check_size().unwrap();
+
+        let estimated = builder.estimated_size();
+        let versioned = builder.clone().build_v0(&[]).unwrap();
+        assert_eq!(
+            versioned.serialized_size(),
+            versioned.serialize().unwrap().len()
+        );
+        let transaction = builder.build().unwrap();
+        assert_eq!(estimated, transaction.serialized_size());
+        assert_eq!(estimated, transaction.serialize_legacy().unwrap().len());
+        assert_eq!(
+            transaction.message.serialized_size(),
+            transaction.message.serialize_for_signing().unwrap().len()
+        );
+
+        // Merged duplicates are left out of the estimate, as they are out of the build
+        let mut merged = TransactionBuilder::new(payer, test_blockhash());
+        merged.set_duplicate_instruction_policy(DuplicateInstructionPolicy::Merge);
+        merged.add_instruction(transfer(&payer, &recipient, 1_000));
+        merged.add_instruction(transfer(&payer, &recipient, 1_000));
+        let estimated = merged.estimated_size();
+        assert_eq!(merged.num_accounts(), 3);
+        let transaction = merged.build().unwrap();
+        assert_eq!(transaction.message.instructions.len(), 1);
+        assert_eq!(estimated, transaction.serialize_legacy().unwrap().len());
+
+        let mut oversized = TransactionBuilder::new(payer, test_blockhash());
+        oversized.add_instruction(Instruction {
+            program_id: token_program(),
+            accounts: vec![],
+            data: vec![0u8; crate::MAX_TRANSACTION_SIZE],
+        });
+        assert!(matches!(
+            oversized.check_size(),
+            Err(SolanaError::SerializationError(_))
+        ));
+    }
 }
@@ -0,0 +1,455 @@
+//! Wire-format codec built on bincode and `short_vec`.
+//!
+//! Mirrors the on-chain serde layout: fixed-size arrays are raw bytes and every vector is
+//! prefixed with a compact-u16 length, so [`serialize`] produces the same bytes as
+//! [`VersionedTransaction::serialize`]. [`deserialize`] applies the same header checks as
+//! the hand-written decoder and, like it, rejects a V0 message without its lookup section;
+//! unlike it, it also rejects trailing bytes.
+
+use crate::error::{Result, SolanaError};
+use crate::short_vec::{self, ShortVec};
+use crate::types::{
+    Blockhash, CompiledInstruction, LegacyMessage, MessageAddressTableLookup, MessageHeader,
+    Pubkey, SignatureBytes, VersionedMessageV0, VersionedTransaction,
+};
+use crate::wire::check_header_counts;
+use bincode::Options;
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Prefix bit marking a versioned message
+const VERSION_PREFIX_MASK: u8 = 0x80;
+
+/// The bincode configuration matching the wire format
+pub fn options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_little_endian()
+        .reject_trailing_bytes()
+}
+
+/// Serialize a transaction to wire bytes
+pub fn serialize(transaction: &VersionedTransaction) -> Result<Vec<u8>> {
+    options()
+        .serialize(&WireTransaction::from(transaction))
+        .map_err(|e| SolanaError::SerializationError(e.to_string()))
+}
+
+/// Deserialize a transaction from wire bytes
+pub fn deserialize(bytes: &[u8]) -> Result<VersionedTransaction> {
+    let transaction = options()
+        .deserialize::<WireTransaction>(bytes)
+        .map_err(|e| SolanaError::DeserializationError(e.to_string()))?;
+    let body = transaction.message.body();
+    check_header_counts(body.header, body.account_keys.len()).map_err(|expected| {
+        SolanaError::DeserializationError(format!(
+            "invalid header {:?}: expected {expected}",
+            body.header
+        ))
+    })?;
+    Ok(VersionedTransaction::from(transaction))
+}
+
+/// A 64-byte signature, serialized as raw bytes
+struct WireSignature([u8; 64]);
+
+#[derive(Serialize, Deserialize)]
+struct WireTransaction {
+    #[serde(with = "short_vec")]
+    signatures: Vec<WireSignature>,
+    message: WireMessage,
+}
+
+enum WireMessage {
+    Legacy(WireMessageBody),
+    V0(WireMessageBody, ShortVec<WireLookup>),
+}
+
+#[derive(Serialize, Deserialize)]
+struct WireMessageBody {
+    header: [u8; 3],
+    #[serde(with = "short_vec")]
+    account_keys: Vec<[u8; 32]>,
+    recent_blockhash: [u8; 32],
+    #[serde(with = "short_vec")]
+    instructions: Vec<WireInstruction>,
+}
+
+/// A legacy message after its first byte, which doubled as the version check
+#[derive(Deserialize)]
+struct LegacyMessageRest {
+    header: [u8; 2],
+    #[serde(with = "short_vec")]
+    account_keys: Vec<[u8; 32]>,
+    recent_blockhash: [u8; 32],
+    #[serde(with = "short_vec")]
+    instructions: Vec<WireInstruction>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WireInstruction {
+    program_id_index: u8,
+    #[serde(with = "short_vec")]
+    accounts: Vec<u8>,
+    #[serde(with = "short_vec")]
+    data: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct WireLookup {
+    account_key: [u8; 32],
+    #[serde(with = "short_vec")]
+    writable_indexes: Vec<u8>,
+    #[serde(with = "short_vec")]
+    readonly_indexes: Vec<u8>,
+}
+
+impl Serialize for WireSignature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(64)?;
+        for byte in &self.0 {
+            tuple.serialize_element(byte)?;
+        }
+        tuple.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for WireSignature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct SignatureVisitor;
+
+        impl<'de> Visitor<'de> for SignatureVisitor {
+            type Value = WireSignature;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("64 signature bytes")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> std::result::Result<WireSignature, A::Error> {
+                let mut bytes = [0u8; 64];
+                for (index, byte) in bytes.iter_mut().enumerate() {
+                    *byte = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(index, &self))?;
+                }
+                Ok(WireSignature(bytes))
+            }
+        }
+
+        deserializer.deserialize_tuple(64, SignatureVisitor)
+    }
+}
+
+impl Serialize for WireMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Self::Legacy(body) => body.serialize(serializer),
+            Self::V0(body, lookups) => {
+                let mut tuple = serializer.serialize_tuple(3)?;
+                tuple.serialize_element(&VERSION_PREFIX_MASK)?;
+                tuple.serialize_element(body)?;
+                tuple.serialize_element(lookups)?;
+                tuple.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WireMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct MessageVisitor;
+
+        impl<'de> Visitor<'de> for MessageVisitor {
+            type Value = WireMessage;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a legacy or versioned message")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> std::result::Result<WireMessage, A::Error> {
+                let prefix: u8 = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                if prefix & VERSION_PREFIX_MASK == 0 {
+                    let rest: LegacyMessageRest = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                    return Ok(WireMessage::Legacy(WireMessageBody {
+                        header: [prefix, rest.header[0], rest.header[1]],
+                        account_keys: rest.account_keys,
+                        recent_blockhash: rest.recent_blockhash,
+                        instructions: rest.instructions,
+                    }));
+                }
+
+                match prefix & !VERSION_PREFIX_MASK {
+                    0 => {
+                        let body = seq
+                            .next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                        let lookups = seq
+                            .next_element()?
+                            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+                        Ok(WireMessage::V0(body, lookups))
+                    }
+                    version => Err(de::Error::invalid_value(
+                        de::Unexpected::Unsigned(version as u64),
+                        &"message version 0",
+                    )),
+                }
+            }
+        }
+
+        deserializer.deserialize_tuple(3, MessageVisitor)
+    }
+}
+
+impl WireMessage {
+    fn body(&self) -> &WireMessageBody {
+        match self {
+            Self::Legacy(body) | Self::V0(body, _) => body,
+        }
+    }
+}
+
+impl WireMessageBody {
+    fn new(
+        header: &MessageHeader,
+        account_keys: &[Pubkey],
+        recent_blockhash: &Blockhash,
+        instructions: &[CompiledInstruction],
+    ) -> Self {
+        Self {
+            header: [
+                header.num_required_signatures,
+                header.num_readonly_signed_accounts,
+                header.num_readonly_unsigned_accounts,
+            ],
+            account_keys: account_keys.iter().map(|key| *key.as_bytes()).collect(),
+            recent_blockhash: *recent_blockhash.as_bytes(),
+            instructions: instructions
+                .iter()
+                .map(|instruction| WireInstruction {
+                    program_id_index: instruction.program_id_index,
+                    accounts: instruction.accounts.clone(),
+                    data: instruction.data.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn into_parts(
+        self,
+    ) -> (
+        MessageHeader,
+        Vec<Pubkey>,
+        Blockhash,
+        Vec<CompiledInstruction>,
+    ) {
+        let [
+            num_required_signatures,
+            num_readonly_signed_accounts,
+            num_readonly_unsigned_accounts,
+        ] = self.header;
+        (
+            MessageHeader {
+                num_required_signatures,
+                num_readonly_signed_accounts,
+                num_readonly_unsigned_accounts,
+            },
+            self.account_keys.into_iter().map(Pubkey::new).collect(),
+            Blockhash::new(self.recent_blockhash),
+            self.instructions
+                .into_iter()
+                .map(|instruction| CompiledInstruction {
+                    program_id_index: instruction.program_id_index,
+                    accounts: instruction.accounts,
+                    data: instruction.data,
+                })
+                .collect(),
+        )
+    }
+}
+
+impl From<&VersionedTransaction> for WireTransaction {
+    fn from(transaction: &VersionedTransaction) -> Self {
+        let message = match transaction {
+            VersionedTransaction::Legacy { message, .. } => {
+                WireMessage::Legacy(WireMessageBody::new(
+                    &message.header,
+                    &message.account_keys,
+                    &message.recent_blockhash,
+                    &message.instructions,
+                ))
+            }
+            VersionedTransaction::V0 { message, .. } => WireMessage::V0(
+                WireMessageBody::new(
+                    &message.header,
+                    &message.account_keys,
+                    &message.recent_blockhash,
+                    &message.instructions,
+                ),
+                ShortVec::new(
+                    message
+                        .address_table_lookups
+                        .iter()
+                        .map(|lookup| WireLookup {
+                            account_key: *lookup.account_key.as_bytes(),
+                            writable_indexes: lookup.writable_indexes.clone(),
+                            readonly_indexes: lookup.readonly_indexes.clone(),
+                        })
+                        .collect(),
+                ),
+            ),
+        };
+        Self {
+            signatures: transaction
+                .signatures()
+                .iter()
+                .map(|signature| WireSignature(*signature.as_bytes()))
+                .collect(),
+            message,
+        }
+    }
+}
+
+impl From<WireTransaction> for VersionedTransaction {
+    fn from(transaction: WireTransaction) -> Self {
+        let signatures = transaction
+            .signatures
+            .into_iter()
+            .map(|signature| SignatureBytes::new(signature.0))
+            .collect();
+        match transaction.message {
+            WireMessage::Legacy(body) => {
+                let (header, account_keys, recent_blockhash, instructions) = body.into_parts();
+                Self::Legacy {
+                    signatures,
+                    message: LegacyMessage {
+                        header,
+                        account_keys,
+                        recent_blockhash,
+                        instructions,
+                    },
+                }
+            }
+            WireMessage::V0(body, lookups) => {
+                let (header, account_keys, recent_blockhash, instructions) = body.into_parts();
+                Self::V0 {
+                    signatures,
+                    message: VersionedMessageV0 {
+                        header,
+                        account_keys,
+                        recent_blockhash,
+                        instructions,
+                        address_table_lookups: lookups
+                            .into_inner()
+                            .into_iter()
+                            .map(|lookup| MessageAddressTableLookup {
+                                account_key: Pubkey::new(lookup.account_key),
+                                writable_indexes: lookup.writable_indexes,
+                                readonly_indexes: lookup.readonly_indexes,
+                            })
+                            .collect(),
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::TransactionBuilder;
+    use crate::crypto::Keypair;
+    use crate::instructions::system::transfer;
+    use crate::types::AddressLookupTableAccount;
+    use crate::view::TransactionView;
+
+    fn samples() -> Vec<Vec<u8>> {
+        let payer = Keypair::from_secret_key(&[1u8; 32]);
+        let recipient = Pubkey::new([2u8; 32]);
+        let builder = || {
+            let mut builder = TransactionBuilder::new(payer.pubkey(), [3u8; 32]);
+            builder.add_instruction(transfer(&payer.pubkey(), &recipient, 1_000));
+            builder
+        };
+
+        let mut legacy = builder().build().unwrap();
+        legacy.sign(&[&payer]).unwrap();
+        let table = AddressLookupTableAccount::new(Pubkey::new([4u8; 32]), vec![recipient]);
+        let mut v0 = builder().build_v0(&[table]).unwrap();
+        v0.sign(&[&payer]).unwrap();
+        let mut v0_without_lookups = builder().build_v0(&[]).unwrap();
+        v0_without_lookups.sign(&[&payer]).unwrap();
+        vec![
+            legacy.serialize_legacy().unwrap(),
+            v0.serialize().unwrap(),
+            v0_without_lookups.serialize().unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_codec_matches_manual_encoding() {
+        for bytes in samples() {
+            let manual = VersionedTransaction::deserialize_with_version(&bytes).unwrap();
+            assert_eq!(serialize(&manual).unwrap(), bytes);
+
+            let decoded = deserialize(&bytes).unwrap();
+            assert_eq!(decoded.serialize().unwrap(), bytes);
+            assert_eq!(decoded.signatures(), manual.signatures());
+        }
+    }
+
+    #[test]
+    fn test_codec_rejects_malformed_input() {
+        for bytes in samples() {
+            for len in 0..bytes.len() {
+                assert!(deserialize(&bytes[..len]).is_err(), "{len}");
+            }
+            let mut trailing = bytes.clone();
+            trailing.push(0);
+            assert!(deserialize(&trailing).is_err());
+        }
+
+        let mut version = samples().pop().unwrap();
+        version[65] = 0x81;
+        assert!(deserialize(&version).is_err());
+    }
+
+    #[test]
+    fn test_codec_agrees_with_manual_decoder_on_headers() {
+        for (bytes, header_offset) in samples().into_iter().zip([65, 66, 66]) {
+            for position in header_offset..header_offset + 3 {
+                for value in 0..=u8::MAX {
+                    let mut mutated = bytes.clone();
+                    mutated[position] = value;
+                    assert_eq!(
+                        deserialize(&mutated).is_ok(),
+                        VersionedTransaction::deserialize_with_version(&mutated).is_ok(),
+                        "header byte {position} = {value}"
+                    );
+                }
+            }
+        }
+
+        // Every truncation, including a V0 message cut before its lookup section
+        for bytes in samples() {
+            for len in 0..bytes.len() {
+                let truncated = &bytes[..len];
+                assert!(deserialize(truncated).is_err(), "{len}");
+                assert!(VersionedTransaction::deserialize_with_version(truncated).is_err());
+                assert!(TransactionView::parse(truncated).is_err(), "{len}");
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! BLAKE3 hashing with the default 32-byte output, as used by `sol_blake3` and for message
+//! hashes.
+
+/// Hash data using BLAKE3
+pub fn blake3(data: &[u8]) -> [u8; 32] {
+    ::blake3::hash(data).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::blake3;
+
+    /// Input of the official test vectors: the byte sequence 0, 1, .., 250 repeated
+    fn vector_input(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_blake3_vectors() {
+        assert_eq!(
+            hex::encode(blake3(b"")),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+        assert_eq!(
+            hex::encode(blake3(b"abc")),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+        for (len, expected) in [
+            (
+                1,
+                "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213",
+            ),
+            (
+                1024,
+                "42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7",
+            ),
+            (
+                1025,
+                "d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444",
+            ),
+            (
+                2048,
+                "e776b6028c7cd22a4d0ba182a8bf62205d2ef576467e838ed6f2529b85fba24a",
+            ),
+        ] {
+            assert_eq!(hex::encode(blake3(&vector_input(len))), expected, "{len}");
+        }
+    }
+}
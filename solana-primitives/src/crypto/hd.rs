@@ -0,0 +1,184 @@
+//! BIP39 mnemonics and SLIP-0010 ed25519 derivation, matching Phantom and Solflare.
+
+use crate::crypto::{Keypair, SecretKey};
+use crate::error::{Result, SolanaError};
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use std::fmt;
+use std::str::FromStr;
+use zeroize::{Zeroize, Zeroizing};
+
+/// SLIP-0044 coin type for Solana
+pub const SOLANA_COIN_TYPE: u32 = 501;
+
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+const ED25519_CURVE_SEED: &[u8] = b"ed25519 seed";
+
+/// A fully hardened derivation path; SLIP-0010 ed25519 only supports hardened children
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// Create a path from child indexes, without the hardened bit
+    pub fn new(indexes: Vec<u32>) -> Result<Self> {
+        if indexes.iter().any(|index| *index >= HARDENED_OFFSET) {
+            return Err(SolanaError::GenericError(
+                "derivation index out of range".to_string(),
+            ));
+        }
+        Ok(Self(indexes))
+    }
+
+    /// `m/44'/501'/{account}'/0'`, the path used by Phantom and Solflare.
+    ///
+    /// Fails if `account` is 2^31 or more.
+    pub fn solana(account: u32) -> Result<Self> {
+        Self::new(vec![44, SOLANA_COIN_TYPE, account, 0])
+    }
+
+    /// Get the child indexes, without the hardened bit
+    pub fn indexes(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = SolanaError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || SolanaError::GenericError(format!("invalid derivation path: {s}"));
+        let mut parts = s.split('/');
+        if parts.next() != Some("m") {
+            return Err(invalid());
+        }
+        let indexes = parts
+            .map(|part| {
+                part.strip_suffix('\'')
+                    .or_else(|| part.strip_suffix('h'))
+                    .ok_or_else(invalid)?
+                    .parse::<u32>()
+                    .map_err(|_| invalid())
+            })
+            .collect::<Result<Vec<u32>>>()?;
+        Self::new(indexes)
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            write!(f, "/{index}'")?;
+        }
+        Ok(())
+    }
+}
+
+/// Validate a BIP39 English mnemonic and compute its 64-byte seed
+pub fn mnemonic_to_seed(phrase: &str, passphrase: &str) -> Result<[u8; 64]> {
+    let mnemonic = bip39::Mnemonic::parse_normalized(phrase)
+        .map_err(|e| SolanaError::GenericError(format!("invalid mnemonic: {e}")))?;
+    Ok(mnemonic.to_seed_normalized(passphrase))
+}
+
+/// Derive the SLIP-0010 ed25519 secret key at `path` from a seed
+pub fn derive_secret_key(seed: &[u8], path: &DerivationPath) -> SecretKey {
+    let (mut key, mut chain_code) = hmac_sha512(ED25519_CURVE_SEED, &[seed]);
+    for index in path.indexes() {
+        let hardened = (index | HARDENED_OFFSET).to_be_bytes();
+        // Replacing the parent key and chain code drops and wipes them
+        (key, chain_code) = hmac_sha512(&*chain_code, &[&[0u8], &*key, &hardened]);
+    }
+    SecretKey::new(*key)
+}
+
+/// Derive the keypair at `path` from a seed
+pub fn derive_keypair(seed: &[u8], path: &DerivationPath) -> Keypair {
+    derive_secret_key(seed, path).to_keypair()
+}
+
+/// Derive the keypair for `account` from a mnemonic, as Phantom and Solflare do
+pub fn keypair_from_mnemonic(phrase: &str, passphrase: &str, account: u32) -> Result<Keypair> {
+    let seed = Zeroizing::new(mnemonic_to_seed(phrase, passphrase)?);
+    Ok(derive_keypair(&*seed, &DerivationPath::solana(account)?))
+}
+
+/// Split HMAC-SHA512 output into a key and a chain code
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> (Zeroizing<[u8; 32]>, Zeroizing<[u8; 32]>) {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for chunk in data {
+        mac.update(chunk);
+    }
+    let mut output = mac.finalize().into_bytes();
+    let mut left = Zeroizing::new([0u8; 32]);
+    let mut right = Zeroizing::new([0u8; 32]);
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..]);
+    output[..].zeroize();
+    (left, right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slip10_vector() {
+        // SLIP-0010 test vector 1 for ed25519
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let cases = [
+            (
+                "m",
+                "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+            ),
+            (
+                "m/0'",
+                "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+            ),
+            (
+                "m/0'/1'",
+                "b1d0bad404bf35da785a64ca1ac54b2617211d2777696fbffaf208f746ae84f2",
+            ),
+        ];
+        for (path, expected) in cases {
+            let path: DerivationPath = path.parse().unwrap();
+            assert_eq!(
+                hex::encode(derive_secret_key(&seed, &path).as_bytes()),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_mnemonic_to_seed() {
+        // BIP39 English test vector
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        assert_eq!(
+            hex::encode(mnemonic_to_seed(phrase, "TREZOR").unwrap()),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+        assert!(mnemonic_to_seed("abandon abandon abandon", "").is_err());
+
+        let first = keypair_from_mnemonic(phrase, "", 0).unwrap();
+        let second = keypair_from_mnemonic(phrase, "", 1).unwrap();
+        assert_ne!(first.pubkey(), second.pubkey());
+        assert!(keypair_from_mnemonic(phrase, "", HARDENED_OFFSET).is_err());
+    }
+
+    #[test]
+    fn test_derivation_path() {
+        let path = DerivationPath::solana(3).unwrap();
+        assert_eq!(path.to_string(), "m/44'/501'/3'/0'");
+        assert_eq!("m/44'/501'/3'/0'".parse::<DerivationPath>().unwrap(), path);
+        assert_eq!(
+            "m/44h/501h".parse::<DerivationPath>().unwrap().indexes(),
+            &[44, 501]
+        );
+        assert!("m/44'/501".parse::<DerivationPath>().is_err());
+        assert!("44'/501'".parse::<DerivationPath>().is_err());
+
+        assert!(DerivationPath::solana(HARDENED_OFFSET - 1).is_ok());
+        assert!(DerivationPath::solana(HARDENED_OFFSET).is_err());
+        assert!(DerivationPath::solana(HARDENED_OFFSET | 3).is_err());
+    }
+}
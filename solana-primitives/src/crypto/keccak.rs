@@ -0,0 +1,36 @@
+//! Keccak-256 as used by Ethereum and the Secp256k1 precompile.
+//!
+//! This is the original Keccak padding, not the NIST SHA3-256 variant.
+
+use sha3::{Digest, Keccak256};
+
+/// Hash data using Keccak-256
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::keccak256;
+
+    #[test]
+    fn test_keccak256_vectors() {
+        assert_eq!(
+            hex::encode(keccak256(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            hex::encode(keccak256(b"abc")),
+            "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"
+        );
+        // Exactly one rate-sized block, so padding spills into a second block
+        assert_eq!(
+            hex::encode(keccak256(&[0x61; 136])),
+            "a6c4d403279fe3e0af03729caada8374b5ca54d8065329a3ebcaeb4b60aa386e"
+        );
+        assert_eq!(
+            hex::encode(keccak256(&[0x61; 1000])),
+            "b6a4ac1f51884d71f30fa397a5e155de3099e11fc0edef5d08b646e621e19de9"
+        );
+    }
+}
@@ -0,0 +1,231 @@
+use crate::crypto::{SecretKey, get_public_key};
+use crate::error::{Result, SolanaError};
+use crate::types::{Pubkey, SignatureBytes};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use ed25519_dalek::{Signer, SigningKey};
+#[cfg(feature = "std")]
+use std::path::Path;
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// An ed25519 keypair, interoperable with `solana-keygen` files.
+///
+/// The secret key is wiped from memory when the keypair is dropped, and `Debug` only shows
+/// the public key.
+#[derive(Clone)]
+pub struct Keypair {
+    signing_key: SigningKey,
+}
+
+impl fmt::Debug for Keypair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keypair")
+            .field("pubkey", &self.pubkey())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Keypair {
+    fn eq(&self, other: &Self) -> bool {
+        // Constant-time comparison of the secret keys
+        self.signing_key == other.signing_key
+    }
+}
+
+impl Eq for Keypair {}
+
+impl Zeroize for Keypair {
+    fn zeroize(&mut self) {
+        self.signing_key = SigningKey::from_bytes(&[0u8; 32]);
+    }
+}
+
+impl ZeroizeOnDrop for Keypair {}
+
+impl Keypair {
+    /// Generate a new random keypair
+    #[cfg(feature = "std")]
+    pub fn generate() -> Result<Self> {
+        let mut secret = Zeroizing::new([0u8; 32]);
+        getrandom::fill(secret.as_mut())
+            .map_err(|e| SolanaError::GenericError(format!("failed to generate keypair: {e}")))?;
+        Ok(Self::from_secret_key(&secret))
+    }
+
+    /// Create a keypair from a 32-byte secret key
+    pub fn from_secret_key(secret: &[u8; 32]) -> Self {
+        Self {
+            signing_key: SigningKey::from_bytes(secret),
+        }
+    }
+
+    /// Derive a keypair from a seed of at least 32 bytes, using the first 32 as the secret key
+    pub fn from_seed(seed: &[u8]) -> Result<Self> {
+        let secret = seed.get(..32).ok_or_else(|| {
+            SolanaError::InvalidSignature(format!(
+                "seed too short: {}, expected at least 32 bytes",
+                seed.len()
+            ))
+        })?;
+        Ok(SecretKey::from_slice(secret)?.to_keypair())
+    }
+
+    /// Create a keypair from 64 bytes: the secret key followed by the public key
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 64 {
+            return Err(SolanaError::InvalidSignature(format!(
+                "invalid keypair length: {}, expected: 64",
+                bytes.len()
+            )));
+        }
+        if get_public_key(&bytes[..32])? != bytes[32..] {
+            return Err(SolanaError::InvalidSignature(
+                "public key does not match secret key".to_string(),
+            ));
+        }
+        Self::from_seed(&bytes[..32])
+    }
+
+    /// Get the 64-byte secret key followed by public key representation.
+    ///
+    /// The returned array is a plain copy of the secret; wrap it in [`Zeroizing`] or use
+    /// [`Keypair::secret_key`] to have it wiped.
+    pub fn to_bytes(&self) -> [u8; 64] {
+        self.signing_key.to_keypair_bytes()
+    }
+
+    /// Get the 32-byte secret key
+    pub fn secret_key(&self) -> SecretKey {
+        SecretKey::new(self.signing_key.to_bytes())
+    }
+
+    /// Get the public key
+    pub fn pubkey(&self) -> Pubkey {
+        Pubkey::new(self.signing_key.verifying_key().to_bytes())
+    }
+
+    /// Sign a message
+    pub fn sign_message(&self, message: &[u8]) -> SignatureBytes {
+        SignatureBytes::new(self.signing_key.sign(message).to_bytes())
+    }
+
+    /// Parse a base58-encoded 64-byte keypair, as exported by wallets
+    pub fn from_base58_string(s: &str) -> Result<Self> {
+        let bytes = Zeroizing::new(bs58::decode(s).into_vec().map_err(|_| {
+            SolanaError::InvalidSignature("failed to decode base58 keypair".to_string())
+        })?);
+        Self::from_bytes(&bytes)
+    }
+
+    /// Encode the 64-byte keypair as base58
+    pub fn to_base58_string(&self) -> String {
+        bs58::encode(Zeroizing::new(self.to_bytes())).into_string()
+    }
+
+    /// Parse the `solana-keygen` JSON format, a 64-element byte array
+    pub fn from_json(json: &str) -> Result<Self> {
+        let invalid = || SolanaError::DeserializationError("invalid keypair JSON".to_string());
+        let inner = json
+            .trim()
+            .strip_prefix('[')
+            .and_then(|s| s.strip_suffix(']'))
+            .ok_or_else(invalid)?;
+        let bytes = Zeroizing::new(
+            inner
+                .split(',')
+                .map(|byte| byte.trim().parse::<u8>().map_err(|_| invalid()))
+                .collect::<Result<Vec<u8>>>()?,
+        );
+        Self::from_bytes(&bytes)
+    }
+
+    /// Encode in the `solana-keygen` JSON format
+    pub fn to_json(&self) -> String {
+        let bytes: Vec<String> = Zeroizing::new(self.to_bytes())
+            .iter()
+            .map(u8::to_string)
+            .collect();
+        format!("[{}]", bytes.join(","))
+    }
+
+    /// Read a `solana-keygen` keypair file such as `~/.config/solana/id.json`
+    #[cfg(feature = "std")]
+    pub fn read_from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let json =
+            Zeroizing::new(std::fs::read_to_string(path).map_err(|e| {
+                SolanaError::GenericError(format!("failed to read keypair file: {e}"))
+            })?);
+        Self::from_json(&json)
+    }
+
+    /// Write a `solana-keygen` keypair file, readable only by the owner on Unix
+    #[cfg(feature = "std")]
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let mut file = options
+            .open(path)
+            .map_err(|e| SolanaError::GenericError(format!("failed to write keypair file: {e}")))?;
+        std::io::Write::write_all(&mut file, Zeroizing::new(self.to_json()).as_bytes())
+            .map_err(|e| SolanaError::GenericError(format!("failed to write keypair file: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::sign_message;
+
+    #[test]
+    fn test_keypair_roundtrips() {
+        let keypair = Keypair::from_secret_key(&[7u8; 32]);
+        assert_eq!(
+            keypair.pubkey().as_bytes(),
+            &get_public_key(&[7u8; 32]).unwrap()
+        );
+
+        assert_eq!(Keypair::from_bytes(&keypair.to_bytes()).unwrap(), keypair);
+        assert_eq!(Keypair::from_json(&keypair.to_json()).unwrap(), keypair);
+        assert_eq!(
+            Keypair::from_base58_string(&keypair.to_base58_string()).unwrap(),
+            keypair
+        );
+        assert_eq!(Keypair::from_seed(&[7u8; 40]).unwrap(), keypair);
+        assert!(!format!("{keypair:?}").contains("signing_key"));
+
+        let mut wiped = keypair.clone();
+        wiped.zeroize();
+        assert_eq!(wiped.secret_key().as_bytes(), &[0u8; 32]);
+        assert_eq!(keypair.secret_key().as_bytes(), &[7u8; 32]);
+    }
+
+    #[test]
+    fn test_keypair_rejects_mismatched_public_key() {
+        let mut bytes = Keypair::from_secret_key(&[7u8; 32]).to_bytes();
+        bytes[63] ^= 1;
+        assert!(Keypair::from_bytes(&bytes).is_err());
+        assert!(Keypair::from_json("[1,2,3]").is_err());
+        assert!(Keypair::from_json("not json").is_err());
+        assert!(Keypair::from_seed(&[0u8; 31]).is_err());
+    }
+
+    #[test]
+    fn test_keypair_file_and_signing() {
+        let keypair = Keypair::generate().unwrap();
+        let path = std::env::temp_dir().join(format!("keypair-{}.json", std::process::id()));
+        keypair.write_to_file(&path).unwrap();
+        let loaded = Keypair::read_from_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, keypair);
+
+        assert_eq!(
+            loaded.sign_message(b"hello"),
+            sign_message(keypair.secret_key().as_bytes(), b"hello").unwrap()
+        );
+    }
+}
@@ -1,6 +1,28 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+
+#[cfg(feature = "blake3")]
+mod blake3;
+#[cfg(feature = "hd")]
+pub mod hd;
+#[cfg(feature = "keccak")]
+mod keccak;
+mod keypair;
+pub mod secp256k1;
+mod secret_key;
+mod signer;
+
+#[cfg(feature = "blake3")]
+pub use blake3::blake3;
+#[cfg(feature = "keccak")]
+pub use keccak::keccak256;
+pub use keypair::Keypair;
+pub use secret_key::SecretKey;
+pub use signer::Signer;
+
 use crate::error::{Result, SolanaError};
-use crate::types::{Pubkey, SignatureBytes, Transaction};
-use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+use crate::types::{Pubkey, SignatureBytes, Transaction, VersionedTransaction};
+use ed25519_dalek::{Signer as _, SigningKey, Verifier, VerifyingKey};
 use sha2::{Digest, Sha256};
 
 /// Get the public key from a private key
@@ -43,51 +65,76 @@ pub fn get_address_from_public_key(public_key: &[u8]) -> Result<String> {
 
 /// Verify that a transaction's signatures are valid
 pub fn verify_transaction(transaction: &Transaction) -> Result<()> {
-    let required = transaction.message.header.num_required_signatures as usize;
-    if transaction.signatures.len() != required {
-        return Err(SolanaError::InvalidSignature(format!(
-            "signature count mismatch: found {}, required {}",
-            transaction.signatures.len(),
-            required
-        )));
-    }
-
-    // Get the message bytes that were signed
     let message_bytes = transaction
         .message
         .serialize_for_signing()
         .map_err(SolanaError::SerializationError)?;
+    verify_signers(
+        &transaction.signatures,
+        &transaction.message.account_keys,
+        transaction.message.header.num_required_signatures as usize,
+        &message_bytes,
+    )
+}
 
-    for (i, signature) in transaction.signatures.iter().enumerate() {
-        let signer_pubkey = &transaction.message.account_keys[i];
-        let verifying_key = VerifyingKey::from_bytes(signer_pubkey.as_bytes()).map_err(|_| {
-            SolanaError::InvalidPubkey("failed to create verifying key from pubkey".to_string())
-        })?;
+/// Verify that a legacy or V0 transaction's signatures are valid.
+///
+/// Signers are always static account keys, so V0 transactions verify without their address
+/// lookup tables.
+pub fn verify_versioned_transaction(transaction: &VersionedTransaction) -> Result<()> {
+    transaction.verify_signatures()
+}
 
-        // Convert our SignatureBytes to the ed25519_dalek Signature type
-        let sig_bytes = signature.as_bytes();
-        if sig_bytes.len() != 64 {
+/// Check there is one signature per required signer and each signs the wire-format message
+pub(crate) fn verify_signers(
+    signatures: &[SignatureBytes],
+    account_keys: &[Pubkey],
+    num_required_signatures: usize,
+    message_bytes: &[u8],
+) -> Result<()> {
+    if signatures.len() != num_required_signatures {
+        return Err(SolanaError::InvalidSignature(format!(
+            "signature count mismatch: found {}, required {}",
+            signatures.len(),
+            num_required_signatures
+        )));
+    }
+
+    for (index, signature) in signatures.iter().enumerate() {
+        let signer = account_keys.get(index).ok_or(SolanaError::InvalidMessage)?;
+        if !verify_signature_strict(signer, message_bytes, signature) {
             return Err(SolanaError::InvalidSignature(format!(
-                "invalid signature length: {}, expected: 64",
-                sig_bytes.len()
+                "signature verification failed for {signer}"
             )));
         }
-
-        let mut sig_array = [0u8; 64];
-        sig_array.copy_from_slice(sig_bytes);
-
-        let dalek_signature = ed25519_dalek::Signature::from_bytes(&sig_array);
-
-        verifying_key
-            .verify(&message_bytes, &dalek_signature)
-            .map_err(|_| {
-                SolanaError::InvalidSignature("signature verification failed".to_string())
-            })?;
     }
 
     Ok(())
 }
 
+/// Check whether `signature` is a valid signature of `message` by `public_key`
+pub fn verify_signature(public_key: &Pubkey, message: &[u8], signature: &SignatureBytes) -> bool {
+    let Ok(verifying_key) = VerifyingKey::from_bytes(public_key.as_bytes()) else {
+        return false;
+    };
+    let signature = ed25519_dalek::Signature::from_bytes(signature.as_bytes());
+    verifying_key.verify(message, &signature).is_ok()
+}
+
+/// Like [`verify_signature`], but also reject small-order public keys and signature points,
+/// as the runtime does when verifying transaction signatures
+pub fn verify_signature_strict(
+    public_key: &Pubkey,
+    message: &[u8],
+    signature: &SignatureBytes,
+) -> bool {
+    let Ok(verifying_key) = VerifyingKey::from_bytes(public_key.as_bytes()) else {
+        return false;
+    };
+    let signature = ed25519_dalek::Signature::from_bytes(signature.as_bytes());
+    verifying_key.verify_strict(message, &signature).is_ok()
+}
+
 /// Sign a message with a private key
 pub fn sign_message(private_key: &[u8], message: &[u8]) -> Result<SignatureBytes> {
     if private_key.len() != 32 {
@@ -143,14 +190,43 @@ mod tests {
 
     #[test]
     fn verify_transaction_accepts_properly_signed_transaction() {
-        let private_key = [1u8; 32];
-        let public_key = get_public_key(&private_key).expect("valid key");
-        let signer = Pubkey::new(public_key);
+        let keypair = Keypair::from_secret_key(&[1u8; 32]);
 
-        let mut transaction = Transaction::new(build_message(signer));
-        transaction.sign(&[&private_key]).expect("sign succeeds");
+        let mut transaction = Transaction::new(build_message(keypair.pubkey()));
+        transaction.sign(&[&keypair]).expect("sign succeeds");
 
         let result = verify_transaction(&transaction);
         assert!(result.is_ok());
+
+        // Signatures cover the wire-format message, so they survive a roundtrip
+        let bytes = transaction.serialize_legacy().unwrap();
+        verify_transaction(&Transaction::deserialize_with_version(&bytes).unwrap()).unwrap();
+        let versioned = VersionedTransaction::deserialize_with_version(&bytes).unwrap();
+        verify_versioned_transaction(&versioned).unwrap();
+
+        transaction.message.recent_blockhash = [1u8; 32].into();
+        assert!(verify_transaction(&transaction).is_err());
+    }
+
+    #[test]
+    fn verify_strict_rejects_small_order_keys() {
+        let keypair = Keypair::from_secret_key(&[1u8; 32]);
+        let signature = keypair.sign_message(b"hello");
+        assert!(signature.verify(&keypair.pubkey(), b"hello"));
+        assert!(keypair.pubkey().verify_strict(b"hello", &signature));
+        assert!(!signature.verify(&keypair.pubkey(), b"hullo"));
+        assert!(!signature.verify(&Pubkey::new([2u8; 32]), b"hello"));
+
+        // The identity point as both key and R with s = 0 satisfies the verification
+        // equation for any message
+        let mut identity = [0u8; 32];
+        identity[0] = 1;
+        let weak_key = Pubkey::new(identity);
+        let mut forged = [0u8; 64];
+        forged[..32].copy_from_slice(&identity);
+        let forged = SignatureBytes::new(forged);
+        assert!(verify_signature(&weak_key, b"anything", &forged));
+        assert!(!verify_signature_strict(&weak_key, b"anything", &forged));
+        assert!(!forged.verify(&weak_key, b"anything"));
     }
 }
@@ -0,0 +1,183 @@
+//! secp256k1 ECDSA with public key recovery, matching the Secp256k1 precompile.
+//!
+//! The precompile hashes the message with Keccak-256 and compares the recovered signer's
+//! Ethereum address, so `sign_message` and `ecrecover` do the same; the `_prehashed`
+//! variants take the 32-byte digest directly.
+//!
+//! The curve arithmetic comes from the `k256` crate and is only available with the
+//! `secp256k1` feature; the length constants are always available for building precompile
+//! instructions.
+
+#[cfg(feature = "secp256k1")]
+use crate::crypto::keccak256;
+#[cfg(feature = "secp256k1")]
+use crate::error::{Result, SolanaError};
+#[cfg(feature = "secp256k1")]
+use alloc::format;
+#[cfg(feature = "secp256k1")]
+use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+
+/// Length of an uncompressed public key without its `0x04` prefix
+pub const PUBLIC_KEY_LENGTH: usize = 64;
+/// Length of a compact signature, `r` followed by `s`
+pub const SIGNATURE_LENGTH: usize = 64;
+/// Length of an Ethereum address
+pub const ETH_ADDRESS_LENGTH: usize = 20;
+
+#[cfg(feature = "secp256k1")]
+fn invalid(reason: &str) -> SolanaError {
+    SolanaError::InvalidSignature(format!("secp256k1: {reason}"))
+}
+
+#[cfg(feature = "secp256k1")]
+fn signing_key(secret_key: &[u8; 32]) -> Result<SigningKey> {
+    SigningKey::from_bytes(secret_key.into()).map_err(|_| invalid("secret key out of range"))
+}
+
+#[cfg(feature = "secp256k1")]
+fn encode_public_key(key: &VerifyingKey) -> [u8; PUBLIC_KEY_LENGTH] {
+    let point = key.to_encoded_point(false);
+    let mut public_key = [0u8; PUBLIC_KEY_LENGTH];
+    public_key.copy_from_slice(&point.as_bytes()[1..]);
+    public_key
+}
+
+/// Uncompressed public key of `secret_key`, without the `0x04` prefix
+#[cfg(feature = "secp256k1")]
+pub fn public_key(secret_key: &[u8; 32]) -> Result<[u8; PUBLIC_KEY_LENGTH]> {
+    Ok(encode_public_key(signing_key(secret_key)?.verifying_key()))
+}
+
+/// Ethereum address of an uncompressed public key, given without its `0x04` prefix
+#[cfg(feature = "secp256k1")]
+pub fn eth_address(public_key: &[u8; PUBLIC_KEY_LENGTH]) -> [u8; ETH_ADDRESS_LENGTH] {
+    let hash = keccak256(public_key);
+    let mut address = [0u8; ETH_ADDRESS_LENGTH];
+    address.copy_from_slice(&hash[32 - ETH_ADDRESS_LENGTH..]);
+    address
+}
+
+/// Sign a 32-byte digest with an RFC 6979 nonce, returning the low-s signature and its
+/// recovery id
+#[cfg(feature = "secp256k1")]
+pub fn sign_prehashed(
+    secret_key: &[u8; 32],
+    digest: &[u8; 32],
+) -> Result<([u8; SIGNATURE_LENGTH], u8)> {
+    let (signature, recovery_id) = signing_key(secret_key)?
+        .sign_prehash_recoverable(digest)
+        .map_err(|_| invalid("signing failed"))?;
+    let mut bytes = [0u8; SIGNATURE_LENGTH];
+    bytes.copy_from_slice(&signature.to_bytes());
+    Ok((bytes, recovery_id.to_byte()))
+}
+
+/// Sign `message` as the precompile verifies it, over its Keccak-256 hash
+#[cfg(feature = "secp256k1")]
+pub fn sign_message(secret_key: &[u8; 32], message: &[u8]) -> Result<([u8; SIGNATURE_LENGTH], u8)> {
+    sign_prehashed(secret_key, &keccak256(message))
+}
+
+/// Recover the public key that signed a 32-byte digest.
+///
+/// `recovery_id` is 0 to 3 as in libsecp256k1; ids 2 and 3 mark an `r` that overflowed
+/// the curve order.
+#[cfg(feature = "secp256k1")]
+pub fn recover_prehashed(
+    digest: &[u8; 32],
+    signature: &[u8; SIGNATURE_LENGTH],
+    recovery_id: u8,
+) -> Result<[u8; PUBLIC_KEY_LENGTH]> {
+    let recovery_id =
+        RecoveryId::from_byte(recovery_id).ok_or_else(|| invalid("recovery id must be 0 to 3"))?;
+    let signature =
+        Signature::from_slice(signature).map_err(|_| invalid("signature scalar out of range"))?;
+    let key = VerifyingKey::recover_from_prehash(digest, &signature, recovery_id)
+        .map_err(|_| invalid("no public key recovers from this signature"))?;
+    Ok(encode_public_key(&key))
+}
+
+/// Recover the Ethereum address that signed `message`, as the precompile does
+#[cfg(feature = "secp256k1")]
+pub fn ecrecover(
+    message: &[u8],
+    signature: &[u8; SIGNATURE_LENGTH],
+    recovery_id: u8,
+) -> Result<[u8; ETH_ADDRESS_LENGTH]> {
+    recover_prehashed(&keccak256(message), signature, recovery_id).map(|key| eth_address(&key))
+}
+
+#[cfg(all(test, feature = "secp256k1"))]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    fn secret(value: u8) -> [u8; 32] {
+        let mut secret = [0u8; 32];
+        secret[31] = value;
+        secret
+    }
+
+    #[test]
+    fn test_public_key_and_address() {
+        let public_key = public_key(&secret(1)).unwrap();
+        assert_eq!(
+            hex::encode(public_key),
+            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+             483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"
+        );
+        assert_eq!(
+            hex::encode(eth_address(&public_key)),
+            "7e5f4552091a69125d5dfcb7b8c2659029395bdf"
+        );
+        assert_eq!(
+            hex::encode(eth_address(&super::public_key(&secret(2)).unwrap())),
+            "2b5ad5c4795c026514f8317c7a215e218dccd6cf"
+        );
+        // Zero and the curve order are not valid secret keys
+        let order: [u8; 32] =
+            hex::decode("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        assert!(super::public_key(&[0u8; 32]).is_err());
+        assert!(super::public_key(&order).is_err());
+    }
+
+    #[test]
+    fn test_sign_rfc6979_vector() {
+        let digest: [u8; 32] = Sha256::digest(b"Satoshi Nakamoto").into();
+        let (signature, recovery_id) = sign_prehashed(&secret(1), &digest).unwrap();
+        assert_eq!(
+            hex::encode(signature),
+            "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8\
+             2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5"
+        );
+        assert_eq!(
+            recover_prehashed(&digest, &signature, recovery_id).unwrap(),
+            public_key(&secret(1)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sign_and_ecrecover_round_trip() {
+        let secret_key = [0x42u8; 32];
+        let address = eth_address(&public_key(&secret_key).unwrap());
+        for message in [&b""[..], b"wormhole vaa body", &[7u8; 300]] {
+            let (signature, recovery_id) = sign_message(&secret_key, message).unwrap();
+            assert!(recovery_id < 2);
+            assert_eq!(
+                ecrecover(message, &signature, recovery_id).unwrap(),
+                address
+            );
+            assert_ne!(
+                ecrecover(message, &signature, recovery_id ^ 1).unwrap(),
+                address
+            );
+        }
+
+        let (signature, _) = sign_message(&secret_key, b"x").unwrap();
+        assert!(ecrecover(b"x", &signature, 4).is_err());
+        assert!(ecrecover(b"x", &[0u8; 64], 0).is_err());
+    }
+}
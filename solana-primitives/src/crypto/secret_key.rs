@@ -0,0 +1,91 @@
+use crate::crypto::Keypair;
+use crate::error::{Result, SolanaError};
+use crate::types::Pubkey;
+use alloc::format;
+use core::fmt;
+use ed25519_dalek::SigningKey;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// A 32-byte ed25519 secret key that is wiped from memory when dropped.
+///
+/// It is deliberately neither `Clone` nor `Copy`, so the secret is never duplicated
+/// implicitly, and its `Debug` output never shows the bytes.
+pub struct SecretKey([u8; 32]);
+
+impl SecretKey {
+    /// Take ownership of `bytes`; the caller's copy, if any, is not wiped
+    pub fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Copy a secret key from a 32-byte slice
+    pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+        let bytes: [u8; 32] = bytes.try_into().map_err(|_| {
+            SolanaError::InvalidSignature(format!(
+                "invalid private key length: {}, expected: 32",
+                bytes.len()
+            ))
+        })?;
+        Ok(Self(bytes))
+    }
+
+    /// Borrow the secret bytes
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Get the public key
+    pub fn pubkey(&self) -> Pubkey {
+        Pubkey::new(SigningKey::from_bytes(&self.0).verifying_key().to_bytes())
+    }
+
+    /// Build the keypair for this secret key
+    pub fn to_keypair(&self) -> Keypair {
+        Keypair::from_secret_key(&self.0)
+    }
+}
+
+impl From<[u8; 32]> for SecretKey {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretKey(<redacted>)")
+    }
+}
+
+impl Zeroize for SecretKey {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretKey {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_key() {
+        let secret = SecretKey::new([7u8; 32]);
+        let keypair = secret.to_keypair();
+        assert_eq!(secret.pubkey(), keypair.pubkey());
+        assert_eq!(keypair.secret_key().as_bytes(), secret.as_bytes());
+        assert_eq!(format!("{secret:?}"), "SecretKey(<redacted>)");
+
+        assert!(SecretKey::from_slice(&[7u8; 31]).is_err());
+        let mut secret = SecretKey::from_slice(&[7u8; 32]).unwrap();
+        secret.zeroize();
+        assert_eq!(secret.as_bytes(), &[0u8; 32]);
+    }
+}
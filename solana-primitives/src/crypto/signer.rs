@@ -0,0 +1,47 @@
+use crate::crypto::Keypair;
+use crate::error::Result;
+use crate::types::{Pubkey, SignatureBytes};
+use alloc::boxed::Box;
+
+/// Anything that can produce ed25519 signatures for a public key.
+///
+/// Implement it for hardware wallets, HSMs or remote signers so they can sign transactions
+/// without handing out private keys. Signing is fallible because such devices can refuse or
+/// disconnect.
+pub trait Signer {
+    /// Public key the signatures verify against
+    fn pubkey(&self) -> Pubkey;
+
+    /// Sign a serialized message
+    fn try_sign_message(&self, message: &[u8]) -> Result<SignatureBytes>;
+}
+
+impl Signer for Keypair {
+    fn pubkey(&self) -> Pubkey {
+        Keypair::pubkey(self)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<SignatureBytes> {
+        Ok(Keypair::sign_message(self, message))
+    }
+}
+
+impl<T: Signer + ?Sized> Signer for &T {
+    fn pubkey(&self) -> Pubkey {
+        (**self).pubkey()
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<SignatureBytes> {
+        (**self).try_sign_message(message)
+    }
+}
+
+impl<T: Signer + ?Sized> Signer for Box<T> {
+    fn pubkey(&self) -> Pubkey {
+        (**self).pubkey()
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<SignatureBytes> {
+        (**self).try_sign_message(message)
+    }
+}
@@ -0,0 +1,178 @@
+//! Bounds-checked little-endian fields for patching and decoding known instruction layouts.
+
+use crate::error::{Result, SolanaError};
+use crate::types::Pubkey;
+use alloc::format;
+use core::marker::PhantomData;
+
+/// A fixed-size value stored little-endian
+pub trait LittleEndian: Copy {
+    /// Encoded size in bytes
+    const SIZE: usize;
+
+    /// Decode from exactly [`Self::SIZE`] bytes
+    fn read_le(bytes: &[u8]) -> Self;
+
+    /// Encode into exactly [`Self::SIZE`] bytes
+    fn write_le(self, bytes: &mut [u8]);
+}
+
+macro_rules! impl_little_endian {
+    ($($ty:ty),*) => {
+        $(
+            impl LittleEndian for $ty {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+
+                fn read_le(bytes: &[u8]) -> Self {
+                    let mut buf = [0u8; core::mem::size_of::<$ty>()];
+                    buf.copy_from_slice(bytes);
+                    <$ty>::from_le_bytes(buf)
+                }
+
+                fn write_le(self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_little_endian!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+/// A typed little-endian value at a fixed byte offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field<T> {
+    offset: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: LittleEndian> Field<T> {
+    /// Describe a field starting at `offset`
+    pub const fn at(offset: usize) -> Self {
+        Self {
+            offset,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Byte offset of the field
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Byte offset just past the field
+    pub const fn end(&self) -> usize {
+        self.offset.saturating_add(T::SIZE)
+    }
+
+    /// Read the field, or `None` when `data` is too short
+    pub fn read(&self, data: &[u8]) -> Option<T> {
+        data.get(self.offset..self.end()).map(T::read_le)
+    }
+
+    /// Overwrite the field in place
+    pub fn write(&self, data: &mut [u8], value: T) -> Result<()> {
+        let len = data.len();
+        let bytes = data.get_mut(self.offset..self.end()).ok_or_else(|| {
+            SolanaError::SerializationError(format!(
+                "field at {}..{} out of bounds for {} bytes",
+                self.offset,
+                self.end(),
+                len
+            ))
+        })?;
+        value.write_le(bytes);
+        Ok(())
+    }
+}
+
+/// Sequential little-endian reader over instruction or account data
+#[derive(Debug, Clone)]
+pub struct DataReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> DataReader<'a> {
+    /// Start reading at the beginning of `data`
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    /// Bytes consumed so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Bytes not yet consumed
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.data[self.offset..]
+    }
+
+    /// Consume the next `len` bytes
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .offset
+            .checked_add(len)
+            .and_then(|end| self.data.get(self.offset..end))
+            .ok_or_else(|| {
+                SolanaError::DeserializationError(format!(
+                    "need {len} bytes at offset {}, have {}",
+                    self.offset,
+                    self.data.len().saturating_sub(self.offset)
+                ))
+            })?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    /// Consume a little-endian value
+    pub fn read<T: LittleEndian>(&mut self) -> Result<T> {
+        self.read_bytes(T::SIZE).map(T::read_le)
+    }
+
+    /// Consume a 32-byte pubkey
+    pub fn read_pubkey(&mut self) -> Result<Pubkey> {
+        let bytes = self.read_bytes(32)?;
+        Ok(Pubkey::new(bytes.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_read_write() {
+        const AMOUNT: Field<u64> = Field::at(1);
+        const FLAG: Field<u8> = Field::at(9);
+
+        let mut data = [0u8; 10];
+        AMOUNT.write(&mut data, 0x0102_0304_0506_0708).unwrap();
+        FLAG.write(&mut data, 7).unwrap();
+
+        assert_eq!(data, [0, 8, 7, 6, 5, 4, 3, 2, 1, 7]);
+        assert_eq!(AMOUNT.read(&data), Some(0x0102_0304_0506_0708));
+        assert_eq!(AMOUNT.end(), 9);
+
+        assert_eq!(AMOUNT.read(&data[..8]), None);
+        assert!(AMOUNT.write(&mut data[..8], 1).is_err());
+        assert!(Field::<i32>::at(usize::MAX).read(&data).is_none());
+    }
+
+    #[test]
+    fn test_data_reader() {
+        let mut data = vec![7u8];
+        data.extend_from_slice(&500u64.to_le_bytes());
+        data.extend_from_slice(&[3u8; 32]);
+
+        let mut reader = DataReader::new(&data);
+        assert_eq!(reader.read::<u8>().unwrap(), 7);
+        assert_eq!(reader.read::<u64>().unwrap(), 500);
+        assert_eq!(reader.read_pubkey().unwrap(), Pubkey::new([3u8; 32]));
+        assert_eq!(reader.offset(), 41);
+        assert!(reader.remaining().is_empty());
+        assert!(reader.read::<u8>().is_err());
+        assert!(reader.read_bytes(usize::MAX).is_err());
+    }
+}
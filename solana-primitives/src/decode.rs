@@ -0,0 +1,174 @@
+//! Decoding of instruction data for well-known programs.
+
+use crate::error::Result;
+use crate::instructions::associated_token::AssociatedTokenInstruction;
+use crate::instructions::compute_budget::ComputeBudgetInstruction;
+use crate::instructions::memo::parse_memo;
+use crate::instructions::program_ids::{
+    ASSOCIATED_TOKEN_PROGRAM_ID, COMPUTE_BUDGET_PROGRAM_ID, MEMO_PROGRAM_ID, MEMO_V1_PROGRAM_ID,
+    STAKE_PROGRAM_ID, SYSTEM_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+use crate::instructions::stake::StakeInstruction;
+use crate::instructions::system::SystemInstruction;
+use crate::instructions::token::TokenInstruction;
+use crate::types::Pubkey;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Parses the data of one program's instructions
+pub type InstructionParser = fn(&[u8]) -> Result<ParsedInstruction>;
+
+/// Programs with a built-in parser, keyed by program ID
+pub const KNOWN_PROGRAMS: [(&str, InstructionParser); 8] = [
+    (SYSTEM_PROGRAM_ID, |data| {
+        SystemInstruction::deserialize(data).map(ParsedInstruction::System)
+    }),
+    (TOKEN_PROGRAM_ID, |data| {
+        TokenInstruction::deserialize(data).map(ParsedInstruction::Token)
+    }),
+    (TOKEN_2022_PROGRAM_ID, |data| {
+        TokenInstruction::deserialize(data).map(ParsedInstruction::Token2022)
+    }),
+    (ASSOCIATED_TOKEN_PROGRAM_ID, |data| {
+        AssociatedTokenInstruction::deserialize(data).map(ParsedInstruction::AssociatedToken)
+    }),
+    (MEMO_PROGRAM_ID, |data| {
+        parse_memo(data).map(ParsedInstruction::Memo)
+    }),
+    (MEMO_V1_PROGRAM_ID, |data| {
+        parse_memo(data).map(ParsedInstruction::Memo)
+    }),
+    (COMPUTE_BUDGET_PROGRAM_ID, |data| {
+        ComputeBudgetInstruction::deserialize(data).map(ParsedInstruction::ComputeBudget)
+    }),
+    (STAKE_PROGRAM_ID, |data| {
+        StakeInstruction::deserialize(data).map(ParsedInstruction::Stake)
+    }),
+];
+
+/// An instruction decoded into named fields
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsedInstruction {
+    /// System program instruction
+    System(SystemInstruction),
+    /// SPL Token instruction
+    Token(TokenInstruction),
+    /// Token-2022 instruction shared with SPL Token
+    Token2022(TokenInstruction),
+    /// Associated Token Account program instruction
+    AssociatedToken(AssociatedTokenInstruction),
+    /// Memo text
+    Memo(String),
+    /// Compute budget instruction
+    ComputeBudget(ComputeBudgetInstruction),
+    /// Stake program instruction
+    Stake(StakeInstruction),
+    /// Instruction of an unknown program, or one its parser could not decode
+    Unknown {
+        /// The invoked program
+        program_id: Pubkey,
+        /// Raw instruction data
+        data: Vec<u8>,
+    },
+}
+
+impl ParsedInstruction {
+    /// Short name of the program, or `"unknown"`
+    pub fn program_name(&self) -> &'static str {
+        match self {
+            Self::System(_) => "system",
+            Self::Token(_) => "spl-token",
+            Self::Token2022(_) => "spl-token-2022",
+            Self::AssociatedToken(_) => "spl-associated-token-account",
+            Self::Memo(_) => "spl-memo",
+            Self::ComputeBudget(_) => "compute-budget",
+            Self::Stake(_) => "stake",
+            Self::Unknown { .. } => "unknown",
+        }
+    }
+
+    /// Whether the instruction was decoded by a known parser
+    pub fn is_known(&self) -> bool {
+        !matches!(self, Self::Unknown { .. })
+    }
+}
+
+impl fmt::Display for ParsedInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.program_name();
+        match self {
+            Self::System(ix) => write!(f, "{name}: {ix:?}"),
+            Self::Token(ix) | Self::Token2022(ix) => write!(f, "{name}: {ix:?}"),
+            Self::AssociatedToken(ix) => write!(f, "{name}: {ix:?}"),
+            Self::Memo(text) => write!(f, "{name}: {text:?}"),
+            Self::ComputeBudget(ix) => write!(f, "{name}: {ix:?}"),
+            Self::Stake(ix) => write!(f, "{name}: {ix:?}"),
+            Self::Unknown { program_id, data } => {
+                write!(f, "{program_id}: 0x{}", hex::encode(data))
+            }
+        }
+    }
+}
+
+/// Find the built-in parser for `program_id`
+pub fn parser_for(program_id: &Pubkey) -> Option<InstructionParser> {
+    let program_id = program_id.to_base58();
+    KNOWN_PROGRAMS
+        .iter()
+        .find(|(id, _)| *id == program_id)
+        .map(|(_, parser)| *parser)
+}
+
+/// Decode instruction data, falling back to [`ParsedInstruction::Unknown`]
+pub fn decode_instruction(program_id: &Pubkey, data: &[u8]) -> ParsedInstruction {
+    parser_for(program_id)
+        .and_then(|parser| parser(data).ok())
+        .unwrap_or_else(|| ParsedInstruction::Unknown {
+            program_id: *program_id,
+            data: data.to_vec(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::compute_budget::set_compute_unit_price;
+    use crate::instructions::memo::memo;
+    use crate::instructions::system::transfer;
+    use crate::types::MicroLamports;
+
+    #[test]
+    fn test_decode_instruction() {
+        let from = Pubkey::new([1u8; 32]);
+        let to = Pubkey::new([2u8; 32]);
+
+        let ix = transfer(&from, &to, 5);
+        let parsed = decode_instruction(&ix.program_id, &ix.data);
+        assert_eq!(
+            parsed,
+            ParsedInstruction::System(SystemInstruction::Transfer { lamports: 5 })
+        );
+        assert_eq!(parsed.to_string(), "system: Transfer { lamports: 5 }");
+
+        let ix = memo("hello", &[&from]);
+        assert_eq!(
+            decode_instruction(&ix.program_id, &ix.data),
+            ParsedInstruction::Memo("hello".to_string())
+        );
+
+        let ix = set_compute_unit_price(MicroLamports(10));
+        assert_eq!(
+            decode_instruction(&ix.program_id, &ix.data),
+            ParsedInstruction::ComputeBudget(ComputeBudgetInstruction::SetComputeUnitPrice {
+                micro_lamports: 10
+            })
+        );
+
+        // Unknown programs and undecodable data fall back to raw bytes
+        let parsed = decode_instruction(&to, &[1, 2]);
+        assert!(!parsed.is_known());
+        assert_eq!(parsed.to_string(), format!("{to}: 0x0102"));
+        assert!(!decode_instruction(&ix.program_id, &[9]).is_known());
+    }
+}
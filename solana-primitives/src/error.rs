@@ -1,3 +1,4 @@
+use alloc::string::{String, ToString};
 use thiserror::Error;
 
 /// A custom error type for Solana operations
@@ -17,6 +18,12 @@ pub enum SolanaError {
     SerializationError(String),
     #[error("Deserialization error: {0}")]
     DeserializationError(String),
+    #[error("Deserialization error: {0}")]
+    WireError(#[from] crate::wire::WireError),
+    #[error("Sanitize error: {0}")]
+    SanitizeError(#[from] crate::types::SanitizeError),
+    #[error("Transaction error: {0}")]
+    TransactionError(#[from] crate::types::TransactionError),
     #[error("{0}")]
     GenericError(String),
 }
@@ -34,4 +41,4 @@ impl From<String> for SolanaError {
 }
 
 /// A type alias for Result with SolanaError
-pub type Result<T> = std::result::Result<T, SolanaError>;
+pub type Result<T> = core::result::Result<T, SolanaError>;
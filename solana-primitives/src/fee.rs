@@ -0,0 +1,296 @@
+//! Transaction fee calculation.
+//!
+//! The fee is `lamports_per_signature` for every signature the runtime verifies, including
+//! those checked by the Ed25519 and Secp256k1 precompiles, plus the priority fee requested
+//! through the Compute Budget program.
+
+use crate::instructions::compute_budget::{
+    parse_compute_unit_limit_data, parse_compute_unit_price_data,
+};
+use crate::instructions::program_ids::{
+    compute_budget_program, ed25519_program, secp256k1_program,
+};
+use crate::types::{CompiledInstruction, ComputeUnits, MicroLamports, PrioritizationFee, Pubkey};
+use alloc::vec::Vec;
+
+/// Fee a transaction pays, split by source
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeDetails {
+    /// Signatures verified by the runtime
+    pub num_signatures: u64,
+    /// Lamports charged for signatures
+    pub signature_fee: u64,
+    /// Requested compute unit price
+    pub compute_unit_price: MicroLamports,
+    /// Compute unit limit the priority fee is charged on
+    pub compute_unit_limit: ComputeUnits,
+    /// Lamports charged for priority
+    pub prioritization_fee: u64,
+}
+
+impl FeeDetails {
+    /// Total fee in lamports
+    pub fn total(&self) -> u64 {
+        self.signature_fee.saturating_add(self.prioritization_fee)
+    }
+}
+
+/// Calculate the fee for a compiled message.
+///
+/// Without a `SetComputeUnitLimit` instruction, the limit defaults to
+/// [`ComputeUnits::DEFAULT_PER_INSTRUCTION`] for every non-Compute-Budget instruction,
+/// capped at [`ComputeUnits::MAX`].
+pub fn calculate_fee(
+    account_keys: &[Pubkey],
+    num_required_signatures: u8,
+    instructions: &[CompiledInstruction],
+    lamports_per_signature: u64,
+) -> FeeDetails {
+    let compute_budget_program_id = compute_budget_program();
+    let precompiles = [ed25519_program(), secp256k1_program()];
+
+    let mut num_signatures = num_required_signatures as u64;
+    let mut compute_unit_price = None;
+    let mut compute_unit_limit = None;
+    let mut num_non_compute_budget = 0u32;
+    for instruction in instructions {
+        let program_id = account_keys.get(instruction.program_id_index as usize);
+        if program_id == Some(&compute_budget_program_id) {
+            compute_unit_price =
+                compute_unit_price.or_else(|| parse_compute_unit_price_data(&instruction.data));
+            compute_unit_limit =
+                compute_unit_limit.or_else(|| parse_compute_unit_limit_data(&instruction.data));
+            continue;
+        }
+        num_non_compute_budget += 1;
+        if program_id.is_some_and(|program_id| precompiles.contains(program_id)) {
+            num_signatures += instruction.data.first().copied().unwrap_or(0) as u64;
+        }
+    }
+
+    let compute_unit_price = compute_unit_price.unwrap_or_default();
+    let compute_unit_limit = compute_unit_limit
+        .unwrap_or(ComputeUnits(
+            ComputeUnits::DEFAULT_PER_INSTRUCTION
+                .get()
+                .saturating_mul(num_non_compute_budget),
+        ))
+        .min(ComputeUnits::MAX);
+
+    FeeDetails {
+        num_signatures,
+        signature_fee: num_signatures.saturating_mul(lamports_per_signature),
+        compute_unit_price,
+        compute_unit_limit,
+        prioritization_fee: compute_unit_price.fee_for(compute_unit_limit),
+    }
+}
+
+/// How aggressively to bid for block space
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PriorityLevel {
+    /// 25th percentile
+    Low,
+    /// 50th percentile
+    #[default]
+    Medium,
+    /// 75th percentile
+    High,
+    /// 90th percentile
+    VeryHigh,
+}
+
+/// Compute unit price recommendations at common percentiles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriorityFeeEstimate {
+    /// 25th percentile price
+    pub p25: MicroLamports,
+    /// Median price
+    pub p50: MicroLamports,
+    /// 75th percentile price
+    pub p75: MicroLamports,
+    /// 90th percentile price
+    pub p90: MicroLamports,
+}
+
+impl PriorityFeeEstimate {
+    /// The recommended price for `level`
+    pub fn price(&self, level: PriorityLevel) -> MicroLamports {
+        match level {
+            PriorityLevel::Low => self.p25,
+            PriorityLevel::Medium => self.p50,
+            PriorityLevel::High => self.p75,
+            PriorityLevel::VeryHigh => self.p90,
+        }
+    }
+}
+
+/// Percentile-based compute unit price recommendations from recent fee samples.
+///
+/// Feed it the result of `getRecentPrioritizationFees` for the accounts a transaction
+/// writes, and optionally the compute unit prices of transactions in recent blocks.
+#[derive(Debug, Clone, Default)]
+pub struct PriorityFeeEstimator {
+    prices: Vec<MicroLamports>,
+    ignore_zero: bool,
+}
+
+impl PriorityFeeEstimator {
+    /// Create an estimator with no samples
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the minimum prices of recent slots from `getRecentPrioritizationFees`
+    pub fn add_recent_fees(&mut self, fees: &[PrioritizationFee]) -> &mut Self {
+        self.prices
+            .extend(fees.iter().map(|fee| fee.prioritization_fee));
+        self
+    }
+
+    /// Add the compute unit prices paid by individual transactions in recent blocks
+    pub fn add_block_prices<I>(&mut self, prices: I) -> &mut Self
+    where
+        I: IntoIterator<Item = MicroLamports>,
+    {
+        self.prices.extend(prices);
+        self
+    }
+
+    /// Leave out zero samples, which dominate quiet slots and pull every percentile down
+    pub fn set_ignore_zero(&mut self, ignore_zero: bool) -> &mut Self {
+        self.ignore_zero = ignore_zero;
+        self
+    }
+
+    /// Number of samples the estimate is drawn from
+    pub fn num_samples(&self) -> usize {
+        self.sorted_prices().len()
+    }
+
+    /// The `percentile` (0-100, nearest rank) of the samples, or zero without samples
+    pub fn percentile(&self, percentile: u8) -> MicroLamports {
+        MicroLamports::nearest_rank(&self.sorted_prices(), percentile)
+    }
+
+    /// Recommendations at the 25th, 50th, 75th and 90th percentiles
+    pub fn estimate(&self) -> PriorityFeeEstimate {
+        let prices = self.sorted_prices();
+        PriorityFeeEstimate {
+            p25: MicroLamports::nearest_rank(&prices, 25),
+            p50: MicroLamports::nearest_rank(&prices, 50),
+            p75: MicroLamports::nearest_rank(&prices, 75),
+            p90: MicroLamports::nearest_rank(&prices, 90),
+        }
+    }
+
+    fn sorted_prices(&self) -> Vec<MicroLamports> {
+        let mut prices: Vec<MicroLamports> = self
+            .prices
+            .iter()
+            .copied()
+            .filter(|price| !self.ignore_zero || *price != MicroLamports::ZERO)
+            .collect();
+        prices.sort_unstable();
+        prices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::TransactionBuilder;
+    use crate::instructions::compute_budget::{set_compute_unit_limit, set_compute_unit_price};
+    use crate::instructions::system::transfer;
+
+    #[test]
+    fn test_calculate_fee() {
+        let payer = Pubkey::new([1u8; 32]);
+        let recipient = Pubkey::new([2u8; 32]);
+
+        let mut builder = TransactionBuilder::new(payer, [0u8; 32]);
+        builder.add_instruction(transfer(&payer, &recipient, 1));
+        builder.add_instruction(transfer(&payer, &recipient, 1));
+        let plain = builder.clone().build().unwrap();
+        assert_eq!(plain.fee(5_000), 5_000);
+
+        builder.add_instruction(set_compute_unit_price(MicroLamports(10_000)));
+        let priced = builder.clone().build().unwrap();
+        let details = calculate_fee(
+            priced.account_keys(),
+            priced.num_required_signatures(),
+            priced.instructions(),
+            5_000,
+        );
+        // Two instructions at the default 200k units each
+        assert_eq!(details.compute_unit_limit, ComputeUnits(400_000));
+        assert_eq!(details.prioritization_fee, 4_000);
+        assert_eq!(details.total(), 9_000);
+
+        builder.add_instruction(set_compute_unit_limit(ComputeUnits(1_000)));
+        let limited = builder.build().unwrap();
+        assert_eq!(limited.fee(5_000), 5_010);
+    }
+
+    #[test]
+    fn test_calculate_fee_counts_precompile_signatures() {
+        let payer = Pubkey::new([1u8; 32]);
+        let instructions = [CompiledInstruction {
+            program_id_index: 1,
+            accounts: vec![],
+            data: vec![2, 0],
+        }];
+        let details = calculate_fee(&[payer, ed25519_program()], 1, &instructions, 5_000);
+        assert_eq!(details.num_signatures, 3);
+        assert_eq!(details.total(), 15_000);
+    }
+
+    #[test]
+    fn test_priority_fee_estimator() {
+        let fees: Vec<PrioritizationFee> = [0, 0, 0, 100, 200, 300, 400, 500]
+            .into_iter()
+            .enumerate()
+            .map(|(slot, fee)| PrioritizationFee {
+                slot: slot as u64,
+                prioritization_fee: MicroLamports(fee),
+            })
+            .collect();
+
+        let mut estimator = PriorityFeeEstimator::new();
+        estimator.add_recent_fees(&fees);
+        let estimate = estimator.estimate();
+        assert_eq!(estimate.p25, MicroLamports(0));
+        assert_eq!(estimate.p50, MicroLamports(100));
+        assert_eq!(estimate.p90, MicroLamports(500));
+
+        estimator
+            .set_ignore_zero(true)
+            .add_block_prices([MicroLamports(1_000), MicroLamports(0)]);
+        assert_eq!(estimator.num_samples(), 6);
+        let estimate = estimator.estimate();
+        assert_eq!(estimate.price(PriorityLevel::Low), MicroLamports(200));
+        assert_eq!(estimate.price(PriorityLevel::Medium), MicroLamports(300));
+        assert_eq!(estimate.price(PriorityLevel::High), MicroLamports(500));
+        assert_eq!(
+            estimate.price(PriorityLevel::VeryHigh),
+            MicroLamports(1_000)
+        );
+        assert_eq!(
+            PriorityFeeEstimator::new().estimate(),
+            PriorityFeeEstimate::default()
+        );
+
+        let payer = Pubkey::new([1u8; 32]);
+        let mut builder = TransactionBuilder::new(payer, [0u8; 32]);
+        builder.add_instruction(transfer(&payer, &Pubkey::new([2u8; 32]), 1));
+        builder.set_compute_unit_price_from_estimate(&estimate, PriorityLevel::High);
+        let transaction = builder.build().unwrap();
+        let details = calculate_fee(
+            transaction.account_keys(),
+            transaction.num_required_signatures(),
+            transaction.instructions(),
+            5_000,
+        );
+        assert_eq!(details.compute_unit_price, MicroLamports(500));
+    }
+}
@@ -0,0 +1,421 @@
+//! C ABI for embedding in mobile wallets.
+//!
+//! Every function returns an [`FfiStatus`] and writes its result through an out pointer.
+//! Byte results are returned as an [`FfiBuffer`] owned by Rust, which the caller releases
+//! with [`solana_primitives_buffer_free`]. On failure, [`solana_primitives_last_error`]
+//! returns a message describing the most recent error on the calling thread. Panics never
+//! unwind into the caller: they are caught and reported as [`FfiStatus::Error`].
+//!
+//! The API is `cbindgen` friendly: all types are `#[repr(C)]` and keys, blockhashes and
+//! secret keys are passed as pointers to 32 bytes. To ship it, depend on this crate with
+//! the `ffi` feature from a `staticlib` or `cdylib` crate; the symbols are exported as is.
+
+use crate::builder::TransactionBuilder;
+use crate::crypto::{Keypair, Signer};
+use crate::error::{Result, SolanaError};
+use crate::types::{Instruction, Pubkey, VersionedTransaction, find_program_address};
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Outcome of an FFI call
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfiStatus {
+    /// The call succeeded and its out pointers were written
+    Ok = 0,
+    /// A required pointer was null
+    NullPointer = 1,
+    /// The call failed; see [`solana_primitives_last_error`]
+    Error = 2,
+}
+
+/// Bytes owned by Rust, released with [`solana_primitives_buffer_free`]
+#[repr(C)]
+#[derive(Debug)]
+pub struct FfiBuffer {
+    /// Start of the bytes, null for an empty buffer
+    pub data: *mut u8,
+    /// Number of bytes
+    pub len: usize,
+}
+
+impl FfiBuffer {
+    fn empty() -> Self {
+        Self {
+            data: core::ptr::null_mut(),
+            len: 0,
+        }
+    }
+
+    fn from_vec(bytes: Vec<u8>) -> Self {
+        let bytes = Box::into_raw(bytes.into_boxed_slice());
+        Self {
+            data: bytes as *mut u8,
+            len: bytes.len(),
+        }
+    }
+}
+
+/// Borrowed bytes passed in by the caller
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FfiSlice {
+    /// Start of the bytes, may be null when `len` is 0
+    pub data: *const u8,
+    /// Number of bytes
+    pub len: usize,
+}
+
+/// Record `result` for [`solana_primitives_last_error`] and convert it to a status
+fn finish(result: Result<()>) -> FfiStatus {
+    match result {
+        Ok(()) => FfiStatus::Ok,
+        Err(error) => {
+            LAST_ERROR.with(|last| *last.borrow_mut() = Some(error.to_string()));
+            FfiStatus::Error
+        }
+    }
+}
+
+/// Run the body of an exported function, reporting a panic as [`FfiStatus::Error`] instead
+/// of unwinding into the caller
+fn guard(body: impl FnOnce() -> FfiStatus) -> FfiStatus {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        LAST_ERROR.with(|last| *last.borrow_mut() = Some(format!("panic: {message}")));
+        FfiStatus::Error
+    })
+}
+
+/// # Safety
+/// `data` must be null with `len` 0, or valid for reads of `len` elements.
+unsafe fn slice<'a, T>(data: *const T, len: usize) -> Option<&'a [T]> {
+    if len == 0 {
+        Some(&[])
+    } else if data.is_null() {
+        None
+    } else {
+        // SAFETY: non-null and valid for `len` elements per the caller's contract
+        Some(unsafe { core::slice::from_raw_parts(data, len) })
+    }
+}
+
+/// # Safety
+/// `data` must be null or valid for reads of 32 bytes.
+unsafe fn bytes32<'a>(data: *const u8) -> Option<&'a [u8; 32]> {
+    if data.is_null() {
+        None
+    } else {
+        // SAFETY: non-null and valid for 32 bytes per the caller's contract
+        Some(unsafe { &*data.cast::<[u8; 32]>() })
+    }
+}
+
+/// Decode a wire-format transaction into its JSON representation.
+///
+/// # Safety
+/// `transaction` must be valid for reads of `transaction_len` bytes and `out` must be
+/// valid for a write of one [`FfiBuffer`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn solana_primitives_decode_transaction(
+    transaction: *const u8,
+    transaction_len: usize,
+    out: *mut FfiBuffer,
+) -> FfiStatus {
+    guard(|| {
+        // SAFETY: forwarded from the caller's contract
+        let Some(bytes) = (unsafe { slice(transaction, transaction_len) }) else {
+            return FfiStatus::NullPointer;
+        };
+        if out.is_null() {
+            return FfiStatus::NullPointer;
+        }
+        finish((|| {
+            let transaction = VersionedTransaction::deserialize_with_version(bytes)?;
+            let json = serde_json::to_vec(&transaction)
+                .map_err(|e| SolanaError::SerializationError(e.to_string()))?;
+            // SAFETY: checked non-null above, valid for writes per the caller's contract
+            unsafe { out.write(FfiBuffer::from_vec(json)) };
+            Ok(())
+        })())
+    })
+}
+
+/// Build an unsigned legacy transaction from a JSON array of instructions.
+///
+/// Instructions use the serde layout of [`Instruction`]: base58 `program_id`, `accounts`
+/// with `pubkey`, `is_signer` and `is_writable`, and `data` as an array of bytes.
+///
+/// # Safety
+/// `fee_payer` and `recent_blockhash` must be valid for reads of 32 bytes,
+/// `instructions_json` for reads of `instructions_json_len` bytes, and `out` for a write
+/// of one [`FfiBuffer`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn solana_primitives_build_transaction(
+    fee_payer: *const u8,
+    recent_blockhash: *const u8,
+    instructions_json: *const u8,
+    instructions_json_len: usize,
+    out: *mut FfiBuffer,
+) -> FfiStatus {
+    guard(|| {
+        // SAFETY: forwarded from the caller's contract
+        let inputs = unsafe {
+            (
+                bytes32(fee_payer),
+                bytes32(recent_blockhash),
+                slice(instructions_json, instructions_json_len),
+            )
+        };
+        let (Some(fee_payer), Some(recent_blockhash), Some(json)) = inputs else {
+            return FfiStatus::NullPointer;
+        };
+        if out.is_null() {
+            return FfiStatus::NullPointer;
+        }
+        finish((|| {
+            let instructions: Vec<Instruction> = serde_json::from_slice(json)
+                .map_err(|e| SolanaError::DeserializationError(e.to_string()))?;
+            let mut builder = TransactionBuilder::new(Pubkey::new(*fee_payer), *recent_blockhash);
+            for instruction in instructions {
+                builder.add_instruction(instruction);
+            }
+            let bytes = builder.build()?.serialize_legacy()?;
+            // SAFETY: checked non-null above, valid for writes per the caller's contract
+            unsafe { out.write(FfiBuffer::from_vec(bytes)) };
+            Ok(())
+        })())
+    })
+}
+
+/// Sign a wire-format transaction with a 32-byte secret key, leaving other signatures as is.
+///
+/// # Safety
+/// `transaction` must be valid for reads of `transaction_len` bytes, `secret_key` for
+/// reads of 32 bytes, and `out` for a write of one [`FfiBuffer`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn solana_primitives_sign_transaction(
+    transaction: *const u8,
+    transaction_len: usize,
+    secret_key: *const u8,
+    out: *mut FfiBuffer,
+) -> FfiStatus {
+    guard(|| {
+        // SAFETY: forwarded from the caller's contract
+        let inputs = unsafe { (slice(transaction, transaction_len), bytes32(secret_key)) };
+        let (Some(bytes), Some(secret_key)) = inputs else {
+            return FfiStatus::NullPointer;
+        };
+        if out.is_null() {
+            return FfiStatus::NullPointer;
+        }
+        finish((|| {
+            let mut transaction = VersionedTransaction::deserialize_with_version(bytes)?;
+            let keypair = Keypair::from_secret_key(secret_key);
+            transaction.partial_sign(&[&keypair as &dyn Signer])?;
+            let bytes = transaction.serialize()?;
+            // SAFETY: checked non-null above, valid for writes per the caller's contract
+            unsafe { out.write(FfiBuffer::from_vec(bytes)) };
+            Ok(())
+        })())
+    })
+}
+
+/// Find a program derived address and its bump seed.
+///
+/// # Safety
+/// `program_id` must be valid for reads of 32 bytes, `seeds` for reads of `seeds_len`
+/// [`FfiSlice`]s that each satisfy their own contract, `out_address` for a write of 32
+/// bytes and `out_bump` for a write of one byte.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn solana_primitives_find_program_address(
+    program_id: *const u8,
+    seeds: *const FfiSlice,
+    seeds_len: usize,
+    out_address: *mut u8,
+    out_bump: *mut u8,
+) -> FfiStatus {
+    guard(|| {
+        // SAFETY: forwarded from the caller's contract
+        let inputs = unsafe { (bytes32(program_id), slice(seeds, seeds_len)) };
+        let (Some(program_id), Some(seeds)) = inputs else {
+            return FfiStatus::NullPointer;
+        };
+        if out_address.is_null() || out_bump.is_null() {
+            return FfiStatus::NullPointer;
+        }
+        let mut seed_bytes = Vec::with_capacity(seeds.len());
+        for seed in seeds {
+            // SAFETY: forwarded from the caller's contract
+            match unsafe { slice(seed.data, seed.len) } {
+                Some(bytes) => seed_bytes.push(bytes),
+                None => return FfiStatus::NullPointer,
+            }
+        }
+        finish((|| {
+            let (address, bump) = find_program_address(&Pubkey::new(*program_id), &seed_bytes)?;
+            // SAFETY: checked non-null above, valid for writes per the caller's contract
+            unsafe {
+                core::ptr::copy_nonoverlapping(address.as_bytes().as_ptr(), out_address, 32);
+                out_bump.write(bump);
+            }
+            Ok(())
+        })())
+    })
+}
+
+/// Copy the calling thread's most recent error message into `out`, empty if there is none.
+///
+/// # Safety
+/// `out` must be valid for a write of one [`FfiBuffer`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn solana_primitives_last_error(out: *mut FfiBuffer) -> FfiStatus {
+    guard(|| {
+        if out.is_null() {
+            return FfiStatus::NullPointer;
+        }
+        let message = LAST_ERROR.with(|last| last.borrow().clone());
+        let buffer = message.map_or_else(FfiBuffer::empty, |m| FfiBuffer::from_vec(m.into_bytes()));
+        // SAFETY: checked non-null above, valid for writes per the caller's contract
+        unsafe { out.write(buffer) };
+        FfiStatus::Ok
+    })
+}
+
+/// Release a buffer returned by this library. Freeing an empty buffer is a no-op.
+///
+/// # Safety
+/// `buffer` must have been returned by this library and not freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn solana_primitives_buffer_free(buffer: FfiBuffer) {
+    if !buffer.data.is_null() {
+        // Nothing to report through; a panic while freeing is dropped rather than unwound
+        let _ = panic::catch_unwind(|| {
+            // SAFETY: allocated by `FfiBuffer::from_vec` as a boxed slice of `len` bytes
+            drop(unsafe {
+                Box::from_raw(core::ptr::slice_from_raw_parts_mut(buffer.data, buffer.len))
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::system::transfer;
+
+    fn take(buffer: FfiBuffer) -> Vec<u8> {
+        // SAFETY: buffers come from the functions under test
+        let bytes = unsafe { slice(buffer.data, buffer.len) }.unwrap().to_vec();
+        unsafe { solana_primitives_buffer_free(buffer) };
+        bytes
+    }
+
+    #[test]
+    fn test_build_sign_decode() {
+        let keypair = Keypair::from_secret_key(&[5u8; 32]);
+        let payer = keypair.pubkey();
+        let instructions = vec![transfer(&payer, &Pubkey::new([2u8; 32]), 1_000)];
+        let json = serde_json::to_vec(&instructions).unwrap();
+        let blockhash = [9u8; 32];
+
+        let mut out = FfiBuffer::empty();
+        let status = unsafe {
+            solana_primitives_build_transaction(
+                payer.as_bytes().as_ptr(),
+                blockhash.as_ptr(),
+                json.as_ptr(),
+                json.len(),
+                &mut out,
+            )
+        };
+        assert_eq!(status, FfiStatus::Ok);
+        let unsigned = take(out);
+
+        let mut out = FfiBuffer::empty();
+        let secret = [5u8; 32];
+        let status = unsafe {
+            solana_primitives_sign_transaction(
+                unsigned.as_ptr(),
+                unsigned.len(),
+                secret.as_ptr(),
+                &mut out,
+            )
+        };
+        assert_eq!(status, FfiStatus::Ok);
+        let signed = take(out);
+        let transaction = VersionedTransaction::deserialize_with_version(&signed).unwrap();
+        assert!(transaction.verify_signatures().is_ok());
+
+        let mut out = FfiBuffer::empty();
+        let status = unsafe {
+            solana_primitives_decode_transaction(signed.as_ptr(), signed.len(), &mut out)
+        };
+        assert_eq!(status, FfiStatus::Ok);
+        let decoded: VersionedTransaction = serde_json::from_slice(&take(out)).unwrap();
+        assert_eq!(decoded.signatures(), transaction.signatures());
+    }
+
+    #[test]
+    fn test_errors_and_pda() {
+        let mut out = FfiBuffer::empty();
+        let garbage = [0xffu8; 3];
+        let status = unsafe {
+            solana_primitives_decode_transaction(garbage.as_ptr(), garbage.len(), &mut out)
+        };
+        assert_eq!(status, FfiStatus::Error);
+        assert_eq!(
+            unsafe {
+                solana_primitives_decode_transaction(garbage.as_ptr(), 3, core::ptr::null_mut())
+            },
+            FfiStatus::NullPointer
+        );
+
+        let mut out = FfiBuffer::empty();
+        assert_eq!(
+            unsafe { solana_primitives_last_error(&mut out) },
+            FfiStatus::Ok
+        );
+        assert!(!take(out).is_empty());
+
+        let program_id = Pubkey::new([7u8; 32]);
+        let seeds = [FfiSlice {
+            data: b"vault".as_ptr(),
+            len: 5,
+        }];
+        let (mut address, mut bump) = ([0u8; 32], 0u8);
+        let status = unsafe {
+            solana_primitives_find_program_address(
+                program_id.as_bytes().as_ptr(),
+                seeds.as_ptr(),
+                seeds.len(),
+                address.as_mut_ptr(),
+                &mut bump,
+            )
+        };
+        assert_eq!(status, FfiStatus::Ok);
+        assert_eq!(
+            (Pubkey::new(address), bump),
+            find_program_address(&program_id, &[b"vault"]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_panics_do_not_unwind() {
+        assert_eq!(guard(|| panic!("boom")), FfiStatus::Error);
+        let mut out = FfiBuffer::empty();
+        assert_eq!(
+            unsafe { solana_primitives_last_error(&mut out) },
+            FfiStatus::Ok
+        );
+        assert_eq!(take(out), b"panic: boom");
+    }
+}
@@ -0,0 +1,136 @@
+//! Entry points for fuzzing the decoders.
+//!
+//! Each function feeds arbitrary bytes to a decoder and panics only when an invariant is
+//! broken: decoders return errors instead of panicking on malformed input, and whatever they
+//! accept survives a serialize/deserialize roundtrip. The cargo-fuzz harnesses in `fuzz/` call
+//! these functions; tests can drive them with their own inputs.
+
+use crate::decode::KNOWN_PROGRAMS;
+use crate::instructions::system::SystemInstruction;
+use crate::instructions::token::TokenInstruction;
+use crate::short_vec::{decode_compact_u16_len, encode_length_to_compact_u16_bytes};
+use crate::types::VersionedTransaction;
+
+/// Decode a transaction and check that its serialization is stable
+pub fn versioned_transaction(data: &[u8]) {
+    let Ok(transaction) = VersionedTransaction::deserialize_with_version(data) else {
+        return;
+    };
+    // Signature and account counts that don't match the header still decode, but only
+    // well-formed transactions must serialize
+    let Ok(bytes) = transaction.serialize() else {
+        return;
+    };
+    let decoded = VersionedTransaction::deserialize_with_version(&bytes)
+        .expect("a serialized transaction must deserialize");
+    assert_eq!(
+        decoded
+            .serialize()
+            .expect("a decoded transaction must serialize"),
+        bytes,
+        "transaction serialization is not stable"
+    );
+}
+
+/// Decode a compact-u16 length and check it re-encodes to the same value
+pub fn compact_u16(data: &[u8]) {
+    let Ok((len, consumed)) = decode_compact_u16_len(data) else {
+        return;
+    };
+    assert!((1..=3).contains(&consumed) && consumed <= data.len());
+    assert!(len <= u16::MAX as usize);
+    let encoded = encode_length_to_compact_u16_bytes(len).expect("a decoded length must encode");
+    assert_eq!(decode_compact_u16_len(&encoded), Ok((len, encoded.len())));
+}
+
+/// Parse System program instruction data and check it reserializes
+pub fn system_instruction(data: &[u8]) {
+    if let Ok(instruction) = SystemInstruction::deserialize(data) {
+        let bytes = instruction.serialize();
+        assert_eq!(
+            SystemInstruction::deserialize(&bytes).ok(),
+            Some(instruction)
+        );
+    }
+}
+
+/// Parse Token program instruction data and check it reserializes
+pub fn token_instruction(data: &[u8]) {
+    if let Ok(instruction) = TokenInstruction::deserialize(data) {
+        let bytes = instruction.serialize();
+        assert_eq!(
+            TokenInstruction::deserialize(&bytes).ok(),
+            Some(instruction)
+        );
+    }
+}
+
+/// Run every built-in instruction parser over `data`
+pub fn known_instructions(data: &[u8]) {
+    for (_, parser) in KNOWN_PROGRAMS {
+        let _ = parser(data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::TransactionBuilder;
+    use crate::instructions::{system, token};
+    use crate::types::Pubkey;
+    use alloc::vec::Vec;
+
+    /// Deterministic byte mutations of `seed`: overwrites, truncations and insertions
+    fn mutations(seed: &[u8], count: usize) -> impl Iterator<Item = Vec<u8>> {
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as usize
+        };
+        let seed = seed.to_vec();
+        (0..count).map(move |_| {
+            let mut data = seed.clone();
+            for _ in 0..next() % 4 + 1 {
+                if data.is_empty() {
+                    break;
+                }
+                let position = next() % data.len();
+                match next() % 3 {
+                    0 => data[position] = next() as u8,
+                    1 => data.truncate(position),
+                    _ => data.insert(position, next() as u8),
+                }
+            }
+            data
+        })
+    }
+
+    #[test]
+    fn test_mutated_inputs_do_not_panic() {
+        let payer = Pubkey::new([1u8; 32]);
+        let mut builder = TransactionBuilder::new(payer, [2u8; 32]);
+        builder.add_instruction(system::transfer(&payer, &Pubkey::new([3u8; 32]), 1_000));
+        let transaction = builder.build_v0(&[]).unwrap().serialize().unwrap();
+        let transfer = token::transfer(&payer, &payer, &payer, 5).data;
+
+        for data in mutations(&transaction, 2_000) {
+            versioned_transaction(&data);
+        }
+        for data in mutations(&transfer, 2_000) {
+            token_instruction(&data);
+            system_instruction(&data);
+            known_instructions(&data);
+        }
+        for data in [
+            &[][..],
+            &[0x80],
+            &[0xff, 0xff, 0x03],
+            &[0xff, 0xff, 0x04],
+            &[0x80, 0x00],
+        ] {
+            compact_u16(data);
+        }
+    }
+}
@@ -0,0 +1,323 @@
+//! Admission checks for relayer and co-signer services.
+//!
+//! [`TransactionGuard`] bundles the checks a co-signing backend runs before adding its
+//! signature: the blockhash is recent, durable nonce transactions use an allow-listed
+//! authority, and the same message has not been seen before.
+//!
+//! Blockhash age is measured in slots against a [`BlockhashSlotResolver`], e.g. a map filled
+//! from `getLatestBlockhash` responses and their context slots or from a block feed.
+
+use crate::crypto::hash_data;
+use crate::instructions::system::is_advance_nonce;
+use crate::sysvars::MAX_PROCESSING_AGE;
+use crate::types::{Blockhash, Pubkey, VersionedTransaction};
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::string::String;
+use alloc::string::ToString;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Default number of message hashes remembered for replay protection
+pub const DEFAULT_REPLAY_CAPACITY: usize = 10_000;
+
+/// Why a transaction was refused
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum GuardViolation {
+    #[error("transaction could not be serialized: {0}")]
+    InvalidTransaction(String),
+    #[error("recent blockhash is {age} slots old, maximum is {max_age}")]
+    BlockhashExpired { age: u64, max_age: u64 },
+    #[error("recent blockhash is not known to the blockhash resolver")]
+    BlockhashUnknown,
+    #[error("malformed AdvanceNonceAccount instruction")]
+    MalformedNonceInstruction,
+    #[error("nonce authority {0} is not allowed")]
+    NonceAuthorityNotAllowed(Pubkey),
+    #[error("transaction message was already seen")]
+    Replayed,
+}
+
+/// Source of the slot each recent blockhash was produced in
+pub trait BlockhashSlotResolver {
+    /// The slot `blockhash` was produced in, or `None` if it is unknown
+    fn blockhash_slot(&self, blockhash: &Blockhash) -> Option<u64>;
+}
+
+#[cfg(feature = "std")]
+impl BlockhashSlotResolver for HashMap<Blockhash, u64> {
+    fn blockhash_slot(&self, blockhash: &Blockhash) -> Option<u64> {
+        self.get(blockhash).copied()
+    }
+}
+
+impl BlockhashSlotResolver for BTreeMap<Blockhash, u64> {
+    fn blockhash_slot(&self, blockhash: &Blockhash) -> Option<u64> {
+        self.get(blockhash).copied()
+    }
+}
+
+/// A bounded set of recently seen message hashes; the oldest hash is evicted first
+#[derive(Debug, Clone)]
+pub struct ReplayCache {
+    capacity: usize,
+    order: VecDeque<[u8; 32]>,
+    seen: BTreeSet<[u8; 32]>,
+}
+
+impl ReplayCache {
+    /// Create a cache remembering up to `capacity` hashes
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            order: VecDeque::with_capacity(capacity),
+            seen: BTreeSet::new(),
+        }
+    }
+
+    /// Check whether `hash` is remembered
+    pub fn contains(&self, hash: &[u8; 32]) -> bool {
+        self.seen.contains(hash)
+    }
+
+    /// Remember `hash`; returns false if it was already present
+    pub fn insert(&mut self, hash: [u8; 32]) -> bool {
+        if self.capacity == 0 || !self.seen.insert(hash) {
+            return false;
+        }
+        if self.order.len() == self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.seen.remove(&oldest);
+        }
+        self.order.push_back(hash);
+        true
+    }
+
+    /// Number of remembered hashes
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Check if no hashes are remembered
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+}
+
+impl Default for ReplayCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_REPLAY_CAPACITY)
+    }
+}
+
+/// Blockhash age, nonce authority, and replay checks packaged as one policy
+#[derive(Debug, Clone)]
+pub struct TransactionGuard {
+    max_blockhash_age: u64,
+    nonce_authorities: BTreeSet<Pubkey>,
+    replay_cache: ReplayCache,
+}
+
+impl Default for TransactionGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransactionGuard {
+    /// Create a guard accepting blockhashes up to [`MAX_PROCESSING_AGE`] slots old and no
+    /// durable nonce transactions
+    pub fn new() -> Self {
+        Self {
+            max_blockhash_age: MAX_PROCESSING_AGE as u64,
+            nonce_authorities: BTreeSet::new(),
+            replay_cache: ReplayCache::default(),
+        }
+    }
+
+    /// Only accept blockhashes produced at most `max_age` slots before the current slot
+    pub fn with_max_blockhash_age(mut self, max_age: u64) -> Self {
+        self.max_blockhash_age = max_age;
+        self
+    }
+
+    /// Accept durable nonce transactions authorized by `authority`
+    pub fn allow_nonce_authority(mut self, authority: Pubkey) -> Self {
+        self.nonce_authorities.insert(authority);
+        self
+    }
+
+    /// Use a replay cache remembering up to `capacity` messages
+    pub fn with_replay_capacity(mut self, capacity: usize) -> Self {
+        self.replay_cache = ReplayCache::new(capacity);
+        self
+    }
+
+    /// Check a transaction at `current_slot` without recording it
+    pub fn check(
+        &self,
+        transaction: &VersionedTransaction,
+        current_slot: u64,
+        blockhashes: &impl BlockhashSlotResolver,
+    ) -> Result<[u8; 32], GuardViolation> {
+        let message = transaction
+            .serialize_message()
+            .map_err(|e| GuardViolation::InvalidTransaction(e.to_string()))?;
+        let message_hash = hash_data(&message);
+        if self.replay_cache.contains(&message_hash) {
+            return Err(GuardViolation::Replayed);
+        }
+
+        match nonce_authority(transaction)? {
+            Some(authority) if self.nonce_authorities.contains(&authority) => {}
+            Some(authority) => return Err(GuardViolation::NonceAuthorityNotAllowed(authority)),
+            None => {
+                let slot = blockhashes
+                    .blockhash_slot(transaction.recent_blockhash())
+                    .ok_or(GuardViolation::BlockhashUnknown)?;
+                let age = current_slot.saturating_sub(slot);
+                if age > self.max_blockhash_age {
+                    return Err(GuardViolation::BlockhashExpired {
+                        age,
+                        max_age: self.max_blockhash_age,
+                    });
+                }
+            }
+        }
+
+        Ok(message_hash)
+    }
+
+    /// Check a transaction and, if it passes, remember its message so it is refused next time
+    pub fn admit(
+        &mut self,
+        transaction: &VersionedTransaction,
+        current_slot: u64,
+        blockhashes: &impl BlockhashSlotResolver,
+    ) -> Result<(), GuardViolation> {
+        let message_hash = self.check(transaction, current_slot, blockhashes)?;
+        self.replay_cache.insert(message_hash);
+        Ok(())
+    }
+}
+
+/// The nonce authority of a durable nonce transaction, or `None` for blockhash transactions
+fn nonce_authority(transaction: &VersionedTransaction) -> Result<Option<Pubkey>, GuardViolation> {
+    let Some(first) = transaction.instructions().first() else {
+        return Ok(None);
+    };
+    let keys = transaction.account_keys();
+    if !is_advance_nonce(keys, first) {
+        return Ok(None);
+    }
+
+    // The authority must sign, so it is always a static key
+    first
+        .accounts
+        .get(2)
+        .and_then(|index| keys.get(*index as usize))
+        .copied()
+        .map(Some)
+        .ok_or(GuardViolation::MalformedNonceInstruction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::TransactionBuilder;
+    use crate::instructions::system::{advance_nonce_account, transfer};
+
+    /// Blockhash `[n; 32]` produced `n` slots before slot 1000
+    fn blockhashes() -> BTreeMap<Blockhash, u64> {
+        (0..200u64)
+            .map(|age| (Blockhash::new([age as u8; 32]), 1_000 - age))
+            .collect()
+    }
+
+    fn transfer_tx(blockhash: [u8; 32], lamports: u64) -> VersionedTransaction {
+        let payer = Pubkey::new([1u8; 32]);
+        let mut builder = TransactionBuilder::new(payer, blockhash);
+        builder.add_instruction(transfer(&payer, &Pubkey::new([2u8; 32]), lamports));
+        builder.build_v0(&[]).unwrap()
+    }
+
+    #[test]
+    fn test_guard_blockhash_age_and_replay() {
+        let mut guard = TransactionGuard::new().with_max_blockhash_age(10);
+        let blockhashes = blockhashes();
+
+        guard
+            .admit(&transfer_tx([3u8; 32], 1), 1_000, &blockhashes)
+            .unwrap();
+        assert_eq!(
+            guard.admit(&transfer_tx([3u8; 32], 1), 1_000, &blockhashes),
+            Err(GuardViolation::Replayed)
+        );
+        guard
+            .admit(&transfer_tx([3u8; 32], 2), 1_000, &blockhashes)
+            .unwrap();
+
+        assert_eq!(
+            guard.check(&transfer_tx([11u8; 32], 1), 1_000, &blockhashes),
+            Err(GuardViolation::BlockhashExpired {
+                age: 11,
+                max_age: 10
+            })
+        );
+        assert_eq!(
+            guard.check(&transfer_tx([255u8; 32], 1), 1_000, &blockhashes),
+            Err(GuardViolation::BlockhashUnknown)
+        );
+
+        // Age counts slots, so skipped slots still age the blockhash
+        let sparse = BTreeMap::from([(Blockhash::new([7u8; 32]), 900)]);
+        assert!(
+            guard
+                .check(&transfer_tx([7u8; 32], 1), 910, &sparse)
+                .is_ok()
+        );
+        assert_eq!(
+            guard.check(&transfer_tx([7u8; 32], 1), 911, &sparse),
+            Err(GuardViolation::BlockhashExpired {
+                age: 11,
+                max_age: 10
+            })
+        );
+    }
+
+    #[test]
+    fn test_guard_nonce_authority_allow_list() {
+        let payer = Pubkey::new([1u8; 32]);
+        let nonce = Pubkey::new([4u8; 32]);
+        let authority = Pubkey::new([5u8; 32]);
+        let mut builder = TransactionBuilder::new(payer, [99u8; 32]);
+        builder.add_instruction(advance_nonce_account(&nonce, &authority));
+        builder.add_instruction(transfer(&payer, &Pubkey::new([2u8; 32]), 1));
+        let transaction = builder.build_v0(&[]).unwrap();
+
+        assert_eq!(
+            TransactionGuard::new().check(&transaction, 1_000, &blockhashes()),
+            Err(GuardViolation::NonceAuthorityNotAllowed(authority))
+        );
+        // The nonce value is not a recent blockhash, so only the authority is checked
+        assert!(
+            TransactionGuard::new()
+                .allow_nonce_authority(authority)
+                .check(&transaction, 1_000, &blockhashes())
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_replay_cache_evicts_oldest() {
+        let mut cache = ReplayCache::new(2);
+        assert!(cache.insert([1u8; 32]));
+        assert!(cache.insert([2u8; 32]));
+        assert!(!cache.insert([2u8; 32]));
+        assert!(cache.insert([3u8; 32]));
+        assert_eq!(cache.len(), 2);
+        assert!(!cache.contains(&[1u8; 32]));
+        assert!(cache.contains(&[3u8; 32]));
+    }
+}
@@ -0,0 +1,440 @@
+//! Runtime encoding and decoding driven by an Anchor IDL.
+//!
+//! Arguments and decoded fields are JSON values: integers up to 64 bits are numbers, 128-bit
+//! integers are decimal strings (numbers are accepted when encoding), pubkeys are base58
+//! strings, `bytes` are arrays of numbers and `option` is `null` or the inner value.
+//! User-defined types are not supported, matching `solana-primitives-codegen`.
+
+use crate::data::DataReader;
+use crate::error::{Result, SolanaError};
+use crate::types::{AccountMeta, Instruction, Pubkey};
+use serde_json::{Map, Value};
+use solana_primitives_codegen::Idl;
+use solana_primitives_codegen::idl::{IdlField, IdlType};
+use std::collections::HashMap;
+
+/// An Anchor program described by its IDL
+#[derive(Debug, Clone)]
+pub struct IdlProgram {
+    idl: Idl,
+    program_id: Pubkey,
+}
+
+/// An instruction decoded with [`IdlProgram::decode_instruction`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedIdlInstruction {
+    /// Instruction name as written in the IDL
+    pub name: String,
+    /// Arguments by name
+    pub args: Map<String, Value>,
+}
+
+/// An account decoded with [`IdlProgram::decode_account`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedIdlAccount {
+    /// Account type name as written in the IDL
+    pub name: String,
+    /// Fields by name
+    pub fields: Map<String, Value>,
+}
+
+impl IdlProgram {
+    /// Load an IDL, taking the program ID from its `address`
+    pub fn from_json(json: &str) -> Result<Self> {
+        let idl = Idl::from_json(json).map_err(|e| SolanaError::GenericError(e.to_string()))?;
+        let address = idl
+            .program_address()
+            .ok_or_else(|| SolanaError::GenericError("IDL has no program address".to_string()))?;
+        let program_id = Pubkey::from_base58(address)?;
+        Ok(Self { idl, program_id })
+    }
+
+    /// Load an IDL for a program deployed at `program_id`
+    pub fn from_json_with_program_id(json: &str, program_id: Pubkey) -> Result<Self> {
+        let idl = Idl::from_json(json).map_err(|e| SolanaError::GenericError(e.to_string()))?;
+        Ok(Self { idl, program_id })
+    }
+
+    /// The program ID instructions are built for
+    pub fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    /// The parsed IDL
+    pub fn idl(&self) -> &Idl {
+        &self.idl
+    }
+
+    /// Build an instruction from a JSON object of arguments and accounts keyed by IDL name.
+    ///
+    /// Accounts in nested groups are named `group_member`. Missing optional accounts are
+    /// passed as the program ID, as Anchor expects.
+    pub fn build_instruction(
+        &self,
+        name: &str,
+        args: &Value,
+        accounts: &HashMap<&str, Pubkey>,
+    ) -> Result<Instruction> {
+        let instruction = self
+            .idl
+            .instructions
+            .iter()
+            .find(|instruction| instruction.name == name)
+            .ok_or_else(|| SolanaError::GenericError(format!("unknown instruction: {name}")))?;
+
+        let account_metas = instruction
+            .flat_accounts()
+            .into_iter()
+            .map(|account| match accounts.get(account.name.as_str()) {
+                Some(pubkey) => Ok(AccountMeta::new(*pubkey, account.signer, account.writable)),
+                None if account.optional => Ok(AccountMeta::new_readonly(self.program_id)),
+                None => Err(SolanaError::GenericError(format!(
+                    "missing account: {}",
+                    account.name
+                ))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut data = instruction.discriminator();
+        for field in &instruction.args {
+            let value = args.get(&field.name).unwrap_or(&Value::Null);
+            encode_value(&parse_type(field)?, value, &mut data)
+                .map_err(|e| field_error(&field.name, e))?;
+        }
+
+        Ok(Instruction {
+            program_id: self.program_id,
+            accounts: account_metas,
+            data,
+        })
+    }
+
+    /// Decode instruction data by matching its discriminator
+    pub fn decode_instruction(&self, data: &[u8]) -> Result<DecodedIdlInstruction> {
+        let (instruction, discriminator) = self
+            .idl
+            .instructions
+            .iter()
+            .map(|instruction| (instruction, instruction.discriminator()))
+            .find(|(_, discriminator)| data.starts_with(discriminator))
+            .ok_or_else(|| {
+                SolanaError::DeserializationError("unknown instruction discriminator".to_string())
+            })?;
+
+        Ok(DecodedIdlInstruction {
+            name: instruction.name.clone(),
+            args: decode_fields(&instruction.args, &data[discriminator.len()..])?,
+        })
+    }
+
+    /// Decode account data by matching its discriminator
+    pub fn decode_account(&self, data: &[u8]) -> Result<DecodedIdlAccount> {
+        let (account, discriminator) = self
+            .idl
+            .accounts
+            .iter()
+            .map(|account| (account, account.discriminator()))
+            .find(|(_, discriminator)| data.starts_with(discriminator))
+            .ok_or_else(|| {
+                SolanaError::DeserializationError("unknown account discriminator".to_string())
+            })?;
+        let layout = self.idl.account_layout(account).ok_or_else(|| {
+            SolanaError::DeserializationError(format!("no layout for account {}", account.name))
+        })?;
+        let fields = layout
+            .fields
+            .iter()
+            .map(|field| {
+                serde_json::from_value::<IdlField>(field.clone())
+                    .map_err(|e| SolanaError::DeserializationError(e.to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(DecodedIdlAccount {
+            name: account.name.clone(),
+            fields: decode_fields(&fields, &data[discriminator.len()..])?,
+        })
+    }
+}
+
+fn parse_type(field: &IdlField) -> Result<IdlType> {
+    IdlType::parse(&field.ty).map_err(|e| field_error(&field.name, e.to_string().into()))
+}
+
+fn field_error(name: &str, error: SolanaError) -> SolanaError {
+    SolanaError::GenericError(format!("field `{name}`: {error}"))
+}
+
+fn decode_fields(fields: &[IdlField], data: &[u8]) -> Result<Map<String, Value>> {
+    let mut reader = DataReader::new(data);
+    fields
+        .iter()
+        .map(|field| {
+            let value = decode_value(&parse_type(field)?, &mut reader)
+                .map_err(|e| field_error(&field.name, e))?;
+            Ok((field.name.clone(), value))
+        })
+        .collect()
+}
+
+fn encode_value(ty: &IdlType, value: &Value, out: &mut Vec<u8>) -> Result<()> {
+    let mismatch = || SolanaError::SerializationError(format!("expected {ty:?}, got {value}"));
+    match ty {
+        IdlType::Bool => out.push(value.as_bool().ok_or_else(mismatch)? as u8),
+        IdlType::U8 => out.push(encode_int::<u8>(value).ok_or_else(mismatch)?),
+        IdlType::U16 => out.extend(encode_int::<u16>(value).ok_or_else(mismatch)?.to_le_bytes()),
+        IdlType::U32 => out.extend(encode_int::<u32>(value).ok_or_else(mismatch)?.to_le_bytes()),
+        IdlType::U64 => out.extend(encode_int::<u64>(value).ok_or_else(mismatch)?.to_le_bytes()),
+        IdlType::U128 => out.extend(
+            encode_int::<u128>(value)
+                .ok_or_else(mismatch)?
+                .to_le_bytes(),
+        ),
+        IdlType::I8 => out.extend(encode_int::<i8>(value).ok_or_else(mismatch)?.to_le_bytes()),
+        IdlType::I16 => out.extend(encode_int::<i16>(value).ok_or_else(mismatch)?.to_le_bytes()),
+        IdlType::I32 => out.extend(encode_int::<i32>(value).ok_or_else(mismatch)?.to_le_bytes()),
+        IdlType::I64 => out.extend(encode_int::<i64>(value).ok_or_else(mismatch)?.to_le_bytes()),
+        IdlType::I128 => out.extend(
+            encode_int::<i128>(value)
+                .ok_or_else(mismatch)?
+                .to_le_bytes(),
+        ),
+        IdlType::Pubkey => {
+            let pubkey = Pubkey::from_base58(value.as_str().ok_or_else(mismatch)?)?;
+            out.extend_from_slice(pubkey.as_bytes());
+        }
+        IdlType::String => {
+            let string = value.as_str().ok_or_else(mismatch)?;
+            out.extend((string.len() as u32).to_le_bytes());
+            out.extend_from_slice(string.as_bytes());
+        }
+        IdlType::Bytes => {
+            let bytes = value.as_array().ok_or_else(mismatch)?;
+            out.extend((bytes.len() as u32).to_le_bytes());
+            for byte in bytes {
+                encode_value(&IdlType::U8, byte, out)?;
+            }
+        }
+        IdlType::Vec(inner) => {
+            let items = value.as_array().ok_or_else(mismatch)?;
+            out.extend((items.len() as u32).to_le_bytes());
+            for item in items {
+                encode_value(inner, item, out)?;
+            }
+        }
+        IdlType::Option(inner) => {
+            if value.is_null() {
+                out.push(0);
+            } else {
+                out.push(1);
+                encode_value(inner, value, out)?;
+            }
+        }
+        IdlType::Array(inner, len) => {
+            let items = value.as_array().ok_or_else(mismatch)?;
+            if items.len() != *len {
+                return Err(mismatch());
+            }
+            for item in items {
+                encode_value(inner, item, out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read an integer from a JSON number or decimal string
+fn encode_int<T: TryFrom<i128> + TryFrom<u128> + std::str::FromStr>(value: &Value) -> Option<T> {
+    match value {
+        Value::Number(number) => {
+            if let Some(unsigned) = number.as_u64() {
+                T::try_from(unsigned as u128).ok()
+            } else {
+                T::try_from(number.as_i64()? as i128).ok()
+            }
+        }
+        Value::String(string) => string.parse().ok(),
+        _ => None,
+    }
+}
+
+fn decode_value(ty: &IdlType, reader: &mut DataReader<'_>) -> Result<Value> {
+    let value = match ty {
+        IdlType::Bool => Value::Bool(reader.read::<u8>()? != 0),
+        IdlType::U8 => reader.read::<u8>()?.into(),
+        IdlType::U16 => reader.read::<u16>()?.into(),
+        IdlType::U32 => reader.read::<u32>()?.into(),
+        IdlType::U64 => reader.read::<u64>()?.into(),
+        IdlType::U128 => reader.read::<u128>()?.to_string().into(),
+        IdlType::I8 => reader.read::<i8>()?.into(),
+        IdlType::I16 => reader.read::<i16>()?.into(),
+        IdlType::I32 => reader.read::<i32>()?.into(),
+        IdlType::I64 => reader.read::<i64>()?.into(),
+        IdlType::I128 => reader.read::<i128>()?.to_string().into(),
+        IdlType::Pubkey => reader.read_pubkey()?.to_base58().into(),
+        IdlType::String => {
+            let len = reader.read::<u32>()? as usize;
+            String::from_utf8(reader.read_bytes(len)?.to_vec())
+                .map_err(|e| SolanaError::DeserializationError(e.to_string()))?
+                .into()
+        }
+        IdlType::Bytes => {
+            let len = reader.read::<u32>()? as usize;
+            reader.read_bytes(len)?.to_vec().into()
+        }
+        IdlType::Vec(inner) => {
+            let len = reader.read::<u32>()? as usize;
+            let items = (0..len)
+                .map(|_| decode_value(inner, reader))
+                .collect::<Result<Vec<_>>>()?;
+            Value::Array(items)
+        }
+        IdlType::Option(inner) => match reader.read::<u8>()? {
+            0 => Value::Null,
+            _ => decode_value(inner, reader)?,
+        },
+        IdlType::Array(inner, len) => Value::Array(
+            (0..*len)
+                .map(|_| decode_value(inner, reader))
+                .collect::<Result<Vec<_>>>()?,
+        ),
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const COUNTER_IDL: &str = r#"{
+        "address": "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr",
+        "metadata": { "name": "counter" },
+        "instructions": [
+            {
+                "name": "increment",
+                "discriminator": [11, 18, 104, 9, 104, 174, 59, 33],
+                "accounts": [
+                    { "name": "counter", "writable": true },
+                    {
+                        "name": "admin",
+                        "accounts": [
+                            { "name": "authority", "signer": true },
+                            { "name": "delegate", "optional": true }
+                        ]
+                    }
+                ],
+                "args": [
+                    { "name": "by", "type": "u32" },
+                    { "name": "memo", "type": { "option": "string" } },
+                    { "name": "tags", "type": { "vec": "pubkey" } },
+                    { "name": "seed", "type": { "array": ["u8", 4] } },
+                    { "name": "flag", "type": "bool" }
+                ]
+            },
+            {
+                "name": "configure",
+                "accounts": [{ "name": "counter", "writable": true }],
+                "args": [{ "name": "settings", "type": { "defined": { "name": "Settings" } } }]
+            }
+        ],
+        "accounts": [
+            { "name": "Counter", "discriminator": [255, 176, 4, 245, 188, 253, 124, 25] }
+        ],
+        "types": [
+            {
+                "name": "Counter",
+                "type": {
+                    "kind": "struct",
+                    "fields": [
+                        { "name": "authority", "type": "pubkey" },
+                        { "name": "count", "type": "u64" },
+                        { "name": "label", "type": "string" },
+                        { "name": "delegate", "type": { "option": "pubkey" } },
+                        { "name": "history", "type": { "vec": "i64" } },
+                        { "name": "bump", "type": "u8" }
+                    ]
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_build_and_decode_instruction() {
+        let program = IdlProgram::from_json(COUNTER_IDL).unwrap();
+        let counter = Pubkey::new([1u8; 32]);
+        let authority = Pubkey::new([2u8; 32]);
+        let tag = Pubkey::new([3u8; 32]);
+
+        let args = json!({
+            "by": 5,
+            "memo": "hi",
+            "tags": [tag.to_base58()],
+            "seed": [1, 2, 3, 4],
+            "flag": true,
+        });
+        let accounts = HashMap::from([("counter", counter), ("admin_authority", authority)]);
+        let ix = program
+            .build_instruction("increment", &args, &accounts)
+            .unwrap();
+
+        assert_eq!(ix.program_id, program.program_id());
+        assert_eq!(ix.accounts.len(), 3);
+        assert!(ix.accounts[0].is_writable);
+        assert!(ix.accounts[1].is_signer);
+        assert_eq!(ix.accounts[2].pubkey, program.program_id());
+        assert_eq!(ix.data[..8], [11, 18, 104, 9, 104, 174, 59, 33]);
+
+        let decoded = program.decode_instruction(&ix.data).unwrap();
+        assert_eq!(decoded.name, "increment");
+        assert_eq!(Value::Object(decoded.args), args);
+
+        assert!(
+            program
+                .build_instruction("increment", &args, &HashMap::new())
+                .is_err()
+        );
+        assert!(
+            program
+                .build_instruction("increment", &json!({ "by": -1 }), &accounts)
+                .is_err()
+        );
+        // User-defined argument types are not supported
+        assert!(
+            program
+                .build_instruction("configure", &json!({}), &accounts)
+                .is_err()
+        );
+        assert!(program.decode_instruction(&[0u8; 8]).is_err());
+    }
+
+    #[test]
+    fn test_decode_account() {
+        let program = IdlProgram::from_json(COUNTER_IDL).unwrap();
+        let authority = Pubkey::new([2u8; 32]);
+
+        let mut data = vec![255, 176, 4, 245, 188, 253, 124, 25];
+        data.extend_from_slice(authority.as_bytes());
+        data.extend_from_slice(&9u64.to_le_bytes());
+        data.extend_from_slice(&[2, 0, 0, 0, b'o', b'k']);
+        data.push(0);
+        data.extend_from_slice(&[1, 0, 0, 0]);
+        data.extend_from_slice(&(-3i64).to_le_bytes());
+        data.push(254);
+
+        let account = program.decode_account(&data).unwrap();
+        assert_eq!(account.name, "Counter");
+        assert_eq!(
+            Value::Object(account.fields),
+            json!({
+                "authority": authority.to_base58(),
+                "count": 9,
+                "label": "ok",
+                "delegate": null,
+                "history": [-3],
+                "bump": 254,
+            })
+        );
+        assert!(program.decode_account(&data[..20]).is_err());
+    }
+}
@@ -0,0 +1,364 @@
+use crate::builder::TransactionBuilder;
+use crate::error::Result;
+use crate::instructions::program_ids::{address_lookup_table_program, system_program};
+use crate::types::{
+    AccountMeta, AddressLookupTableAccount, Instruction, MAX_TRANSACTION_SIZE, Pubkey,
+    find_program_address,
+};
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Maximum number of addresses a lookup table can hold
+pub const LOOKUP_TABLE_MAX_ADDRESSES: usize = 256;
+
+/// Address lookup table program instructions (bincode encoded)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgramInstruction {
+    /// Create a lookup table at the PDA derived from the authority and a recent slot
+    /// 0. `[WRITE]` Uninitialized lookup table account
+    /// 1. `[]` Lookup table authority
+    /// 2. `[WRITE, SIGNER]` Payer
+    /// 3. `[]` System program
+    CreateLookupTable {
+        /// A recent slot, used as a derivation seed
+        recent_slot: u64,
+        /// Bump seed of the lookup table address
+        bump_seed: u8,
+    },
+    /// Permanently freeze a lookup table
+    /// 0. `[WRITE]` Lookup table account
+    /// 1. `[SIGNER]` Lookup table authority
+    FreezeLookupTable,
+    /// Append addresses to a lookup table
+    /// 0. `[WRITE]` Lookup table account
+    /// 1. `[SIGNER]` Lookup table authority
+    /// 2. `[WRITE, SIGNER]` Payer, if the table needs more rent
+    /// 3. `[]` System program, if the table needs more rent
+    ExtendLookupTable {
+        /// Addresses to append
+        new_addresses: Vec<Pubkey>,
+    },
+    /// Deactivate a lookup table so it can be closed after cooldown
+    /// 0. `[WRITE]` Lookup table account
+    /// 1. `[SIGNER]` Lookup table authority
+    DeactivateLookupTable,
+    /// Close a deactivated lookup table
+    /// 0. `[WRITE]` Lookup table account
+    /// 1. `[SIGNER]` Lookup table authority
+    /// 2. `[WRITE]` Recipient of the reclaimed lamports
+    CloseLookupTable,
+}
+
+impl ProgramInstruction {
+    /// Serialize the instruction
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        match self {
+            Self::CreateLookupTable {
+                recent_slot,
+                bump_seed,
+            } => {
+                data.extend_from_slice(&0u32.to_le_bytes());
+                data.extend_from_slice(&recent_slot.to_le_bytes());
+                data.push(*bump_seed);
+            }
+            Self::FreezeLookupTable => data.extend_from_slice(&1u32.to_le_bytes()),
+            Self::ExtendLookupTable { new_addresses } => {
+                data.extend_from_slice(&2u32.to_le_bytes());
+                data.extend_from_slice(&(new_addresses.len() as u64).to_le_bytes());
+                for address in new_addresses {
+                    data.extend_from_slice(address.as_bytes());
+                }
+            }
+            Self::DeactivateLookupTable => data.extend_from_slice(&3u32.to_le_bytes()),
+            Self::CloseLookupTable => data.extend_from_slice(&4u32.to_le_bytes()),
+        }
+        data
+    }
+}
+
+/// Derive the address of a lookup table created by `authority` at `recent_slot`
+pub fn derive_lookup_table_address(authority: &Pubkey, recent_slot: u64) -> Result<(Pubkey, u8)> {
+    find_program_address(
+        &address_lookup_table_program(),
+        &[authority.as_bytes(), &recent_slot.to_le_bytes()],
+    )
+}
+
+/// Create a lookup table, returning the instruction and the new table's address
+pub fn create_lookup_table(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    recent_slot: u64,
+) -> Result<(Instruction, Pubkey)> {
+    let (lookup_table, bump_seed) = derive_lookup_table_address(authority, recent_slot)?;
+    let instruction = Instruction {
+        program_id: address_lookup_table_program(),
+        accounts: vec![
+            AccountMeta::new_writable(lookup_table),
+            AccountMeta::new_readonly(*authority),
+            AccountMeta::new_signer_writable(*payer),
+            AccountMeta::new_readonly(system_program()),
+        ],
+        data: ProgramInstruction::CreateLookupTable {
+            recent_slot,
+            bump_seed,
+        }
+        .serialize(),
+    };
+    Ok((instruction, lookup_table))
+}
+
+/// Append addresses to a lookup table, funding extra rent from `payer` if given
+pub fn extend_lookup_table(
+    lookup_table: &Pubkey,
+    authority: &Pubkey,
+    payer: Option<&Pubkey>,
+    new_addresses: Vec<Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_writable(*lookup_table),
+        AccountMeta::new_signer(*authority),
+    ];
+    if let Some(payer) = payer {
+        accounts.push(AccountMeta::new_signer_writable(*payer));
+        accounts.push(AccountMeta::new_readonly(system_program()));
+    }
+
+    Instruction {
+        program_id: address_lookup_table_program(),
+        accounts,
+        data: ProgramInstruction::ExtendLookupTable { new_addresses }.serialize(),
+    }
+}
+
+/// Permanently freeze a lookup table
+pub fn freeze_lookup_table(lookup_table: &Pubkey, authority: &Pubkey) -> Instruction {
+    authority_instruction(
+        lookup_table,
+        authority,
+        ProgramInstruction::FreezeLookupTable,
+    )
+}
+
+/// Deactivate a lookup table
+pub fn deactivate_lookup_table(lookup_table: &Pubkey, authority: &Pubkey) -> Instruction {
+    authority_instruction(
+        lookup_table,
+        authority,
+        ProgramInstruction::DeactivateLookupTable,
+    )
+}
+
+/// Close a deactivated lookup table, sending its lamports to `recipient`
+pub fn close_lookup_table(
+    lookup_table: &Pubkey,
+    authority: &Pubkey,
+    recipient: &Pubkey,
+) -> Instruction {
+    let mut instruction = authority_instruction(
+        lookup_table,
+        authority,
+        ProgramInstruction::CloseLookupTable,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_writable(*recipient));
+    instruction
+}
+
+fn authority_instruction(
+    lookup_table: &Pubkey,
+    authority: &Pubkey,
+    instruction: ProgramInstruction,
+) -> Instruction {
+    Instruction {
+        program_id: address_lookup_table_program(),
+        accounts: vec![
+            AccountMeta::new_writable(*lookup_table),
+            AccountMeta::new_signer(*authority),
+        ],
+        data: instruction.serialize(),
+    }
+}
+
+/// Result of diffing a lookup table against the addresses a caller wants in it
+#[derive(Debug, Clone)]
+pub struct ExtensionPlan {
+    /// Desired addresses already present in the table
+    pub existing: Vec<Pubkey>,
+    /// Addresses to append, in the order they will be added
+    pub to_append: Vec<Pubkey>,
+    /// Desired addresses that don't fit under the 256-entry cap
+    pub overflow: Vec<Pubkey>,
+    /// Table entries that weren't requested
+    pub unused: Vec<Pubkey>,
+    /// `ExtendLookupTable` instructions, each sized to fit in its own transaction
+    pub instructions: Vec<Instruction>,
+}
+
+impl ExtensionPlan {
+    /// Whether some desired addresses need a new table because this one is full
+    pub fn requires_new_table(&self) -> bool {
+        !self.overflow.is_empty()
+    }
+}
+
+/// Plan how to extend `table` so it contains `desired`.
+///
+/// Duplicates in `desired` are ignored. The `ExtendLookupTable` instructions are chunked so
+/// each fits, alone, in a legacy transaction paid by `payer` and signed by `authority`.
+pub fn plan_extension(
+    table: &AddressLookupTableAccount,
+    desired: &[Pubkey],
+    authority: &Pubkey,
+    payer: &Pubkey,
+) -> Result<ExtensionPlan> {
+    let present: BTreeSet<&Pubkey> = table.addresses.iter().collect();
+    let mut seen = BTreeSet::new();
+    let mut existing = Vec::new();
+    let mut missing = Vec::new();
+    for address in desired {
+        if !seen.insert(address) {
+            continue;
+        }
+        if present.contains(address) {
+            existing.push(*address);
+        } else {
+            missing.push(*address);
+        }
+    }
+
+    let capacity = LOOKUP_TABLE_MAX_ADDRESSES.saturating_sub(table.len());
+    let overflow = missing.split_off(capacity.min(missing.len()));
+    let to_append = missing;
+    let unused = table
+        .addresses
+        .iter()
+        .filter(|address| !seen.contains(address))
+        .copied()
+        .collect();
+
+    let chunk_size = max_addresses_per_extend(&table.key, authority, payer)?;
+    let instructions = to_append
+        .chunks(chunk_size)
+        .map(|chunk| extend_lookup_table(&table.key, authority, Some(payer), chunk.to_vec()))
+        .collect();
+
+    Ok(ExtensionPlan {
+        existing,
+        to_append,
+        overflow,
+        unused,
+        instructions,
+    })
+}
+
+/// Largest number of addresses a single `ExtendLookupTable` transaction can carry
+fn max_addresses_per_extend(
+    lookup_table: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+) -> Result<usize> {
+    // Past 4 addresses the instruction data length needs a 2-byte compact-u16, after which
+    // each further address costs exactly 32 bytes.
+    const BASE_ADDRESSES: usize = 4;
+    let mut builder = TransactionBuilder::new(*payer, [0u8; 32]);
+    builder.add_instruction(extend_lookup_table(
+        lookup_table,
+        authority,
+        Some(payer),
+        vec![Pubkey::new([0u8; 32]); BASE_ADDRESSES],
+    ));
+    let base_size = builder.build()?.serialize_legacy()?.len();
+    Ok(BASE_ADDRESSES + MAX_TRANSACTION_SIZE.saturating_sub(base_size) / 32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addresses(range: core::ops::Range<u8>) -> Vec<Pubkey> {
+        range.map(|i| Pubkey::new([i; 32])).collect()
+    }
+
+    #[test]
+    fn test_extend_lookup_table_encoding() {
+        let table = Pubkey::new([1u8; 32]);
+        let authority = Pubkey::new([2u8; 32]);
+        let instruction = extend_lookup_table(&table, &authority, None, addresses(5..7));
+
+        assert_eq!(instruction.program_id, address_lookup_table_program());
+        assert_eq!(instruction.accounts.len(), 2);
+        assert!(instruction.accounts[1].is_signer);
+        assert_eq!(&instruction.data[..4], &[2, 0, 0, 0]);
+        assert_eq!(&instruction.data[4..12], &2u64.to_le_bytes());
+        assert_eq!(&instruction.data[12..44], &[5u8; 32]);
+        assert_eq!(instruction.data.len(), 12 + 64);
+    }
+
+    #[test]
+    fn test_create_lookup_table_derives_address() {
+        let authority = Pubkey::new([2u8; 32]);
+        let payer = Pubkey::new([3u8; 32]);
+        let (instruction, table) = create_lookup_table(&authority, &payer, 42).unwrap();
+        let (expected, bump) = derive_lookup_table_address(&authority, 42).unwrap();
+
+        assert_eq!(table, expected);
+        assert_eq!(instruction.accounts[0].pubkey, table);
+        assert_eq!(&instruction.data[..4], &[0, 0, 0, 0]);
+        assert_eq!(&instruction.data[4..12], &42u64.to_le_bytes());
+        assert_eq!(instruction.data[12], bump);
+    }
+
+    #[test]
+    fn test_plan_extension_diffs_and_chunks() {
+        let authority = Pubkey::new([200u8; 32]);
+        let payer = Pubkey::new([201u8; 32]);
+        let table = AddressLookupTableAccount::new(Pubkey::new([202u8; 32]), addresses(0..10));
+
+        let mut desired = addresses(5..70);
+        desired.push(Pubkey::new([6u8; 32]));
+        let plan = plan_extension(&table, &desired, &authority, &payer).unwrap();
+
+        assert_eq!(plan.existing, addresses(5..10));
+        assert_eq!(plan.to_append, addresses(10..70));
+        assert_eq!(plan.unused, addresses(0..5));
+        assert!(!plan.requires_new_table());
+
+        let appended: Vec<Pubkey> = plan
+            .instructions
+            .iter()
+            .flat_map(|ix| {
+                ix.data[12..]
+                    .chunks(32)
+                    .map(|c| Pubkey::new(c.try_into().unwrap()))
+            })
+            .collect();
+        assert_eq!(appended, plan.to_append);
+        assert!(plan.instructions.len() > 1);
+
+        for instruction in &plan.instructions {
+            let mut builder = TransactionBuilder::new(payer, [0u8; 32]);
+            builder.add_instruction(instruction.clone());
+            builder.build().unwrap().validate_size().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_plan_extension_respects_cap() {
+        let authority = Pubkey::new([1u8; 32]);
+        let table = AddressLookupTableAccount::new(
+            Pubkey::new([2u8; 32]),
+            vec![Pubkey::new([3u8; 32]); LOOKUP_TABLE_MAX_ADDRESSES - 2],
+        );
+
+        let plan = plan_extension(&table, &addresses(10..15), &authority, &authority).unwrap();
+
+        assert_eq!(plan.to_append, addresses(10..12));
+        assert_eq!(plan.overflow, addresses(12..15));
+        assert!(plan.requires_new_table());
+        assert_eq!(plan.instructions.len(), 1);
+    }
+}
@@ -1,12 +1,42 @@
+use crate::error::{Result, SolanaError};
 use crate::instructions::program_ids::{
     associated_token_program, rent_sysvar, system_program, token_program,
 };
 use crate::types::{AccountMeta, Instruction, Pubkey, find_program_address};
+use alloc::vec::Vec;
+use alloc::{format, vec};
 
 /// Discriminator for the `Create` variant of the AssociatedTokenAccount program.
 const CREATE_DISCRIMINATOR: &[u8] = &[];
 /// Discriminator for the `CreateIdempotent` variant of the AssociatedTokenAccount program.
 const IDEMPOTENT_DISCRIMINATOR: &[u8] = &[1];
+/// Discriminator for the `RecoverNested` variant of the AssociatedTokenAccount program.
+const RECOVER_NESTED_DISCRIMINATOR: &[u8] = &[2];
+
+/// Associated Token Account program instructions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssociatedTokenInstruction {
+    /// Create an associated token account, failing if it exists
+    Create,
+    /// Create an associated token account if it does not exist
+    CreateIdempotent,
+    /// Transfer tokens out of a nested associated token account and close it
+    RecoverNested,
+}
+
+impl AssociatedTokenInstruction {
+    /// Deserialize instruction data; empty data is the legacy encoding of `Create`
+    pub fn deserialize(data: &[u8]) -> Result<Self> {
+        match data.first() {
+            None | Some(0) => Ok(Self::Create),
+            Some(1) => Ok(Self::CreateIdempotent),
+            Some(2) => Ok(Self::RecoverNested),
+            Some(tag) => Err(SolanaError::DeserializationError(format!(
+                "unknown associated token instruction: {tag}"
+            ))),
+        }
+    }
+}
 
 /// Create an associated token account instruction (defaults to the SPL Token program)
 pub fn create_associated_token_account(
@@ -146,6 +176,62 @@ fn create_associated_token_account_instruction(
     }
 }
 
+/// Recover tokens sent to an associated token account owned by another associated token
+/// account of the same wallet (defaults to the SPL Token program).
+///
+/// Moves the balance of the nested account (`owner_token_mint` ATA's ATA for
+/// `nested_token_mint`) into the wallet's own ATA for `nested_token_mint` and closes it.
+pub fn recover_nested(
+    wallet_address: &Pubkey,
+    owner_token_mint_address: &Pubkey,
+    nested_token_mint_address: &Pubkey,
+) -> Instruction {
+    recover_nested_with_program_id(
+        wallet_address,
+        owner_token_mint_address,
+        nested_token_mint_address,
+        &token_program(),
+    )
+}
+
+/// Recover tokens from a nested associated token account using the provided token program
+pub fn recover_nested_with_program_id(
+    wallet_address: &Pubkey,
+    owner_token_mint_address: &Pubkey,
+    nested_token_mint_address: &Pubkey,
+    token_program_id: &Pubkey,
+) -> Instruction {
+    let owner_associated_account_address = get_associated_token_address_with_program_id(
+        wallet_address,
+        owner_token_mint_address,
+        token_program_id,
+    );
+    let destination_associated_account_address = get_associated_token_address_with_program_id(
+        wallet_address,
+        nested_token_mint_address,
+        token_program_id,
+    );
+    let nested_associated_account_address = get_associated_token_address_with_program_id(
+        &owner_associated_account_address,
+        nested_token_mint_address,
+        token_program_id,
+    );
+
+    Instruction {
+        program_id: associated_token_program(),
+        accounts: vec![
+            AccountMeta::new_writable(nested_associated_account_address),
+            AccountMeta::new_readonly(*nested_token_mint_address),
+            AccountMeta::new_writable(destination_associated_account_address),
+            AccountMeta::new_readonly(owner_associated_account_address),
+            AccountMeta::new_readonly(*owner_token_mint_address),
+            AccountMeta::new_signer_writable(*wallet_address),
+            AccountMeta::new_readonly(*token_program_id),
+        ],
+        data: RECOVER_NESTED_DISCRIMINATOR.to_vec(),
+    }
+}
+
 /// Derive the associated token account address for a wallet address and token mint
 pub fn get_associated_token_address(
     wallet_address: &Pubkey,
@@ -184,7 +270,8 @@ mod tests {
     use crate::Pubkey;
     use crate::instructions::program_ids::{
         ASSOCIATED_TOKEN_PROGRAM_ID, SYSTEM_PROGRAM_ID, SYSVAR_RENT_ID, TOKEN_2022_PROGRAM_ID,
-        TOKEN_PROGRAM_ID,
+        TOKEN_PROGRAM_ID, associated_token_program, rent_sysvar, system_program,
+        token_2022_program, token_program,
     };
 
     fn mint_pubkey() -> Pubkey {
@@ -374,4 +461,58 @@ mod tests {
         assert_eq!(explicit_instruction.accounts[5].pubkey, token_program_id);
         assert_eq!(explicit_instruction.data, vec![1]);
     }
+
+    #[test]
+    fn test_recover_nested() {
+        let wallet_address = owner_pubkey();
+        let owner_mint = mint_pubkey();
+        let nested_mint = payer_pubkey();
+
+        let instruction = recover_nested(&wallet_address, &owner_mint, &nested_mint);
+        assert_eq!(instruction.data, vec![2]);
+        assert_eq!(
+            AssociatedTokenInstruction::deserialize(&instruction.data).unwrap(),
+            AssociatedTokenInstruction::RecoverNested
+        );
+        assert_eq!(instruction.accounts.len(), 7);
+
+        let owner_ata = get_associated_token_address(&wallet_address, &owner_mint);
+        assert_eq!(
+            instruction.accounts[0].pubkey,
+            get_associated_token_address(&owner_ata, &nested_mint)
+        );
+        assert!(instruction.accounts[0].is_writable);
+        assert_eq!(
+            instruction.accounts[2].pubkey,
+            get_associated_token_address(&wallet_address, &nested_mint)
+        );
+        assert_eq!(instruction.accounts[3].pubkey, owner_ata);
+        assert!(!instruction.accounts[3].is_writable);
+        assert_eq!(instruction.accounts[5].pubkey, wallet_address);
+        assert!(instruction.accounts[5].is_signer);
+        assert_eq!(
+            instruction.accounts[6].pubkey,
+            Pubkey::from_base58(TOKEN_PROGRAM_ID).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_program_id_helpers() {
+        let program_ids = [
+            (associated_token_program(), ASSOCIATED_TOKEN_PROGRAM_ID),
+            (system_program(), SYSTEM_PROGRAM_ID),
+            (rent_sysvar(), SYSVAR_RENT_ID),
+            (token_program(), TOKEN_PROGRAM_ID),
+            (token_2022_program(), TOKEN_2022_PROGRAM_ID),
+        ];
+        for (pubkey, address) in program_ids {
+            assert_eq!(pubkey, Pubkey::from_base58(address).unwrap());
+        }
+
+        let instruction =
+            create_associated_token_account(&owner_pubkey(), &owner_pubkey(), &mint_pubkey());
+        assert_eq!(instruction.program_id, associated_token_program());
+        assert_eq!(instruction.accounts[4].pubkey, system_program());
+        assert_eq!(instruction.accounts[5].pubkey, token_program());
+    }
 }
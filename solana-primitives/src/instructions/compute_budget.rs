@@ -1,14 +1,22 @@
-use crate::instructions::program_ids::{compute_budget_program, system_program};
-use crate::types::Instruction;
+use crate::data::{DataReader, Field};
+use crate::error::{Result, SolanaError};
+use crate::instructions::program_ids::compute_budget_program;
+use crate::instructions::system::is_advance_nonce_instruction;
+use crate::types::{ComputeUnits, Instruction, MicroLamports};
+use alloc::vec::Vec;
+use alloc::{format, vec};
 
 /// Compute budget instruction discriminant for setting compute unit limit.
 pub const SET_COMPUTE_UNIT_LIMIT_DISCRIMINANT: u8 = 2;
 /// Compute budget instruction discriminant for setting compute unit price.
 pub const SET_COMPUTE_UNIT_PRICE_DISCRIMINANT: u8 = 3;
-/// System program instruction discriminant for `AdvanceNonceAccount` (4-byte LE encoded).
-const ADVANCE_NONCE_ACCOUNT_DISCRIMINANT: [u8; 4] = [4, 0, 0, 0];
+/// Units field of a `SetComputeUnitLimit` payload.
+pub const COMPUTE_UNIT_LIMIT_FIELD: Field<u32> = Field::at(1);
+/// Micro-lamports field of a `SetComputeUnitPrice` payload.
+pub const COMPUTE_UNIT_PRICE_FIELD: Field<u64> = Field::at(1);
 
 /// Compute Budget Instructions
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ComputeBudgetInstruction {
     /// Request a specific transaction-wide compute unit limit
     RequestUnits {
@@ -62,6 +70,32 @@ impl ComputeBudgetInstruction {
         }
         data
     }
+
+    /// Deserialize compute budget instruction data, the inverse of [`ComputeBudgetInstruction::serialize`]
+    pub fn deserialize(data: &[u8]) -> Result<Self> {
+        let mut reader = DataReader::new(data);
+        let instruction = match reader.read::<u8>()? {
+            0 => Self::RequestUnits {
+                units: reader.read()?,
+                additional_fee: reader.read()?,
+            },
+            1 => Self::RequestHeapFrame {
+                bytes: reader.read()?,
+            },
+            SET_COMPUTE_UNIT_LIMIT_DISCRIMINANT => Self::SetComputeUnitLimit {
+                units: reader.read()?,
+            },
+            SET_COMPUTE_UNIT_PRICE_DISCRIMINANT => Self::SetComputeUnitPrice {
+                micro_lamports: reader.read()?,
+            },
+            tag => {
+                return Err(SolanaError::DeserializationError(format!(
+                    "unknown compute budget instruction: {tag}"
+                )));
+            }
+        };
+        Ok(instruction)
+    }
 }
 
 /// Request a specific transaction-wide compute unit limit
@@ -87,47 +121,50 @@ pub fn request_heap_frame(bytes: u32) -> Instruction {
 }
 
 /// Set a specific compute unit price
-pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+pub fn set_compute_unit_price(price: MicroLamports) -> Instruction {
     Instruction {
         program_id: compute_budget_program(),
         accounts: vec![],
-        data: ComputeBudgetInstruction::SetComputeUnitPrice { micro_lamports }.serialize(),
+        data: ComputeBudgetInstruction::SetComputeUnitPrice {
+            micro_lamports: price.get(),
+        }
+        .serialize(),
     }
 }
 
 /// Set a specific compute unit limit
-pub fn set_compute_unit_limit(units: u32) -> Instruction {
+pub fn set_compute_unit_limit(units: ComputeUnits) -> Instruction {
     Instruction {
         program_id: compute_budget_program(),
         accounts: vec![],
-        data: ComputeBudgetInstruction::SetComputeUnitLimit { units }.serialize(),
+        data: ComputeBudgetInstruction::SetComputeUnitLimit { units: units.get() }.serialize(),
     }
 }
 
 /// Parse compute unit limit from one compute budget instruction payload.
-pub fn parse_compute_unit_limit_data(data: &[u8]) -> Option<u32> {
-    if data.len() == 5 && data[0] == SET_COMPUTE_UNIT_LIMIT_DISCRIMINANT {
-        let mut bytes = [0u8; 4];
-        bytes.copy_from_slice(&data[1..5]);
-        Some(u32::from_le_bytes(bytes))
+pub fn parse_compute_unit_limit_data(data: &[u8]) -> Option<ComputeUnits> {
+    if data.len() == COMPUTE_UNIT_LIMIT_FIELD.end()
+        && data[0] == SET_COMPUTE_UNIT_LIMIT_DISCRIMINANT
+    {
+        COMPUTE_UNIT_LIMIT_FIELD.read(data).map(ComputeUnits)
     } else {
         None
     }
 }
 
 /// Parse compute unit price from one compute budget instruction payload.
-pub fn parse_compute_unit_price_data(data: &[u8]) -> Option<u64> {
-    if data.len() == 9 && data[0] == SET_COMPUTE_UNIT_PRICE_DISCRIMINANT {
-        let mut bytes = [0u8; 8];
-        bytes.copy_from_slice(&data[1..9]);
-        Some(u64::from_le_bytes(bytes))
+pub fn parse_compute_unit_price_data(data: &[u8]) -> Option<MicroLamports> {
+    if data.len() == COMPUTE_UNIT_PRICE_FIELD.end()
+        && data[0] == SET_COMPUTE_UNIT_PRICE_DISCRIMINANT
+    {
+        COMPUTE_UNIT_PRICE_FIELD.read(data).map(MicroLamports)
     } else {
         None
     }
 }
 
 /// Get the first compute unit limit present in a list of instructions.
-pub fn get_compute_unit_limit(instructions: &[Instruction]) -> Option<u32> {
+pub fn get_compute_unit_limit(instructions: &[Instruction]) -> Option<ComputeUnits> {
     let compute_budget_program_id = compute_budget_program();
     instructions.iter().find_map(|instruction| {
         if instruction.program_id == compute_budget_program_id {
@@ -140,7 +177,10 @@ pub fn get_compute_unit_limit(instructions: &[Instruction]) -> Option<u32> {
 
 /// Ensure a compute unit price instruction exists at the beginning of the instruction list.
 /// Returns true when the instruction was inserted and false when it already existed.
-pub fn ensure_compute_unit_price(instructions: &mut Vec<Instruction>, micro_lamports: u64) -> bool {
+pub fn ensure_compute_unit_price(
+    instructions: &mut Vec<Instruction>,
+    price: MicroLamports,
+) -> bool {
     let compute_budget_program_id = compute_budget_program();
     let has_price = instructions.iter().any(|instruction| {
         instruction.program_id == compute_budget_program_id
@@ -152,15 +192,12 @@ pub fn ensure_compute_unit_price(instructions: &mut Vec<Instruction>, micro_lamp
     }
 
     // Durable-nonce txs require AdvanceNonceAccount as instruction 0; insert after it.
-    let insert_pos = if instructions.first().is_some_and(|ix| {
-        ix.program_id == system_program()
-            && ix.data.get(0..4) == Some(&ADVANCE_NONCE_ACCOUNT_DISCRIMINANT[..])
-    }) {
-        1
-    } else {
-        0
-    };
-    instructions.insert(insert_pos, set_compute_unit_price(micro_lamports));
+    let insert_pos = usize::from(
+        instructions
+            .first()
+            .is_some_and(is_advance_nonce_instruction),
+    );
+    instructions.insert(insert_pos, set_compute_unit_price(price));
     true
 }
 
@@ -173,8 +210,8 @@ mod tests {
 
     #[test]
     fn test_compute_budget_discriminants() {
-        let limit_ix = set_compute_unit_limit(200_000);
-        let price_ix = set_compute_unit_price(1_000);
+        let limit_ix = set_compute_unit_limit(ComputeUnits(200_000));
+        let price_ix = set_compute_unit_price(MicroLamports(1_000));
 
         assert_eq!(
             limit_ix.data[0], SET_COMPUTE_UNIT_LIMIT_DISCRIMINANT,
@@ -194,8 +231,11 @@ mod tests {
 
         assert_eq!(get_compute_unit_limit(&instructions), None);
 
-        instructions.insert(0, set_compute_unit_limit(250_000));
-        assert_eq!(get_compute_unit_limit(&instructions), Some(250_000));
+        instructions.insert(0, set_compute_unit_limit(ComputeUnits(250_000)));
+        assert_eq!(
+            get_compute_unit_limit(&instructions),
+            Some(ComputeUnits(250_000))
+        );
     }
 
     #[test]
@@ -204,21 +244,21 @@ mod tests {
         let recipient = Pubkey::new([2u8; 32]);
         let mut instructions = vec![transfer(&payer, &recipient, 10)];
 
-        let inserted = ensure_compute_unit_price(&mut instructions, 5_000);
+        let inserted = ensure_compute_unit_price(&mut instructions, MicroLamports(5_000));
         assert!(inserted);
         assert_eq!(instructions.len(), 2);
         assert_eq!(instructions[0].program_id, compute_budget_program());
         assert_eq!(
             parse_compute_unit_price_data(&instructions[0].data),
-            Some(5_000)
+            Some(MicroLamports(5_000))
         );
 
-        let inserted_again = ensure_compute_unit_price(&mut instructions, 9_999);
+        let inserted_again = ensure_compute_unit_price(&mut instructions, MicroLamports(9_999));
         assert!(!inserted_again);
         assert_eq!(instructions.len(), 2);
         assert_eq!(
             parse_compute_unit_price_data(&instructions[0].data),
-            Some(5_000)
+            Some(MicroLamports(5_000))
         );
 
         assert_eq!(instructions[1].program_id, system_program());
@@ -235,7 +275,7 @@ mod tests {
             crate::instructions::system::advance_nonce_account(&nonce_pubkey, &authority_pubkey);
         let mut instructions = vec![advance_nonce_ix.clone(), transfer(&payer, &recipient, 10)];
 
-        let inserted = ensure_compute_unit_price(&mut instructions, 5_000);
+        let inserted = ensure_compute_unit_price(&mut instructions, MicroLamports(5_000));
         assert!(inserted);
         assert_eq!(instructions.len(), 3);
 
@@ -245,7 +285,7 @@ mod tests {
         assert_eq!(instructions[1].program_id, compute_budget_program());
         assert_eq!(
             parse_compute_unit_price_data(&instructions[1].data),
-            Some(5_000)
+            Some(MicroLamports(5_000))
         );
     }
 }
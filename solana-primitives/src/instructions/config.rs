@@ -0,0 +1,352 @@
+use crate::error::{Result, SolanaError};
+use crate::instructions::program_ids::config_program;
+use crate::instructions::system::create_account as create_system_account;
+use crate::pubkey;
+use crate::short_vec::{decode_compact_u16_len, encode_length_to_compact_u16_bytes};
+use crate::types::{AccountMeta, Instruction, Pubkey};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use serde::{Deserialize, Serialize};
+
+/// Key that marks a config account as a validator info record
+pub const VALIDATOR_INFO_ID: &str = "Va1idator1nfo111111111111111111111111111111";
+
+/// Maximum length of the validator info JSON string
+pub const MAX_VALIDATOR_INFO: usize = 576;
+
+/// Helper function to get the validator info marker Pubkey
+pub const fn validator_info_key() -> Pubkey {
+    pubkey!(VALIDATOR_INFO_ID)
+}
+
+/// A key listed at the start of a config account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigKey {
+    /// The key
+    pub pubkey: Pubkey,
+    /// Whether the key must sign updates to the account
+    pub is_signer: bool,
+}
+
+impl ConfigKey {
+    /// Create a config key
+    pub fn new(pubkey: Pubkey, is_signer: bool) -> Self {
+        Self { pubkey, is_signer }
+    }
+}
+
+/// Serialize config keys as a short_vec of `(pubkey, is_signer)` pairs
+pub fn serialize_config_keys(keys: &[ConfigKey]) -> Result<Vec<u8>> {
+    let mut data =
+        encode_length_to_compact_u16_bytes(keys.len()).map_err(SolanaError::SerializationError)?;
+    for key in keys {
+        data.extend_from_slice(key.pubkey.as_bytes());
+        data.push(key.is_signer as u8);
+    }
+    Ok(data)
+}
+
+/// Account space needed for `num_keys` keys and up to `max_data_len` bytes of data
+pub fn config_account_space(num_keys: usize, max_data_len: usize) -> u64 {
+    let keys_len = encode_length_to_compact_u16_bytes(num_keys)
+        .map(|prefix| prefix.len())
+        .unwrap_or(3);
+    (keys_len + num_keys * 33 + max_data_len) as u64
+}
+
+/// Store keys and data into a config account
+///
+/// `data` is the already serialized config payload. The config account signs when it has
+/// no keys stored yet; afterwards every signer key listed in `keys` must sign.
+pub fn store(
+    config_account: &Pubkey,
+    is_config_signer: bool,
+    keys: &[ConfigKey],
+    data: &[u8],
+) -> Result<Instruction> {
+    let mut accounts = vec![AccountMeta::new(*config_account, is_config_signer, true)];
+    accounts.extend(
+        keys.iter()
+            .filter(|key| key.is_signer && key.pubkey != *config_account)
+            .map(|key| AccountMeta::new_signer(key.pubkey)),
+    );
+
+    let mut instruction_data = serialize_config_keys(keys)?;
+    instruction_data.extend_from_slice(data);
+
+    Ok(Instruction {
+        program_id: config_program(),
+        accounts,
+        data: instruction_data,
+    })
+}
+
+/// Create and initialize a config account sized for `keys` and `max_data_len` bytes of data
+///
+/// `default_data` is written as the initial payload, e.g. an empty serialized record.
+pub fn create_account(
+    payer: &Pubkey,
+    config_account: &Pubkey,
+    lamports: u64,
+    keys: &[ConfigKey],
+    default_data: &[u8],
+    max_data_len: usize,
+) -> Result<Vec<Instruction>> {
+    let space = config_account_space(keys.len(), max_data_len);
+    let mut data = serialize_config_keys(keys)?;
+    data.extend_from_slice(default_data);
+
+    Ok(vec![
+        create_system_account(payer, config_account, lamports, space, &config_program()),
+        Instruction {
+            program_id: config_program(),
+            accounts: vec![AccountMeta::new_signer_writable(*config_account)],
+            data,
+        },
+    ])
+}
+
+/// A parsed config account: its keys followed by the raw config payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigAccount {
+    /// Keys stored at the start of the account
+    pub keys: Vec<ConfigKey>,
+    /// The config payload, including any unused trailing space
+    pub data: Vec<u8>,
+}
+
+impl ConfigAccount {
+    /// Parse config account data
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let (num_keys, mut offset) = decode_compact_u16_len(data)
+            .map_err(|e| SolanaError::DeserializationError(e.to_string()))?;
+        let keys_end = num_keys
+            .checked_mul(33)
+            .and_then(|len| len.checked_add(offset))
+            .filter(|end| *end <= data.len())
+            .ok_or_else(|| {
+                SolanaError::DeserializationError(format!(
+                    "config account too short for {num_keys} keys"
+                ))
+            })?;
+
+        let mut keys = Vec::with_capacity(num_keys);
+        while offset < keys_end {
+            let mut pubkey = [0u8; 32];
+            pubkey.copy_from_slice(&data[offset..offset + 32]);
+            let is_signer = match data[offset + 32] {
+                0 => false,
+                1 => true,
+                other => {
+                    return Err(SolanaError::DeserializationError(format!(
+                        "invalid config key signer flag: {other}"
+                    )));
+                }
+            };
+            keys.push(ConfigKey::new(Pubkey::new(pubkey), is_signer));
+            offset += 33;
+        }
+
+        Ok(Self {
+            keys,
+            data: data[keys_end..].to_vec(),
+        })
+    }
+}
+
+/// Fields of the validator info JSON, as written by `solana validator-info publish`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorInfoFields {
+    /// Validator name
+    pub name: String,
+    /// Validator website
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+    /// Validator description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    /// Keybase username
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keybase_username: Option<String>,
+    /// URL of the validator icon
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_url: Option<String>,
+}
+
+/// A validator info record stored in a config account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorInfo {
+    /// The validator identity that signed the record
+    pub identity: Pubkey,
+    /// The info JSON string, parseable as [`ValidatorInfoFields`]
+    pub info: String,
+}
+
+impl ValidatorInfo {
+    /// Create a validator info record
+    pub fn new(identity: Pubkey, info: impl Into<String>) -> Self {
+        Self {
+            identity,
+            info: info.into(),
+        }
+    }
+
+    /// Parse a validator info record from config account data
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let account = ConfigAccount::from_account_data(data)?;
+        let identity = match account.keys.as_slice() {
+            [marker, identity] if marker.pubkey == validator_info_key() && identity.is_signer => {
+                identity.pubkey
+            }
+            _ => {
+                return Err(SolanaError::DeserializationError(
+                    "config account is not a validator info record".to_string(),
+                ));
+            }
+        };
+
+        let info = account
+            .data
+            .get(..8)
+            .map(|len| u64::from_le_bytes(len.try_into().unwrap()) as usize)
+            .filter(|len| *len <= MAX_VALIDATOR_INFO)
+            .and_then(|len| account.data.get(8..8 + len))
+            .ok_or_else(|| {
+                SolanaError::DeserializationError("invalid validator info length".to_string())
+            })?;
+        let info = String::from_utf8(info.to_vec())
+            .map_err(|e| SolanaError::DeserializationError(e.to_string()))?;
+
+        Ok(Self { identity, info })
+    }
+
+    /// Config keys for this record
+    pub fn keys(&self) -> [ConfigKey; 2] {
+        [
+            ConfigKey::new(validator_info_key(), false),
+            ConfigKey::new(self.identity, true),
+        ]
+    }
+
+    /// Serialize the info string as the config payload
+    pub fn serialize_data(&self) -> Result<Vec<u8>> {
+        if self.info.len() > MAX_VALIDATOR_INFO {
+            return Err(SolanaError::SerializationError(format!(
+                "validator info is {} bytes, maximum is {}",
+                self.info.len(),
+                MAX_VALIDATOR_INFO
+            )));
+        }
+        let mut data = (self.info.len() as u64).to_le_bytes().to_vec();
+        data.extend_from_slice(self.info.as_bytes());
+        Ok(data)
+    }
+
+    /// Account space needed for a validator info record
+    pub fn account_space() -> u64 {
+        config_account_space(2, 8 + MAX_VALIDATOR_INFO)
+    }
+}
+
+/// Create a validator info account and publish the record to it
+pub fn publish_validator_info(
+    payer: &Pubkey,
+    info_account: &Pubkey,
+    lamports: u64,
+    validator_info: &ValidatorInfo,
+) -> Result<Vec<Instruction>> {
+    let keys = validator_info.keys();
+    let mut instructions = create_account(
+        payer,
+        info_account,
+        lamports,
+        &keys,
+        &0u64.to_le_bytes(),
+        8 + MAX_VALIDATOR_INFO,
+    )?;
+    instructions.push(store(
+        info_account,
+        true,
+        &keys,
+        &validator_info.serialize_data()?,
+    )?);
+    Ok(instructions)
+}
+
+/// Update the record in an existing validator info account
+pub fn update_validator_info(
+    info_account: &Pubkey,
+    validator_info: &ValidatorInfo,
+) -> Result<Instruction> {
+    store(
+        info_account,
+        false,
+        &validator_info.keys(),
+        &validator_info.serialize_data()?,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validator_info_roundtrip() {
+        let payer = Pubkey::new([1u8; 32]);
+        let info_account = Pubkey::new([2u8; 32]);
+        let identity = Pubkey::new([3u8; 32]);
+        let record = ValidatorInfo::new(identity, r#"{"name":"Example Validator"}"#);
+
+        let instructions = publish_validator_info(&payer, &info_account, 1, &record).unwrap();
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[1].data[0], 2);
+        assert_eq!(instructions[1].data.len(), 1 + 66 + 8);
+        assert_eq!(instructions[2].program_id, config_program());
+        let metas: Vec<_> = instructions[2]
+            .accounts
+            .iter()
+            .map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable))
+            .collect();
+        assert_eq!(
+            metas,
+            vec![(info_account, true, true), (identity, true, false)]
+        );
+
+        // The account holds the instruction payload followed by unused space
+        let mut account_data = instructions[2].data.clone();
+        account_data.resize(ValidatorInfo::account_space() as usize, 0);
+        assert_eq!(account_data.len(), 651);
+
+        let parsed = ValidatorInfo::from_account_data(&account_data).unwrap();
+        assert_eq!(parsed, record);
+
+        let update = update_validator_info(&info_account, &record).unwrap();
+        assert!(!update.accounts[0].is_signer);
+        assert_eq!(update.data, instructions[2].data);
+    }
+
+    #[test]
+    fn test_config_account_rejects_truncated_keys() {
+        let keys = [ConfigKey::new(Pubkey::new([1u8; 32]), true)];
+        let data = serialize_config_keys(&keys).unwrap();
+        assert_eq!(
+            ConfigAccount::from_account_data(&data).unwrap().keys,
+            keys.to_vec()
+        );
+        assert!(ConfigAccount::from_account_data(&data[..20]).is_err());
+
+        // Not a validator info record: the marker key is missing
+        let mut data = data;
+        data.extend_from_slice(&[0u8; 8]);
+        assert!(ValidatorInfo::from_account_data(&data).is_err());
+    }
+
+    #[test]
+    fn test_validator_info_rejects_oversized_info() {
+        let record = ValidatorInfo::new(Pubkey::new([3u8; 32]), "x".repeat(MAX_VALIDATOR_INFO + 1));
+        assert!(record.serialize_data().is_err());
+    }
+}
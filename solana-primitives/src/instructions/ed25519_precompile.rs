@@ -0,0 +1,203 @@
+use crate::data::DataReader;
+use crate::error::{Result, SolanaError};
+use crate::instructions::program_ids::ed25519_program;
+use crate::types::{Instruction, Pubkey, SignatureBytes};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+/// Encoded size of one [`Ed25519SignatureOffsets`] entry
+pub const SIGNATURE_OFFSETS_SERIALIZED_SIZE: usize = 14;
+/// Start of the offsets table, after the signature count and a padding byte
+pub const SIGNATURE_OFFSETS_START: usize = 2;
+/// Instruction index that refers to the precompile instruction itself
+pub const CURRENT_INSTRUCTION_INDEX: u16 = u16::MAX;
+
+/// Where the precompile finds one signature, public key and message.
+///
+/// Offsets are into the data of the instruction at the matching index, or of the precompile
+/// instruction itself for [`CURRENT_INSTRUCTION_INDEX`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ed25519SignatureOffsets {
+    /// Offset of the 64-byte signature
+    pub signature_offset: u16,
+    /// Instruction holding the signature
+    pub signature_instruction_index: u16,
+    /// Offset of the 32-byte public key
+    pub public_key_offset: u16,
+    /// Instruction holding the public key
+    pub public_key_instruction_index: u16,
+    /// Offset of the signed message
+    pub message_data_offset: u16,
+    /// Length of the signed message
+    pub message_data_size: u16,
+    /// Instruction holding the message
+    pub message_instruction_index: u16,
+}
+
+impl Ed25519SignatureOffsets {
+    /// Encode as the precompile's little-endian table entry
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_OFFSETS_SERIALIZED_SIZE] {
+        let mut bytes = [0u8; SIGNATURE_OFFSETS_SERIALIZED_SIZE];
+        let fields = [
+            self.signature_offset,
+            self.signature_instruction_index,
+            self.public_key_offset,
+            self.public_key_instruction_index,
+            self.message_data_offset,
+            self.message_data_size,
+            self.message_instruction_index,
+        ];
+        for (chunk, field) in bytes.chunks_exact_mut(2).zip(fields) {
+            chunk.copy_from_slice(&field.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decode a table entry
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let mut reader = DataReader::new(data);
+        Ok(Self {
+            signature_offset: reader.read()?,
+            signature_instruction_index: reader.read()?,
+            public_key_offset: reader.read()?,
+            public_key_instruction_index: reader.read()?,
+            message_data_offset: reader.read()?,
+            message_data_size: reader.read()?,
+            message_instruction_index: reader.read()?,
+        })
+    }
+}
+
+/// Verify signatures located by `offsets`, with `payload` appended after the offsets table.
+///
+/// Offsets into this instruction count from the start of its data, so they include the
+/// [`SIGNATURE_OFFSETS_START`] header and the table itself.
+pub fn verify_with_offsets(
+    offsets: &[Ed25519SignatureOffsets],
+    payload: &[u8],
+) -> Result<Instruction> {
+    let num_signatures = u8::try_from(offsets.len()).map_err(|_| {
+        SolanaError::GenericError(format!(
+            "{} ed25519 signatures, maximum is 255",
+            offsets.len()
+        ))
+    })?;
+    let mut data = Vec::with_capacity(
+        SIGNATURE_OFFSETS_START + offsets.len() * SIGNATURE_OFFSETS_SERIALIZED_SIZE + payload.len(),
+    );
+    data.extend_from_slice(&[num_signatures, 0]);
+    for entry in offsets {
+        data.extend_from_slice(&entry.to_bytes());
+    }
+    data.extend_from_slice(payload);
+
+    Ok(Instruction {
+        program_id: ed25519_program(),
+        accounts: vec![],
+        data,
+    })
+}
+
+/// Verify one signature of `message` by `pubkey`, carried in the instruction itself
+pub fn verify(pubkey: &Pubkey, signature: &SignatureBytes, message: &[u8]) -> Result<Instruction> {
+    let public_key_offset = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+    let signature_offset = public_key_offset + 32;
+    let message_data_offset = signature_offset + 64;
+    let message_data_size = u16::try_from(message.len())
+        .ok()
+        .filter(|size| message_data_offset + *size as usize <= u16::MAX as usize)
+        .ok_or_else(|| {
+            SolanaError::GenericError(format!(
+                "ed25519 message is {} bytes, too long to address",
+                message.len()
+            ))
+        })?;
+
+    let offsets = Ed25519SignatureOffsets {
+        signature_offset: signature_offset as u16,
+        signature_instruction_index: CURRENT_INSTRUCTION_INDEX,
+        public_key_offset: public_key_offset as u16,
+        public_key_instruction_index: CURRENT_INSTRUCTION_INDEX,
+        message_data_offset: message_data_offset as u16,
+        message_data_size,
+        message_instruction_index: CURRENT_INSTRUCTION_INDEX,
+    };
+    let mut payload = Vec::with_capacity(32 + 64 + message.len());
+    payload.extend_from_slice(pubkey.as_bytes());
+    payload.extend_from_slice(signature.as_bytes());
+    payload.extend_from_slice(message);
+    verify_with_offsets(&[offsets], &payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{Keypair, verify_signature};
+
+    #[test]
+    fn test_verify_layout() {
+        let keypair = Keypair::from_seed(&[3u8; 32]).unwrap();
+        let message = b"bridge message";
+        let signature = keypair.sign_message(message);
+
+        let instruction = verify(&keypair.pubkey(), &signature, message).unwrap();
+        assert_eq!(instruction.program_id, ed25519_program());
+        assert_eq!(&instruction.data[..2], &[1, 0]);
+
+        let offsets = Ed25519SignatureOffsets::from_bytes(&instruction.data[2..16]).unwrap();
+        assert_eq!(
+            offsets,
+            Ed25519SignatureOffsets {
+                signature_offset: 48,
+                signature_instruction_index: u16::MAX,
+                public_key_offset: 16,
+                public_key_instruction_index: u16::MAX,
+                message_data_offset: 112,
+                message_data_size: message.len() as u16,
+                message_instruction_index: u16::MAX,
+            }
+        );
+
+        let data = &instruction.data;
+        let slice = |offset: u16, len: usize| &data[offset as usize..offset as usize + len];
+        let pubkey = Pubkey::try_from(slice(offsets.public_key_offset, 32)).unwrap();
+        let signature = SignatureBytes::try_from(slice(offsets.signature_offset, 64)).unwrap();
+        let signed = slice(
+            offsets.message_data_offset,
+            offsets.message_data_size as usize,
+        );
+        assert_eq!(pubkey, keypair.pubkey());
+        assert!(verify_signature(&pubkey, signed, &signature));
+    }
+
+    #[test]
+    fn test_verify_with_offsets_references_other_instructions() {
+        let entry = Ed25519SignatureOffsets {
+            signature_offset: 1,
+            signature_instruction_index: 2,
+            public_key_offset: 65,
+            public_key_instruction_index: 2,
+            message_data_offset: 0,
+            message_data_size: 10,
+            message_instruction_index: 3,
+        };
+        let instruction = verify_with_offsets(&[entry, entry], &[]).unwrap();
+        assert_eq!(
+            instruction.data.len(),
+            2 + 2 * SIGNATURE_OFFSETS_SERIALIZED_SIZE
+        );
+        assert_eq!(instruction.data[0], 2);
+        assert_eq!(
+            Ed25519SignatureOffsets::from_bytes(&instruction.data[16..]).unwrap(),
+            entry
+        );
+        assert!(
+            verify(
+                &Pubkey::new([1; 32]),
+                &SignatureBytes::default(),
+                &[0; 65_536]
+            )
+            .is_err()
+        );
+    }
+}
@@ -1,5 +1,12 @@
-use crate::instructions::program_ids::MEMO_PROGRAM_ID;
+use crate::error::{Result, SolanaError};
+use crate::instructions::program_ids::{memo_program, memo_v1_program};
 use crate::types::{AccountMeta, Instruction, Pubkey};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Longest memo that fits a transaction alongside one signer
+pub const MAX_MEMO_LENGTH: usize = 566;
 
 /// Create a memo instruction
 pub fn memo(memo_text: &str, signers: &[&Pubkey]) -> Instruction {
@@ -13,8 +20,78 @@ pub fn memo(memo_text: &str, signers: &[&Pubkey]) -> Instruction {
         .collect::<Vec<AccountMeta>>();
 
     Instruction {
-        program_id: Pubkey::from_base58(MEMO_PROGRAM_ID).unwrap(),
+        program_id: memo_program(),
         accounts: account_metas,
         data: memo_text.as_bytes().to_vec(),
     }
 }
+
+/// Create a memo instruction for the current memo program, validating the memo.
+///
+/// Every account in `signers` must sign the transaction.
+pub fn memo_checked(memo: &[u8], signers: &[&Pubkey]) -> Result<Instruction> {
+    memo_with_program_id(memo, signers, &memo_program())
+}
+
+/// Create a memo instruction for the legacy (v1) memo program, validating the memo.
+///
+/// The v1 program ignores accounts; `signers` still have to sign the transaction.
+pub fn memo_v1(memo: &[u8], signers: &[&Pubkey]) -> Result<Instruction> {
+    memo_with_program_id(memo, signers, &memo_v1_program())
+}
+
+/// Create a memo instruction for the given memo program, rejecting memos that are not UTF-8
+/// or longer than [`MAX_MEMO_LENGTH`]
+pub fn memo_with_program_id(
+    memo: &[u8],
+    signers: &[&Pubkey],
+    memo_program_id: &Pubkey,
+) -> Result<Instruction> {
+    if memo.len() > MAX_MEMO_LENGTH {
+        return Err(SolanaError::GenericError(format!(
+            "memo is {} bytes, maximum is {MAX_MEMO_LENGTH}",
+            memo.len()
+        )));
+    }
+    parse_memo(memo)?;
+    Ok(Instruction {
+        program_id: *memo_program_id,
+        accounts: signers
+            .iter()
+            .map(|signer| AccountMeta::new_signer(**signer))
+            .collect(),
+        data: memo.to_vec(),
+    })
+}
+
+/// Decode memo instruction data as UTF-8 text
+pub fn parse_memo(data: &[u8]) -> Result<String> {
+    String::from_utf8(data.to_vec())
+        .map_err(|e| SolanaError::DeserializationError(format!("invalid memo: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::program_ids::MEMO_V1_PROGRAM_ID;
+
+    #[test]
+    fn test_memo_checked() {
+        let signer = Pubkey::new([1u8; 32]);
+
+        let instruction = memo_checked("hello".as_bytes(), &[&signer]).unwrap();
+        let unchecked = memo("hello", &[&signer]);
+        assert_eq!(instruction.program_id, unchecked.program_id);
+        assert_eq!(instruction.accounts[0].pubkey, unchecked.accounts[0].pubkey);
+        assert!(instruction.accounts[0].is_signer && !instruction.accounts[0].is_writable);
+        assert_eq!(instruction.data, unchecked.data);
+
+        let instruction = memo_v1(b"hi", &[&signer]).unwrap();
+        assert_eq!(instruction.program_id.to_base58(), MEMO_V1_PROGRAM_ID);
+        assert!(instruction.accounts[0].is_signer);
+
+        assert!(memo_checked(&[0xff, 0xfe], &[]).is_err());
+        assert!(memo_checked(&[b'a'; MAX_MEMO_LENGTH], &[]).is_ok());
+        assert!(memo_checked(&[b'a'; MAX_MEMO_LENGTH + 1], &[]).is_err());
+    }
+}
@@ -1,13 +1,20 @@
 // Re-export instruction modules
+pub mod address_lookup_table;
 pub mod anchor;
 pub mod associated_token;
 pub mod compute_budget;
+pub mod config;
+pub mod ed25519_precompile;
 pub mod memo;
+pub mod secp256k1_precompile;
+pub mod stake;
 pub mod system;
 pub mod token;
+pub mod token_2022;
 
 // Program IDs
 pub mod program_ids {
+    use crate::pubkey;
     use crate::types::Pubkey;
 
     /// System program ID
@@ -25,52 +32,140 @@ pub mod program_ids {
     /// Memo program ID
     pub const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
 
+    /// Legacy (v1) Memo program ID
+    pub const MEMO_V1_PROGRAM_ID: &str = "Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo";
+
     /// BPF Loader program ID
     pub const BPF_LOADER_PROGRAM_ID: &str = "BPFLoaderUpgradeab1e11111111111111111111111";
 
     /// Compute Budget program ID
     pub const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
 
+    /// Address Lookup Table program ID
+    pub const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: &str = "AddressLookupTab1e1111111111111111111111111";
+
+    /// Config program ID
+    pub const CONFIG_PROGRAM_ID: &str = "Config1111111111111111111111111111111111111";
+
+    /// Stake program ID
+    pub const STAKE_PROGRAM_ID: &str = "Stake11111111111111111111111111111111111111";
+
+    /// Ed25519 signature verification precompile ID
+    pub const ED25519_PROGRAM_ID: &str = "Ed25519SigVerify111111111111111111111111111";
+
+    /// Secp256k1 signature verification precompile ID
+    pub const SECP256K1_PROGRAM_ID: &str = "KeccakSecp256k11111111111111111111111111111";
+
     /// Rent sysvar ID
     pub const SYSVAR_RENT_ID: &str = "SysvarRent111111111111111111111111111111111";
 
     /// Helper function to get System program Pubkey
-    pub fn system_program() -> Pubkey {
-        Pubkey::from_base58(SYSTEM_PROGRAM_ID).unwrap()
+    pub const fn system_program() -> Pubkey {
+        pubkey!(SYSTEM_PROGRAM_ID)
     }
 
     /// Helper function to get Token program Pubkey
-    pub fn token_program() -> Pubkey {
-        Pubkey::from_base58(TOKEN_PROGRAM_ID).unwrap()
+    pub const fn token_program() -> Pubkey {
+        pubkey!(TOKEN_PROGRAM_ID)
     }
 
     /// Helper function to get Token 2022 program Pubkey
-    pub fn token_2022_program() -> Pubkey {
-        Pubkey::from_base58(TOKEN_2022_PROGRAM_ID).unwrap()
+    pub const fn token_2022_program() -> Pubkey {
+        pubkey!(TOKEN_2022_PROGRAM_ID)
     }
 
     /// Helper function to get Associated Token program Pubkey
-    pub fn associated_token_program() -> Pubkey {
-        Pubkey::from_base58(ASSOCIATED_TOKEN_PROGRAM_ID).unwrap()
+    pub const fn associated_token_program() -> Pubkey {
+        pubkey!(ASSOCIATED_TOKEN_PROGRAM_ID)
     }
 
     /// Helper function to get Memo program Pubkey
-    pub fn memo_program() -> Pubkey {
-        Pubkey::from_base58(MEMO_PROGRAM_ID).unwrap()
+    pub const fn memo_program() -> Pubkey {
+        pubkey!(MEMO_PROGRAM_ID)
+    }
+
+    /// Helper function to get legacy (v1) Memo program Pubkey
+    pub const fn memo_v1_program() -> Pubkey {
+        pubkey!(MEMO_V1_PROGRAM_ID)
     }
 
     /// Helper function to get BPF Loader program Pubkey
-    pub fn bpf_loader_program() -> Pubkey {
-        Pubkey::from_base58(BPF_LOADER_PROGRAM_ID).unwrap()
+    pub const fn bpf_loader_program() -> Pubkey {
+        pubkey!(BPF_LOADER_PROGRAM_ID)
     }
 
     /// Helper function to get Compute Budget program Pubkey
-    pub fn compute_budget_program() -> Pubkey {
-        Pubkey::from_base58(COMPUTE_BUDGET_PROGRAM_ID).unwrap()
+    pub const fn compute_budget_program() -> Pubkey {
+        pubkey!(COMPUTE_BUDGET_PROGRAM_ID)
     }
 
+    /// Helper function to get Address Lookup Table program Pubkey
+    pub const fn address_lookup_table_program() -> Pubkey {
+        pubkey!(ADDRESS_LOOKUP_TABLE_PROGRAM_ID)
+    }
+
+    /// Helper function to get Config program Pubkey
+    pub const fn config_program() -> Pubkey {
+        pubkey!(CONFIG_PROGRAM_ID)
+    }
+
+    /// Helper function to get Stake program Pubkey
+    pub const fn stake_program() -> Pubkey {
+        pubkey!(STAKE_PROGRAM_ID)
+    }
+
+    /// Helper function to get Ed25519 precompile Pubkey
+    pub const fn ed25519_program() -> Pubkey {
+        pubkey!(ED25519_PROGRAM_ID)
+    }
+
+    /// Helper function to get Secp256k1 precompile Pubkey
+    pub const fn secp256k1_program() -> Pubkey {
+        pubkey!(SECP256K1_PROGRAM_ID)
+    }
+
+    /// SlotHashes sysvar ID
+    pub const SYSVAR_SLOT_HASHES_ID: &str = "SysvarS1otHashes111111111111111111111111111";
+
+    /// Clock sysvar ID
+    pub const SYSVAR_CLOCK_ID: &str = "SysvarC1ock11111111111111111111111111111111";
+
+    /// EpochSchedule sysvar ID
+    pub const SYSVAR_EPOCH_SCHEDULE_ID: &str = "SysvarEpochSchedu1e111111111111111111111111";
+
+    /// RecentBlockhashes sysvar ID
+    pub const SYSVAR_RECENT_BLOCKHASHES_ID: &str = "SysvarRecentB1ockHashes11111111111111111111";
+
+    /// StakeHistory sysvar ID
+    pub const SYSVAR_STAKE_HISTORY_ID: &str = "SysvarStakeHistory1111111111111111111111111";
+
     /// Helper function to get Rent sysvar Pubkey
-    pub fn rent_sysvar() -> Pubkey {
-        Pubkey::from_base58(SYSVAR_RENT_ID).unwrap()
+    pub const fn rent_sysvar() -> Pubkey {
+        pubkey!(SYSVAR_RENT_ID)
+    }
+
+    /// Helper function to get SlotHashes sysvar Pubkey
+    pub const fn slot_hashes_sysvar() -> Pubkey {
+        pubkey!(SYSVAR_SLOT_HASHES_ID)
+    }
+
+    /// Helper function to get Clock sysvar Pubkey
+    pub const fn clock_sysvar() -> Pubkey {
+        pubkey!(SYSVAR_CLOCK_ID)
+    }
+
+    /// Helper function to get EpochSchedule sysvar Pubkey
+    pub const fn epoch_schedule_sysvar() -> Pubkey {
+        pubkey!(SYSVAR_EPOCH_SCHEDULE_ID)
+    }
+
+    /// Helper function to get RecentBlockhashes sysvar Pubkey
+    pub const fn recent_blockhashes_sysvar() -> Pubkey {
+        pubkey!(SYSVAR_RECENT_BLOCKHASHES_ID)
+    }
+
+    /// Helper function to get StakeHistory sysvar Pubkey
+    pub const fn stake_history_sysvar() -> Pubkey {
+        pubkey!(SYSVAR_STAKE_HISTORY_ID)
     }
 }
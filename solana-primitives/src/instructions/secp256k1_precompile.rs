@@ -0,0 +1,168 @@
+use crate::crypto::secp256k1::{ETH_ADDRESS_LENGTH, SIGNATURE_LENGTH};
+use crate::data::DataReader;
+use crate::error::{Result, SolanaError};
+use crate::instructions::program_ids::secp256k1_program;
+use crate::types::Instruction;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+/// Encoded size of one [`Secp256k1SignatureOffsets`] entry
+pub const SIGNATURE_OFFSETS_SERIALIZED_SIZE: usize = 11;
+/// Start of the offsets table, after the signature count
+pub const SIGNATURE_OFFSETS_START: usize = 1;
+
+/// Where the precompile finds one signature, Ethereum address and message.
+///
+/// Offsets are into the data of the instruction at the matching index in the transaction;
+/// unlike Ed25519 there is no index meaning "this instruction".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Secp256k1SignatureOffsets {
+    /// Offset of the 64-byte signature followed by the recovery id
+    pub signature_offset: u16,
+    /// Instruction holding the signature
+    pub signature_instruction_index: u8,
+    /// Offset of the 20-byte Ethereum address
+    pub eth_address_offset: u16,
+    /// Instruction holding the Ethereum address
+    pub eth_address_instruction_index: u8,
+    /// Offset of the signed message
+    pub message_data_offset: u16,
+    /// Length of the signed message
+    pub message_data_size: u16,
+    /// Instruction holding the message
+    pub message_instruction_index: u8,
+}
+
+impl Secp256k1SignatureOffsets {
+    /// Encode as the precompile's little-endian table entry
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_OFFSETS_SERIALIZED_SIZE] {
+        let mut bytes = [0u8; SIGNATURE_OFFSETS_SERIALIZED_SIZE];
+        bytes[0..2].copy_from_slice(&self.signature_offset.to_le_bytes());
+        bytes[2] = self.signature_instruction_index;
+        bytes[3..5].copy_from_slice(&self.eth_address_offset.to_le_bytes());
+        bytes[5] = self.eth_address_instruction_index;
+        bytes[6..8].copy_from_slice(&self.message_data_offset.to_le_bytes());
+        bytes[8..10].copy_from_slice(&self.message_data_size.to_le_bytes());
+        bytes[10] = self.message_instruction_index;
+        bytes
+    }
+
+    /// Decode a table entry
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let mut reader = DataReader::new(data);
+        Ok(Self {
+            signature_offset: reader.read()?,
+            signature_instruction_index: reader.read()?,
+            eth_address_offset: reader.read()?,
+            eth_address_instruction_index: reader.read()?,
+            message_data_offset: reader.read()?,
+            message_data_size: reader.read()?,
+            message_instruction_index: reader.read()?,
+        })
+    }
+}
+
+/// Verify signatures located by `offsets`, with `payload` appended after the offsets table.
+///
+/// Offsets into this instruction count from the start of its data, so they include the
+/// [`SIGNATURE_OFFSETS_START`] header and the table itself.
+pub fn verify_with_offsets(
+    offsets: &[Secp256k1SignatureOffsets],
+    payload: &[u8],
+) -> Result<Instruction> {
+    let num_signatures = u8::try_from(offsets.len()).map_err(|_| {
+        SolanaError::GenericError(format!(
+            "{} secp256k1 signatures, maximum is 255",
+            offsets.len()
+        ))
+    })?;
+    let mut data = Vec::with_capacity(
+        SIGNATURE_OFFSETS_START + offsets.len() * SIGNATURE_OFFSETS_SERIALIZED_SIZE + payload.len(),
+    );
+    data.push(num_signatures);
+    for entry in offsets {
+        data.extend_from_slice(&entry.to_bytes());
+    }
+    data.extend_from_slice(payload);
+
+    Ok(Instruction {
+        program_id: secp256k1_program(),
+        accounts: vec![],
+        data,
+    })
+}
+
+/// Verify one recoverable signature of `message` by `eth_address`, carried in the instruction.
+///
+/// The precompile hashes `message` with Keccak-256 before recovering the signer, and reads
+/// its own data through `instruction_index`, which must be the instruction's position in
+/// the transaction.
+pub fn verify(
+    eth_address: &[u8; ETH_ADDRESS_LENGTH],
+    signature: &[u8; SIGNATURE_LENGTH],
+    recovery_id: u8,
+    message: &[u8],
+    instruction_index: u8,
+) -> Result<Instruction> {
+    let eth_address_offset = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SERIALIZED_SIZE;
+    let signature_offset = eth_address_offset + ETH_ADDRESS_LENGTH;
+    let message_data_offset = signature_offset + SIGNATURE_LENGTH + 1;
+    let message_data_size = u16::try_from(message.len())
+        .ok()
+        .filter(|size| message_data_offset + *size as usize <= u16::MAX as usize)
+        .ok_or_else(|| {
+            SolanaError::GenericError(format!(
+                "secp256k1 message is {} bytes, too long to address",
+                message.len()
+            ))
+        })?;
+
+    let offsets = Secp256k1SignatureOffsets {
+        signature_offset: signature_offset as u16,
+        signature_instruction_index: instruction_index,
+        eth_address_offset: eth_address_offset as u16,
+        eth_address_instruction_index: instruction_index,
+        message_data_offset: message_data_offset as u16,
+        message_data_size,
+        message_instruction_index: instruction_index,
+    };
+    let mut payload = Vec::with_capacity(ETH_ADDRESS_LENGTH + SIGNATURE_LENGTH + 1 + message.len());
+    payload.extend_from_slice(eth_address);
+    payload.extend_from_slice(signature);
+    payload.push(recovery_id);
+    payload.extend_from_slice(message);
+    verify_with_offsets(&[offsets], &payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_layout() {
+        let address = [0xAA; ETH_ADDRESS_LENGTH];
+        let signature = [0xBB; SIGNATURE_LENGTH];
+        let instruction = verify(&address, &signature, 1, b"hello", 0).unwrap();
+        assert_eq!(instruction.program_id, secp256k1_program());
+        assert_eq!(instruction.data[0], 1);
+
+        let offsets = Secp256k1SignatureOffsets::from_bytes(&instruction.data[1..12]).unwrap();
+        assert_eq!(
+            offsets,
+            Secp256k1SignatureOffsets {
+                signature_offset: 32,
+                signature_instruction_index: 0,
+                eth_address_offset: 12,
+                eth_address_instruction_index: 0,
+                message_data_offset: 97,
+                message_data_size: 5,
+                message_instruction_index: 0,
+            }
+        );
+        assert_eq!(offsets.to_bytes(), instruction.data[1..12]);
+        assert_eq!(&instruction.data[12..32], &address);
+        assert_eq!(&instruction.data[32..96], &signature);
+        assert_eq!(instruction.data[96], 1);
+        assert_eq!(&instruction.data[97..], b"hello");
+    }
+}
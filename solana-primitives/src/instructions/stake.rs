@@ -0,0 +1,237 @@
+use crate::data::DataReader;
+use crate::error::{Result, SolanaError};
+use crate::types::Pubkey;
+use alloc::format;
+
+/// Which stake authority an instruction changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StakeAuthorize {
+    /// Authority to delegate and deactivate
+    Staker,
+    /// Authority to withdraw and change either authority
+    Withdrawer,
+}
+
+/// Staker and withdrawer of a stake account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Authorized {
+    /// Authority to delegate and deactivate
+    pub staker: Pubkey,
+    /// Authority to withdraw
+    pub withdrawer: Pubkey,
+}
+
+/// Lockup preventing withdrawals until a time or epoch, unless signed by the custodian
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lockup {
+    /// Unix timestamp the lockup ends at
+    pub unix_timestamp: i64,
+    /// Epoch the lockup ends at
+    pub epoch: u64,
+    /// Custodian allowed to bypass the lockup
+    pub custodian: Pubkey,
+}
+
+/// Stake program instructions.
+///
+/// Only the variants needed to interpret common transactions are decoded; seeded authorize
+/// instructions and `SetLockup` variants fail to deserialize.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StakeInstruction {
+    /// Initialize a stake account
+    /// 0. `[WRITE]` Stake account
+    /// 1. `[]` Rent sysvar
+    Initialize {
+        /// Stake authorities
+        authorized: Authorized,
+        /// Withdrawal lockup
+        lockup: Lockup,
+    },
+    /// Change a stake authority
+    /// 0. `[WRITE]` Stake account
+    /// 1. `[]` Clock sysvar
+    /// 2. `[SIGNER]` Current authority
+    Authorize {
+        /// New authority
+        new_authority: Pubkey,
+        /// Which authority to change
+        stake_authorize: StakeAuthorize,
+    },
+    /// Delegate stake to a vote account
+    /// 0. `[WRITE]` Stake account
+    /// 1. `[]` Vote account
+    /// 2. `[]` Clock sysvar
+    /// 3. `[]` Stake history sysvar
+    /// 4. `[]` Stake config account
+    /// 5. `[SIGNER]` Stake authority
+    DelegateStake,
+    /// Split lamports into a new stake account
+    /// 0. `[WRITE]` Stake account
+    /// 1. `[WRITE]` Uninitialized split account
+    /// 2. `[SIGNER]` Stake authority
+    Split {
+        /// Lamports to move into the new account
+        lamports: u64,
+    },
+    /// Withdraw unstaked lamports
+    /// 0. `[WRITE]` Stake account
+    /// 1. `[WRITE]` Recipient account
+    /// 2. `[]` Clock sysvar
+    /// 3. `[]` Stake history sysvar
+    /// 4. `[SIGNER]` Withdraw authority
+    Withdraw {
+        /// Lamports to withdraw
+        lamports: u64,
+    },
+    /// Deactivate delegated stake
+    /// 0. `[WRITE]` Stake account
+    /// 1. `[]` Clock sysvar
+    /// 2. `[SIGNER]` Stake authority
+    Deactivate,
+    /// Merge one stake account into another
+    /// 0. `[WRITE]` Destination stake account
+    /// 1. `[WRITE]` Source stake account
+    /// 2. `[]` Clock sysvar
+    /// 3. `[]` Stake history sysvar
+    /// 4. `[SIGNER]` Stake authority
+    Merge,
+    /// Initialize a stake account with signing authorities and no lockup
+    InitializeChecked,
+    /// Change a stake authority, with the new authority signing
+    AuthorizeChecked {
+        /// Which authority to change
+        stake_authorize: StakeAuthorize,
+    },
+    /// Return the minimum delegation as return data
+    GetMinimumDelegation,
+    /// Deactivate stake delegated to a delinquent vote account
+    DeactivateDelinquent,
+    /// Redelegate stake to another vote account (disabled on mainnet)
+    Redelegate,
+    /// Move stake between two active stake accounts
+    MoveStake {
+        /// Lamports of stake to move
+        lamports: u64,
+    },
+    /// Move unstaked lamports between stake accounts
+    MoveLamports {
+        /// Lamports to move
+        lamports: u64,
+    },
+}
+
+impl StakeInstruction {
+    /// Deserialize stake instruction data: a u32 variant index followed by bincode fields
+    pub fn deserialize(data: &[u8]) -> Result<Self> {
+        let mut reader = DataReader::new(data);
+        let instruction = match reader.read::<u32>()? {
+            0 => Self::Initialize {
+                authorized: Authorized {
+                    staker: reader.read_pubkey()?,
+                    withdrawer: reader.read_pubkey()?,
+                },
+                lockup: Lockup {
+                    unix_timestamp: reader.read()?,
+                    epoch: reader.read()?,
+                    custodian: reader.read_pubkey()?,
+                },
+            },
+            1 => Self::Authorize {
+                new_authority: reader.read_pubkey()?,
+                stake_authorize: read_stake_authorize(&mut reader)?,
+            },
+            2 => Self::DelegateStake,
+            3 => Self::Split {
+                lamports: reader.read()?,
+            },
+            4 => Self::Withdraw {
+                lamports: reader.read()?,
+            },
+            5 => Self::Deactivate,
+            7 => Self::Merge,
+            9 => Self::InitializeChecked,
+            10 => Self::AuthorizeChecked {
+                stake_authorize: read_stake_authorize(&mut reader)?,
+            },
+            13 => Self::GetMinimumDelegation,
+            14 => Self::DeactivateDelinquent,
+            15 => Self::Redelegate,
+            16 => Self::MoveStake {
+                lamports: reader.read()?,
+            },
+            17 => Self::MoveLamports {
+                lamports: reader.read()?,
+            },
+            tag => {
+                return Err(SolanaError::DeserializationError(format!(
+                    "unsupported stake instruction: {tag}"
+                )));
+            }
+        };
+        Ok(instruction)
+    }
+}
+
+fn read_stake_authorize(reader: &mut DataReader<'_>) -> Result<StakeAuthorize> {
+    match reader.read::<u32>()? {
+        0 => Ok(StakeAuthorize::Staker),
+        1 => Ok(StakeAuthorize::Withdrawer),
+        value => Err(SolanaError::DeserializationError(format!(
+            "unknown stake authorize: {value}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stake_instruction_deserialize() {
+        let staker = Pubkey::new([1u8; 32]);
+        let withdrawer = Pubkey::new([2u8; 32]);
+
+        let mut data = 0u32.to_le_bytes().to_vec();
+        data.extend_from_slice(staker.as_bytes());
+        data.extend_from_slice(withdrawer.as_bytes());
+        data.extend_from_slice(&(-1i64).to_le_bytes());
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&[0u8; 32]);
+        assert_eq!(
+            StakeInstruction::deserialize(&data).unwrap(),
+            StakeInstruction::Initialize {
+                authorized: Authorized { staker, withdrawer },
+                lockup: Lockup {
+                    unix_timestamp: -1,
+                    epoch: 7,
+                    custodian: Pubkey::new([0u8; 32]),
+                },
+            }
+        );
+
+        let mut data = 1u32.to_le_bytes().to_vec();
+        data.extend_from_slice(staker.as_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        assert_eq!(
+            StakeInstruction::deserialize(&data).unwrap(),
+            StakeInstruction::Authorize {
+                new_authority: staker,
+                stake_authorize: StakeAuthorize::Withdrawer,
+            }
+        );
+
+        let mut data = 4u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&500u64.to_le_bytes());
+        assert_eq!(
+            StakeInstruction::deserialize(&data).unwrap(),
+            StakeInstruction::Withdraw { lamports: 500 }
+        );
+        assert_eq!(
+            StakeInstruction::deserialize(&5u32.to_le_bytes()).unwrap(),
+            StakeInstruction::Deactivate
+        );
+
+        assert!(StakeInstruction::deserialize(&8u32.to_le_bytes()).is_err());
+        assert!(StakeInstruction::deserialize(&3u32.to_le_bytes()).is_err());
+    }
+}
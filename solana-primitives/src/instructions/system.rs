@@ -1,7 +1,15 @@
-use crate::instructions::program_ids::SYSTEM_PROGRAM_ID;
-use crate::types::{AccountMeta, Instruction, Pubkey};
+use crate::data::DataReader;
+use crate::error::{Result, SolanaError};
+use crate::instructions::program_ids::{recent_blockhashes_sysvar, rent_sysvar, system_program};
+use crate::types::{AccountMeta, CompiledInstruction, Instruction, Pubkey};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
 use borsh::{BorshDeserialize, BorshSerialize};
 
+/// Discriminant of `AdvanceNonceAccount`, little-endian encoded
+const ADVANCE_NONCE_ACCOUNT_DISCRIMINANT: [u8; 4] = [4, 0, 0, 0];
+
 /// System program instruction types
 #[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub enum SystemInstruction {
@@ -138,15 +146,15 @@ impl SystemInstruction {
             Self::CreateAccount { .. } => 52, // 4 + 8 + 8 + 32
             Self::Assign { .. } => 36,        // 4 + 32
             Self::Transfer { .. } => 12,      // 4 + 8
-            Self::CreateAccountWithSeed { seed, .. } => 116 + seed.len(), // 4 + 32 + (4 + len) + 8 + 8 + 32
-            Self::AdvanceNonceAccount { .. } => 36,                       // 4 + 32
-            Self::WithdrawNonceAccount { .. } => 12,                      // 4 + 8
-            Self::InitializeNonceAccount { .. } => 36,                    // 4 + 32
-            Self::AuthorizeNonceAccount { .. } => 36,                     // 4 + 32
-            Self::Allocate { .. } => 12,                                  // 4 + 8
-            Self::AllocateWithSeed { seed, .. } => 84 + seed.len(), // 4 + 32 + (4 + len) + 8 + 32
-            Self::AssignWithSeed { seed, .. } => 72 + seed.len(),   // 4 + 32 + (4 + len) + 32
-            Self::TransferWithSeed { seed, .. } => 48 + seed.len(), // 4 + 8 + (4 + len) + 32
+            Self::CreateAccountWithSeed { seed, .. } => 92 + seed.len(), // 4 + 32 + (8 + len) + 8 + 8 + 32
+            Self::AdvanceNonceAccount { .. } => 36,                      // 4 + 32
+            Self::WithdrawNonceAccount { .. } => 12,                     // 4 + 8
+            Self::InitializeNonceAccount { .. } => 36,                   // 4 + 32
+            Self::AuthorizeNonceAccount { .. } => 36,                    // 4 + 32
+            Self::Allocate { .. } => 12,                                 // 4 + 8
+            Self::AllocateWithSeed { seed, .. } => 84 + seed.len(), // 4 + 32 + (8 + len) + 8 + 32
+            Self::AssignWithSeed { seed, .. } => 76 + seed.len(),   // 4 + 32 + (8 + len) + 32
+            Self::TransferWithSeed { seed, .. } => 52 + seed.len(), // 4 + 8 + (8 + len) + 32
         }
     }
 
@@ -182,14 +190,14 @@ impl SystemInstruction {
                 data.extend_from_slice(&[3, 0, 0, 0]); // instruction index
                 data.extend_from_slice(base.as_bytes());
                 let seed_bytes = seed.as_bytes();
-                data.extend_from_slice(&(seed_bytes.len() as u32).to_le_bytes());
+                data.extend_from_slice(&(seed_bytes.len() as u64).to_le_bytes());
                 data.extend_from_slice(seed_bytes);
                 data.extend_from_slice(&lamports.to_le_bytes());
                 data.extend_from_slice(&space.to_le_bytes());
                 data.extend_from_slice(owner.as_bytes());
             }
             Self::AdvanceNonceAccount { authorized } => {
-                data.extend_from_slice(&[4, 0, 0, 0]); // instruction index
+                data.extend_from_slice(&ADVANCE_NONCE_ACCOUNT_DISCRIMINANT);
                 data.extend_from_slice(authorized.as_bytes());
             }
             Self::WithdrawNonceAccount { lamports } => {
@@ -217,7 +225,7 @@ impl SystemInstruction {
                 data.extend_from_slice(&[9, 0, 0, 0]); // instruction index
                 data.extend_from_slice(base.as_bytes());
                 let seed_bytes = seed.as_bytes();
-                data.extend_from_slice(&(seed_bytes.len() as u32).to_le_bytes());
+                data.extend_from_slice(&(seed_bytes.len() as u64).to_le_bytes());
                 data.extend_from_slice(seed_bytes);
                 data.extend_from_slice(&space.to_le_bytes());
                 data.extend_from_slice(owner.as_bytes());
@@ -226,7 +234,7 @@ impl SystemInstruction {
                 data.extend_from_slice(&[10, 0, 0, 0]); // instruction index
                 data.extend_from_slice(base.as_bytes());
                 let seed_bytes = seed.as_bytes();
-                data.extend_from_slice(&(seed_bytes.len() as u32).to_le_bytes());
+                data.extend_from_slice(&(seed_bytes.len() as u64).to_le_bytes());
                 data.extend_from_slice(seed_bytes);
                 data.extend_from_slice(owner.as_bytes());
             }
@@ -238,13 +246,83 @@ impl SystemInstruction {
                 data.extend_from_slice(&[11, 0, 0, 0]); // instruction index
                 data.extend_from_slice(&lamports.to_le_bytes());
                 let seed_bytes = seed.as_bytes();
-                data.extend_from_slice(&(seed_bytes.len() as u32).to_le_bytes());
+                data.extend_from_slice(&(seed_bytes.len() as u64).to_le_bytes());
                 data.extend_from_slice(seed_bytes);
                 data.extend_from_slice(owner.as_bytes());
             }
         }
         data
     }
+
+    /// Deserialize system instruction data, the inverse of [`SystemInstruction::serialize`]
+    pub fn deserialize(data: &[u8]) -> Result<Self> {
+        let mut reader = DataReader::new(data);
+        let instruction = match reader.read::<u32>()? {
+            0 => Self::CreateAccount {
+                lamports: reader.read()?,
+                space: reader.read()?,
+                owner: reader.read_pubkey()?,
+            },
+            1 => Self::Assign {
+                owner: reader.read_pubkey()?,
+            },
+            2 => Self::Transfer {
+                lamports: reader.read()?,
+            },
+            3 => Self::CreateAccountWithSeed {
+                base: reader.read_pubkey()?,
+                seed: read_seed(&mut reader)?,
+                lamports: reader.read()?,
+                space: reader.read()?,
+                owner: reader.read_pubkey()?,
+            },
+            4 => Self::AdvanceNonceAccount {
+                authorized: reader.read_pubkey()?,
+            },
+            5 => Self::WithdrawNonceAccount {
+                lamports: reader.read()?,
+            },
+            6 => Self::InitializeNonceAccount {
+                authorized: reader.read_pubkey()?,
+            },
+            7 => Self::AuthorizeNonceAccount {
+                authorized: reader.read_pubkey()?,
+            },
+            8 => Self::Allocate {
+                space: reader.read()?,
+            },
+            9 => Self::AllocateWithSeed {
+                base: reader.read_pubkey()?,
+                seed: read_seed(&mut reader)?,
+                space: reader.read()?,
+                owner: reader.read_pubkey()?,
+            },
+            10 => Self::AssignWithSeed {
+                base: reader.read_pubkey()?,
+                seed: read_seed(&mut reader)?,
+                owner: reader.read_pubkey()?,
+            },
+            11 => Self::TransferWithSeed {
+                lamports: reader.read()?,
+                seed: read_seed(&mut reader)?,
+                owner: reader.read_pubkey()?,
+            },
+            tag => {
+                return Err(SolanaError::DeserializationError(format!(
+                    "unknown system instruction: {tag}"
+                )));
+            }
+        };
+        Ok(instruction)
+    }
+}
+
+/// Read a seed string: a u64 byte length followed by UTF-8 bytes
+fn read_seed(reader: &mut DataReader<'_>) -> Result<String> {
+    let len = usize::try_from(reader.read::<u64>()?)
+        .map_err(|_| SolanaError::DeserializationError("seed too long".to_string()))?;
+    String::from_utf8(reader.read_bytes(len)?.to_vec())
+        .map_err(|e| SolanaError::DeserializationError(format!("invalid seed: {e}")))
 }
 
 // Helper functions for creating system program instructions
@@ -277,7 +355,7 @@ pub fn create_account(
     };
 
     Instruction {
-        program_id: Pubkey::from_base58(SYSTEM_PROGRAM_ID).unwrap(),
+        program_id: system_program(),
         accounts: account_metas,
         data: instruction.serialize(),
     }
@@ -294,7 +372,7 @@ pub fn assign(pubkey: &Pubkey, owner: &Pubkey) -> Instruction {
     let instruction = SystemInstruction::Assign { owner: *owner };
 
     Instruction {
-        program_id: Pubkey::from_base58(SYSTEM_PROGRAM_ID).unwrap(),
+        program_id: system_program(),
         accounts: account_metas,
         data: instruction.serialize(),
     }
@@ -318,7 +396,7 @@ pub fn transfer(from_pubkey: &Pubkey, to_pubkey: &Pubkey, lamports: u64) -> Inst
     let instruction = SystemInstruction::Transfer { lamports };
 
     Instruction {
-        program_id: Pubkey::from_base58(SYSTEM_PROGRAM_ID).unwrap(),
+        program_id: system_program(),
         accounts: account_metas,
         data: instruction.serialize(),
     }
@@ -334,7 +412,7 @@ pub fn advance_nonce_account(nonce_pubkey: &Pubkey, authorized_pubkey: &Pubkey)
         },
         // Recent blockhashes sysvar
         AccountMeta {
-            pubkey: Pubkey::from_base58("SysvarRecentB1ockHashes11111111111111111111").unwrap(),
+            pubkey: recent_blockhashes_sysvar(),
             is_signer: false,
             is_writable: false,
         },
@@ -350,12 +428,28 @@ pub fn advance_nonce_account(nonce_pubkey: &Pubkey, authorized_pubkey: &Pubkey)
     };
 
     Instruction {
-        program_id: Pubkey::from_base58(SYSTEM_PROGRAM_ID).unwrap(),
+        program_id: system_program(),
         accounts: account_metas,
         data: instruction.serialize(),
     }
 }
 
+/// Whether `instruction`, compiled against `keys`, is System `AdvanceNonceAccount`
+pub(crate) fn is_advance_nonce(keys: &[Pubkey], instruction: &CompiledInstruction) -> bool {
+    keys.get(instruction.program_id_index as usize)
+        .is_some_and(|program_id| invokes_advance_nonce(program_id, &instruction.data))
+}
+
+/// Whether `instruction` is System `AdvanceNonceAccount`
+pub(crate) fn is_advance_nonce_instruction(instruction: &Instruction) -> bool {
+    invokes_advance_nonce(&instruction.program_id, &instruction.data)
+}
+
+fn invokes_advance_nonce(program_id: &Pubkey, data: &[u8]) -> bool {
+    *program_id == system_program()
+        && data.get(..4) == Some(&ADVANCE_NONCE_ACCOUNT_DISCRIMINANT[..])
+}
+
 /// Withdraw lamports from a nonce account
 pub fn withdraw_nonce_account(
     nonce_pubkey: &Pubkey,
@@ -376,13 +470,13 @@ pub fn withdraw_nonce_account(
         },
         // Recent blockhashes sysvar
         AccountMeta {
-            pubkey: Pubkey::from_base58("SysvarRecentB1ockHashes11111111111111111111").unwrap(),
+            pubkey: recent_blockhashes_sysvar(),
             is_signer: false,
             is_writable: false,
         },
         // Rent sysvar
         AccountMeta {
-            pubkey: Pubkey::from_base58("SysvarRent111111111111111111111111111111111").unwrap(),
+            pubkey: rent_sysvar(),
             is_signer: false,
             is_writable: false,
         },
@@ -396,7 +490,7 @@ pub fn withdraw_nonce_account(
     let instruction = SystemInstruction::WithdrawNonceAccount { lamports };
 
     Instruction {
-        program_id: Pubkey::from_base58(SYSTEM_PROGRAM_ID).unwrap(),
+        program_id: system_program(),
         accounts: account_metas,
         data: instruction.serialize(),
     }
@@ -416,7 +510,7 @@ pub fn create_nonce_account(
             nonce_pubkey,
             lamports,
             80, // Space for a nonce account
-            &Pubkey::from_base58(SYSTEM_PROGRAM_ID).unwrap(),
+            &system_program(),
         ),
         // Initialize the nonce account
         initialize_nonce_account(nonce_pubkey, authority_pubkey),
@@ -433,13 +527,13 @@ pub fn initialize_nonce_account(nonce_pubkey: &Pubkey, authority_pubkey: &Pubkey
         },
         // Recent blockhashes sysvar
         AccountMeta {
-            pubkey: Pubkey::from_base58("SysvarRecentB1ockHashes11111111111111111111").unwrap(),
+            pubkey: recent_blockhashes_sysvar(),
             is_signer: false,
             is_writable: false,
         },
         // Rent sysvar
         AccountMeta {
-            pubkey: Pubkey::from_base58("SysvarRent111111111111111111111111111111111").unwrap(),
+            pubkey: rent_sysvar(),
             is_signer: false,
             is_writable: false,
         },
@@ -450,7 +544,7 @@ pub fn initialize_nonce_account(nonce_pubkey: &Pubkey, authority_pubkey: &Pubkey
     };
 
     Instruction {
-        program_id: Pubkey::from_base58(SYSTEM_PROGRAM_ID).unwrap(),
+        program_id: system_program(),
         accounts: account_metas,
         data: instruction.serialize(),
     }
@@ -480,7 +574,7 @@ pub fn authorize_nonce_account(
     };
 
     Instruction {
-        program_id: Pubkey::from_base58(SYSTEM_PROGRAM_ID).unwrap(),
+        program_id: system_program(),
         accounts: account_metas,
         data: instruction.serialize(),
     }
@@ -514,10 +608,7 @@ mod tests {
         let instruction = create_account(&from, &to, lamports, space, &owner);
 
         // Verify instruction details
-        assert_eq!(
-            instruction.program_id,
-            Pubkey::from_base58(SYSTEM_PROGRAM_ID).unwrap()
-        );
+        assert_eq!(instruction.program_id, system_program());
         assert_eq!(instruction.accounts.len(), 2);
 
         // From account
@@ -587,7 +678,7 @@ mod tests {
 
         // Create instruction with the accounts
         let instruction = Instruction {
-            program_id: Pubkey::from_base58(SYSTEM_PROGRAM_ID).unwrap(),
+            program_id: system_program(),
             accounts,
             data: vec![0, 1, 2, 3], // Some dummy data
         };
@@ -599,4 +690,49 @@ mod tests {
         // For now we'll just assert that the number of accounts is correct
         assert_eq!(instruction.accounts.len(), 3);
     }
+
+    #[test]
+    fn test_system_instruction_deserialize_roundtrip() {
+        let instructions = [
+            SystemInstruction::CreateAccount {
+                lamports: 1_000,
+                space: 165,
+                owner: owner_pubkey(),
+            },
+            SystemInstruction::Transfer { lamports: 42 },
+            SystemInstruction::CreateAccountWithSeed {
+                base: from_pubkey(),
+                seed: "stake:0".to_string(),
+                lamports: 1_000,
+                space: 200,
+                owner: owner_pubkey(),
+            },
+            SystemInstruction::AdvanceNonceAccount {
+                authorized: owner_pubkey(),
+            },
+            SystemInstruction::TransferWithSeed {
+                lamports: 7,
+                seed: "seed".to_string(),
+                owner: owner_pubkey(),
+            },
+        ];
+        for instruction in instructions {
+            let data = instruction.serialize();
+            assert_eq!(data.len(), instruction.size());
+            assert_eq!(SystemInstruction::deserialize(&data).unwrap(), instruction);
+        }
+
+        let ix = transfer(&from_pubkey(), &to_pubkey(), 5);
+        assert_eq!(
+            SystemInstruction::deserialize(&ix.data).unwrap(),
+            SystemInstruction::Transfer { lamports: 5 }
+        );
+
+        assert!(SystemInstruction::deserialize(&[2, 0, 0]).is_err());
+        assert!(SystemInstruction::deserialize(&[12, 0, 0, 0]).is_err());
+        let mut truncated_seed = vec![3, 0, 0, 0];
+        truncated_seed.extend_from_slice(from_pubkey().as_bytes());
+        truncated_seed.extend_from_slice(&u64::MAX.to_le_bytes());
+        assert!(SystemInstruction::deserialize(&truncated_seed).is_err());
+    }
 }
@@ -1,7 +1,12 @@
+use crate::data::DataReader;
+use crate::error::{Result, SolanaError};
 use crate::instructions::program_ids::{rent_sysvar, token_program};
 use crate::types::{AccountMeta, Instruction, Pubkey};
+use alloc::vec::Vec;
+use alloc::{format, vec};
 
 /// Token program instruction types
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TokenInstruction {
     /// Initialize a new mint
     InitializeMint {
@@ -111,6 +116,7 @@ pub enum TokenInstruction {
 }
 
 /// Authority types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AuthorityType {
     /// Authority to mint new tokens
     MintTokens,
@@ -237,6 +243,102 @@ impl TokenInstruction {
         }
         data
     }
+
+    /// Deserialize token instruction data, the inverse of [`TokenInstruction::serialize`]
+    pub fn deserialize(data: &[u8]) -> Result<Self> {
+        let mut reader = DataReader::new(data);
+        let instruction = match reader.read::<u8>()? {
+            0 => Self::InitializeMint {
+                decimals: reader.read()?,
+                mint_authority: reader.read_pubkey()?,
+                freeze_authority: read_optional_pubkey(&mut reader)?,
+            },
+            1 => Self::InitializeAccount,
+            2 => Self::InitializeMultisig { m: reader.read()? },
+            3 => Self::Transfer {
+                amount: reader.read()?,
+            },
+            4 => Self::Approve {
+                amount: reader.read()?,
+            },
+            5 => Self::Revoke,
+            6 => Self::SetAuthority {
+                authority_type: AuthorityType::try_from(reader.read::<u8>()?)?,
+                new_authority: read_optional_pubkey(&mut reader)?,
+            },
+            7 => Self::MintTo {
+                amount: reader.read()?,
+            },
+            8 => Self::Burn {
+                amount: reader.read()?,
+            },
+            9 => Self::CloseAccount,
+            10 => Self::FreezeAccount,
+            11 => Self::ThawAccount,
+            12 => Self::TransferChecked {
+                amount: reader.read()?,
+                decimals: reader.read()?,
+            },
+            13 => Self::ApproveChecked {
+                amount: reader.read()?,
+                decimals: reader.read()?,
+            },
+            14 => Self::MintToChecked {
+                amount: reader.read()?,
+                decimals: reader.read()?,
+            },
+            15 => Self::BurnChecked {
+                amount: reader.read()?,
+                decimals: reader.read()?,
+            },
+            16 => Self::InitializeAccount2 {
+                owner: reader.read_pubkey()?,
+            },
+            17 => Self::SyncNative,
+            18 => Self::InitializeAccount3 {
+                owner: reader.read_pubkey()?,
+            },
+            19 => Self::InitializeMultisig2 { m: reader.read()? },
+            20 => Self::InitializeMint2 {
+                decimals: reader.read()?,
+                mint_authority: reader.read_pubkey()?,
+                freeze_authority: read_optional_pubkey(&mut reader)?,
+            },
+            tag => {
+                return Err(SolanaError::DeserializationError(format!(
+                    "unknown token instruction: {tag}"
+                )));
+            }
+        };
+        Ok(instruction)
+    }
+}
+
+/// Read a one-byte presence flag followed by a pubkey when the flag is set
+fn read_optional_pubkey(reader: &mut DataReader<'_>) -> Result<Option<Pubkey>> {
+    match reader.read::<u8>()? {
+        0 => Ok(None),
+        1 => reader.read_pubkey().map(Some),
+        flag => Err(SolanaError::DeserializationError(format!(
+            "invalid option flag: {flag}"
+        ))),
+    }
+}
+
+impl TryFrom<u8> for AuthorityType {
+    type Error = SolanaError;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Self::MintTokens),
+            1 => Ok(Self::FreezeAccount),
+            2 => Ok(Self::AccountOwner),
+            3 => Ok(Self::CloseAccount),
+            _ => Err(SolanaError::DeserializationError(format!(
+                "unknown authority type: {value}"
+            ))),
+        }
+    }
 }
 
 impl From<&AuthorityType> for u8 {
@@ -693,12 +795,119 @@ pub fn sync_native_with_program_id(account: &Pubkey, token_program_id: &Pubkey)
     }
 }
 
+/// Token account cleanup: burn the remaining balance, then close the account
+#[derive(Debug, Clone)]
+pub struct BurnAndClose {
+    /// `BurnChecked` (omitted for an empty balance) followed by `CloseAccount`
+    pub instructions: Vec<Instruction>,
+    /// Account receiving the closed account's rent lamports
+    pub lamports_recipient: Pubkey,
+}
+
+impl BurnAndClose {
+    /// Send the reclaimed rent lamports to `recipient` instead of the original destination
+    pub fn with_lamports_recipient(mut self, recipient: Pubkey) -> Self {
+        if let Some(close) = self.instructions.last_mut() {
+            close.accounts[1].pubkey = recipient;
+        }
+        self.lamports_recipient = recipient;
+        self
+    }
+}
+
+/// Burn a token account's full `balance` and close it (defaults to the SPL Token program).
+///
+/// `owner` is either a single signing owner (pass no `signers`) or a multisig account whose
+/// `signers` co-sign both instructions. Rent lamports go to `destination`.
+pub fn burn_and_close(
+    account: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    owner: &Pubkey,
+    signers: &[&Pubkey],
+    balance: u64,
+    decimals: u8,
+) -> BurnAndClose {
+    burn_and_close_with_program_id(
+        account,
+        mint,
+        destination,
+        owner,
+        signers,
+        balance,
+        decimals,
+        &token_program(),
+    )
+}
+
+/// Burn a token account's full `balance` and close it, using the provided token program
+#[allow(clippy::too_many_arguments)]
+pub fn burn_and_close_with_program_id(
+    account: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    owner: &Pubkey,
+    signers: &[&Pubkey],
+    balance: u64,
+    decimals: u8,
+    token_program_id: &Pubkey,
+) -> BurnAndClose {
+    let mut instructions = Vec::with_capacity(2);
+    if balance > 0 {
+        let mut accounts = vec![
+            AccountMeta::new_writable(*account),
+            AccountMeta::new_writable(*mint),
+        ];
+        accounts.extend(authority_metas(owner, signers));
+        instructions.push(Instruction {
+            program_id: *token_program_id,
+            accounts,
+            data: TokenInstruction::BurnChecked {
+                amount: balance,
+                decimals,
+            }
+            .serialize(),
+        });
+    }
+
+    let mut accounts = vec![
+        AccountMeta::new_writable(*account),
+        AccountMeta::new_writable(*destination),
+    ];
+    accounts.extend(authority_metas(owner, signers));
+    instructions.push(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data: TokenInstruction::CloseAccount.serialize(),
+    });
+
+    BurnAndClose {
+        instructions,
+        lamports_recipient: *destination,
+    }
+}
+
+/// Authority accounts: a signing owner, or a multisig owner followed by its signers
+pub(crate) fn authority_metas(owner: &Pubkey, signers: &[&Pubkey]) -> Vec<AccountMeta> {
+    if signers.is_empty() {
+        return vec![AccountMeta::new_signer(*owner)];
+    }
+    core::iter::once(AccountMeta::new_readonly(*owner))
+        .chain(
+            signers
+                .iter()
+                .map(|signer| AccountMeta::new_signer(**signer)),
+        )
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::Pubkey;
     use crate::instructions::program_ids::{
-        SYSVAR_RENT_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+        SYSVAR_RENT_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, rent_sysvar, token_2022_program,
+        token_program,
     };
 
     // Use the same public keys as in the JavaScript test file
@@ -1076,4 +1285,136 @@ mod tests {
         assert_eq!(instruction.program_id, token_2022_program);
         assert_eq!(instruction.data, vec![9]);
     }
+
+    #[test]
+    fn test_burn_and_close() {
+        let account = token_pubkey();
+        let mint = mint_pubkey();
+        let owner = authority_pubkey();
+
+        let cleanup = burn_and_close(&account, &mint, &owner, &owner, &[], 500, 6);
+        assert_eq!(cleanup.lamports_recipient, owner);
+        assert_eq!(cleanup.instructions.len(), 2);
+
+        let burn = &cleanup.instructions[0];
+        assert_eq!(burn.data[0], 15);
+        assert_eq!(&burn.data[1..9], &500u64.to_le_bytes());
+        assert_eq!(burn.data[9], 6);
+        assert!(burn.accounts[2].is_signer);
+
+        let close = &cleanup.instructions[1];
+        assert_eq!(close.data, vec![9]);
+        assert_eq!(close.accounts[1].pubkey, owner);
+
+        let payer = payer_pubkey();
+        let redirected = burn_and_close(&account, &mint, &owner, &owner, &[], 0, 6)
+            .with_lamports_recipient(payer);
+        assert_eq!(redirected.instructions.len(), 1);
+        assert_eq!(redirected.lamports_recipient, payer);
+        assert_eq!(redirected.instructions[0].accounts[1].pubkey, payer);
+    }
+
+    #[test]
+    fn test_burn_and_close_multisig() {
+        let signer_a = Pubkey::new([1u8; 32]);
+        let signer_b = Pubkey::new([2u8; 32]);
+        let multisig = authority_pubkey();
+
+        let payer = payer_pubkey();
+        let cleanup = burn_and_close(
+            &token_pubkey(),
+            &mint_pubkey(),
+            &payer,
+            &multisig,
+            &[&signer_a, &signer_b],
+            1,
+            0,
+        );
+        // Rent goes to the explicit destination, not the multisig account
+        assert_eq!(cleanup.lamports_recipient, payer);
+        assert_eq!(cleanup.instructions[1].accounts[1].pubkey, payer);
+
+        for instruction in &cleanup.instructions {
+            let authority = &instruction.accounts[2..];
+            assert_eq!(authority.len(), 3);
+            assert_eq!(authority[0].pubkey, multisig);
+            assert!(!authority[0].is_signer);
+            assert!(authority[1].is_signer && authority[2].is_signer);
+            assert_eq!(authority[2].pubkey, signer_b);
+        }
+    }
+
+    #[test]
+    fn test_token_instruction_deserialize_roundtrip() {
+        let instructions = [
+            TokenInstruction::InitializeMint {
+                decimals: 6,
+                mint_authority: authority_pubkey(),
+                freeze_authority: None,
+            },
+            TokenInstruction::InitializeMint2 {
+                decimals: 9,
+                mint_authority: authority_pubkey(),
+                freeze_authority: Some(mint_pubkey()),
+            },
+            TokenInstruction::Transfer { amount: 42 },
+            TokenInstruction::SetAuthority {
+                authority_type: AuthorityType::CloseAccount,
+                new_authority: Some(authority_pubkey()),
+            },
+            TokenInstruction::CloseAccount,
+            TokenInstruction::TransferChecked {
+                amount: 1_000_000,
+                decimals: 6,
+            },
+            TokenInstruction::InitializeAccount3 {
+                owner: authority_pubkey(),
+            },
+            TokenInstruction::SyncNative,
+        ];
+        for instruction in instructions {
+            assert_eq!(
+                TokenInstruction::deserialize(&instruction.serialize()).unwrap(),
+                instruction
+            );
+        }
+
+        let ix = transfer_checked(
+            &token_pubkey(),
+            &mint_pubkey(),
+            &token_pubkey(),
+            &authority_pubkey(),
+            5,
+            2,
+        );
+        assert_eq!(
+            TokenInstruction::deserialize(&ix.data).unwrap(),
+            TokenInstruction::TransferChecked {
+                amount: 5,
+                decimals: 2
+            }
+        );
+
+        assert!(TokenInstruction::deserialize(&[]).is_err());
+        assert!(TokenInstruction::deserialize(&[3, 1, 2]).is_err());
+        assert!(TokenInstruction::deserialize(&[99]).is_err());
+        assert!(TokenInstruction::deserialize(&[6, 9, 0]).is_err());
+    }
+
+    #[test]
+    fn test_program_id_helpers() {
+        assert_eq!(
+            token_program(),
+            Pubkey::from_base58(TOKEN_PROGRAM_ID).unwrap()
+        );
+        assert_eq!(
+            token_2022_program(),
+            Pubkey::from_base58(TOKEN_2022_PROGRAM_ID).unwrap()
+        );
+        assert_eq!(rent_sysvar(), Pubkey::from_base58(SYSVAR_RENT_ID).unwrap());
+
+        let instruction = initialize_mint(&mint_pubkey(), &authority_pubkey(), None, 9);
+        assert_eq!(instruction.program_id, token_program());
+        assert_eq!(instruction.accounts[1].pubkey, rent_sysvar());
+    }
 }
@@ -0,0 +1,565 @@
+use crate::error::{Result, SolanaError};
+use crate::types::{AccountMeta, Instruction, Pubkey, find_program_address};
+use alloc::collections::BTreeMap;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// Token-2022 `ExtensionType::TransferHook` TLV type.
+const TRANSFER_HOOK_EXTENSION_TYPE: u16 = 14;
+/// Size of an SPL token account; mints are padded to this length before the account type byte.
+const BASE_ACCOUNT_LEN: usize = 165;
+/// Account type byte written after the padding of an extended mint.
+const ACCOUNT_TYPE_MINT: u8 = 1;
+/// Seed prefix of the transfer hook validation (extra account metas) PDA.
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+/// `spl-transfer-hook-interface:execute` discriminator, also the TLV type of the meta list.
+pub const EXECUTE_DISCRIMINATOR: [u8; 8] = [105, 37, 101, 197, 75, 251, 102, 26];
+/// Token instruction discriminant for `TransferChecked`.
+const TRANSFER_CHECKED_DISCRIMINANT: u8 = 12;
+/// Size of one packed `ExtraAccountMeta` entry.
+const EXTRA_ACCOUNT_META_LEN: usize = 35;
+
+/// Source of raw account data used while resolving transfer hook accounts.
+pub trait AccountDataResolver {
+    /// Return the data of `pubkey`, or `None` when the account does not exist.
+    fn account_data(&self, pubkey: &Pubkey) -> Result<Option<Vec<u8>>>;
+}
+
+impl<F> AccountDataResolver for F
+where
+    F: Fn(&Pubkey) -> Result<Option<Vec<u8>>>,
+{
+    fn account_data(&self, pubkey: &Pubkey) -> Result<Option<Vec<u8>>> {
+        self(pubkey)
+    }
+}
+
+#[cfg(feature = "std")]
+impl AccountDataResolver for HashMap<Pubkey, Vec<u8>> {
+    fn account_data(&self, pubkey: &Pubkey) -> Result<Option<Vec<u8>>> {
+        Ok(self.get(pubkey).cloned())
+    }
+}
+
+impl AccountDataResolver for BTreeMap<Pubkey, Vec<u8>> {
+    fn account_data(&self, pubkey: &Pubkey) -> Result<Option<Vec<u8>>> {
+        Ok(self.get(pubkey).cloned())
+    }
+}
+
+/// Get the transfer hook validation account for a mint and hook program.
+pub fn get_extra_account_metas_address(mint: &Pubkey, hook_program_id: &Pubkey) -> Pubkey {
+    find_program_address(
+        hook_program_id,
+        &[EXTRA_ACCOUNT_METAS_SEED, mint.as_bytes()],
+    )
+    .expect("seeds are within limits")
+    .0
+}
+
+/// Read the transfer hook program configured on a Token-2022 mint.
+///
+/// Returns `None` for mints without the `TransferHook` extension or with the program unset.
+pub fn get_transfer_hook_program_id(mint_data: &[u8]) -> Result<Option<Pubkey>> {
+    if mint_data.len() <= BASE_ACCOUNT_LEN {
+        return Ok(None);
+    }
+    if mint_data[BASE_ACCOUNT_LEN] != ACCOUNT_TYPE_MINT {
+        return Err(SolanaError::DeserializationError(
+            "account is not a Token-2022 mint".to_string(),
+        ));
+    }
+
+    let mut offset = BASE_ACCOUNT_LEN + 1;
+    while offset + 4 <= mint_data.len() {
+        let extension_type = u16::from_le_bytes([mint_data[offset], mint_data[offset + 1]]);
+        let length = u16::from_le_bytes([mint_data[offset + 2], mint_data[offset + 3]]) as usize;
+        offset += 4;
+        if offset + length > mint_data.len() {
+            return Err(SolanaError::DeserializationError(
+                "mint extension data is truncated".to_string(),
+            ));
+        }
+
+        if extension_type == TRANSFER_HOOK_EXTENSION_TYPE {
+            // TransferHook { authority: OptionalNonZeroPubkey, program_id: OptionalNonZeroPubkey }
+            if length != 64 {
+                return Err(SolanaError::DeserializationError(format!(
+                    "invalid transfer hook extension length: {length}, expected: 64"
+                )));
+            }
+            let program_id: [u8; 32] = mint_data[offset + 32..offset + 64]
+                .try_into()
+                .map_err(|_| SolanaError::InvalidMessage)?;
+            if program_id == [0u8; 32] {
+                return Ok(None);
+            }
+            return Ok(Some(Pubkey::new(program_id)));
+        }
+        offset += length;
+    }
+
+    Ok(None)
+}
+
+/// Append the accounts required by a mint's transfer hook to a `transfer_checked` instruction.
+///
+/// Mirrors `add_extra_account_metas_for_execute` from the SPL transfer hook interface: the
+/// extra accounts listed in the validation PDA are resolved (including PDAs seeded from
+/// instruction data or other accounts' data, and pubkeys read from either), followed by the
+/// hook program and the validation account itself. Instructions for mints without a
+/// transfer hook are left as-is.
+pub fn resolve_transfer_hook_accounts<R: AccountDataResolver + ?Sized>(
+    resolver: &R,
+    instruction: &mut Instruction,
+    mint: &Pubkey,
+    source: &Pubkey,
+    destination: &Pubkey,
+    owner: &Pubkey,
+) -> Result<()> {
+    if instruction.data.len() != 10 || instruction.data[0] != TRANSFER_CHECKED_DISCRIMINANT {
+        return Err(SolanaError::InvalidInstructionData);
+    }
+    for key in [source, mint, destination, owner] {
+        if !instruction.accounts.iter().any(|meta| meta.pubkey == *key) {
+            return Err(SolanaError::InvalidPubkey(format!(
+                "{key} is not an account of the transfer instruction"
+            )));
+        }
+    }
+
+    let mint_data = resolver
+        .account_data(mint)?
+        .ok_or_else(|| SolanaError::GenericError(format!("mint account {mint} not found")))?;
+    let Some(hook_program_id) = get_transfer_hook_program_id(&mint_data)? else {
+        return Ok(());
+    };
+
+    let validation_pubkey = get_extra_account_metas_address(mint, &hook_program_id);
+    let validation_data = resolver.account_data(&validation_pubkey)?.ok_or_else(|| {
+        SolanaError::GenericError(format!(
+            "transfer hook validation account {validation_pubkey} not found"
+        ))
+    })?;
+
+    // Seeds may reference the `Execute` instruction's data: discriminator + amount.
+    let mut execute_data = EXECUTE_DISCRIMINATOR.to_vec();
+    execute_data.extend_from_slice(&instruction.data[1..9]);
+
+    // Seeds index into the `Execute` account list, which grows as metas are resolved.
+    let mut execute_accounts = vec![
+        AccountMeta::new_readonly(*source),
+        AccountMeta::new_readonly(*mint),
+        AccountMeta::new_readonly(*destination),
+        AccountMeta::new_readonly(*owner),
+        AccountMeta::new_readonly(validation_pubkey),
+    ];
+
+    for entry in extra_account_metas(&validation_data)?.chunks_exact(EXTRA_ACCOUNT_META_LEN) {
+        let discriminator = entry[0];
+        let address_config: &[u8; 32] = entry[1..33]
+            .try_into()
+            .map_err(|_| SolanaError::InvalidMessage)?;
+        let is_signer = entry[33] != 0;
+        let is_writable = entry[34] != 0;
+
+        let pubkey = match discriminator {
+            0 => Pubkey::new(*address_config),
+            1 => derive_pda(
+                resolver,
+                &hook_program_id,
+                address_config,
+                &execute_data,
+                &execute_accounts,
+            )?,
+            2 => resolve_pubkey_data(resolver, address_config, &execute_data, &execute_accounts)?,
+            index if index >= 128 => {
+                let program_id = execute_accounts
+                    .get((index - 128) as usize)
+                    .ok_or_else(|| {
+                        SolanaError::DeserializationError(format!(
+                            "extra account meta references missing program account {}",
+                            index - 128
+                        ))
+                    })?
+                    .pubkey;
+                derive_pda(
+                    resolver,
+                    &program_id,
+                    address_config,
+                    &execute_data,
+                    &execute_accounts,
+                )?
+            }
+            other => {
+                return Err(SolanaError::DeserializationError(format!(
+                    "unknown extra account meta discriminator: {other}"
+                )));
+            }
+        };
+
+        execute_accounts.push(AccountMeta::new(pubkey, is_signer, is_writable));
+    }
+
+    instruction
+        .accounts
+        .extend_from_slice(&execute_accounts[5..]);
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(hook_program_id));
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(validation_pubkey));
+
+    Ok(())
+}
+
+/// Locate the packed `ExtraAccountMeta` entries for the `Execute` instruction.
+fn extra_account_metas(data: &[u8]) -> Result<&[u8]> {
+    let mut offset = 0;
+    while offset + 12 <= data.len() {
+        let discriminator = &data[offset..offset + 8];
+        let length = u32::from_le_bytes(
+            data[offset + 8..offset + 12]
+                .try_into()
+                .map_err(|_| SolanaError::InvalidMessage)?,
+        ) as usize;
+        offset += 12;
+        let value = offset
+            .checked_add(length)
+            .and_then(|end| data.get(offset..end))
+            .ok_or_else(|| {
+                SolanaError::DeserializationError(
+                    "extra account meta list is truncated".to_string(),
+                )
+            })?;
+
+        if discriminator == EXECUTE_DISCRIMINATOR {
+            if value.len() < 4 {
+                return Err(SolanaError::DeserializationError(
+                    "extra account meta list is missing its length".to_string(),
+                ));
+            }
+            let count = u32::from_le_bytes(
+                value[0..4]
+                    .try_into()
+                    .map_err(|_| SolanaError::InvalidMessage)?,
+            ) as usize;
+            return count
+                .checked_mul(EXTRA_ACCOUNT_META_LEN)
+                .and_then(|len| value.get(4..4 + len))
+                .ok_or_else(|| {
+                    SolanaError::DeserializationError(format!(
+                        "extra account meta list is too short for {count} entries"
+                    ))
+                });
+        }
+        offset += length;
+    }
+
+    Err(SolanaError::DeserializationError(
+        "validation account has no Execute extra account metas".to_string(),
+    ))
+}
+
+/// Read the pubkey a `PubkeyData` meta points at, in instruction data or another account's data.
+fn resolve_pubkey_data<R: AccountDataResolver + ?Sized>(
+    resolver: &R,
+    config: &[u8; 32],
+    execute_data: &[u8],
+    execute_accounts: &[AccountMeta],
+) -> Result<Pubkey> {
+    let read_pubkey = |data: &[u8], index: usize| {
+        data.get(index..index + 32)
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .map(Pubkey::new)
+    };
+
+    match config[0] {
+        // InstructionData { index }
+        1 => read_pubkey(execute_data, config[1] as usize).ok_or_else(|| {
+            SolanaError::DeserializationError(
+                "pubkey data references out-of-range instruction data".to_string(),
+            )
+        }),
+        // AccountData { account_index, data_index }
+        2 => {
+            let pubkey = execute_accounts
+                .get(config[1] as usize)
+                .ok_or_else(|| {
+                    SolanaError::DeserializationError(format!(
+                        "pubkey data references missing account {}",
+                        config[1]
+                    ))
+                })?
+                .pubkey;
+            let data = resolver.account_data(&pubkey)?.ok_or_else(|| {
+                SolanaError::GenericError(format!("pubkey data account {pubkey} not found"))
+            })?;
+            read_pubkey(&data, config[2] as usize).ok_or_else(|| {
+                SolanaError::DeserializationError(format!(
+                    "pubkey data references out-of-range data of account {pubkey}"
+                ))
+            })
+        }
+        other => Err(SolanaError::DeserializationError(format!(
+            "unknown pubkey data discriminator: {other}"
+        ))),
+    }
+}
+
+/// Unpack the seed configuration of a PDA meta and derive the address.
+fn derive_pda<R: AccountDataResolver + ?Sized>(
+    resolver: &R,
+    program_id: &Pubkey,
+    config: &[u8; 32],
+    execute_data: &[u8],
+    execute_accounts: &[AccountMeta],
+) -> Result<Pubkey> {
+    let truncated = || SolanaError::DeserializationError("seed configuration is truncated".into());
+    let account_at = |index: u8| {
+        execute_accounts
+            .get(index as usize)
+            .map(|meta| meta.pubkey)
+            .ok_or_else(|| {
+                SolanaError::DeserializationError(format!(
+                    "seed references missing account {index}"
+                ))
+            })
+    };
+
+    let mut seeds: Vec<Vec<u8>> = Vec::new();
+    let mut offset = 0;
+    while offset < config.len() {
+        match config[offset] {
+            // Uninitialized: end of the seed list
+            0 => break,
+            // Literal { length, bytes }
+            1 => {
+                let length = *config.get(offset + 1).ok_or_else(truncated)? as usize;
+                let bytes = config
+                    .get(offset + 2..offset + 2 + length)
+                    .ok_or_else(truncated)?;
+                seeds.push(bytes.to_vec());
+                offset += 2 + length;
+            }
+            // InstructionData { index, length }
+            2 => {
+                let index = *config.get(offset + 1).ok_or_else(truncated)? as usize;
+                let length = *config.get(offset + 2).ok_or_else(truncated)? as usize;
+                let bytes = execute_data.get(index..index + length).ok_or_else(|| {
+                    SolanaError::DeserializationError(
+                        "seed references out-of-range instruction data".to_string(),
+                    )
+                })?;
+                seeds.push(bytes.to_vec());
+                offset += 3;
+            }
+            // AccountKey { index }
+            3 => {
+                let index = *config.get(offset + 1).ok_or_else(truncated)?;
+                seeds.push(account_at(index)?.as_bytes().to_vec());
+                offset += 2;
+            }
+            // AccountData { account_index, data_index, length }
+            4 => {
+                let account_index = *config.get(offset + 1).ok_or_else(truncated)?;
+                let data_index = *config.get(offset + 2).ok_or_else(truncated)? as usize;
+                let length = *config.get(offset + 3).ok_or_else(truncated)? as usize;
+                let pubkey = account_at(account_index)?;
+                let data = resolver.account_data(&pubkey)?.ok_or_else(|| {
+                    SolanaError::GenericError(format!("seed account {pubkey} not found"))
+                })?;
+                let bytes = data.get(data_index..data_index + length).ok_or_else(|| {
+                    SolanaError::DeserializationError(format!(
+                        "seed references out-of-range data of account {pubkey}"
+                    ))
+                })?;
+                seeds.push(bytes.to_vec());
+                offset += 4;
+            }
+            other => {
+                return Err(SolanaError::DeserializationError(format!(
+                    "unknown seed discriminator: {other}"
+                )));
+            }
+        }
+    }
+
+    let seed_refs: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+    Ok(find_program_address(program_id, &seed_refs)?.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::program_ids::token_2022_program;
+    use crate::instructions::token::transfer_checked_with_program_id;
+
+    fn mint_with_transfer_hook(hook_program_id: &Pubkey) -> Vec<u8> {
+        let mut data = vec![0u8; BASE_ACCOUNT_LEN];
+        data.push(ACCOUNT_TYPE_MINT);
+        data.extend_from_slice(&TRANSFER_HOOK_EXTENSION_TYPE.to_le_bytes());
+        data.extend_from_slice(&64u16.to_le_bytes());
+        data.extend_from_slice(&[7u8; 32]); // authority
+        data.extend_from_slice(hook_program_id.as_bytes());
+        data
+    }
+
+    fn extra_account_meta(discriminator: u8, config: &[u8], is_writable: bool) -> Vec<u8> {
+        let mut entry = vec![discriminator];
+        let mut address_config = [0u8; 32];
+        address_config[..config.len()].copy_from_slice(config);
+        entry.extend_from_slice(&address_config);
+        entry.push(0);
+        entry.push(is_writable as u8);
+        entry
+    }
+
+    fn validation_data(entries: &[Vec<u8>]) -> Vec<u8> {
+        let mut value = (entries.len() as u32).to_le_bytes().to_vec();
+        for entry in entries {
+            value.extend_from_slice(entry);
+        }
+        let mut data = EXECUTE_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        data.extend_from_slice(&value);
+        data
+    }
+
+    #[test]
+    fn test_resolve_transfer_hook_accounts() {
+        let hook_program_id = Pubkey::new([9u8; 32]);
+        let mint = Pubkey::new([1u8; 32]);
+        let source = Pubkey::new([2u8; 32]);
+        let destination = Pubkey::new([3u8; 32]);
+        let owner = Pubkey::new([4u8; 32]);
+        let fixed_account = Pubkey::new([5u8; 32]);
+        let amount = 42u64;
+
+        let validation_pubkey = get_extra_account_metas_address(&mint, &hook_program_id);
+        let entries = vec![
+            extra_account_meta(0, fixed_account.as_bytes(), true),
+            // PDA seeded by literal "counter" and the destination key (index 2)
+            extra_account_meta(
+                1,
+                &[1, 7, b'c', b'o', b'u', b'n', b't', b'e', b'r', 3, 2],
+                false,
+            ),
+            // PDA seeded by the amount in the execute instruction data
+            extra_account_meta(1, &[2, 8, 8], true),
+            // Pubkey stored at byte 32 of the source account's data
+            extra_account_meta(2, &[2, 0, 32], false),
+        ];
+
+        let mut source_data = vec![0u8; 72];
+        source_data[32..64].copy_from_slice(owner.as_bytes());
+
+        let mut accounts = HashMap::new();
+        accounts.insert(mint, mint_with_transfer_hook(&hook_program_id));
+        accounts.insert(source, source_data);
+        accounts.insert(validation_pubkey, validation_data(&entries));
+
+        let mut instruction = transfer_checked_with_program_id(
+            &source,
+            &mint,
+            &destination,
+            &owner,
+            amount,
+            6,
+            &token_2022_program(),
+        );
+        resolve_transfer_hook_accounts(
+            &accounts,
+            &mut instruction,
+            &mint,
+            &source,
+            &destination,
+            &owner,
+        )
+        .unwrap();
+
+        let counter = find_program_address(&hook_program_id, &[b"counter", destination.as_bytes()])
+            .unwrap()
+            .0;
+        let by_amount = find_program_address(&hook_program_id, &[&amount.to_le_bytes()])
+            .unwrap()
+            .0;
+
+        assert_eq!(instruction.accounts.len(), 10);
+        assert_eq!(instruction.accounts[4].pubkey, fixed_account);
+        assert!(instruction.accounts[4].is_writable);
+        assert_eq!(instruction.accounts[5].pubkey, counter);
+        assert!(!instruction.accounts[5].is_writable);
+        assert_eq!(instruction.accounts[6].pubkey, by_amount);
+        assert_eq!(instruction.accounts[7].pubkey, owner);
+        assert_eq!(instruction.accounts[8].pubkey, hook_program_id);
+        assert_eq!(instruction.accounts[9].pubkey, validation_pubkey);
+        assert!(!instruction.accounts[9].is_writable);
+    }
+
+    #[test]
+    fn test_resolve_transfer_hook_accounts_without_extension() {
+        let mint = Pubkey::new([1u8; 32]);
+        let source = Pubkey::new([2u8; 32]);
+        let destination = Pubkey::new([3u8; 32]);
+        let owner = Pubkey::new([4u8; 32]);
+
+        let mut accounts = HashMap::new();
+        accounts.insert(mint, vec![0u8; 82]);
+
+        let mut instruction = transfer_checked_with_program_id(
+            &source,
+            &mint,
+            &destination,
+            &owner,
+            1,
+            0,
+            &token_2022_program(),
+        );
+        resolve_transfer_hook_accounts(
+            &accounts,
+            &mut instruction,
+            &mint,
+            &source,
+            &destination,
+            &owner,
+        )
+        .unwrap();
+
+        assert_eq!(instruction.accounts.len(), 4);
+    }
+
+    #[test]
+    fn test_resolve_transfer_hook_accounts_rejects_missing_account() {
+        let mint = Pubkey::new([1u8; 32]);
+        let source = Pubkey::new([2u8; 32]);
+        let destination = Pubkey::new([3u8; 32]);
+        let owner = Pubkey::new([4u8; 32]);
+
+        let accounts: HashMap<Pubkey, Vec<u8>> = HashMap::new();
+        let mut instruction = transfer_checked_with_program_id(
+            &source,
+            &mint,
+            &destination,
+            &owner,
+            1,
+            0,
+            &token_2022_program(),
+        );
+        let stranger = Pubkey::new([8u8; 32]);
+
+        let result = resolve_transfer_hook_accounts(
+            &accounts,
+            &mut instruction,
+            &mint,
+            &stranger,
+            &destination,
+            &owner,
+        );
+        assert!(matches!(result, Err(SolanaError::InvalidPubkey(_))));
+    }
+}
@@ -1,17 +1,67 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod analysis;
+pub mod base58;
 pub mod borsh_helpers;
 pub mod builder;
+#[cfg(feature = "bincode")]
+pub mod codec;
 pub mod crypto;
+pub mod data;
+pub mod decode;
 pub mod error;
+pub mod fee;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+pub mod guard;
+#[cfg(feature = "idl")]
+pub mod idl;
 pub mod instructions;
+pub mod multisig;
+pub mod nonce;
+pub mod offchain;
+pub mod qr;
+pub mod rent;
+#[cfg(feature = "schema")]
+pub mod schema;
 pub mod short_vec;
+pub mod simulation;
+pub mod state;
+pub mod sysvars;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod types;
+pub mod view;
+pub mod wire;
 
 pub use borsh_helpers::{bytes_to_compact_array, compact_array_to_bytes};
-pub use builder::{InstructionBuilder, InstructionDataBuilder, TransactionBuilder};
+pub use builder::{
+    AnchorInstructionBuilder, BUILDER_LAYOUT_VERSION, BuildMetadata, DuplicateInstruction,
+    DuplicateInstructionPolicy, DuplicateKeyPolicy, InstructionBuilder, InstructionDataBuilder,
+    KeyMerge, KeyMergeKind, TransactionBuilder, TransactionPacker,
+};
 pub use crypto::*;
 pub use error::{Result, SolanaError};
 pub use instructions::*;
 pub use short_vec::{
-    ShortU16, ShortVec, decode_compact_u16_len, encode_length_to_compact_u16_bytes,
+    ShortU16, ShortVec, compact_u16_len, decode_compact_u16_len, encode_length_to_compact_u16_bytes,
 };
 pub use types::*;
+
+/// The most commonly used types and helpers, for glob import:
+/// `use solana_primitives::prelude::*;`
+pub mod prelude {
+    pub use crate::builder::{InstructionBuilder, InstructionDataBuilder, TransactionBuilder};
+    pub use crate::crypto::{Keypair, Signer};
+    pub use crate::error::{Result, SolanaError};
+    pub use crate::instructions::{associated_token, compute_budget, program_ids, system, token};
+    pub use crate::pubkey;
+    pub use crate::types::{
+        AccountMeta, AddressLookupTableAccount, ComputeUnits, Instruction, MicroLamports, Pubkey,
+        SignatureBytes, Transaction, VersionedMessage, VersionedTransaction, find_program_address,
+    };
+}
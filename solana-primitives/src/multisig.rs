@@ -0,0 +1,226 @@
+//! SPL Token multisig accounts and coordination of partially signed transactions.
+//!
+//! A token multisig is an M-of-N authority: instructions name the multisig account as the
+//! authority and list the co-signers right after it (see [`signer_metas`]). Each co-signer
+//! signs the same transaction, usually on a different machine; [`SigningStatus`] reports
+//! who is still missing and [`merge_signatures`] combines the copies. Decode multisig
+//! accounts with [`MultisigState`](crate::state::MultisigState).
+
+use crate::crypto::verify_signature_strict;
+use crate::error::{Result, SolanaError};
+use crate::instructions::program_ids::{rent_sysvar, token_program};
+use crate::instructions::token::{TokenInstruction, authority_metas};
+use crate::state::MAX_SIGNERS;
+use crate::types::{AccountMeta, Instruction, Pubkey, VersionedTransaction};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+/// Initialize an M-of-N token multisig (defaults to the SPL Token program)
+pub fn initialize_multisig(multisig: &Pubkey, signers: &[&Pubkey], m: u8) -> Result<Instruction> {
+    initialize_multisig_with_program_id(multisig, signers, m, &token_program())
+}
+
+/// Initialize an M-of-N token multisig using the provided token program
+pub fn initialize_multisig_with_program_id(
+    multisig: &Pubkey,
+    signers: &[&Pubkey],
+    m: u8,
+    token_program_id: &Pubkey,
+) -> Result<Instruction> {
+    check_threshold(signers, m)?;
+    let accounts = [
+        AccountMeta::new_writable(*multisig),
+        AccountMeta::new_readonly(rent_sysvar()),
+    ]
+    .into_iter()
+    .chain(
+        signers
+            .iter()
+            .map(|signer| AccountMeta::new_readonly(**signer)),
+    )
+    .collect();
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data: TokenInstruction::InitializeMultisig { m }.serialize(),
+    })
+}
+
+/// Initialize an M-of-N token multisig without the rent sysvar account
+pub fn initialize_multisig2(
+    multisig: &Pubkey,
+    signers: &[&Pubkey],
+    m: u8,
+    token_program_id: &Pubkey,
+) -> Result<Instruction> {
+    check_threshold(signers, m)?;
+    let accounts = core::iter::once(AccountMeta::new_writable(*multisig))
+        .chain(
+            signers
+                .iter()
+                .map(|signer| AccountMeta::new_readonly(**signer)),
+        )
+        .collect();
+    Ok(Instruction {
+        program_id: *token_program_id,
+        accounts,
+        data: TokenInstruction::InitializeMultisig2 { m }.serialize(),
+    })
+}
+
+/// Authority accounts for an instruction run by `multisig`: the multisig account followed by
+/// the co-signers
+pub fn signer_metas(multisig: &Pubkey, signers: &[&Pubkey]) -> Vec<AccountMeta> {
+    authority_metas(multisig, signers)
+}
+
+fn check_threshold(signers: &[&Pubkey], m: u8) -> Result<()> {
+    if signers.is_empty() || signers.len() > MAX_SIGNERS || m == 0 || m as usize > signers.len() {
+        return Err(SolanaError::GenericError(format!(
+            "invalid multisig threshold: {m} of {}",
+            signers.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Which required signers of a transaction have provided a valid signature
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SigningStatus {
+    /// Signers with a valid signature
+    pub signed: Vec<Pubkey>,
+    /// Signers still missing, in signature slot order
+    pub missing: Vec<Pubkey>,
+}
+
+impl SigningStatus {
+    /// Inspect the signature slots of a transaction
+    pub fn of(transaction: &VersionedTransaction) -> Result<Self> {
+        let mut status = Self::default();
+        for requirement in transaction.signing_requirements()? {
+            if requirement.signed {
+                status.signed.push(requirement.pubkey);
+            } else {
+                status.missing.push(requirement.pubkey);
+            }
+        }
+        Ok(status)
+    }
+
+    /// Whether every required signer has signed
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Copy valid signatures from `other` into the empty or invalid slots of `transaction`.
+///
+/// Both must carry the same message. Signatures are checked with
+/// [`verify_signature_strict`], so malleable signatures and small-order keys are never
+/// copied. Returns the number of signatures copied.
+pub fn merge_signatures(
+    transaction: &mut VersionedTransaction,
+    other: &VersionedTransaction,
+) -> Result<usize> {
+    let message_bytes = transaction.serialize_message()?;
+    if other.serialize_message()? != message_bytes {
+        return Err(SolanaError::InvalidSignature(
+            "cannot merge signatures of different messages".to_string(),
+        ));
+    }
+
+    let mut merged = 0;
+    for requirement in transaction.signing_requirements()? {
+        if requirement.signed {
+            continue;
+        }
+        if let Some(signature) = other
+            .signatures()
+            .get(requirement.index)
+            .filter(|signature| {
+                verify_signature_strict(&requirement.pubkey, &message_bytes, signature)
+            })
+        {
+            transaction.add_signature_for(&requirement.pubkey, *signature)?;
+            merged += 1;
+        }
+    }
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::TransactionBuilder;
+    use crate::crypto::Keypair;
+    use crate::instructions::token::transfer_with_program_id;
+
+    #[test]
+    fn test_initialize_multisig() {
+        let multisig = Pubkey::new([1u8; 32]);
+        let (a, b, c) = (
+            Pubkey::new([2u8; 32]),
+            Pubkey::new([3u8; 32]),
+            Pubkey::new([4u8; 32]),
+        );
+
+        let instruction = initialize_multisig(&multisig, &[&a, &b, &c], 2).unwrap();
+        assert_eq!(instruction.data, vec![2, 2]);
+        assert_eq!(instruction.accounts.len(), 5);
+        assert_eq!(instruction.accounts[1].pubkey, rent_sysvar());
+
+        let instruction = initialize_multisig2(&multisig, &[&a, &b], 1, &token_program()).unwrap();
+        assert_eq!(instruction.data, vec![19, 1]);
+        assert_eq!(instruction.accounts.len(), 3);
+
+        assert!(initialize_multisig(&multisig, &[&a], 2).is_err());
+        assert!(initialize_multisig(&multisig, &[&a], 0).is_err());
+        assert!(initialize_multisig(&multisig, &[&a; 12], 1).is_err());
+    }
+
+    #[test]
+    fn test_merge_signatures() {
+        let payer = Keypair::from_secret_key(&[1u8; 32]);
+        let alice = Keypair::from_secret_key(&[2u8; 32]);
+        let bob = Keypair::from_secret_key(&[3u8; 32]);
+        let multisig = Pubkey::new([4u8; 32]);
+
+        let mut instruction = transfer_with_program_id(
+            &Pubkey::new([5u8; 32]),
+            &Pubkey::new([6u8; 32]),
+            &multisig,
+            10,
+            &token_program(),
+        );
+        instruction.accounts.truncate(2);
+        instruction
+            .accounts
+            .extend(signer_metas(&multisig, &[&alice.pubkey(), &bob.pubkey()]));
+
+        let mut builder = TransactionBuilder::new(payer.pubkey(), [9u8; 32]);
+        builder.add_instruction(instruction);
+        let unsigned = builder.build_v0(&[]).unwrap();
+        assert_eq!(SigningStatus::of(&unsigned).unwrap().missing.len(), 3);
+
+        let mut payer_copy = unsigned.clone();
+        payer_copy.partial_sign(&[&payer]).unwrap();
+        let mut alice_copy = unsigned.clone();
+        alice_copy.partial_sign(&[&alice]).unwrap();
+        let mut bob_copy = unsigned.clone();
+        bob_copy.partial_sign(&[&bob]).unwrap();
+
+        assert_eq!(merge_signatures(&mut payer_copy, &alice_copy).unwrap(), 1);
+        let status = SigningStatus::of(&payer_copy).unwrap();
+        assert_eq!(status.missing, vec![bob.pubkey()]);
+        assert!(!status.is_complete());
+
+        assert_eq!(merge_signatures(&mut payer_copy, &bob_copy).unwrap(), 1);
+        assert_eq!(merge_signatures(&mut payer_copy, &bob_copy).unwrap(), 0);
+        assert!(SigningStatus::of(&payer_copy).unwrap().is_complete());
+        payer_copy.verify_signatures().unwrap();
+
+        let other_message = unsigned.rebase([8u8; 32]);
+        assert!(merge_signatures(&mut payer_copy, &other_message).is_err());
+    }
+}
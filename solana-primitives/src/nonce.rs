@@ -0,0 +1,109 @@
+//! Durable nonce account state.
+//!
+//! A durable nonce replaces the recent blockhash so a transaction can be signed offline and
+//! submitted any time later. Fetch the nonce account with `getAccountInfo`, decode it with
+//! [`NonceState::from_account_data`] and pass the result to
+//! [`TransactionBuilder::with_durable_nonce`](crate::TransactionBuilder::with_durable_nonce).
+
+use crate::data::DataReader;
+use crate::error::{Result, SolanaError};
+use crate::types::{Blockhash, Pubkey};
+use alloc::format;
+
+/// Size of a nonce account's data
+pub const NONCE_ACCOUNT_LENGTH: usize = 80;
+
+/// Data stored in an initialized nonce account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonceData {
+    /// Account allowed to advance, withdraw from and re-authorize the nonce
+    pub authority: Pubkey,
+    /// Value to use as the transaction's recent blockhash
+    pub blockhash: Blockhash,
+    /// Fee rate recorded when the nonce was last advanced
+    pub lamports_per_signature: u64,
+}
+
+/// State of a nonce account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonceState {
+    /// Allocated but not yet initialized
+    Uninitialized,
+    /// Holding a durable nonce
+    Initialized(NonceData),
+}
+
+impl NonceState {
+    /// Decode nonce account data: a u32 version, a u32 state and, when initialized, the
+    /// authority, the nonce and the fee calculator
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let mut reader = DataReader::new(data);
+        let version = reader.read::<u32>()?;
+        if version > 1 {
+            return Err(SolanaError::DeserializationError(format!(
+                "unknown nonce account version: {version}"
+            )));
+        }
+        match reader.read::<u32>()? {
+            0 => Ok(Self::Uninitialized),
+            1 => Ok(Self::Initialized(NonceData {
+                authority: reader.read_pubkey()?,
+                blockhash: reader.read_bytes(32)?.try_into().unwrap(),
+                lamports_per_signature: reader.read()?,
+            })),
+            state => Err(SolanaError::DeserializationError(format!(
+                "unknown nonce account state: {state}"
+            ))),
+        }
+    }
+
+    /// Nonce data, if initialized
+    pub fn data(&self) -> Option<&NonceData> {
+        match self {
+            Self::Uninitialized => None,
+            Self::Initialized(data) => Some(data),
+        }
+    }
+
+    /// Nonce authority, if initialized
+    pub fn authority(&self) -> Option<&Pubkey> {
+        self.data().map(|data| &data.authority)
+    }
+
+    /// Stored nonce, if initialized
+    pub fn blockhash(&self) -> Option<&Blockhash> {
+        self.data().map(|data| &data.blockhash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn initialized_data() -> Vec<u8> {
+        let mut data = Vec::with_capacity(NONCE_ACCOUNT_LENGTH);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&[7u8; 32]);
+        data.extend_from_slice(&[9u8; 32]);
+        data.extend_from_slice(&5_000u64.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_nonce_state_from_account_data() {
+        let data = initialized_data();
+        assert_eq!(data.len(), NONCE_ACCOUNT_LENGTH);
+
+        let state = NonceState::from_account_data(&data).unwrap();
+        assert_eq!(state.authority(), Some(&Pubkey::new([7u8; 32])));
+        assert_eq!(state.blockhash(), Some(&Blockhash::new([9u8; 32])));
+        assert_eq!(state.data().unwrap().lamports_per_signature, 5_000);
+
+        assert_eq!(
+            NonceState::from_account_data(&[0u8; NONCE_ACCOUNT_LENGTH]).unwrap(),
+            NonceState::Uninitialized
+        );
+        assert!(NonceState::from_account_data(&data[..40]).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+//! Off-chain message signing.
+//!
+//! Wraps arbitrary text in the framing the Solana CLI and wallets use for `solana
+//! sign-offchain-message`, so a signature over it can never be replayed as a transaction.
+//! [`siws`] covers the Sign In With Solana text format used for wallet authentication.
+
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+pub mod siws;
+
+use crate::crypto::{Signer, verify_signature};
+use crate::error::{Result, SolanaError};
+use crate::types::{Pubkey, SignatureBytes};
+
+/// Prefix of every off-chain message; `0xff` can never start a valid transaction
+pub const SIGNING_DOMAIN: &[u8; 16] = b"\xffsolana offchain";
+/// Size of the version 0 header: signing domain, version, format and length
+pub const HEADER_LEN: usize = SIGNING_DOMAIN.len() + 4;
+/// Longest version 0 message body
+pub const MAX_LEN: usize = u16::MAX as usize - HEADER_LEN;
+/// Longest body a Ledger device will display, matching the transaction packet size
+pub const MAX_LEN_LEDGER: usize = 1232 - HEADER_LEN;
+
+/// Encoding of an off-chain message body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Printable ASCII up to [`MAX_LEN_LEDGER`] bytes
+    RestrictedAscii,
+    /// UTF-8 up to [`MAX_LEN_LEDGER`] bytes
+    LimitedUtf8,
+    /// UTF-8 up to [`MAX_LEN`] bytes
+    ExtendedUtf8,
+}
+
+impl MessageFormat {
+    fn from_u8(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Self::RestrictedAscii),
+            1 => Ok(Self::LimitedUtf8),
+            2 => Ok(Self::ExtendedUtf8),
+            _ => Err(SolanaError::DeserializationError(format!(
+                "unknown off-chain message format {value}"
+            ))),
+        }
+    }
+
+    /// The most restrictive format that can carry `message`
+    fn detect(message: &[u8]) -> Result<Self> {
+        let is_utf8 = core::str::from_utf8(message).is_ok();
+        if message.is_empty() {
+            Err(SolanaError::GenericError(
+                "off-chain message is empty".to_string(),
+            ))
+        } else if message.len() <= MAX_LEN_LEDGER
+            && message.iter().all(|b| (0x20..=0x7e).contains(b))
+        {
+            Ok(Self::RestrictedAscii)
+        } else if message.len() <= MAX_LEN_LEDGER && is_utf8 {
+            Ok(Self::LimitedUtf8)
+        } else if message.len() <= MAX_LEN && is_utf8 {
+            Ok(Self::ExtendedUtf8)
+        } else if is_utf8 {
+            Err(SolanaError::GenericError(format!(
+                "off-chain message is {} bytes, maximum is {MAX_LEN}",
+                message.len()
+            )))
+        } else {
+            Err(SolanaError::GenericError(
+                "off-chain message is not valid UTF-8".to_string(),
+            ))
+        }
+    }
+}
+
+/// A version 0 off-chain message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffchainMessage {
+    format: MessageFormat,
+    message: Vec<u8>,
+}
+
+impl OffchainMessage {
+    /// Frame `message`, picking the most restrictive format that fits it
+    pub fn new(message: impl Into<Vec<u8>>) -> Result<Self> {
+        let message = message.into();
+        let format = MessageFormat::detect(&message)?;
+        Ok(Self { format, message })
+    }
+
+    /// Header version, always 0
+    pub fn version(&self) -> u8 {
+        0
+    }
+
+    /// Encoding of the body
+    pub fn format(&self) -> MessageFormat {
+        self.format
+    }
+
+    /// The message body
+    pub fn message(&self) -> &[u8] {
+        &self.message
+    }
+
+    /// The framed bytes that get signed
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.message.len());
+        bytes.extend_from_slice(SIGNING_DOMAIN);
+        bytes.push(self.version());
+        bytes.push(self.format as u8);
+        bytes.extend_from_slice(&(self.message.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(&self.message);
+        bytes
+    }
+
+    /// Parse framed bytes, rejecting a format that does not match the body
+    pub fn deserialize(bytes: &[u8]) -> Result<Self> {
+        let invalid = |reason: &str| {
+            SolanaError::DeserializationError(format!("invalid off-chain message: {reason}"))
+        };
+        let header = bytes
+            .get(..HEADER_LEN)
+            .ok_or_else(|| invalid("truncated header"))?;
+        if &header[..SIGNING_DOMAIN.len()] != SIGNING_DOMAIN {
+            return Err(invalid("missing signing domain"));
+        }
+        if header[16] != 0 {
+            return Err(invalid("unsupported version"));
+        }
+        let format = MessageFormat::from_u8(header[17])?;
+        let len = u16::from_le_bytes([header[18], header[19]]) as usize;
+        let message = &bytes[HEADER_LEN..];
+        if message.len() != len {
+            return Err(invalid("length does not match body"));
+        }
+
+        let parsed = Self::new(message)?;
+        if parsed.format != format {
+            return Err(invalid("format does not match body"));
+        }
+        Ok(parsed)
+    }
+
+    /// Sign the framed message
+    pub fn sign(&self, signer: &dyn Signer) -> Result<SignatureBytes> {
+        signer.try_sign_message(&self.serialize())
+    }
+
+    /// Check `signature` over the framed message by `pubkey`
+    pub fn verify(&self, pubkey: &Pubkey, signature: &SignatureBytes) -> bool {
+        verify_signature(pubkey, &self.serialize(), signature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::Keypair;
+
+    #[test]
+    fn test_format_detection() {
+        assert_eq!(
+            OffchainMessage::new("Hello").unwrap().format(),
+            MessageFormat::RestrictedAscii
+        );
+        assert_eq!(
+            OffchainMessage::new("line\nbreak").unwrap().format(),
+            MessageFormat::LimitedUtf8
+        );
+        assert_eq!(
+            OffchainMessage::new("a".repeat(MAX_LEN_LEDGER + 1))
+                .unwrap()
+                .format(),
+            MessageFormat::ExtendedUtf8
+        );
+        assert!(OffchainMessage::new("").is_err());
+        assert!(OffchainMessage::new(vec![0xff, 0xfe]).is_err());
+        assert!(OffchainMessage::new("a".repeat(MAX_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_serialize_sign_verify() {
+        let message = OffchainMessage::new("Hello").unwrap();
+        let bytes = message.serialize();
+        assert_eq!(&bytes[..16], SIGNING_DOMAIN);
+        assert_eq!(&bytes[16..20], &[0, 0, 5, 0]);
+        assert_eq!(&bytes[20..], b"Hello");
+        assert_eq!(OffchainMessage::deserialize(&bytes).unwrap(), message);
+
+        let mut wrong_format = bytes.clone();
+        wrong_format[17] = 1;
+        assert!(OffchainMessage::deserialize(&wrong_format).is_err());
+        assert!(OffchainMessage::deserialize(&bytes[..bytes.len() - 1]).is_err());
+
+        let keypair = Keypair::from_seed(&[9u8; 32]).unwrap();
+        let signature = message.sign(&keypair).unwrap();
+        assert!(message.verify(&keypair.pubkey(), &signature));
+        assert!(
+            !OffchainMessage::new("Hellp")
+                .unwrap()
+                .verify(&keypair.pubkey(), &signature)
+        );
+        // A raw signature over the body is not a signature over the framed message
+        assert!(!message.verify(&keypair.pubkey(), &keypair.sign_message(b"Hello")));
+    }
+}
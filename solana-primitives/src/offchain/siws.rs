@@ -0,0 +1,311 @@
+//! Sign In With Solana (SIWS) messages.
+//!
+//! Renders and parses the text format wallets sign for `solana:signIn`, an adaptation of
+//! EIP-4361. A backend builds the [`SignInMessage`] it expects, hands the fields to the
+//! wallet, and calls [`SignInMessage::verify`] on the text and signature that come back.
+
+use crate::crypto::verify_signature;
+use crate::error::{Result, SolanaError};
+use crate::types::{Pubkey, SignatureBytes};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Solana account:";
+
+/// A Sign In With Solana message.
+///
+/// Signed messages always carry `domain` and `address`; every other field is rendered when set.
+/// Timestamps are kept as the ISO 8601 strings the wallet signed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignInMessage {
+    /// Domain requesting the sign in, e.g. `example.com`
+    pub domain: String,
+    /// Account signing in, always set on a parsed message
+    pub address: Option<Pubkey>,
+    /// Human readable statement, without line breaks
+    pub statement: Option<String>,
+    /// URI the sign in is for
+    pub uri: Option<String>,
+    /// Message version, `1` in current wallets
+    pub version: Option<String>,
+    /// Cluster, e.g. `mainnet` or `devnet`
+    pub chain_id: Option<String>,
+    /// Server issued nonce that prevents replay
+    pub nonce: Option<String>,
+    /// When the message was issued
+    pub issued_at: Option<String>,
+    /// When the sign in stops being valid
+    pub expiration_time: Option<String>,
+    /// When the sign in becomes valid
+    pub not_before: Option<String>,
+    /// Opaque identifier for the request
+    pub request_id: Option<String>,
+    /// URIs the user is asked to authorize
+    pub resources: Vec<String>,
+}
+
+/// Optional single line fields in rendering order
+const FIELDS: [&str; 8] = [
+    "URI: ",
+    "Version: ",
+    "Chain ID: ",
+    "Nonce: ",
+    "Issued At: ",
+    "Expiration Time: ",
+    "Not Before: ",
+    "Request ID: ",
+];
+
+impl SignInMessage {
+    /// Create a message for `domain` and `address` with no optional fields
+    pub fn new(domain: impl Into<String>, address: Pubkey) -> Self {
+        Self {
+            domain: domain.into(),
+            address: Some(address),
+            ..Self::default()
+        }
+    }
+
+    fn fields(&self) -> [&Option<String>; 8] {
+        [
+            &self.uri,
+            &self.version,
+            &self.chain_id,
+            &self.nonce,
+            &self.issued_at,
+            &self.expiration_time,
+            &self.not_before,
+            &self.request_id,
+        ]
+    }
+
+    fn fields_mut(&mut self) -> [&mut Option<String>; 8] {
+        [
+            &mut self.uri,
+            &mut self.version,
+            &mut self.chain_id,
+            &mut self.nonce,
+            &mut self.issued_at,
+            &mut self.expiration_time,
+            &mut self.not_before,
+            &mut self.request_id,
+        ]
+    }
+
+    /// Parse signed message text, rejecting anything that does not render back identically
+    pub fn parse(text: &str) -> Result<Self> {
+        let invalid =
+            |reason: &str| SolanaError::GenericError(format!("invalid SIWS message: {reason}"));
+        let mut lines = text.split('\n');
+        let domain = lines
+            .next()
+            .and_then(|line| line.strip_suffix(HEADER_SUFFIX))
+            .filter(|domain| !domain.is_empty())
+            .ok_or_else(|| invalid("missing header"))?;
+        let address = lines
+            .next()
+            .ok_or_else(|| invalid("missing address"))?
+            .parse::<Pubkey>()
+            .map_err(|_| invalid("malformed address"))?;
+        let mut message = Self::new(domain, address);
+
+        let rest: Vec<&str> = lines.collect();
+        let mut rest = rest.as_slice();
+        let is_field = |line: &str| {
+            line == "Resources:" || FIELDS.iter().any(|prefix| line.starts_with(prefix))
+        };
+        if let ["", line, tail @ ..] = rest
+            && !is_field(line)
+        {
+            message.statement = Some(line.to_string());
+            rest = tail;
+        }
+        if let ["", tail @ ..] = rest {
+            rest = tail;
+        }
+
+        let mut next_field = 0;
+        while let [line, tail @ ..] = rest {
+            rest = tail;
+            if *line == "Resources:" {
+                for resource in rest {
+                    let resource = resource
+                        .strip_prefix("- ")
+                        .ok_or_else(|| invalid("malformed resource"))?;
+                    message.resources.push(resource.to_string());
+                }
+                break;
+            }
+            let index = FIELDS[next_field..]
+                .iter()
+                .position(|prefix| line.starts_with(prefix))
+                .map(|offset| next_field + offset)
+                .ok_or_else(|| invalid("unexpected line"))?;
+            *message.fields_mut()[index] = Some(line[FIELDS[index].len()..].to_string());
+            next_field = index + 1;
+        }
+
+        if message.to_string() != text {
+            return Err(invalid("not in canonical form"));
+        }
+        Ok(message)
+    }
+
+    /// Check a wallet's response against this expected message.
+    ///
+    /// The signed text must parse, carry the same domain, and match every optional field
+    /// set here; an unset `address` accepts any account. The signature must be a valid
+    /// Ed25519 signature by the signed address. Timestamps are compared as strings only:
+    /// checking `expiration_time` and `not_before` against the clock is left to the caller.
+    pub fn verify(&self, signed_message: &[u8], signature: &SignatureBytes) -> Result<Self> {
+        let text = core::str::from_utf8(signed_message)
+            .map_err(|_| SolanaError::GenericError("SIWS message is not UTF-8".to_string()))?;
+        let signed = Self::parse(text)?;
+        let mismatch =
+            |field: &str| SolanaError::GenericError(format!("SIWS message {field} does not match"));
+
+        if signed.domain != self.domain {
+            return Err(mismatch("domain"));
+        }
+        if self.address.is_some() && signed.address != self.address {
+            return Err(mismatch("address"));
+        }
+        if self.statement.is_some() && signed.statement != self.statement {
+            return Err(mismatch("statement"));
+        }
+        for ((expected, actual), prefix) in self.fields().iter().zip(signed.fields()).zip(FIELDS) {
+            if expected.is_some() && *expected != actual {
+                return Err(mismatch(prefix.trim_end_matches(": ")));
+            }
+        }
+        if !self.resources.is_empty() && signed.resources != self.resources {
+            return Err(mismatch("resources"));
+        }
+
+        let address = signed.address.as_ref().ok_or_else(|| mismatch("address"))?;
+        if !verify_signature(address, signed_message, signature) {
+            return Err(SolanaError::InvalidSignature(
+                "SIWS signature does not match address".to_string(),
+            ));
+        }
+        Ok(signed)
+    }
+}
+
+impl fmt::Display for SignInMessage {
+    /// Render the text a wallet signs; an unset address renders as an empty line
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}{HEADER_SUFFIX}", self.domain)?;
+        if let Some(address) = &self.address {
+            write!(f, "{address}")?;
+        }
+        if let Some(statement) = &self.statement {
+            write!(f, "\n\n{statement}")?;
+        }
+
+        let mut separator = "\n\n";
+        for (value, prefix) in self.fields().into_iter().zip(FIELDS) {
+            if let Some(value) = value {
+                write!(f, "{separator}{prefix}{value}")?;
+                separator = "\n";
+            }
+        }
+        if !self.resources.is_empty() {
+            write!(f, "{separator}Resources:")?;
+            for resource in &self.resources {
+                write!(f, "\n- {resource}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SignInMessage;
+    use crate::crypto::Keypair;
+
+    fn expected(address: crate::Pubkey) -> SignInMessage {
+        SignInMessage {
+            statement: Some("Sign in to Example".to_string()),
+            uri: Some("https://example.com/login".to_string()),
+            version: Some("1".to_string()),
+            chain_id: Some("mainnet".to_string()),
+            nonce: Some("32891756".to_string()),
+            issued_at: Some("2024-01-01T00:00:00.000Z".to_string()),
+            resources: vec!["https://example.com/terms".to_string()],
+            ..SignInMessage::new("example.com", address)
+        }
+    }
+
+    #[test]
+    fn test_render_and_parse() {
+        let keypair = Keypair::from_seed(&[3u8; 32]).unwrap();
+        let message = expected(keypair.pubkey());
+        let text = message.to_string();
+        assert_eq!(
+            text,
+            format!(
+                "example.com wants you to sign in with your Solana account:\n{}\n\n\
+                 Sign in to Example\n\n\
+                 URI: https://example.com/login\nVersion: 1\nChain ID: mainnet\n\
+                 Nonce: 32891756\nIssued At: 2024-01-01T00:00:00.000Z\n\
+                 Resources:\n- https://example.com/terms",
+                keypair.pubkey()
+            )
+        );
+        assert_eq!(SignInMessage::parse(&text).unwrap(), message);
+
+        let minimal = SignInMessage::new("example.com", keypair.pubkey());
+        assert_eq!(SignInMessage::parse(&minimal.to_string()).unwrap(), minimal);
+        let no_statement = SignInMessage {
+            nonce: Some("1".to_string()),
+            ..minimal
+        };
+        assert_eq!(
+            SignInMessage::parse(&no_statement.to_string()).unwrap(),
+            no_statement
+        );
+
+        // Fields out of order or trailing whitespace are not canonical
+        let swapped = text.replace(
+            "Version: 1\nChain ID: mainnet",
+            "Chain ID: mainnet\nVersion: 1",
+        );
+        assert!(SignInMessage::parse(&swapped).is_err());
+        assert!(SignInMessage::parse(&format!("{text}\n")).is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        let keypair = Keypair::from_seed(&[3u8; 32]).unwrap();
+        let message = expected(keypair.pubkey());
+        let text = message.to_string();
+        let signature = keypair.sign_message(text.as_bytes());
+
+        assert_eq!(
+            message.verify(text.as_bytes(), &signature).unwrap(),
+            message
+        );
+
+        // A backend that only pins domain and nonce accepts any signing account
+        let loose = SignInMessage {
+            domain: "example.com".to_string(),
+            nonce: message.nonce.clone(),
+            ..SignInMessage::default()
+        };
+        assert!(loose.verify(text.as_bytes(), &signature).is_ok());
+
+        let replayed = SignInMessage {
+            nonce: Some("other".to_string()),
+            ..message.clone()
+        };
+        assert!(replayed.verify(text.as_bytes(), &signature).is_err());
+
+        let other = Keypair::from_seed(&[4u8; 32]).unwrap();
+        let forged = other.sign_message(text.as_bytes());
+        assert!(message.verify(text.as_bytes(), &forged).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+//! Chunked text encoding of transactions for QR code transfer.
+//!
+//! Airgapped signing moves a partially signed transaction between devices as a series of
+//! QR codes. [`encode_chunks`] splits the bytes into strings of at most a given length,
+//! each shaped `soltx:<index>/<total>/<checksum>/<base64url>`, and [`ChunkAssembler`]
+//! collects scanned chunks in any order, ignoring repeats, until the bytes are complete.
+//! The checksum is the first four bytes of the SHA-256 of the whole payload, so chunks of
+//! different transactions are never mixed.
+
+use crate::error::{Result, SolanaError};
+use crate::types::VersionedTransaction;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use sha2::{Digest, Sha256};
+
+/// Prefix of every chunk
+pub const CHUNK_PREFIX: &str = "soltx:";
+/// Chunk length that fits a version 15 QR code at medium error correction
+pub const DEFAULT_CHUNK_LEN: usize = 400;
+/// Most chunks a payload may be split into
+pub const MAX_CHUNKS: usize = 999;
+
+/// Length of the `soltx:iii/nnn/cccccccc/` header with three-digit counters
+const HEADER_LEN: usize = CHUNK_PREFIX.len() + 3 + 1 + 3 + 1 + 8 + 1;
+
+fn checksum(bytes: &[u8]) -> String {
+    hex::encode(&Sha256::digest(bytes)[..4])
+}
+
+/// Split `bytes` into chunks of at most `max_chunk_len` characters.
+///
+/// Fails if `max_chunk_len` leaves no room for data or the payload needs more than
+/// [`MAX_CHUNKS`] chunks.
+pub fn encode_chunks(bytes: &[u8], max_chunk_len: usize) -> Result<Vec<String>> {
+    // Four base64 characters carry three bytes
+    let bytes_per_chunk = max_chunk_len.saturating_sub(HEADER_LEN) / 4 * 3;
+    if bytes_per_chunk == 0 {
+        return Err(SolanaError::SerializationError(format!(
+            "chunk length {max_chunk_len} leaves no room for data"
+        )));
+    }
+    let total = bytes.len().div_ceil(bytes_per_chunk).max(1);
+    if total > MAX_CHUNKS {
+        return Err(SolanaError::SerializationError(format!(
+            "{} bytes need {total} chunks, more than {MAX_CHUNKS}",
+            bytes.len()
+        )));
+    }
+
+    let checksum = checksum(bytes);
+    let chunks = if bytes.is_empty() {
+        Vec::from([&[][..]])
+    } else {
+        bytes.chunks(bytes_per_chunk).collect()
+    };
+    Ok(chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, data)| {
+            format!(
+                "{CHUNK_PREFIX}{}/{total}/{checksum}/{}",
+                index + 1,
+                URL_SAFE_NO_PAD.encode(data)
+            )
+        })
+        .collect())
+}
+
+/// Reassemble the bytes of a complete set of chunks, in any order
+pub fn decode_chunks<S: AsRef<str>>(chunks: &[S]) -> Result<Vec<u8>> {
+    let mut assembler = ChunkAssembler::default();
+    for chunk in chunks {
+        assembler.add(chunk.as_ref())?;
+    }
+    assembler.finish()
+}
+
+/// Serialize a transaction, signed or not, into chunks
+pub fn encode_transaction(
+    transaction: &VersionedTransaction,
+    max_chunk_len: usize,
+) -> Result<Vec<String>> {
+    encode_chunks(&transaction.serialize()?, max_chunk_len)
+}
+
+/// Reassemble and deserialize a transaction from its chunks
+pub fn decode_transaction<S: AsRef<str>>(chunks: &[S]) -> Result<VersionedTransaction> {
+    VersionedTransaction::deserialize_with_version(&decode_chunks(chunks)?)
+}
+
+/// Collects chunks one scan at a time
+#[derive(Debug, Clone, Default)]
+pub struct ChunkAssembler {
+    checksum: Option<String>,
+    chunks: Vec<Option<Vec<u8>>>,
+}
+
+impl ChunkAssembler {
+    /// Add a scanned chunk, returning whether every chunk has now been seen.
+    ///
+    /// Repeated chunks are ignored. Fails on malformed chunks and on chunks from a
+    /// different payload than the first one added.
+    pub fn add(&mut self, chunk: &str) -> Result<bool> {
+        let invalid =
+            |reason: &str| SolanaError::DeserializationError(format!("invalid QR chunk: {reason}"));
+        let mut parts = chunk
+            .strip_prefix(CHUNK_PREFIX)
+            .ok_or_else(|| invalid("missing prefix"))?
+            .splitn(4, '/');
+        let mut next = || parts.next().ok_or_else(|| invalid("missing field"));
+        let (index, total, checksum, data) = (next()?, next()?, next()?, next()?);
+        let index: usize = index.parse().map_err(|_| invalid("bad index"))?;
+        let total: usize = total.parse().map_err(|_| invalid("bad total"))?;
+        if total == 0 || total > MAX_CHUNKS || index == 0 || index > total {
+            return Err(invalid("index out of range"));
+        }
+        let data = URL_SAFE_NO_PAD
+            .decode(data)
+            .map_err(|e| invalid(&e.to_string()))?;
+
+        match &self.checksum {
+            Some(expected) if expected != checksum || self.chunks.len() != total => {
+                return Err(invalid("chunk belongs to a different payload"));
+            }
+            Some(_) => {}
+            None => {
+                self.checksum = Some(checksum.to_string());
+                self.chunks = vec![None; total];
+            }
+        }
+        self.chunks[index - 1].get_or_insert(data);
+        Ok(self.is_complete())
+    }
+
+    /// Chunks seen so far and the total, `(0, 0)` before the first chunk
+    pub fn progress(&self) -> (usize, usize) {
+        let seen = self.chunks.iter().filter(|chunk| chunk.is_some()).count();
+        (seen, self.chunks.len())
+    }
+
+    /// Whether every chunk has been seen
+    pub fn is_complete(&self) -> bool {
+        !self.chunks.is_empty() && self.chunks.iter().all(Option::is_some)
+    }
+
+    /// Join the chunks, checking them against the payload checksum
+    pub fn finish(self) -> Result<Vec<u8>> {
+        if !self.is_complete() {
+            let (seen, total) = self.progress();
+            return Err(SolanaError::DeserializationError(format!(
+                "missing QR chunks: have {seen} of {total}"
+            )));
+        }
+        let bytes: Vec<u8> = self.chunks.into_iter().flatten().flatten().collect();
+        if self.checksum.as_deref() != Some(checksum(&bytes).as_str()) {
+            return Err(SolanaError::DeserializationError(
+                "QR chunks do not match their checksum".to_string(),
+            ));
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_in_any_order() {
+        let bytes: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let chunks = encode_chunks(&bytes, 120).unwrap();
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 120));
+        assert!(chunks[0].starts_with(&format!("soltx:1/{}/", chunks.len())));
+
+        let mut assembler = ChunkAssembler::default();
+        for chunk in chunks.iter().rev().chain(&chunks[..1]) {
+            assembler.add(chunk).unwrap();
+        }
+        assert!(assembler.is_complete());
+        assert_eq!(assembler.finish().unwrap(), bytes);
+
+        assert_eq!(
+            decode_chunks(&encode_chunks(&[], 120).unwrap()).unwrap(),
+            Vec::<u8>::new()
+        );
+        assert!(encode_chunks(&bytes, HEADER_LEN + 3).is_err());
+    }
+
+    #[test]
+    fn test_rejects_mixed_and_incomplete_sets() {
+        let first = encode_chunks(&[1u8; 300], 100).unwrap();
+        let second = encode_chunks(&[2u8; 300], 100).unwrap();
+
+        let mut assembler = ChunkAssembler::default();
+        assert!(!assembler.add(&first[0]).unwrap());
+        assert_eq!(assembler.progress(), (1, first.len()));
+        assert!(assembler.add(&second[1]).is_err());
+        assert!(assembler.add("soltx:9/3/00000000/AA").is_err());
+        assert!(assembler.add("nope").is_err());
+        assert!(assembler.finish().is_err());
+
+        // A tampered chunk is caught by the payload checksum
+        let mut tampered = first.clone();
+        tampered[0] = first[0].replace("/AQEB", "/AgEB");
+        assert!(decode_chunks(&tampered).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+//! Rent-exemption calculation.
+//!
+//! [`Rent::default`] matches the parameters of every public cluster, so the minimum balance
+//! for a new account can be computed without a `getMinimumBalanceForRentExemption` call. Use
+//! [`Rent::from_account_data`] on the Rent sysvar when the live values are needed.
+
+use crate::data::DataReader;
+use crate::error::Result;
+
+/// Bytes of account metadata charged rent on top of the data
+pub const ACCOUNT_STORAGE_OVERHEAD: u64 = 128;
+
+/// Default rental rate in lamports per byte-year
+pub const DEFAULT_LAMPORTS_PER_BYTE_YEAR: u64 = 3_480;
+
+/// Default years of rent an account must hold to be exempt
+pub const DEFAULT_EXEMPTION_THRESHOLD: f64 = 2.0;
+
+/// Default percentage of collected rent that is burned
+pub const DEFAULT_BURN_PERCENT: u8 = 50;
+
+/// Rent parameters, as stored in the Rent sysvar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rent {
+    /// Rental rate in lamports per byte-year
+    pub lamports_per_byte_year: u64,
+    /// Years of rent an account must hold to be exempt
+    pub exemption_threshold: f64,
+    /// Percentage of collected rent that is burned
+    pub burn_percent: u8,
+}
+
+impl Default for Rent {
+    fn default() -> Self {
+        Self {
+            lamports_per_byte_year: DEFAULT_LAMPORTS_PER_BYTE_YEAR,
+            exemption_threshold: DEFAULT_EXEMPTION_THRESHOLD,
+            burn_percent: DEFAULT_BURN_PERCENT,
+        }
+    }
+}
+
+impl Rent {
+    /// Decode Rent sysvar account data
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let mut reader = DataReader::new(data);
+        Ok(Self {
+            lamports_per_byte_year: reader.read()?,
+            exemption_threshold: f64::from_bits(reader.read()?),
+            burn_percent: reader.read()?,
+        })
+    }
+
+    /// Lamports an account with `data_len` bytes of data needs to be rent exempt
+    pub fn minimum_balance(&self, data_len: usize) -> u64 {
+        let bytes = ACCOUNT_STORAGE_OVERHEAD.saturating_add(data_len as u64);
+        (bytes.saturating_mul(self.lamports_per_byte_year) as f64 * self.exemption_threshold) as u64
+    }
+
+    /// Whether `lamports` keeps an account with `data_len` bytes rent exempt
+    pub fn is_exempt(&self, lamports: u64, data_len: usize) -> bool {
+        lamports >= self.minimum_balance(data_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minimum_balance() {
+        let rent = Rent::default();
+        assert_eq!(rent.minimum_balance(0), 890_880);
+        // SPL token account
+        assert_eq!(rent.minimum_balance(165), 2_039_280);
+        assert!(rent.is_exempt(2_039_280, 165));
+        assert!(!rent.is_exempt(2_039_279, 165));
+    }
+
+    #[test]
+    fn test_rent_from_account_data() {
+        let mut data = 3_480u64.to_le_bytes().to_vec();
+        data.extend_from_slice(&2.0f64.to_le_bytes());
+        data.push(50);
+
+        assert_eq!(Rent::from_account_data(&data).unwrap(), Rent::default());
+        assert!(Rent::from_account_data(&data[..16]).is_err());
+    }
+}
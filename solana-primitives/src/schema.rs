@@ -0,0 +1,161 @@
+//! JSON Schema export for the crate's serde types.
+
+use crate::instructions::config::ValidatorInfoFields;
+use crate::types::{
+    AccountMeta, AddressLookupTableAccount, CompiledInstruction, ComputeUnits, Hash, Instruction,
+    Lamports, LegacyMessage, Message, MessageAddressTableLookup, MessageHeader, MicroLamports,
+    PrioritizationFee, Pubkey, SignatureBytes, SigningRequest, Transaction, TransactionRequest,
+    VersionedMessage, VersionedMessageV0, VersionedTransaction,
+};
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema, schema_for};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+impl JsonSchema for Pubkey {
+    fn schema_name() -> Cow<'static, str> {
+        "Pubkey".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "description": "Base58-encoded 32-byte public key",
+            "pattern": "^[1-9A-HJ-NP-Za-km-z]{32,44}$"
+        })
+    }
+}
+
+impl JsonSchema for Hash {
+    fn schema_name() -> Cow<'static, str> {
+        "Hash".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "description": "Base58-encoded 32-byte hash",
+            "pattern": "^[1-9A-HJ-NP-Za-km-z]{32,44}$"
+        })
+    }
+}
+
+impl JsonSchema for SignatureBytes {
+    fn schema_name() -> Cow<'static, str> {
+        "Signature".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "description": "Base58-encoded 64-byte ed25519 signature",
+            "pattern": "^[1-9A-HJ-NP-Za-km-z]{64,88}$"
+        })
+    }
+}
+
+/// Schema for a `Vec<u8>` serialized with [`crate::short_vec`]: the compact-u16 length as a
+/// nested array of one to three bytes, followed by the elements
+pub(crate) fn short_vec_u8(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "type": "array",
+        "prefixItems": [{
+            "type": "array",
+            "description": "Compact-u16 length bytes",
+            "items": { "type": "integer", "minimum": 0, "maximum": 255 },
+            "minItems": 1,
+            "maxItems": 3
+        }],
+        "items": { "type": "integer", "minimum": 0, "maximum": 255 },
+        "minItems": 1
+    })
+}
+
+/// JSON Schemas for every public serde type, keyed by type name
+pub fn schemas() -> BTreeMap<&'static str, Schema> {
+    BTreeMap::from([
+        ("AccountMeta", schema_for!(AccountMeta)),
+        (
+            "AddressLookupTableAccount",
+            schema_for!(AddressLookupTableAccount),
+        ),
+        ("CompiledInstruction", schema_for!(CompiledInstruction)),
+        ("ComputeUnits", schema_for!(ComputeUnits)),
+        ("Hash", schema_for!(Hash)),
+        ("Instruction", schema_for!(Instruction)),
+        ("Lamports", schema_for!(Lamports)),
+        ("LegacyMessage", schema_for!(LegacyMessage)),
+        ("Message", schema_for!(Message)),
+        (
+            "MessageAddressTableLookup",
+            schema_for!(MessageAddressTableLookup),
+        ),
+        ("MessageHeader", schema_for!(MessageHeader)),
+        ("MicroLamports", schema_for!(MicroLamports)),
+        ("PrioritizationFee", schema_for!(PrioritizationFee)),
+        ("Pubkey", schema_for!(Pubkey)),
+        ("Signature", schema_for!(SignatureBytes)),
+        ("SigningRequest", schema_for!(SigningRequest)),
+        ("Transaction", schema_for!(Transaction)),
+        ("TransactionRequest", schema_for!(TransactionRequest)),
+        ("ValidatorInfoFields", schema_for!(ValidatorInfoFields)),
+        ("VersionedMessage", schema_for!(VersionedMessage)),
+        ("VersionedMessageV0", schema_for!(VersionedMessageV0)),
+        ("VersionedTransaction", schema_for!(VersionedTransaction)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schemas() {
+        let schemas = schemas();
+        assert_eq!(schemas.len(), 22);
+
+        let request = serde_json::to_value(&schemas["TransactionRequest"]).unwrap();
+        assert_eq!(request["properties"]["requiredSigners"]["type"], "array");
+        assert!(request["properties"].get("expiresAt").is_some());
+
+        let pubkey = serde_json::to_value(&schemas["Pubkey"]).unwrap();
+        assert_eq!(pubkey["type"], "string");
+
+        let units = serde_json::to_value(&schemas["ComputeUnits"]).unwrap();
+        assert_eq!(units["type"], "integer");
+    }
+
+    #[test]
+    fn test_schemas_accept_serialized_lookups() {
+        let schemas = schemas();
+        let lookup = MessageAddressTableLookup::new(
+            Pubkey::new([3u8; 32]),
+            (0..200).map(|i| i as u8).collect(),
+            vec![],
+        );
+        let instance = serde_json::to_value(&lookup).unwrap();
+        assert_eq!(instance["writable_indexes"][0], serde_json::json!([200, 1]));
+        assert_eq!(instance["readonly_indexes"], serde_json::json!([[0]]));
+
+        let schema = serde_json::to_value(&schemas["MessageAddressTableLookup"]).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        assert!(validator.is_valid(&instance));
+        let mut plain = instance.clone();
+        plain["writable_indexes"] = serde_json::json!([1, 2]);
+        assert!(!validator.is_valid(&plain));
+
+        let message = VersionedMessage::V0(VersionedMessageV0 {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 0,
+            },
+            account_keys: vec![Pubkey::new([1u8; 32])],
+            recent_blockhash: Hash::new([2u8; 32]),
+            instructions: vec![],
+            address_table_lookups: vec![lookup],
+        });
+        let schema = serde_json::to_value(&schemas["VersionedMessage"]).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        assert!(validator.is_valid(&serde_json::to_value(&message).unwrap()));
+    }
+}
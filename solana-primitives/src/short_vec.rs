@@ -1,12 +1,15 @@
 // Compact serde-encoding of vectors with small length.
 use borsh::{BorshDeserialize, BorshSerialize};
 
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt, marker::PhantomData};
 use serde::{
     Deserialize, Serialize,
     de::{self, Deserializer, SeqAccess, Visitor},
     ser::{self, SerializeTuple, Serializer},
 };
-use std::{convert::TryFrom, fmt, marker::PhantomData, vec::Vec};
 
 /// Represents a ShortU16.
 ///
@@ -202,28 +205,43 @@ where
     }
 }
 
+/// Number of bytes the compact-u16 encoding of `len` takes
+pub const fn compact_u16_len(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
 // Helper function to encode a usize length into Compact-U16 format bytes.
 // Returns a Vec<u8> with the encoded length or an Err if length is too large for u16.
 pub fn encode_length_to_compact_u16_bytes(len: usize) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(compact_u16_len(len));
+    write_compact_u16(&mut bytes, len)?;
+    Ok(bytes)
+}
+
+/// Append the compact-u16 encoding of `len` to `out`, without an intermediate buffer
+pub fn write_compact_u16(out: &mut Vec<u8>, len: usize) -> Result<(), String> {
     if len > u16::MAX as usize {
         return Err(format!(
             "Length {len} exceeds u16::MAX, cannot encode as Compact-U16"
         ));
     }
-    let mut bytes = Vec::new();
     let mut rem_val = len as u16; // Safe to cast now
     loop {
         let mut elem = (rem_val & 0x7f) as u8;
         rem_val >>= 7;
         if rem_val == 0 {
-            bytes.push(elem);
+            out.push(elem);
             break;
         } else {
             elem |= 0x80; // More bytes to follow, set MSB
-            bytes.push(elem);
+            out.push(elem);
         }
     }
-    Ok(bytes)
+    Ok(())
 }
 
 // Helper function to decode Compact-U16 length
@@ -273,7 +291,8 @@ where
     D: Deserializer<'de>,
     T: Deserialize<'de>,
 {
-    deserializer.deserialize_seq(ShortVecVisitor { _t: PhantomData })
+    // A tuple, not a seq, so binary formats don't expect their own length prefix
+    deserializer.deserialize_tuple(usize::MAX, ShortVecVisitor { _t: PhantomData })
 }
 
 /// A newtype to provide Compact-U16 (AKA short_vec) serialization for `Vec<T>`
@@ -320,8 +339,8 @@ impl<T: Clone> Clone for ShortVec<T> {
     }
 }
 
-impl<T: std::fmt::Debug> std::fmt::Debug for ShortVec<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<T: core::fmt::Debug> core::fmt::Debug for ShortVec<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("ShortVec").field(&self.inner).finish()
     }
 }
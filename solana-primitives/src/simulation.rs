@@ -0,0 +1,299 @@
+//! Decoding of `simulateTransaction` results.
+//!
+//! [`SimulationLogs`] rebuilds the invocation tree from the runtime's log lines, and
+//! [`TransactionReturnData`] decodes the `returnData` field of the RPC response.
+
+use crate::error::{Result, SolanaError};
+use crate::types::{ComputeUnits, Pubkey};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::{Deserialize, Serialize};
+
+/// Data a program set with `sol_set_return_data`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "UiReturnData", into = "UiReturnData")]
+pub struct TransactionReturnData {
+    /// The program that set the data
+    pub program_id: Pubkey,
+    /// The returned bytes
+    pub data: Vec<u8>,
+}
+
+/// RPC JSON form: `{"programId": "...", "data": ["<base64>", "base64"]}`
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UiReturnData {
+    program_id: Pubkey,
+    data: (String, String),
+}
+
+impl TryFrom<UiReturnData> for TransactionReturnData {
+    type Error = SolanaError;
+
+    fn try_from(ui: UiReturnData) -> Result<Self> {
+        let (data, encoding) = ui.data;
+        if encoding != "base64" {
+            return Err(SolanaError::DeserializationError(format!(
+                "unsupported return data encoding: {encoding}"
+            )));
+        }
+        Self::from_base64(ui.program_id, &data)
+    }
+}
+
+impl From<TransactionReturnData> for UiReturnData {
+    fn from(return_data: TransactionReturnData) -> Self {
+        Self {
+            program_id: return_data.program_id,
+            data: (STANDARD.encode(return_data.data), "base64".to_string()),
+        }
+    }
+}
+
+impl TransactionReturnData {
+    /// Decode base64 return data set by `program_id`
+    pub fn from_base64(program_id: Pubkey, data: &str) -> Result<Self> {
+        let data = STANDARD
+            .decode(data)
+            .map_err(|e| SolanaError::DeserializationError(format!("invalid return data: {e}")))?;
+        Ok(Self { program_id, data })
+    }
+}
+
+/// How a program invocation ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvocationOutcome {
+    /// `Program <id> success`
+    Success,
+    /// `Program <id> failed: <error>`
+    Failed(String),
+}
+
+/// One program invocation reconstructed from the logs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramInvocation {
+    /// The invoked program
+    pub program_id: Pubkey,
+    /// Invoke depth, 1 for a top-level instruction
+    pub depth: u8,
+    /// Index of the invocation that made this CPI, `None` at depth 1
+    pub parent: Option<usize>,
+    /// Units consumed, including any CPIs it made
+    pub consumed: Option<ComputeUnits>,
+    /// `Program log:` messages emitted while this program was running
+    pub logs: Vec<String>,
+    /// `None` if the logs end before the invocation does
+    pub outcome: Option<InvocationOutcome>,
+}
+
+impl ProgramInvocation {
+    /// The code of a `custom program error: 0x..` failure
+    pub fn custom_error(&self) -> Option<u32> {
+        match &self.outcome {
+            Some(InvocationOutcome::Failed(error)) => error
+                .strip_prefix("custom program error: 0x")
+                .and_then(|code| u32::from_str_radix(code, 16).ok()),
+            _ => None,
+        }
+    }
+}
+
+/// Program invocations, compute usage and return data parsed from simulation logs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimulationLogs {
+    invocations: Vec<ProgramInvocation>,
+    return_data: Option<TransactionReturnData>,
+    truncated: bool,
+}
+
+impl SimulationLogs {
+    /// Parse the `logs` of a simulation or `getTransaction` response.
+    ///
+    /// Lines that do not follow the runtime's formats are ignored.
+    pub fn parse<S: AsRef<str>>(logs: &[S]) -> Self {
+        let mut parsed = Self::default();
+        let mut stack: Vec<usize> = vec![];
+        for line in logs {
+            let line = line.as_ref();
+            if line == "Log truncated" {
+                parsed.truncated = true;
+                continue;
+            }
+            if let Some(message) = line.strip_prefix("Program log: ") {
+                if let Some(&current) = stack.last() {
+                    parsed.invocations[current].logs.push(message.to_string());
+                }
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("Program return: ") {
+                parsed.return_data = rest.split_once(' ').and_then(|(program_id, data)| {
+                    let program_id = program_id.parse().ok()?;
+                    TransactionReturnData::from_base64(program_id, data).ok()
+                });
+                continue;
+            }
+
+            let Some((program_id, event)) = line
+                .strip_prefix("Program ")
+                .and_then(|rest| rest.split_once(' '))
+                .and_then(|(id, event)| Some((id.parse::<Pubkey>().ok()?, event)))
+            else {
+                continue;
+            };
+            if let Some(depth) = event
+                .strip_prefix("invoke [")
+                .and_then(|depth| depth.strip_suffix(']'))
+                .and_then(|depth| depth.parse().ok())
+            {
+                parsed.invocations.push(ProgramInvocation {
+                    program_id,
+                    depth,
+                    parent: stack.last().copied(),
+                    consumed: None,
+                    logs: vec![],
+                    outcome: None,
+                });
+                stack.push(parsed.invocations.len() - 1);
+                continue;
+            }
+
+            // The remaining events belong to the innermost running invocation
+            let Some(&current) = stack.last() else {
+                continue;
+            };
+            let invocation = &mut parsed.invocations[current];
+            if invocation.program_id != program_id {
+                continue;
+            }
+            if let Some(consumed) = event
+                .strip_prefix("consumed ")
+                .and_then(|rest| rest.split_once(' '))
+                .and_then(|(units, _)| units.parse().ok())
+            {
+                invocation.consumed = Some(ComputeUnits(consumed));
+            } else if event == "success" {
+                invocation.outcome = Some(InvocationOutcome::Success);
+                stack.pop();
+            } else if let Some(error) = event.strip_prefix("failed: ") {
+                invocation.outcome = Some(InvocationOutcome::Failed(error.to_string()));
+                stack.pop();
+            }
+        }
+        parsed
+    }
+
+    /// Every invocation in the order it started
+    pub fn invocations(&self) -> &[ProgramInvocation] {
+        &self.invocations
+    }
+
+    /// Units consumed by the top-level instructions that reported it
+    pub fn total_consumed(&self) -> ComputeUnits {
+        ComputeUnits(
+            self.invocations
+                .iter()
+                .filter(|invocation| invocation.depth == 1)
+                .filter_map(|invocation| invocation.consumed)
+                .map(|units| units.0)
+                .sum(),
+        )
+    }
+
+    /// The innermost invocation that failed, where the error originated
+    pub fn failure(&self) -> Option<&ProgramInvocation> {
+        self.invocations
+            .iter()
+            .filter(|invocation| matches!(invocation.outcome, Some(InvocationOutcome::Failed(_))))
+            .max_by_key(|invocation| invocation.depth)
+    }
+
+    /// The custom error code of the failing program, if it returned one
+    pub fn custom_error(&self) -> Option<u32> {
+        self.failure().and_then(ProgramInvocation::custom_error)
+    }
+
+    /// The last return data logged
+    pub fn return_data(&self) -> Option<&TransactionReturnData> {
+        self.return_data.as_ref()
+    }
+
+    /// Whether the runtime cut the logs short
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::program_ids::{system_program, token_program};
+
+    #[test]
+    fn test_parse_nested_failure() {
+        let (program, token, system) = (Pubkey::new([7u8; 32]), token_program(), system_program());
+        let logs = vec![
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]".to_string(),
+            "Program ComputeBudget111111111111111111111111111111 success".to_string(),
+            format!("Program {program} invoke [1]"),
+            "Program log: Instruction: Swap".to_string(),
+            format!("Program {system} invoke [2]"),
+            format!("Program {system} success"),
+            format!("Program {token} invoke [2]"),
+            "Program log: Error: insufficient funds".to_string(),
+            format!("Program {token} consumed 4645 of 180000 compute units"),
+            format!("Program {token} failed: custom program error: 0x1"),
+            format!("Program {program} consumed 24150 of 199850 compute units"),
+            format!("Program {program} failed: custom program error: 0x1"),
+        ];
+
+        let parsed = SimulationLogs::parse(&logs);
+        let invocations = parsed.invocations();
+        assert_eq!(invocations.len(), 4);
+        assert_eq!(
+            invocations
+                .iter()
+                .map(|invocation| (invocation.depth, invocation.parent))
+                .collect::<Vec<_>>(),
+            vec![(1, None), (1, None), (2, Some(1)), (2, Some(1))]
+        );
+        assert_eq!(invocations[1].logs, vec!["Instruction: Swap"]);
+        assert_eq!(invocations[3].logs, vec!["Error: insufficient funds"]);
+        assert_eq!(invocations[2].outcome, Some(InvocationOutcome::Success));
+        assert_eq!(parsed.total_consumed(), ComputeUnits(24150));
+
+        let failure = parsed.failure().unwrap();
+        assert_eq!(failure.program_id, token);
+        assert_eq!(failure.consumed, Some(ComputeUnits(4645)));
+        assert_eq!(parsed.custom_error(), Some(1));
+        assert!(!parsed.is_truncated());
+    }
+
+    #[test]
+    fn test_return_data() {
+        let program = Pubkey::new([7u8; 32]);
+        let logs = [
+            format!("Program {program} invoke [1]"),
+            format!("Program return: {program} AQID"),
+            format!("Program {program} success"),
+            "Log truncated".to_string(),
+        ];
+        let parsed = SimulationLogs::parse(&logs);
+        let expected = TransactionReturnData {
+            program_id: program,
+            data: vec![1, 2, 3],
+        };
+        assert_eq!(parsed.return_data(), Some(&expected));
+        assert!(parsed.is_truncated());
+
+        let json = format!(r#"{{"programId":"{program}","data":["AQID","base64"]}}"#);
+        let decoded: TransactionReturnData = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, expected);
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+        assert!(
+            serde_json::from_str::<TransactionReturnData>(&json.replace("base64", "base58"))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,377 @@
+//! SPL Token and Token-2022 account state.
+//!
+//! Decodes the raw `data` of mint, token and multisig accounts, e.g. from `getAccountInfo`.
+//! Token-2022 accounts carry extensions after the base layout as type-length-value entries;
+//! they are returned undecoded in [`TokenExtension`].
+
+use crate::data::DataReader;
+use crate::error::{Result, SolanaError};
+use crate::types::Pubkey;
+use alloc::format;
+use alloc::vec::Vec;
+
+/// Size of a mint without extensions
+pub const MINT_LENGTH: usize = 82;
+
+/// Size of a token account without extensions
+pub const TOKEN_ACCOUNT_LENGTH: usize = 165;
+
+/// Size of a token multisig account
+pub const MULTISIG_LENGTH: usize = 355;
+
+/// Maximum number of signers a token multisig can hold
+pub const MAX_SIGNERS: usize = 11;
+
+/// Token-2022 account type byte for mints, stored after the padded base layout
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+/// Token-2022 account type byte for token accounts
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+/// A Token-2022 extension entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenExtension {
+    /// `ExtensionType` discriminant
+    pub extension_type: u16,
+    /// Raw extension data
+    pub data: Vec<u8>,
+}
+
+/// A decoded mint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MintState {
+    /// Authority allowed to mint new tokens
+    pub mint_authority: Option<Pubkey>,
+    /// Total supply
+    pub supply: u64,
+    /// Number of base 10 digits to the right of the decimal place
+    pub decimals: u8,
+    /// Whether the mint has been initialized
+    pub is_initialized: bool,
+    /// Authority allowed to freeze token accounts
+    pub freeze_authority: Option<Pubkey>,
+    /// Token-2022 extensions
+    pub extensions: Vec<TokenExtension>,
+}
+
+impl MintState {
+    /// Decode SPL Token or Token-2022 mint data
+    pub fn unpack(data: &[u8]) -> Result<Self> {
+        let extensions = if data.len() == MINT_LENGTH {
+            Vec::new()
+        } else {
+            unpack_extensions(data, ACCOUNT_TYPE_MINT)?
+        };
+        let mut reader = DataReader::new(data);
+        Ok(Self {
+            mint_authority: read_optional_pubkey(&mut reader)?,
+            supply: reader.read()?,
+            decimals: reader.read()?,
+            is_initialized: reader.read::<u8>()? != 0,
+            freeze_authority: read_optional_pubkey(&mut reader)?,
+            extensions,
+        })
+    }
+
+    /// Data of the first extension of the given type
+    pub fn extension(&self, extension_type: u16) -> Option<&[u8]> {
+        find_extension(&self.extensions, extension_type)
+    }
+}
+
+/// State of a token account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountState {
+    /// Not yet initialized
+    Uninitialized,
+    /// Initialized and usable
+    Initialized,
+    /// Frozen by the mint's freeze authority
+    Frozen,
+}
+
+/// A decoded token account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenAccountState {
+    /// Mint of the tokens held
+    pub mint: Pubkey,
+    /// Owner of the tokens
+    pub owner: Pubkey,
+    /// Token balance
+    pub amount: u64,
+    /// Delegate allowed to transfer up to `delegated_amount`
+    pub delegate: Option<Pubkey>,
+    /// Account state
+    pub state: AccountState,
+    /// Rent-exempt reserve of a wrapped SOL account
+    pub is_native: Option<u64>,
+    /// Amount the delegate may transfer
+    pub delegated_amount: u64,
+    /// Authority allowed to close the account
+    pub close_authority: Option<Pubkey>,
+    /// Token-2022 extensions
+    pub extensions: Vec<TokenExtension>,
+}
+
+impl TokenAccountState {
+    /// Decode SPL Token or Token-2022 token account data
+    pub fn unpack(data: &[u8]) -> Result<Self> {
+        let extensions = if data.len() == TOKEN_ACCOUNT_LENGTH {
+            Vec::new()
+        } else {
+            unpack_extensions(data, ACCOUNT_TYPE_ACCOUNT)?
+        };
+        let mut reader = DataReader::new(data);
+        Ok(Self {
+            mint: reader.read_pubkey()?,
+            owner: reader.read_pubkey()?,
+            amount: reader.read()?,
+            delegate: read_optional_pubkey(&mut reader)?,
+            state: match reader.read::<u8>()? {
+                0 => AccountState::Uninitialized,
+                1 => AccountState::Initialized,
+                2 => AccountState::Frozen,
+                state => {
+                    return Err(SolanaError::DeserializationError(format!(
+                        "invalid token account state: {state}"
+                    )));
+                }
+            },
+            is_native: match reader.read::<u32>()? {
+                0 => {
+                    reader.read::<u64>()?;
+                    None
+                }
+                _ => Some(reader.read()?),
+            },
+            delegated_amount: reader.read()?,
+            close_authority: read_optional_pubkey(&mut reader)?,
+            extensions,
+        })
+    }
+
+    /// Whether the account holds wrapped SOL
+    pub fn is_native(&self) -> bool {
+        self.is_native.is_some()
+    }
+
+    /// Whether the account is frozen
+    pub fn is_frozen(&self) -> bool {
+        self.state == AccountState::Frozen
+    }
+
+    /// Data of the first extension of the given type
+    pub fn extension(&self, extension_type: u16) -> Option<&[u8]> {
+        find_extension(&self.extensions, extension_type)
+    }
+}
+
+/// A decoded token multisig account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultisigState {
+    /// Signatures required
+    pub m: u8,
+    /// Valid signers
+    pub n: u8,
+    /// Whether the account has been initialized
+    pub is_initialized: bool,
+    /// The first `n` signer slots
+    pub signers: Vec<Pubkey>,
+}
+
+impl MultisigState {
+    /// Decode token multisig account data
+    pub fn unpack(data: &[u8]) -> Result<Self> {
+        if data.len() != MULTISIG_LENGTH {
+            return Err(SolanaError::DeserializationError(format!(
+                "invalid multisig length: {}",
+                data.len()
+            )));
+        }
+        let mut reader = DataReader::new(data);
+        let m = reader.read::<u8>()?;
+        let n = reader.read::<u8>()?;
+        let is_initialized = reader.read::<u8>()? != 0;
+        if n as usize > MAX_SIGNERS || m > n {
+            return Err(SolanaError::DeserializationError(format!(
+                "invalid multisig threshold: {m} of {n}"
+            )));
+        }
+        let signers = (0..n)
+            .map(|_| reader.read_pubkey())
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            m,
+            n,
+            is_initialized,
+            signers,
+        })
+    }
+
+    /// Whether `signer` is one of the multisig's signers
+    pub fn is_signer(&self, signer: &Pubkey) -> bool {
+        self.signers.contains(signer)
+    }
+}
+
+/// Walk the Token-2022 extensions stored after the account type byte.
+///
+/// Extended accounts are padded to [`TOKEN_ACCOUNT_LENGTH`], followed by the account type and
+/// `(u16 type, u16 length, data)` entries.
+pub fn unpack_extensions(data: &[u8], account_type: u8) -> Result<Vec<TokenExtension>> {
+    match data.get(TOKEN_ACCOUNT_LENGTH) {
+        Some(found) if *found == account_type => {}
+        Some(found) => {
+            return Err(SolanaError::DeserializationError(format!(
+                "unexpected token account type: {found}, expected: {account_type}"
+            )));
+        }
+        None => {
+            return Err(SolanaError::DeserializationError(format!(
+                "invalid token account length: {}",
+                data.len()
+            )));
+        }
+    }
+
+    let mut reader = DataReader::new(&data[TOKEN_ACCOUNT_LENGTH + 1..]);
+    let mut extensions = Vec::new();
+    while reader.remaining().len() >= 4 {
+        let extension_type = reader.read::<u16>()?;
+        let length = reader.read::<u16>()? as usize;
+        let data = reader.read_bytes(length)?.to_vec();
+        // Zeroed tail space reserved for future extensions
+        if extension_type == 0 && length == 0 {
+            break;
+        }
+        extensions.push(TokenExtension {
+            extension_type,
+            data,
+        });
+    }
+    Ok(extensions)
+}
+
+fn find_extension(extensions: &[TokenExtension], extension_type: u16) -> Option<&[u8]> {
+    extensions
+        .iter()
+        .find(|extension| extension.extension_type == extension_type)
+        .map(|extension| extension.data.as_slice())
+}
+
+/// Read a `COption<Pubkey>`: a u32 tag followed by 32 bytes
+fn read_optional_pubkey(reader: &mut DataReader<'_>) -> Result<Option<Pubkey>> {
+    let tag = reader.read::<u32>()?;
+    let pubkey = reader.read_pubkey()?;
+    Ok((tag != 0).then_some(pubkey))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coption_pubkey(pubkey: Option<[u8; 32]>) -> Vec<u8> {
+        let mut data = (pubkey.is_some() as u32).to_le_bytes().to_vec();
+        data.extend_from_slice(&pubkey.unwrap_or_default());
+        data
+    }
+
+    fn mint_data() -> Vec<u8> {
+        let mut data = coption_pubkey(Some([1u8; 32]));
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.extend_from_slice(&[6, 1]);
+        data.extend(coption_pubkey(None));
+        data
+    }
+
+    fn token_account_data() -> Vec<u8> {
+        let mut data = [2u8; 32].to_vec();
+        data.extend_from_slice(&[3u8; 32]);
+        data.extend_from_slice(&500u64.to_le_bytes());
+        data.extend(coption_pubkey(Some([4u8; 32])));
+        data.push(2);
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&2_039_280u64.to_le_bytes());
+        data.extend_from_slice(&100u64.to_le_bytes());
+        data.extend(coption_pubkey(None));
+        data
+    }
+
+    #[test]
+    fn test_unpack_mint() {
+        let data = mint_data();
+        assert_eq!(data.len(), MINT_LENGTH);
+
+        let mint = MintState::unpack(&data).unwrap();
+        assert_eq!(mint.mint_authority, Some(Pubkey::new([1u8; 32])));
+        assert_eq!(mint.supply, 1_000);
+        assert_eq!(mint.decimals, 6);
+        assert!(mint.is_initialized);
+        assert_eq!(mint.freeze_authority, None);
+        assert!(mint.extensions.is_empty());
+
+        assert!(MintState::unpack(&data[..80]).is_err());
+    }
+
+    #[test]
+    fn test_unpack_token_2022_mint_extensions() {
+        let mut data = mint_data();
+        data.resize(TOKEN_ACCOUNT_LENGTH, 0);
+        data.push(ACCOUNT_TYPE_MINT);
+        data.extend_from_slice(&14u16.to_le_bytes());
+        data.extend_from_slice(&64u16.to_le_bytes());
+        data.extend_from_slice(&[5u8; 64]);
+        data.extend_from_slice(&[0u8; 8]);
+
+        let mint = MintState::unpack(&data).unwrap();
+        assert_eq!(mint.decimals, 6);
+        assert_eq!(mint.extensions.len(), 1);
+        assert_eq!(mint.extension(14), Some(&[5u8; 64][..]));
+        assert_eq!(mint.extension(1), None);
+
+        data[TOKEN_ACCOUNT_LENGTH] = ACCOUNT_TYPE_ACCOUNT;
+        assert!(MintState::unpack(&data).is_err());
+
+        data[TOKEN_ACCOUNT_LENGTH] = ACCOUNT_TYPE_MINT;
+        data.truncate(data.len() - 20);
+        assert!(MintState::unpack(&data).is_err());
+    }
+
+    #[test]
+    fn test_unpack_token_account() {
+        let mut data = token_account_data();
+        assert_eq!(data.len(), TOKEN_ACCOUNT_LENGTH);
+
+        let account = TokenAccountState::unpack(&data).unwrap();
+        assert_eq!(account.mint, Pubkey::new([2u8; 32]));
+        assert_eq!(account.owner, Pubkey::new([3u8; 32]));
+        assert_eq!(account.amount, 500);
+        assert_eq!(account.delegate, Some(Pubkey::new([4u8; 32])));
+        assert!(account.is_frozen());
+        assert_eq!(account.is_native, Some(2_039_280));
+        assert_eq!(account.delegated_amount, 100);
+        assert_eq!(account.close_authority, None);
+
+        data.push(ACCOUNT_TYPE_ACCOUNT);
+        data.extend_from_slice(&7u16.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+        let account = TokenAccountState::unpack(&data).unwrap();
+        assert_eq!(account.extension(7), Some(&[][..]));
+    }
+
+    #[test]
+    fn test_unpack_multisig() {
+        let mut data = vec![0u8; MULTISIG_LENGTH];
+        data[..3].copy_from_slice(&[2, 3, 1]);
+        for (slot, byte) in [5u8, 6, 7].into_iter().enumerate() {
+            data[3 + slot * 32..35 + slot * 32].copy_from_slice(&[byte; 32]);
+        }
+
+        let multisig = MultisigState::unpack(&data).unwrap();
+        assert_eq!((multisig.m, multisig.n), (2, 3));
+        assert!(multisig.is_initialized);
+        assert!(multisig.is_signer(&Pubkey::new([7u8; 32])));
+        assert!(!multisig.is_signer(&Pubkey::new([8u8; 32])));
+        assert!(MultisigState::unpack(&data[..100]).is_err());
+    }
+}
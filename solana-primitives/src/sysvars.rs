@@ -0,0 +1,373 @@
+//! Decoders for sysvar account data.
+//!
+//! Sysvar addresses are in [`program_ids`](crate::instructions::program_ids), e.g.
+//! [`clock_sysvar`](crate::instructions::program_ids::clock_sysvar). The Rent sysvar is
+//! decoded by [`Rent`](crate::rent::Rent).
+
+use crate::data::DataReader;
+use crate::error::{Result, SolanaError};
+use crate::types::{Blockhash, Hash, VersionedTransaction};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+/// Maximum number of entries kept in the SlotHashes sysvar
+pub const MAX_SLOT_HASHES: usize = 512;
+
+/// Number of blocks a recent blockhash stays usable for
+pub const MAX_PROCESSING_AGE: usize = 150;
+
+/// Maximum number of entries kept in the RecentBlockhashes sysvar
+pub const MAX_RECENT_BLOCKHASHES: usize = 150;
+
+/// Maximum number of entries kept in the StakeHistory sysvar
+pub const MAX_STAKE_HISTORY: usize = 512;
+
+/// A `(slot, hash)` pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotHash {
+    /// The slot
+    pub slot: u64,
+    /// The hash recorded for the slot
+    pub hash: Hash,
+}
+
+/// Snapshot of recent slot hashes, newest first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SlotHashes {
+    entries: Vec<SlotHash>,
+}
+
+/// Where a blockhash sits in a [`RecentBlockhashes`] snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockhashStatus {
+    /// Within [`MAX_PROCESSING_AGE`] blocks of the newest entry
+    Valid {
+        /// Blocks since the hash was produced
+        age: usize,
+    },
+    /// Not in the snapshot: newer than it, or expired.
+    ///
+    /// The sysvar keeps [`MAX_RECENT_BLOCKHASHES`] entries, no more than
+    /// [`MAX_PROCESSING_AGE`], so an expired blockhash has already left the snapshot.
+    Unknown,
+}
+
+impl BlockhashStatus {
+    /// Whether a transaction using the blockhash can still land
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Self::Valid { .. })
+    }
+}
+
+impl SlotHashes {
+    /// Create a snapshot from entries, sorted newest first
+    pub fn new(mut entries: Vec<SlotHash>) -> Self {
+        entries.sort_by_key(|entry| core::cmp::Reverse(entry.slot));
+        Self { entries }
+    }
+
+    /// Decode SlotHashes sysvar account data: a u64 count followed by `(slot, hash)` pairs
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let len = data
+            .get(..8)
+            .map(|len| u64::from_le_bytes(len.try_into().unwrap()) as usize)
+            .ok_or_else(|| {
+                SolanaError::DeserializationError("slot hashes too short".to_string())
+            })?;
+        if len > MAX_SLOT_HASHES || data.len() < 8 + len * 40 {
+            return Err(SolanaError::DeserializationError(format!(
+                "invalid slot hashes length: {len}"
+            )));
+        }
+
+        let entries = data[8..8 + len * 40]
+            .chunks_exact(40)
+            .map(|entry| SlotHash {
+                slot: u64::from_le_bytes(entry[..8].try_into().unwrap()),
+                hash: Hash::try_from(&entry[8..]).unwrap(),
+            })
+            .collect();
+        Ok(Self::new(entries))
+    }
+
+    /// Entries, newest first
+    pub fn entries(&self) -> &[SlotHash] {
+        &self.entries
+    }
+
+    /// Get the hash recorded for `slot`
+    pub fn get(&self, slot: u64) -> Option<&Hash> {
+        self.entries
+            .iter()
+            .find(|entry| entry.slot == slot)
+            .map(|entry| &entry.hash)
+    }
+
+    /// Number of blocks since `hash` was recorded, if it is in the snapshot
+    pub fn age_of(&self, hash: &Hash) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.hash == *hash)
+    }
+}
+
+/// The Clock sysvar
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Clock {
+    /// Current slot
+    pub slot: u64,
+    /// Unix timestamp of the first slot in this epoch
+    pub epoch_start_timestamp: i64,
+    /// Current epoch
+    pub epoch: u64,
+    /// Future epoch for which the leader schedule has most recently been calculated
+    pub leader_schedule_epoch: u64,
+    /// Estimated unix timestamp of the current slot
+    pub unix_timestamp: i64,
+}
+
+impl Clock {
+    /// Decode Clock sysvar account data
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let mut reader = DataReader::new(data);
+        Ok(Self {
+            slot: reader.read()?,
+            epoch_start_timestamp: reader.read()?,
+            epoch: reader.read()?,
+            leader_schedule_epoch: reader.read()?,
+            unix_timestamp: reader.read()?,
+        })
+    }
+}
+
+/// The EpochSchedule sysvar
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EpochSchedule {
+    /// Slots in each epoch after warmup
+    pub slots_per_epoch: u64,
+    /// Slots before an epoch starts to calculate its leader schedule
+    pub leader_schedule_slot_offset: u64,
+    /// Whether epochs start short and grow
+    pub warmup: bool,
+    /// First epoch with `slots_per_epoch` slots
+    pub first_normal_epoch: u64,
+    /// First slot of `first_normal_epoch`
+    pub first_normal_slot: u64,
+}
+
+impl EpochSchedule {
+    /// Decode EpochSchedule sysvar account data
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let mut reader = DataReader::new(data);
+        Ok(Self {
+            slots_per_epoch: reader.read()?,
+            leader_schedule_slot_offset: reader.read()?,
+            warmup: reader.read::<u8>()? != 0,
+            first_normal_epoch: reader.read()?,
+            first_normal_slot: reader.read()?,
+        })
+    }
+}
+
+/// A RecentBlockhashes entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecentBlockhash {
+    /// The blockhash
+    pub blockhash: Blockhash,
+    /// Fee rate when the blockhash was produced
+    pub lamports_per_signature: u64,
+}
+
+/// The deprecated RecentBlockhashes sysvar, newest first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecentBlockhashes {
+    /// Entries, newest first
+    pub entries: Vec<RecentBlockhash>,
+}
+
+impl RecentBlockhashes {
+    /// Decode RecentBlockhashes sysvar account data: a u64 count followed by entries
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let mut reader = DataReader::new(data);
+        let len = read_len(&mut reader, MAX_RECENT_BLOCKHASHES)?;
+        let entries = (0..len)
+            .map(|_| {
+                Ok(RecentBlockhash {
+                    blockhash: reader.read_bytes(32)?.try_into().unwrap(),
+                    lamports_per_signature: reader.read()?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { entries })
+    }
+
+    /// Number of blocks since `blockhash` was produced, if it is in the snapshot
+    pub fn age_of(&self, blockhash: &Blockhash) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.blockhash == *blockhash)
+    }
+
+    /// Check a blockhash against the snapshot; expired blockhashes are reported as
+    /// [`BlockhashStatus::Unknown`]
+    pub fn check_blockhash(&self, blockhash: &Blockhash) -> BlockhashStatus {
+        match self.age_of(blockhash) {
+            Some(age) => BlockhashStatus::Valid { age },
+            None => BlockhashStatus::Unknown,
+        }
+    }
+
+    /// Check a transaction's recent blockhash against the snapshot
+    pub fn check_transaction(&self, transaction: &VersionedTransaction) -> BlockhashStatus {
+        self.check_blockhash(transaction.recent_blockhash())
+    }
+}
+
+/// Stake activation totals at the end of an epoch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StakeHistoryEntry {
+    /// The epoch
+    pub epoch: u64,
+    /// Effective stake
+    pub effective: u64,
+    /// Stake being activated
+    pub activating: u64,
+    /// Stake being deactivated
+    pub deactivating: u64,
+}
+
+/// The StakeHistory sysvar, newest epoch first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StakeHistory {
+    /// Entries, newest epoch first
+    pub entries: Vec<StakeHistoryEntry>,
+}
+
+impl StakeHistory {
+    /// Decode StakeHistory sysvar account data: a u64 count followed by entries
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let mut reader = DataReader::new(data);
+        let len = read_len(&mut reader, MAX_STAKE_HISTORY)?;
+        let entries = (0..len)
+            .map(|_| {
+                Ok(StakeHistoryEntry {
+                    epoch: reader.read()?,
+                    effective: reader.read()?,
+                    activating: reader.read()?,
+                    deactivating: reader.read()?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { entries })
+    }
+
+    /// Get the entry for `epoch`
+    pub fn get(&self, epoch: u64) -> Option<&StakeHistoryEntry> {
+        self.entries.iter().find(|entry| entry.epoch == epoch)
+    }
+}
+
+/// Read a u64 entry count, rejecting counts above `max`
+fn read_len(reader: &mut DataReader<'_>, max: usize) -> Result<usize> {
+    let len = reader.read::<u64>()?;
+    if len > max as u64 {
+        return Err(SolanaError::DeserializationError(format!(
+            "invalid sysvar length: {len}"
+        )));
+    }
+    Ok(len as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_hashes_from_account_data() {
+        let mut data = 2u64.to_le_bytes().to_vec();
+        for (slot, byte) in [(10u64, 1u8), (11, 2)] {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[byte; 32]);
+        }
+
+        let slot_hashes = SlotHashes::from_account_data(&data).unwrap();
+        assert_eq!(slot_hashes.entries()[0].slot, 11);
+        assert_eq!(slot_hashes.get(10), Some(&Hash::new([1u8; 32])));
+        assert!(SlotHashes::from_account_data(&data[..50]).is_err());
+    }
+
+    #[test]
+    fn test_check_blockhash() {
+        // A full sysvar, as decoded from account data
+        let mut data = (MAX_RECENT_BLOCKHASHES as u64).to_le_bytes().to_vec();
+        for age in 0..MAX_RECENT_BLOCKHASHES {
+            data.extend_from_slice(&[age as u8; 32]);
+            data.extend_from_slice(&5_000u64.to_le_bytes());
+        }
+        let recent = RecentBlockhashes::from_account_data(&data).unwrap();
+
+        assert_eq!(
+            recent.check_blockhash(&Hash::new([0u8; 32])),
+            BlockhashStatus::Valid { age: 0 }
+        );
+        assert_eq!(
+            recent.check_blockhash(&Hash::new([149u8; 32])),
+            BlockhashStatus::Valid { age: 149 }
+        );
+        // The next older blockhash has expired and left the sysvar
+        assert_eq!(
+            recent.check_blockhash(&Hash::new([150u8; 32])),
+            BlockhashStatus::Unknown
+        );
+        assert_eq!(
+            recent.check_blockhash(&Hash::new([255u8; 32])),
+            BlockhashStatus::Unknown
+        );
+    }
+
+    #[test]
+    fn test_clock_and_epoch_schedule_from_account_data() {
+        let data: Vec<u8> = [100u64, 1_700_000_000, 5, 6, 1_700_000_400]
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        let clock = Clock::from_account_data(&data).unwrap();
+        assert_eq!(clock.slot, 100);
+        assert_eq!(clock.epoch, 5);
+        assert_eq!(clock.unix_timestamp, 1_700_000_400);
+        assert!(Clock::from_account_data(&data[..39]).is_err());
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&432_000u64.to_le_bytes());
+        data.extend_from_slice(&432_000u64.to_le_bytes());
+        data.push(0);
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        let schedule = EpochSchedule::from_account_data(&data).unwrap();
+        assert_eq!(schedule.slots_per_epoch, 432_000);
+        assert!(!schedule.warmup);
+    }
+
+    #[test]
+    fn test_recent_blockhashes_and_stake_history_from_account_data() {
+        let mut data = 1u64.to_le_bytes().to_vec();
+        data.extend_from_slice(&[4u8; 32]);
+        data.extend_from_slice(&5_000u64.to_le_bytes());
+        let recent = RecentBlockhashes::from_account_data(&data).unwrap();
+        assert_eq!(
+            recent.entries,
+            vec![RecentBlockhash {
+                blockhash: Blockhash::new([4u8; 32]),
+                lamports_per_signature: 5_000
+            }]
+        );
+
+        let data: Vec<u8> = [1u64, 7, 10, 20, 30]
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        let history = StakeHistory::from_account_data(&data).unwrap();
+        assert_eq!(history.get(7).unwrap().activating, 20);
+        assert!(history.get(8).is_none());
+        assert!(StakeHistory::from_account_data(&u64::MAX.to_le_bytes()).is_err());
+    }
+}
@@ -0,0 +1,813 @@
+//! In-memory execution of System and SPL Token instructions.
+//!
+//! [`Bank`] keeps accounts in a map and applies transfers, account creation, mints and burns
+//! the way the runtime would, so tests can run transactions built by this crate and check the
+//! resulting balances without a validator. Only a small set of instructions is modelled:
+//!
+//! - System: `CreateAccount`, `Transfer`
+//! - Token and Token-2022: `InitializeMint{,2}`, `InitializeAccount{,2,3}`,
+//!   `Transfer{,Checked}`, `MintTo{,Checked}`, `Burn{,Checked}`
+//! - Compute budget and memo instructions are accepted and do nothing
+//!
+//! Other instructions of these programs fail with `InvalidInstructionData` and other programs
+//! with `UnsupportedProgramId`. Rent, delegates and multisig authorities are not modelled.
+
+use crate::analysis::is_writable_index;
+use crate::error::SolanaError;
+use crate::instructions::program_ids::{
+    compute_budget_program, memo_program, memo_v1_program, system_program, token_2022_program,
+    token_program,
+};
+use crate::instructions::system::SystemInstruction;
+use crate::instructions::token::TokenInstruction;
+use crate::rent::Rent;
+use crate::state::{AccountState, MINT_LENGTH, MintState, TOKEN_ACCOUNT_LENGTH, TokenAccountState};
+use crate::types::{
+    Account, AccountMeta, CompiledInstruction, InstructionError, Pubkey, TransactionError,
+    VersionedTransaction,
+};
+use alloc::collections::BTreeMap;
+
+/// Fee charged per signature unless changed with [`Bank::set_lamports_per_signature`]
+pub const DEFAULT_LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// `SystemError::AccountAlreadyInUse`
+const SYSTEM_ACCOUNT_ALREADY_IN_USE: u32 = 0;
+/// `SystemError::ResultWithNegativeLamports`
+const SYSTEM_NEGATIVE_LAMPORTS: u32 = 1;
+
+/// `TokenError::InsufficientFunds`
+const TOKEN_INSUFFICIENT_FUNDS: u32 = 1;
+/// `TokenError::InvalidMint`
+const TOKEN_INVALID_MINT: u32 = 2;
+/// `TokenError::MintMismatch`
+const TOKEN_MINT_MISMATCH: u32 = 3;
+/// `TokenError::OwnerMismatch`
+const TOKEN_OWNER_MISMATCH: u32 = 4;
+/// `TokenError::FixedSupply`
+const TOKEN_FIXED_SUPPLY: u32 = 5;
+/// `TokenError::AlreadyInUse`
+const TOKEN_ALREADY_IN_USE: u32 = 6;
+/// `TokenError::Overflow`
+const TOKEN_OVERFLOW: u32 = 14;
+/// `TokenError::AccountFrozen`
+const TOKEN_ACCOUNT_FROZEN: u32 = 17;
+/// `TokenError::MintDecimalsMismatch`
+const TOKEN_DECIMALS_MISMATCH: u32 = 18;
+
+/// Byte range of the token amount in token account data
+const TOKEN_AMOUNT: core::ops::Range<usize> = 64..72;
+/// Byte range of the supply in mint data
+const MINT_SUPPLY: core::ops::Range<usize> = 36..44;
+
+type InstructionResult<T = ()> = Result<T, InstructionError>;
+
+/// An in-memory account store that executes System and Token instructions
+#[derive(Debug, Clone)]
+pub struct Bank {
+    accounts: BTreeMap<Pubkey, Account>,
+    lamports_per_signature: u64,
+    verify_signatures: bool,
+}
+
+impl Default for Bank {
+    fn default() -> Self {
+        Self {
+            accounts: BTreeMap::new(),
+            lamports_per_signature: DEFAULT_LAMPORTS_PER_SIGNATURE,
+            verify_signatures: true,
+        }
+    }
+}
+
+impl Bank {
+    /// Create an empty bank
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the fee charged per signature
+    pub fn set_lamports_per_signature(&mut self, lamports: u64) -> &mut Self {
+        self.lamports_per_signature = lamports;
+        self
+    }
+
+    /// Whether to reject transactions without valid signatures, on by default.
+    ///
+    /// With verification off, signer flags still come from the message header.
+    pub fn set_verify_signatures(&mut self, verify: bool) -> &mut Self {
+        self.verify_signatures = verify;
+        self
+    }
+
+    /// Store `account` at `pubkey`, replacing any existing account
+    pub fn set_account(&mut self, pubkey: Pubkey, account: Account) -> &mut Self {
+        self.accounts.insert(pubkey, account);
+        self
+    }
+
+    /// Credit `lamports` to `pubkey`, creating a system account if needed.
+    ///
+    /// Fails without changing the balance if it would overflow.
+    pub fn airdrop(&mut self, pubkey: Pubkey, lamports: u64) -> Result<&mut Self, SolanaError> {
+        let account = self.accounts.entry(pubkey).or_insert_with(system_account);
+        account.lamports = account.lamports.checked_add(lamports).ok_or_else(|| {
+            SolanaError::GenericError(format!("airdrop overflows the balance of {pubkey}"))
+        })?;
+        Ok(self)
+    }
+
+    /// Store an initialized SPL Token mint with no supply or freeze authority
+    pub fn create_mint(&mut self, mint: Pubkey, authority: Pubkey, decimals: u8) -> &mut Self {
+        let mut data = vec![0u8; MINT_LENGTH];
+        write_mint(&mut data, &authority, decimals, None);
+        self.set_account(mint, token_account(data))
+    }
+
+    /// Store an initialized SPL Token account holding `amount` of `mint`.
+    ///
+    /// The mint's supply is increased by `amount` if the mint is in the bank.
+    pub fn create_token_account(
+        &mut self,
+        address: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+    ) -> &mut Self {
+        let mut data = vec![0u8; TOKEN_ACCOUNT_LENGTH];
+        write_token_account(&mut data, &mint, &owner);
+        data[TOKEN_AMOUNT].copy_from_slice(&amount.to_le_bytes());
+        if let Some(mint) = self.accounts.get_mut(&mint)
+            && let Some(supply) = mint.data.get_mut(MINT_SUPPLY)
+        {
+            let total = read_u64(supply) + amount;
+            supply.copy_from_slice(&total.to_le_bytes());
+        }
+        self.set_account(address, token_account(data))
+    }
+
+    /// The account stored at `pubkey`
+    pub fn account(&self, pubkey: &Pubkey) -> Option<&Account> {
+        self.accounts.get(pubkey)
+    }
+
+    /// Lamports held by `pubkey`, zero if there is no account
+    pub fn balance(&self, pubkey: &Pubkey) -> u64 {
+        self.account(pubkey).map_or(0, |account| account.lamports)
+    }
+
+    /// Token amount held by the token account at `pubkey`
+    pub fn token_balance(&self, pubkey: &Pubkey) -> Option<u64> {
+        let account = self.account(pubkey)?;
+        Some(TokenAccountState::unpack(&account.data).ok()?.amount)
+    }
+
+    /// Supply of the mint at `pubkey`
+    pub fn mint_supply(&self, pubkey: &Pubkey) -> Option<u64> {
+        let account = self.account(pubkey)?;
+        Some(MintState::unpack(&account.data).ok()?.supply)
+    }
+
+    /// Execute `transaction`.
+    ///
+    /// The fee is charged to the fee payer even if an instruction fails; every other change
+    /// is applied only if all instructions succeed. Transactions that load accounts from
+    /// address lookup tables are rejected.
+    pub fn process_transaction(
+        &mut self,
+        transaction: &VersionedTransaction,
+    ) -> Result<(), TransactionError> {
+        if self.verify_signatures && transaction.verify_signatures().is_err() {
+            return Err(TransactionError::SignatureFailure);
+        }
+        let keys = transaction
+            .resolve_addresses(&[])
+            .map_err(|_| TransactionError::AddressLookupTableNotFound)?;
+        let payer = keys
+            .first()
+            .ok_or(TransactionError::MissingSignatureForFee)?;
+
+        let fee = transaction.fee(self.lamports_per_signature);
+        match self.accounts.get_mut(payer) {
+            None => return Err(TransactionError::AccountNotFound),
+            Some(account) if account.owner != system_program() || !account.data.is_empty() => {
+                return Err(TransactionError::InvalidAccountForFee);
+            }
+            Some(account) if account.lamports < fee => {
+                return Err(TransactionError::InsufficientFundsForFee);
+            }
+            Some(account) => account.lamports -= fee,
+        }
+
+        let num_signers = transaction.num_required_signatures() as usize;
+        let mut accounts = self.accounts.clone();
+        for (index, instruction) in transaction.instructions().iter().enumerate() {
+            let (program_id, metas) =
+                instruction_accounts(transaction, &keys, num_signers, instruction)?;
+            execute(&mut accounts, &program_id, &metas, &instruction.data)
+                .map_err(|error| TransactionError::InstructionError(index as u8, error))?;
+        }
+        self.accounts = accounts;
+        Ok(())
+    }
+}
+
+fn system_account() -> Account {
+    Account {
+        lamports: 0,
+        owner: system_program(),
+        data: vec![],
+        executable: false,
+    }
+}
+
+/// A rent-exempt account owned by the Token program
+fn token_account(data: Vec<u8>) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        owner: token_program(),
+        data,
+        executable: false,
+    }
+}
+
+/// The program and account metas of a compiled instruction
+fn instruction_accounts(
+    transaction: &VersionedTransaction,
+    keys: &[Pubkey],
+    num_signers: usize,
+    instruction: &CompiledInstruction,
+) -> Result<(Pubkey, Vec<AccountMeta>), TransactionError> {
+    let program_id = *keys
+        .get(instruction.program_id_index as usize)
+        .ok_or(TransactionError::InvalidAccountIndex)?;
+    let metas = instruction
+        .accounts
+        .iter()
+        .map(|&index| {
+            let index = index as usize;
+            let pubkey = *keys
+                .get(index)
+                .ok_or(TransactionError::InvalidAccountIndex)?;
+            Ok(AccountMeta {
+                pubkey,
+                is_signer: index < num_signers,
+                is_writable: is_writable_index(transaction, index),
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((program_id, metas))
+}
+
+fn execute(
+    accounts: &mut BTreeMap<Pubkey, Account>,
+    program_id: &Pubkey,
+    metas: &[AccountMeta],
+    data: &[u8],
+) -> InstructionResult {
+    if *program_id == system_program() {
+        let instruction = SystemInstruction::deserialize(data)
+            .map_err(|_| InstructionError::InvalidInstructionData)?;
+        execute_system(accounts, metas, instruction)
+    } else if *program_id == token_program() || *program_id == token_2022_program() {
+        let instruction = TokenInstruction::deserialize(data)
+            .map_err(|_| InstructionError::InvalidInstructionData)?;
+        Token {
+            accounts,
+            program_id,
+            metas,
+        }
+        .execute(instruction)
+    } else if [compute_budget_program(), memo_program(), memo_v1_program()].contains(program_id) {
+        Ok(())
+    } else {
+        Err(InstructionError::UnsupportedProgramId)
+    }
+}
+
+fn meta(metas: &[AccountMeta], position: usize) -> InstructionResult<&AccountMeta> {
+    metas
+        .get(position)
+        .ok_or(InstructionError::NotEnoughAccountKeys)
+}
+
+fn signer(metas: &[AccountMeta], position: usize) -> InstructionResult<&AccountMeta> {
+    let meta = meta(metas, position)?;
+    if !meta.is_signer {
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    Ok(meta)
+}
+
+fn writable(metas: &[AccountMeta], position: usize) -> InstructionResult<&AccountMeta> {
+    let meta = meta(metas, position)?;
+    if !meta.is_writable {
+        return Err(InstructionError::ReadonlyDataModified);
+    }
+    Ok(meta)
+}
+
+fn execute_system(
+    accounts: &mut BTreeMap<Pubkey, Account>,
+    metas: &[AccountMeta],
+    instruction: SystemInstruction,
+) -> InstructionResult {
+    match instruction {
+        SystemInstruction::Transfer { lamports } => {
+            let from = signer(metas, 0)?.pubkey;
+            let to = writable(metas, 1)?.pubkey;
+            writable(metas, 0)?;
+            debit_system_account(accounts, &from, lamports)?;
+            let to = accounts.entry(to).or_insert_with(system_account);
+            to.lamports = to
+                .lamports
+                .checked_add(lamports)
+                .ok_or(InstructionError::ArithmeticOverflow)?;
+            Ok(())
+        }
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let from = signer(metas, 0)?.pubkey;
+            let new = signer(metas, 1)?.pubkey;
+            writable(metas, 0)?;
+            writable(metas, 1)?;
+            if accounts.get(&new).is_some_and(|account| {
+                account.lamports > 0
+                    || !account.data.is_empty()
+                    || account.owner != system_program()
+            }) {
+                return Err(InstructionError::Custom(SYSTEM_ACCOUNT_ALREADY_IN_USE));
+            }
+            debit_system_account(accounts, &from, lamports)?;
+            accounts.insert(
+                new,
+                Account {
+                    lamports,
+                    owner,
+                    data: vec![0u8; space as usize],
+                    executable: false,
+                },
+            );
+            Ok(())
+        }
+        _ => Err(InstructionError::InvalidInstructionData),
+    }
+}
+
+fn debit_system_account(
+    accounts: &mut BTreeMap<Pubkey, Account>,
+    pubkey: &Pubkey,
+    lamports: u64,
+) -> InstructionResult {
+    let account = accounts.entry(*pubkey).or_insert_with(system_account);
+    if !account.data.is_empty() {
+        return Err(InstructionError::InvalidArgument);
+    }
+    if account.owner != system_program() {
+        return Err(InstructionError::ExternalAccountLamportSpend);
+    }
+    account.lamports = account
+        .lamports
+        .checked_sub(lamports)
+        .ok_or(InstructionError::Custom(SYSTEM_NEGATIVE_LAMPORTS))?;
+    Ok(())
+}
+
+/// One Token or Token-2022 instruction being executed
+struct Token<'a> {
+    accounts: &'a mut BTreeMap<Pubkey, Account>,
+    program_id: &'a Pubkey,
+    metas: &'a [AccountMeta],
+}
+
+impl Token<'_> {
+    fn execute(mut self, instruction: TokenInstruction) -> InstructionResult {
+        match instruction {
+            TokenInstruction::InitializeMint {
+                decimals,
+                mint_authority,
+                freeze_authority,
+            }
+            | TokenInstruction::InitializeMint2 {
+                decimals,
+                mint_authority,
+                freeze_authority,
+            } => {
+                let mint = writable(self.metas, 0)?.pubkey;
+                let data = self.data_mut(&mint, MINT_LENGTH)?;
+                if data[45] != 0 {
+                    return Err(InstructionError::Custom(TOKEN_ALREADY_IN_USE));
+                }
+                write_mint(data, &mint_authority, decimals, freeze_authority.as_ref());
+                Ok(())
+            }
+            TokenInstruction::InitializeAccount => {
+                let owner = meta(self.metas, 2)?.pubkey;
+                self.initialize_account(&owner)
+            }
+            TokenInstruction::InitializeAccount2 { owner }
+            | TokenInstruction::InitializeAccount3 { owner } => self.initialize_account(&owner),
+            TokenInstruction::Transfer { amount } => self.transfer(0, None, 1, 2, amount, None),
+            TokenInstruction::TransferChecked { amount, decimals } => {
+                self.transfer(0, Some(1), 2, 3, amount, Some(decimals))
+            }
+            TokenInstruction::MintTo { amount } => self.mint_to(amount, None),
+            TokenInstruction::MintToChecked { amount, decimals } => {
+                self.mint_to(amount, Some(decimals))
+            }
+            TokenInstruction::Burn { amount } => self.burn(amount, None),
+            TokenInstruction::BurnChecked { amount, decimals } => self.burn(amount, Some(decimals)),
+            _ => Err(InstructionError::InvalidInstructionData),
+        }
+    }
+
+    /// Data of an account owned by the invoked program, at least `len` bytes long
+    fn data_mut(&mut self, pubkey: &Pubkey, len: usize) -> InstructionResult<&mut [u8]> {
+        let account = self
+            .accounts
+            .get_mut(pubkey)
+            .filter(|account| account.owner == *self.program_id)
+            .ok_or(InstructionError::IncorrectProgramId)?;
+        if account.data.len() < len {
+            return Err(InstructionError::InvalidAccountData);
+        }
+        Ok(&mut account.data)
+    }
+
+    fn mint(&mut self, pubkey: &Pubkey) -> InstructionResult<MintState> {
+        let mint = MintState::unpack(self.data_mut(pubkey, MINT_LENGTH)?)
+            .map_err(|_| InstructionError::InvalidAccountData)?;
+        if !mint.is_initialized {
+            return Err(InstructionError::UninitializedAccount);
+        }
+        Ok(mint)
+    }
+
+    fn token_account(&mut self, pubkey: &Pubkey) -> InstructionResult<TokenAccountState> {
+        let account = TokenAccountState::unpack(self.data_mut(pubkey, TOKEN_ACCOUNT_LENGTH)?)
+            .map_err(|_| InstructionError::InvalidAccountData)?;
+        match account.state {
+            AccountState::Uninitialized => Err(InstructionError::UninitializedAccount),
+            AccountState::Frozen => Err(InstructionError::Custom(TOKEN_ACCOUNT_FROZEN)),
+            AccountState::Initialized => Ok(account),
+        }
+    }
+
+    fn set_amount(&mut self, pubkey: &Pubkey, amount: u64) -> InstructionResult {
+        self.data_mut(pubkey, TOKEN_ACCOUNT_LENGTH)?[TOKEN_AMOUNT]
+            .copy_from_slice(&amount.to_le_bytes());
+        Ok(())
+    }
+
+    fn set_supply(&mut self, pubkey: &Pubkey, supply: u64) -> InstructionResult {
+        self.data_mut(pubkey, MINT_LENGTH)?[MINT_SUPPLY].copy_from_slice(&supply.to_le_bytes());
+        Ok(())
+    }
+
+    /// Check the authority account at `position` signed and is `expected`
+    fn authorize(&self, position: usize, expected: Option<&Pubkey>) -> InstructionResult {
+        let authority = signer(self.metas, position)?;
+        match expected {
+            Some(expected) if *expected == authority.pubkey => Ok(()),
+            Some(_) => Err(InstructionError::Custom(TOKEN_OWNER_MISMATCH)),
+            None => Err(InstructionError::Custom(TOKEN_FIXED_SUPPLY)),
+        }
+    }
+
+    fn initialize_account(&mut self, owner: &Pubkey) -> InstructionResult {
+        let account = writable(self.metas, 0)?.pubkey;
+        let mint = meta(self.metas, 1)?.pubkey;
+        self.mint(&mint)
+            .map_err(|_| InstructionError::Custom(TOKEN_INVALID_MINT))?;
+        let data = self.data_mut(&account, TOKEN_ACCOUNT_LENGTH)?;
+        if data[108] != 0 {
+            return Err(InstructionError::Custom(TOKEN_ALREADY_IN_USE));
+        }
+        write_token_account(data, &mint, owner);
+        Ok(())
+    }
+
+    fn transfer(
+        &mut self,
+        source: usize,
+        mint: Option<usize>,
+        destination: usize,
+        authority: usize,
+        amount: u64,
+        decimals: Option<u8>,
+    ) -> InstructionResult {
+        let source = writable(self.metas, source)?.pubkey;
+        let destination = writable(self.metas, destination)?.pubkey;
+        let from = self.token_account(&source)?;
+        let to = self.token_account(&destination)?;
+        if from.mint != to.mint {
+            return Err(InstructionError::Custom(TOKEN_MINT_MISMATCH));
+        }
+        if let Some(mint) = mint {
+            self.check_mint(&from, meta(self.metas, mint)?.pubkey, decimals)?;
+        }
+        self.authorize(authority, Some(&from.owner))?;
+        let remaining = from
+            .amount
+            .checked_sub(amount)
+            .ok_or(InstructionError::Custom(TOKEN_INSUFFICIENT_FUNDS))?;
+
+        self.set_amount(&source, remaining)?;
+        // Re-read in case the source and destination are the same account
+        let received = self.token_account(&destination)?.amount;
+        let received = received
+            .checked_add(amount)
+            .ok_or(InstructionError::Custom(TOKEN_OVERFLOW))?;
+        self.set_amount(&destination, received)
+    }
+
+    fn mint_to(&mut self, amount: u64, decimals: Option<u8>) -> InstructionResult {
+        let mint = writable(self.metas, 0)?.pubkey;
+        let destination = writable(self.metas, 1)?.pubkey;
+        let to = self.token_account(&destination)?;
+        let state = self.check_mint(&to, mint, decimals)?;
+        self.authorize(2, state.mint_authority.as_ref())?;
+
+        let supply = state.supply.checked_add(amount);
+        let received = to.amount.checked_add(amount);
+        let (Some(supply), Some(received)) = (supply, received) else {
+            return Err(InstructionError::Custom(TOKEN_OVERFLOW));
+        };
+        self.set_supply(&mint, supply)?;
+        self.set_amount(&destination, received)
+    }
+
+    fn burn(&mut self, amount: u64, decimals: Option<u8>) -> InstructionResult {
+        let account = writable(self.metas, 0)?.pubkey;
+        let mint = writable(self.metas, 1)?.pubkey;
+        let from = self.token_account(&account)?;
+        let state = self.check_mint(&from, mint, decimals)?;
+        self.authorize(2, Some(&from.owner))?;
+
+        let remaining = from
+            .amount
+            .checked_sub(amount)
+            .ok_or(InstructionError::Custom(TOKEN_INSUFFICIENT_FUNDS))?;
+        self.set_amount(&account, remaining)?;
+        self.set_supply(&mint, state.supply.saturating_sub(amount))
+    }
+
+    /// Load `mint`, checking it is the mint of `account` and has the asserted decimals
+    fn check_mint(
+        &mut self,
+        account: &TokenAccountState,
+        mint: Pubkey,
+        decimals: Option<u8>,
+    ) -> InstructionResult<MintState> {
+        if account.mint != mint {
+            return Err(InstructionError::Custom(TOKEN_MINT_MISMATCH));
+        }
+        let state = self.mint(&mint)?;
+        if decimals.is_some_and(|decimals| decimals != state.decimals) {
+            return Err(InstructionError::Custom(TOKEN_DECIMALS_MISMATCH));
+        }
+        Ok(state)
+    }
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().unwrap_or_default())
+}
+
+/// Write an initialized mint over the base layout, keeping the supply
+fn write_mint(data: &mut [u8], authority: &Pubkey, decimals: u8, freeze: Option<&Pubkey>) {
+    data[..4].copy_from_slice(&1u32.to_le_bytes());
+    data[4..36].copy_from_slice(authority.as_bytes());
+    data[44] = decimals;
+    data[45] = 1;
+    if let Some(freeze) = freeze {
+        data[46..50].copy_from_slice(&1u32.to_le_bytes());
+        data[50..82].copy_from_slice(freeze.as_bytes());
+    }
+}
+
+/// Write an initialized, empty token account over the base layout
+fn write_token_account(data: &mut [u8], mint: &Pubkey, owner: &Pubkey) {
+    data[..32].copy_from_slice(mint.as_bytes());
+    data[32..64].copy_from_slice(owner.as_bytes());
+    data[108] = AccountState::Initialized as u8;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::TransactionBuilder;
+    use crate::crypto::{Keypair, Signer};
+    use crate::instructions::{system, token};
+    use crate::types::Instruction;
+
+    fn keypair(seed: u8) -> Keypair {
+        Keypair::from_seed(&[seed; 32]).unwrap()
+    }
+
+    fn run(
+        bank: &mut Bank,
+        payer: &Keypair,
+        signers: &[&Keypair],
+        instructions: Vec<Instruction>,
+    ) -> Result<(), TransactionError> {
+        let mut builder = TransactionBuilder::new(payer.pubkey(), [9u8; 32]);
+        for instruction in instructions {
+            builder.add_instruction(instruction);
+        }
+        let mut transaction = builder.build_v0(&[]).unwrap();
+        let signers: Vec<&dyn Signer> = [payer]
+            .into_iter()
+            .chain(signers.iter().copied())
+            .map(|signer| signer as &dyn Signer)
+            .collect();
+        transaction.sign(&signers).unwrap();
+        bank.process_transaction(&transaction)
+    }
+
+    #[test]
+    fn test_system_transfer_and_create_account() {
+        let (payer, new_account) = (keypair(1), keypair(2));
+        let recipient = Pubkey::new([3u8; 32]);
+        let mut bank = Bank::new();
+        bank.airdrop(payer.pubkey(), 1_000_000).unwrap();
+
+        run(
+            &mut bank,
+            &payer,
+            &[&new_account],
+            vec![
+                system::transfer(&payer.pubkey(), &recipient, 100_000),
+                system::create_account(
+                    &payer.pubkey(),
+                    &new_account.pubkey(),
+                    50_000,
+                    10,
+                    &token_program(),
+                ),
+            ],
+        )
+        .unwrap();
+        assert_eq!(bank.balance(&recipient), 100_000);
+        assert_eq!(bank.balance(&payer.pubkey()), 1_000_000 - 150_000 - 10_000);
+        let created = bank.account(&new_account.pubkey()).unwrap();
+        assert_eq!(created.owner, token_program());
+        assert_eq!(created.data.len(), 10);
+
+        // A failing instruction rolls back the others but still pays the fee
+        let balance = bank.balance(&payer.pubkey());
+        let error = run(
+            &mut bank,
+            &payer,
+            &[],
+            vec![
+                system::transfer(&payer.pubkey(), &recipient, 1),
+                system::transfer(&payer.pubkey(), &recipient, u64::MAX),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(error.custom_error(), Some(SYSTEM_NEGATIVE_LAMPORTS));
+        assert_eq!(error.instruction_error().unwrap().0, 1);
+        assert_eq!(bank.balance(&recipient), 100_000);
+        assert_eq!(bank.balance(&payer.pubkey()), balance - 5_000);
+
+        let unsigned = TransactionBuilder::new(payer.pubkey(), [9u8; 32])
+            .build_v0(&[])
+            .unwrap();
+        assert_eq!(
+            bank.process_transaction(&unsigned),
+            Err(TransactionError::SignatureFailure)
+        );
+        bank.set_verify_signatures(false);
+        assert_eq!(bank.process_transaction(&unsigned), Ok(()));
+    }
+
+    #[test]
+    fn test_lamport_overflow() {
+        let payer = keypair(1);
+        let recipient = Pubkey::new([3u8; 32]);
+        let mut bank = Bank::new();
+        bank.airdrop(payer.pubkey(), 1_000_000)
+            .unwrap()
+            .airdrop(recipient, u64::MAX)
+            .unwrap();
+        assert!(bank.airdrop(recipient, 1).is_err());
+        assert_eq!(bank.balance(&recipient), u64::MAX);
+
+        let error = run(
+            &mut bank,
+            &payer,
+            &[],
+            vec![system::transfer(&payer.pubkey(), &recipient, 1)],
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            TransactionError::InstructionError(0, InstructionError::ArithmeticOverflow)
+        );
+        assert_eq!(bank.balance(&payer.pubkey()), 1_000_000 - 5_000);
+    }
+
+    #[test]
+    fn test_token_mint_transfer_burn() {
+        let (payer, owner) = (keypair(1), keypair(2));
+        let (mint, source, destination) = (
+            Pubkey::new([4u8; 32]),
+            Pubkey::new([5u8; 32]),
+            Pubkey::new([6u8; 32]),
+        );
+        let mut bank = Bank::new();
+        bank.airdrop(payer.pubkey(), 1_000_000)
+            .unwrap()
+            .create_mint(mint, payer.pubkey(), 6)
+            .create_token_account(source, mint, owner.pubkey(), 0)
+            .create_token_account(destination, mint, payer.pubkey(), 0);
+
+        run(
+            &mut bank,
+            &payer,
+            &[&owner],
+            vec![
+                token::mint_to(&mint, &source, &payer.pubkey(), 1_000),
+                token::transfer(&source, &destination, &owner.pubkey(), 300),
+                token::burn(&source, &mint, &owner.pubkey(), 200),
+            ],
+        )
+        .unwrap();
+        assert_eq!(bank.token_balance(&source), Some(500));
+        assert_eq!(bank.token_balance(&destination), Some(300));
+        assert_eq!(bank.mint_supply(&mint), Some(800));
+
+        let error = run(
+            &mut bank,
+            &payer,
+            &[],
+            vec![token::transfer(&destination, &source, &payer.pubkey(), 301)],
+        )
+        .unwrap_err();
+        assert_eq!(error.custom_error(), Some(TOKEN_INSUFFICIENT_FUNDS));
+
+        let error = run(
+            &mut bank,
+            &payer,
+            &[],
+            vec![token::transfer(&source, &destination, &payer.pubkey(), 1)],
+        )
+        .unwrap_err();
+        assert_eq!(error.custom_error(), Some(TOKEN_OWNER_MISMATCH));
+        assert_eq!(bank.token_balance(&source), Some(500));
+    }
+
+    #[test]
+    fn test_token_accounts_created_in_a_transaction() {
+        let (payer, mint, account) = (keypair(1), keypair(2), keypair(3));
+        let mut bank = Bank::new();
+        bank.airdrop(payer.pubkey(), 100_000_000).unwrap();
+        let rent = Rent::default();
+
+        run(
+            &mut bank,
+            &payer,
+            &[&mint, &account],
+            vec![
+                system::create_account(
+                    &payer.pubkey(),
+                    &mint.pubkey(),
+                    rent.minimum_balance(MINT_LENGTH),
+                    MINT_LENGTH as u64,
+                    &token_program(),
+                ),
+                token::initialize_mint(&mint.pubkey(), &payer.pubkey(), None, 9),
+                system::create_account(
+                    &payer.pubkey(),
+                    &account.pubkey(),
+                    rent.minimum_balance(TOKEN_ACCOUNT_LENGTH),
+                    TOKEN_ACCOUNT_LENGTH as u64,
+                    &token_program(),
+                ),
+                token::initialize_account(&account.pubkey(), &mint.pubkey(), &payer.pubkey()),
+                token::mint_to(&mint.pubkey(), &account.pubkey(), &payer.pubkey(), 42),
+            ],
+        )
+        .unwrap();
+        assert_eq!(bank.token_balance(&account.pubkey()), Some(42));
+        assert_eq!(bank.mint_supply(&mint.pubkey()), Some(42));
+
+        let error = run(
+            &mut bank,
+            &payer,
+            &[],
+            vec![token::initialize_mint(
+                &mint.pubkey(),
+                &payer.pubkey(),
+                None,
+                9,
+            )],
+        )
+        .unwrap_err();
+        assert_eq!(error.custom_error(), Some(TOKEN_ALREADY_IN_USE));
+    }
+}
@@ -0,0 +1,184 @@
+//! A corpus of real transactions for wire-format regression tests.
+//!
+//! Each fixture is a `<name>.b64` file holding one serialized transaction as base64, which may
+//! be wrapped across lines. Lines starting with `#` are comments, e.g. the explorer link the
+//! transaction was taken from. The crate's own corpus lives in `tests/fixtures/transactions`
+//! and holds a Jupiter and a Mayan swap from mainnet plus an unsigned legacy transaction with
+//! compute-budget instructions. Other shapes, such as stake operations, are not in the corpus
+//! yet; their instruction layouts are covered by the instruction tests.
+
+use crate::error::{Result, SolanaError};
+use crate::types::VersionedTransaction;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use std::path::{Path, PathBuf};
+
+/// File extension of fixture files
+pub const FIXTURE_EXTENSION: &str = "b64";
+
+/// A serialized transaction loaded from the corpus
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    /// File name without the extension
+    pub name: String,
+    /// Serialized transaction bytes
+    pub bytes: Vec<u8>,
+}
+
+impl Fixture {
+    /// Load a fixture file
+    pub fn load(path: &Path) -> Result<Self> {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            SolanaError::DeserializationError(format!("failed to read fixture {name}: {e}"))
+        })?;
+        let encoded: String = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+        let bytes = STANDARD.decode(encoded).map_err(|e| {
+            SolanaError::DeserializationError(format!("invalid base64 in fixture {name}: {e}"))
+        })?;
+        Ok(Self { name, bytes })
+    }
+
+    /// Deserialize the transaction
+    pub fn transaction(&self) -> Result<VersionedTransaction> {
+        VersionedTransaction::deserialize_with_version(&self.bytes)
+    }
+
+    /// Panic unless the transaction reserializes to exactly the stored bytes
+    #[track_caller]
+    pub fn assert_roundtrip(&self) {
+        assert_roundtrip(&self.name, &self.bytes);
+    }
+}
+
+/// Iterator over the fixtures of a directory in name order, loading each file on demand
+#[derive(Debug)]
+pub struct Fixtures {
+    paths: std::vec::IntoIter<PathBuf>,
+}
+
+impl Iterator for Fixtures {
+    type Item = Result<Fixture>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.paths.next().map(|path| Fixture::load(&path))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.paths.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Fixtures {}
+
+/// The crate's fixture directory, `tests/fixtures/transactions`
+pub fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("transactions")
+}
+
+/// Fixtures in the crate's fixture directory
+pub fn fixtures() -> Result<Fixtures> {
+    fixtures_in(&fixture_dir())
+}
+
+/// Fixtures in `dir`; other files are skipped
+pub fn fixtures_in(dir: &Path) -> Result<Fixtures> {
+    let entries = std::fs::read_dir(dir).map_err(|e| {
+        SolanaError::DeserializationError(format!(
+            "failed to read fixture directory {}: {e}",
+            dir.display()
+        ))
+    })?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == FIXTURE_EXTENSION))
+        .collect();
+    paths.sort();
+    Ok(Fixtures {
+        paths: paths.into_iter(),
+    })
+}
+
+/// Panic unless `bytes` deserialize and reserialize to exactly the same bytes
+#[track_caller]
+pub fn assert_roundtrip(name: &str, bytes: &[u8]) {
+    let transaction = VersionedTransaction::deserialize_with_version(bytes)
+        .unwrap_or_else(|e| panic!("fixture `{name}` failed to deserialize: {e}"));
+    let reserialized = transaction
+        .serialize()
+        .unwrap_or_else(|e| panic!("fixture `{name}` failed to reserialize: {e}"));
+    if reserialized != bytes {
+        let offset = reserialized
+            .iter()
+            .zip(bytes)
+            .position(|(a, b)| a != b)
+            .unwrap_or(reserialized.len().min(bytes.len()));
+        panic!(
+            "fixture `{name}` roundtrip differs at byte offset {offset} (stored {} bytes, got {} bytes)",
+            bytes.len(),
+            reserialized.len()
+        );
+    }
+}
+
+/// Assert every fixture in `dir` roundtrips, returning how many were checked
+#[track_caller]
+pub fn assert_fixtures_roundtrip(dir: &Path) -> usize {
+    let fixtures = fixtures_in(dir).unwrap_or_else(|e| panic!("{e}"));
+    let mut count = 0;
+    for fixture in fixtures {
+        fixture.unwrap_or_else(|e| panic!("{e}")).assert_roundtrip();
+        count += 1;
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corpus_roundtrips() {
+        let names: Vec<String> = fixtures()
+            .unwrap()
+            .map(|fixture| fixture.unwrap().name)
+            .collect();
+        assert_eq!(
+            names,
+            ["jupiter_swap_v0", "legacy_compute_budget", "mayan_swap_v0"]
+        );
+        assert_eq!(assert_fixtures_roundtrip(&fixture_dir()), names.len());
+
+        let mayan = Fixture::load(&fixture_dir().join("mayan_swap_v0.b64")).unwrap();
+        let transaction = mayan.transaction().unwrap();
+        assert_eq!(
+            transaction.id().unwrap().to_base58(),
+            "2DZEgrPpdwCu2JcQZJCFivcmLSNMHMmDth9ujqFFZ8UeaEX6EqJmFTfZ43c7LgWqu85wiFhqo2h8PukruvpS4g4u"
+        );
+
+        let jupiter = Fixture::load(&fixture_dir().join("jupiter_swap_v0.b64")).unwrap();
+        let transaction = jupiter.transaction().unwrap();
+        assert_eq!(transaction.signatures().len(), 2);
+        assert_eq!(
+            transaction.id().unwrap().to_base58(),
+            "2dUtuLXqDEVXppXc6FDP4RRupp2VuHoki8fmR5WqF6aPwAZfcc2wEaRDmjYhhmdDGx6df7kX2ddDhRnfVJvB6egr"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "fixture `trailing` roundtrip differs")]
+    fn test_roundtrip_rejects_trailing_bytes() {
+        let mut bytes = fixtures().unwrap().next().unwrap().unwrap().bytes;
+        bytes.push(0);
+        assert_roundtrip("trailing", &bytes);
+    }
+}
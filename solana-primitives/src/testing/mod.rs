@@ -0,0 +1,348 @@
+//! Test helpers for transaction-construction code.
+//!
+//! [`ExpectedTransaction`] describes a transaction in a few lines of Rust or JSON and checks a
+//! built transaction against it; [`assert_snapshot`] pins the exact serialized bytes.
+//! [`bank::Bank`] executes System and Token instructions against in-memory accounts.
+//! [`fixtures`] loads a corpus of real transactions and checks they roundtrip byte for byte;
+//! [`generators`] produces arbitrary well-formed ones for property tests.
+
+pub mod bank;
+pub mod fixtures;
+pub mod generators;
+mod snapshot;
+
+pub use snapshot::{assert_bytes_snapshot, assert_snapshot, assert_snapshot_in};
+
+use crate::analysis::is_writable_index;
+use crate::error::{Result, SolanaError};
+use crate::types::{Pubkey, VersionedTransaction};
+use serde::{Deserialize, Deserializer};
+use std::str::FromStr;
+
+/// An expected instruction account, written `<pubkey>` with an optional `:s`, `:w` or `:sw`
+/// suffix for signer and writable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpectedAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl ExpectedAccount {
+    /// Create an expected account
+    pub fn new(pubkey: Pubkey, is_signer: bool, is_writable: bool) -> Self {
+        Self {
+            pubkey,
+            is_signer,
+            is_writable,
+        }
+    }
+}
+
+impl FromStr for ExpectedAccount {
+    type Err = SolanaError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (pubkey, flags) = s.split_once(':').unwrap_or((s, ""));
+        if !flags.chars().all(|c| c == 's' || c == 'w') {
+            return Err(SolanaError::DeserializationError(format!(
+                "invalid account flags: {flags}, expected a combination of 's' and 'w'"
+            )));
+        }
+        Ok(Self::new(
+            Pubkey::from_base58(pubkey)?,
+            flags.contains('s'),
+            flags.contains('w'),
+        ))
+    }
+}
+
+impl<'de> Deserialize<'de> for ExpectedAccount {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <String as Deserialize>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// An expected program invocation
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ExpectedInstruction {
+    /// Program being invoked
+    pub program: Pubkey,
+    /// Accounts in order; `None` skips the account check
+    #[serde(default)]
+    pub accounts: Option<Vec<ExpectedAccount>>,
+    /// Instruction data as hex; `None` skips the data check
+    #[serde(default)]
+    pub data: Option<String>,
+}
+
+impl ExpectedInstruction {
+    /// Expect an invocation of `program`
+    pub fn new(program: Pubkey) -> Self {
+        Self {
+            program,
+            accounts: None,
+            data: None,
+        }
+    }
+
+    /// Expect these accounts, in order
+    pub fn accounts(mut self, accounts: impl IntoIterator<Item = ExpectedAccount>) -> Self {
+        self.accounts = Some(accounts.into_iter().collect());
+        self
+    }
+
+    /// Expect this instruction data
+    pub fn data(mut self, data: &[u8]) -> Self {
+        self.data = Some(hex::encode(data));
+        self
+    }
+
+    /// Expect this instruction data, given as hex
+    pub fn data_hex(mut self, data: &str) -> Self {
+        self.data = Some(data.to_lowercase());
+        self
+    }
+}
+
+/// A concise description of a transaction to assert against.
+///
+/// ```ignore
+/// let expected = ExpectedTransaction::from_json(r#"{
+///     "signers": ["<payer>"],
+///     "instructions": [
+///         { "program": "11111111111111111111111111111111",
+///           "accounts": ["<payer>:sw", "<recipient>:w"],
+///           "data": "02000000e803000000000000" }
+///     ]
+/// }"#)?;
+/// expected.assert_matches(&transaction);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ExpectedTransaction {
+    /// Required signers in signature slot order
+    #[serde(default)]
+    pub signers: Vec<Pubkey>,
+    /// Program invocations in order
+    #[serde(default)]
+    pub instructions: Vec<ExpectedInstruction>,
+}
+
+impl ExpectedTransaction {
+    /// Create an empty expectation
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse an expectation from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| SolanaError::DeserializationError(e.to_string()))
+    }
+
+    /// Expect a required signer; signers are matched in order
+    pub fn signer(mut self, signer: Pubkey) -> Self {
+        self.signers.push(signer);
+        self
+    }
+
+    /// Expect a program invocation; instructions are matched in order
+    pub fn instruction(mut self, instruction: ExpectedInstruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// List every difference between the expectation and `transaction`.
+    ///
+    /// Accounts loaded from address lookup tables can't be resolved here and are reported as
+    /// mismatches if the expectation lists accounts for that instruction.
+    pub fn check(
+        &self,
+        transaction: &VersionedTransaction,
+    ) -> std::result::Result<(), Vec<String>> {
+        let mut mismatches = Vec::new();
+        let keys = transaction.account_keys();
+        let num_signers = transaction.num_required_signatures() as usize;
+
+        let signers = keys.get(..num_signers).unwrap_or(keys);
+        if signers != self.signers.as_slice() {
+            mismatches.push(format!(
+                "signers: expected {:?}, found {:?}",
+                self.signers, signers
+            ));
+        }
+
+        let instructions = transaction.instructions();
+        if instructions.len() != self.instructions.len() {
+            mismatches.push(format!(
+                "instruction count: expected {}, found {}",
+                self.instructions.len(),
+                instructions.len()
+            ));
+        }
+
+        for (index, (expected, actual)) in self.instructions.iter().zip(instructions).enumerate() {
+            let program = keys.get(actual.program_id_index as usize);
+            if program != Some(&expected.program) {
+                mismatches.push(format!(
+                    "instructions[{index}].program: expected {}, found {:?}",
+                    expected.program, program
+                ));
+            }
+
+            if let Some(accounts) = &expected.accounts {
+                let found: Vec<Option<ExpectedAccount>> = actual
+                    .accounts
+                    .iter()
+                    .map(|&i| {
+                        let i = i as usize;
+                        keys.get(i).map(|pubkey| {
+                            ExpectedAccount::new(
+                                *pubkey,
+                                i < num_signers,
+                                is_writable_index(transaction, i),
+                            )
+                        })
+                    })
+                    .collect();
+                if found.len() != accounts.len() {
+                    mismatches.push(format!(
+                        "instructions[{index}].accounts: expected {} accounts, found {}",
+                        accounts.len(),
+                        found.len()
+                    ));
+                }
+                for (position, (expected, found)) in accounts.iter().zip(&found).enumerate() {
+                    if Some(expected) != found.as_ref() {
+                        mismatches.push(format!(
+                            "instructions[{index}].accounts[{position}]: expected {expected:?}, found {found:?}"
+                        ));
+                    }
+                }
+            }
+
+            if let Some(data) = &expected.data {
+                let found = hex::encode(&actual.data);
+                if *data != found {
+                    mismatches.push(format!(
+                        "instructions[{index}].data: expected {data}, found {found}"
+                    ));
+                }
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+
+    /// Panic with every difference if `transaction` doesn't match
+    #[track_caller]
+    pub fn assert_matches(&self, transaction: &VersionedTransaction) {
+        if let Err(mismatches) = self.check(transaction) {
+            panic!(
+                "transaction does not match expectation:\n  {}",
+                mismatches.join("\n  ")
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::TransactionBuilder;
+    use crate::instructions::program_ids::system_program;
+    use crate::instructions::system::transfer;
+
+    fn sample() -> (Pubkey, Pubkey, VersionedTransaction) {
+        let payer = Pubkey::new([1u8; 32]);
+        let recipient = Pubkey::new([2u8; 32]);
+        let mut builder = TransactionBuilder::new(payer, [3u8; 32]);
+        builder.add_instruction(transfer(&payer, &recipient, 1_000));
+        (payer, recipient, builder.build_v0(&[]).unwrap())
+    }
+
+    #[test]
+    fn test_expected_transaction_dsl() {
+        let (payer, recipient, transaction) = sample();
+
+        ExpectedTransaction::new()
+            .signer(payer)
+            .instruction(
+                ExpectedInstruction::new(system_program())
+                    .accounts([
+                        ExpectedAccount::new(payer, true, true),
+                        ExpectedAccount::new(recipient, false, true),
+                    ])
+                    .data_hex("02000000E803000000000000"),
+            )
+            .assert_matches(&transaction);
+    }
+
+    #[test]
+    fn test_expected_transaction_json() {
+        let (payer, recipient, transaction) = sample();
+        let json = format!(
+            r#"{{
+                "signers": ["{payer}"],
+                "instructions": [{{
+                    "program": "11111111111111111111111111111111",
+                    "accounts": ["{payer}:sw", "{recipient}:w"],
+                    "data": "02000000e803000000000000"
+                }}]
+            }}"#
+        );
+        ExpectedTransaction::from_json(&json)
+            .unwrap()
+            .assert_matches(&transaction);
+    }
+
+    #[test]
+    fn test_expected_transaction_reports_mismatches() {
+        let (payer, recipient, transaction) = sample();
+        let mismatches = ExpectedTransaction::new()
+            .signer(recipient)
+            .instruction(
+                ExpectedInstruction::new(system_program())
+                    .accounts([
+                        ExpectedAccount::new(payer, true, false),
+                        ExpectedAccount::new(recipient, false, true),
+                    ])
+                    .data(&[2, 0, 0, 0]),
+            )
+            .check(&transaction)
+            .unwrap_err();
+
+        assert_eq!(mismatches.len(), 3);
+        assert!(mismatches[0].starts_with("signers"));
+        assert!(mismatches[1].starts_with("instructions[0].accounts[0]"));
+        assert!(mismatches[2].starts_with("instructions[0].data"));
+        assert!("x:q".parse::<ExpectedAccount>().is_err());
+    }
+
+    #[test]
+    fn test_expected_transaction_tolerates_malformed_header() {
+        let (payer, recipient, mut transaction) = sample();
+        if let VersionedTransaction::V0 { message, .. } = &mut transaction {
+            message.header.num_readonly_signed_accounts = 5;
+            message.header.num_readonly_unsigned_accounts = 9;
+        }
+        // Both accounts now read as readonly, without underflowing on the header counts
+        let mismatches = ExpectedTransaction::new()
+            .signer(payer)
+            .instruction(ExpectedInstruction::new(system_program()).accounts([
+                ExpectedAccount::new(payer, true, true),
+                ExpectedAccount::new(recipient, false, true),
+            ]))
+            .check(&transaction)
+            .unwrap_err();
+        assert_eq!(mismatches.len(), 2, "{mismatches:?}");
+        assert!(mismatches[0].starts_with("instructions[0].accounts[0]"));
+    }
+}
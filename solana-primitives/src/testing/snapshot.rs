@@ -0,0 +1,86 @@
+use crate::types::VersionedTransaction;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that makes snapshot assertions overwrite stored snapshots
+const UPDATE_ENV: &str = "SOLANA_PRIMITIVES_UPDATE_SNAPSHOTS";
+
+/// Assert the serialized bytes of `transaction` match the snapshot `name`.
+///
+/// Snapshots live in `tests/snapshots/<name>.snap` under the crate being tested. A missing
+/// snapshot is written and the assertion passes; set `SOLANA_PRIMITIVES_UPDATE_SNAPSHOTS=1` to
+/// overwrite existing ones.
+#[track_caller]
+pub fn assert_snapshot(name: &str, transaction: &VersionedTransaction) {
+    let bytes = transaction
+        .serialize()
+        .expect("failed to serialize transaction for snapshot");
+    assert_bytes_snapshot(name, &bytes);
+}
+
+/// Assert `bytes` match the snapshot `name` in the default snapshot directory
+#[track_caller]
+pub fn assert_bytes_snapshot(name: &str, bytes: &[u8]) {
+    let root = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    assert_snapshot_in(&root.join("tests").join("snapshots"), name, bytes);
+}
+
+/// Assert `bytes` match the snapshot `name` stored in `dir`
+#[track_caller]
+pub fn assert_snapshot_in(dir: &Path, name: &str, bytes: &[u8]) {
+    let path = dir.join(format!("{name}.snap"));
+    let encoded = hex::encode(bytes);
+    let update = std::env::var(UPDATE_ENV).is_ok_and(|v| v == "1");
+
+    match std::fs::read_to_string(&path) {
+        Ok(stored) if !update => {
+            let stored = stored.trim();
+            if stored != encoded {
+                let offset = stored
+                    .as_bytes()
+                    .chunks(2)
+                    .zip(encoded.as_bytes().chunks(2))
+                    .position(|(a, b)| a != b)
+                    .unwrap_or(stored.len().min(encoded.len()) / 2);
+                panic!(
+                    "snapshot `{name}` differs at byte offset {offset} (stored {} bytes, got {} bytes); \
+                     rerun with {UPDATE_ENV}=1 to accept\n  stored: {stored}\n     got: {encoded}",
+                    stored.len() / 2,
+                    bytes.len()
+                );
+            }
+        }
+        _ => {
+            std::fs::create_dir_all(dir).expect("failed to create snapshot directory");
+            std::fs::write(&path, encoded + "\n").expect("failed to write snapshot");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let dir = std::env::temp_dir().join(format!(
+            "solana-primitives-snapshots-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_snapshot_in(&dir, "bytes", &[1, 2, 3]);
+        assert_eq!(
+            std::fs::read_to_string(dir.join("bytes.snap")).unwrap(),
+            "010203\n"
+        );
+        assert_snapshot_in(&dir, "bytes", &[1, 2, 3]);
+
+        let result = std::panic::catch_unwind(|| assert_snapshot_in(&dir, "bytes", &[1, 9, 3]));
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("differs at byte offset 1"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
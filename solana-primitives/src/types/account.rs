@@ -1,5 +1,7 @@
+use crate::data::DataReader;
 use crate::types::Pubkey;
 use crate::{Result, SolanaError};
+use alloc::vec::Vec;
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
@@ -10,14 +12,23 @@ const LOOKUP_TABLE_DISCRIMINANT: u32 = 1;
 /// Address lookup table lookup information
 /// Used to describe which addresses in a lookup table to use in a transaction
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MessageAddressTableLookup {
     /// Address lookup table account key
     pub account_key: Pubkey,
     /// List of indices used to load writable account addresses
     #[serde(with = "crate::short_vec")]
+    #[cfg_attr(
+        feature = "schema",
+        schemars(schema_with = "crate::schema::short_vec_u8")
+    )]
     pub writable_indexes: Vec<u8>,
     /// List of indices used to load readonly account addresses
     #[serde(with = "crate::short_vec")]
+    #[cfg_attr(
+        feature = "schema",
+        schemars(schema_with = "crate::schema::short_vec_u8")
+    )]
     pub readonly_indexes: Vec<u8>,
 }
 
@@ -32,8 +43,69 @@ impl MessageAddressTableLookup {
     }
 }
 
+/// An on-chain account as returned by `getAccountInfo`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+    /// Balance in lamports
+    pub lamports: u64,
+    /// Program that owns the account
+    pub owner: Pubkey,
+    /// Raw account data
+    pub data: Vec<u8>,
+    /// Whether the account holds a loaded program
+    pub executable: bool,
+}
+
+/// Lookup table metadata stored ahead of the addresses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LookupTableMeta {
+    /// Slot the table was deactivated at, `u64::MAX` while active
+    pub deactivation_slot: u64,
+    /// Slot of the last extension
+    pub last_extended_slot: u64,
+    /// Number of addresses before the last extension
+    pub last_extended_slot_start_index: u8,
+    /// Authority allowed to extend, deactivate and close the table; `None` once frozen
+    pub authority: Option<Pubkey>,
+}
+
+impl LookupTableMeta {
+    /// Decode the metadata of lookup table account data
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        if data.len() < LOOKUP_TABLE_META_SIZE {
+            return Err(SolanaError::InvalidMessage);
+        }
+        let mut reader = DataReader::new(data);
+        if reader.read::<u32>()? != LOOKUP_TABLE_DISCRIMINANT {
+            return Err(SolanaError::InvalidMessage);
+        }
+        let deactivation_slot = reader.read()?;
+        let last_extended_slot = reader.read()?;
+        let last_extended_slot_start_index = reader.read()?;
+        let has_authority = reader.read::<u8>()? != 0;
+        let authority = reader.read_pubkey()?;
+        Ok(Self {
+            deactivation_slot,
+            last_extended_slot,
+            last_extended_slot_start_index,
+            authority: has_authority.then_some(authority),
+        })
+    }
+
+    /// Whether the table has not been deactivated
+    pub fn is_active(&self) -> bool {
+        self.deactivation_slot == u64::MAX
+    }
+
+    /// Whether the table can no longer be changed
+    pub fn is_frozen(&self) -> bool {
+        self.authority.is_none()
+    }
+}
+
 /// Address lookup table account
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AddressLookupTableAccount {
     /// The lookup table's public key
     pub key: Pubkey,
@@ -91,6 +163,17 @@ impl AddressLookupTableAccount {
         Ok(Self { key, addresses })
     }
 
+    /// Parse an address lookup table account and its metadata from raw account data.
+    pub fn from_account_data_with_meta(
+        key: Pubkey,
+        data: &[u8],
+    ) -> Result<(Self, LookupTableMeta)> {
+        Ok((
+            Self::from_account_data(key, data)?,
+            LookupTableMeta::from_account_data(data)?,
+        ))
+    }
+
     /// Parse an address lookup table account from a base58 key and raw account data.
     pub fn from_base58_account_data(key: &str, data: &[u8]) -> Result<Self> {
         let key = Pubkey::from_base58(key)?;
@@ -175,4 +258,32 @@ mod tests {
 
         assert!(matches!(result, Err(SolanaError::InvalidMessage)));
     }
+
+    #[test]
+    fn test_lookup_table_meta_from_account_data() {
+        let key = Pubkey::new([9; 32]);
+        let mut data = LOOKUP_TABLE_DISCRIMINANT.to_le_bytes().to_vec();
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.push(1);
+        data.push(1);
+        data.extend_from_slice(&[7u8; 32]);
+        data.extend_from_slice(&[0u8; 2]);
+        data.extend_from_slice(&[2u8; 32]);
+
+        let (table, meta) =
+            AddressLookupTableAccount::from_account_data_with_meta(key, &data).unwrap();
+        assert_eq!(table.addresses, vec![Pubkey::new([2u8; 32])]);
+        assert!(meta.is_active());
+        assert!(!meta.is_frozen());
+        assert_eq!(meta.last_extended_slot, 42);
+        assert_eq!(meta.last_extended_slot_start_index, 1);
+        assert_eq!(meta.authority, Some(Pubkey::new([7u8; 32])));
+
+        data[4..12].copy_from_slice(&100u64.to_le_bytes());
+        data[21] = 0;
+        let meta = LookupTableMeta::from_account_data(&data).unwrap();
+        assert!(!meta.is_active());
+        assert!(meta.is_frozen());
+    }
 }
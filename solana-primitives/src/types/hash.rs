@@ -0,0 +1,160 @@
+use crate::base58;
+use crate::error::{Result, SolanaError};
+use alloc::format;
+use alloc::string::String;
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::fmt;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// A 32-byte SHA-256 hash, displayed as base58
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct Hash([u8; 32]);
+
+/// A recent blockhash or durable nonce
+pub type Blockhash = Hash;
+
+impl Hash {
+    /// Create a hash from bytes
+    pub const fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parse a base58-encoded hash
+    pub fn from_base58(s: &str) -> Result<Self> {
+        base58::decode_array(s)
+            .map(Self)
+            .map_err(|e| SolanaError::GenericError(format!("invalid hash: {e}")))
+    }
+
+    /// Encode the hash as base58
+    pub fn to_base58(&self) -> String {
+        bs58::encode(&self.0).into_string()
+    }
+
+    /// Get the bytes of the hash
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Copy out the bytes of the hash
+    pub fn to_bytes(self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl fmt::Display for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_base58())
+    }
+}
+
+impl FromStr for Hash {
+    type Err = SolanaError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_base58(s)
+    }
+}
+
+impl From<[u8; 32]> for Hash {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Hash> for [u8; 32] {
+    fn from(hash: Hash) -> Self {
+        hash.0
+    }
+}
+
+impl TryFrom<&[u8]> for Hash {
+    type Error = SolanaError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        bytes.try_into().map(Self).map_err(|_| {
+            SolanaError::GenericError(format!(
+                "invalid hash length: {}, expected: 32",
+                bytes.len()
+            ))
+        })
+    }
+}
+
+impl AsRef<[u8]> for Hash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Serialize for Hash {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_base58())
+    }
+}
+
+impl<'de> Deserialize<'de> for Hash {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = <String as Deserialize>::deserialize(deserializer)?;
+        Self::from_base58(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Pubkey, SignatureBytes};
+
+    #[test]
+    fn test_hash_conversions() {
+        let hash = Hash::new([7u8; 32]);
+        let encoded = hash.to_string();
+        assert_eq!(encoded.parse::<Hash>().unwrap(), hash);
+        assert_eq!(Hash::try_from(hash.as_ref()).unwrap(), hash);
+        assert!(Hash::try_from(&[0u8; 31][..]).is_err());
+        assert!("not base58!".parse::<Hash>().is_err());
+        assert_eq!(<[u8; 32]>::from(hash), [7u8; 32]);
+        assert_eq!(
+            serde_json::to_string(&hash).unwrap(),
+            format!("\"{encoded}\"")
+        );
+        assert_eq!(Hash::default(), Hash::from([0u8; 32]));
+    }
+
+    #[test]
+    fn test_pubkey_and_signature_conversions() {
+        let pubkey = Pubkey::from([1u8; 32]);
+        assert_eq!(Pubkey::try_from(pubkey.as_ref()).unwrap(), pubkey);
+        assert!(Pubkey::try_from(&[1u8; 33][..]).is_err());
+
+        let signature = SignatureBytes::from([2u8; 64]);
+        assert_eq!(
+            signature.to_string().parse::<SignatureBytes>().unwrap(),
+            signature
+        );
+        assert_eq!(
+            SignatureBytes::try_from(signature.as_ref()).unwrap(),
+            signature
+        );
+        assert!(SignatureBytes::try_from(&[2u8; 32][..]).is_err());
+    }
+}
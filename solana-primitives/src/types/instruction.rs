@@ -1,9 +1,11 @@
 use super::pubkey::Pubkey;
+use alloc::vec::Vec;
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
 /// Represents a Solana instruction
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Instruction {
     /// The program ID that will process this instruction
     #[serde(alias = "programId")]
@@ -17,6 +19,7 @@ pub struct Instruction {
 
 /// Metadata about an account in an instruction
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AccountMeta {
     /// The account's public key
     #[serde(alias = "publicKey")]
@@ -62,6 +65,7 @@ impl AccountMeta {
 
 /// A compiled instruction that references accounts by their indices
 #[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CompiledInstruction {
     /// Index into the account keys array indicating the program to execute
     pub program_id_index: u8,
@@ -0,0 +1,77 @@
+use crate::types::Pubkey;
+use alloc::collections::BTreeMap;
+
+/// `Pubkey` to position lookup over a message's account keys, in `O(log n)` per query.
+///
+/// Nothing is cached on the message: building the index is `O(n log n)`, so it only pays off
+/// when reused for several queries. It borrows the keys it was built from, so it can't
+/// outlive a mutation of the message.
+#[derive(Debug, Clone)]
+pub struct KeyIndex<'a> {
+    keys: &'a [Pubkey],
+    positions: BTreeMap<&'a Pubkey, usize>,
+}
+
+impl<'a> KeyIndex<'a> {
+    /// Index the given keys; for duplicated keys the first position wins
+    pub fn new(keys: &'a [Pubkey]) -> Self {
+        let mut positions = BTreeMap::new();
+        for (index, key) in keys.iter().enumerate() {
+            positions.entry(key).or_insert(index);
+        }
+        Self { keys, positions }
+    }
+
+    /// Get the position of a key
+    pub fn position(&self, key: &Pubkey) -> Option<usize> {
+        self.positions.get(key).copied()
+    }
+
+    /// Check whether a key is present
+    pub fn contains(&self, key: &Pubkey) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    /// Get the key at a position
+    pub fn get(&self, index: usize) -> Option<&'a Pubkey> {
+        self.keys.get(index)
+    }
+
+    /// Get the indexed keys
+    pub fn keys(&self) -> &'a [Pubkey] {
+        self.keys
+    }
+
+    /// Get the number of indexed keys
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Check if there are no indexed keys
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_index() {
+        let keys = vec![
+            Pubkey::new([1; 32]),
+            Pubkey::new([2; 32]),
+            Pubkey::new([1; 32]),
+        ];
+        let index = KeyIndex::new(&keys);
+
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.position(&Pubkey::new([1; 32])), Some(0));
+        assert_eq!(index.position(&Pubkey::new([2; 32])), Some(1));
+        assert_eq!(index.position(&Pubkey::new([3; 32])), None);
+        assert!(index.contains(&Pubkey::new([2; 32])));
+        assert_eq!(index.get(1), Some(&Pubkey::new([2; 32])));
+        assert!(KeyIndex::new(&[]).is_empty());
+    }
+}
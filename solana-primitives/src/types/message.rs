@@ -1,53 +1,94 @@
-use crate::types::{CompiledInstruction, MessageAddressTableLookup, Pubkey};
+#[cfg(feature = "blake3")]
+use crate::crypto::blake3;
+use crate::error::SolanaError;
+use crate::short_vec::{compact_u16_len, write_compact_u16};
+#[cfg(feature = "blake3")]
+use crate::types::Hash;
+use crate::types::sanitize::{SanitizeError, sanitize_message};
+use crate::types::{
+    AddressLookupTableAccount, Blockhash, CompiledInstruction, KeyIndex, MessageAddressTableLookup,
+    Pubkey,
+};
 use borsh::{BorshDeserialize, BorshSerialize};
 
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 
-/// Serialize the common message body (header + account keys + blockhash + instructions).
+/// Domain prefix validators hash ahead of the message bytes
+#[cfg(feature = "blake3")]
+const MESSAGE_HASH_PREFIX: &[u8] = b"solana-tx-message-v1";
+
+/// Append the common message body (header + account keys + blockhash + instructions) to `out`.
 /// Shared by Legacy, Message, and V0 message types.
-fn serialize_message_body(
+fn write_message_body(
+    out: &mut Vec<u8>,
     header: &MessageHeader,
     account_keys: &[Pubkey],
-    recent_blockhash: &[u8; 32],
+    recent_blockhash: &Blockhash,
     instructions: &[CompiledInstruction],
-) -> Result<Vec<u8>, String> {
-    let mut bytes = Vec::new();
-
+) -> Result<(), String> {
     // 1. Header (3 bytes)
-    bytes.push(header.num_required_signatures);
-    bytes.push(header.num_readonly_signed_accounts);
-    bytes.push(header.num_readonly_unsigned_accounts);
+    out.push(header.num_required_signatures);
+    out.push(header.num_readonly_signed_accounts);
+    out.push(header.num_readonly_unsigned_accounts);
 
     // 2. Account keys
-    let len = crate::encode_length_to_compact_u16_bytes(account_keys.len())?;
-    bytes.extend_from_slice(&len);
+    write_compact_u16(out, account_keys.len())?;
     for pubkey in account_keys {
-        bytes.extend_from_slice(pubkey.as_bytes());
+        out.extend_from_slice(pubkey.as_bytes());
     }
 
     // 3. Recent blockhash (32 bytes)
-    bytes.extend_from_slice(recent_blockhash);
+    out.extend_from_slice(recent_blockhash.as_bytes());
 
     // 4. Instructions
-    let len = crate::encode_length_to_compact_u16_bytes(instructions.len())?;
-    bytes.extend_from_slice(&len);
+    write_compact_u16(out, instructions.len())?;
     for ix in instructions {
-        bytes.push(ix.program_id_index);
+        out.push(ix.program_id_index);
 
-        let len = crate::encode_length_to_compact_u16_bytes(ix.accounts.len())?;
-        bytes.extend_from_slice(&len);
-        bytes.extend_from_slice(&ix.accounts);
+        write_compact_u16(out, ix.accounts.len())?;
+        out.extend_from_slice(&ix.accounts);
 
-        let len = crate::encode_length_to_compact_u16_bytes(ix.data.len())?;
-        bytes.extend_from_slice(&len);
-        bytes.extend_from_slice(&ix.data);
+        write_compact_u16(out, ix.data.len())?;
+        out.extend_from_slice(&ix.data);
     }
 
-    Ok(bytes)
+    Ok(())
+}
+
+/// Wire size of the common message body, matching [`write_message_body`], given the account
+/// count and data length of each instruction
+pub(crate) fn message_body_size(
+    num_account_keys: usize,
+    instructions: impl ExactSizeIterator<Item = (usize, usize)>,
+) -> usize {
+    let num_instructions = instructions.len();
+    let instructions_size: usize = instructions
+        .map(|(num_accounts, data_len)| {
+            1 + compact_u16_len(num_accounts) + num_accounts + compact_u16_len(data_len) + data_len
+        })
+        .sum();
+    3 + compact_u16_len(num_account_keys)
+        + num_account_keys * 32
+        + 32
+        + compact_u16_len(num_instructions)
+        + instructions_size
+}
+
+/// Account count and data length of each compiled instruction, for [`message_body_size`]
+fn instruction_sizes(
+    instructions: &[CompiledInstruction],
+) -> impl ExactSizeIterator<Item = (usize, usize)> + '_ {
+    instructions
+        .iter()
+        .map(|ix| (ix.accounts.len(), ix.data.len()))
 }
 
 /// The message header, identifying signed and read-only `account_keys`.
 #[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MessageHeader {
     /// The number of signatures required for this message to be considered valid.
     pub num_required_signatures: u8,
@@ -59,37 +100,65 @@ pub struct MessageHeader {
 
 /// Legacy message format (pre-versioned transactions)
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LegacyMessage {
     /// The message header, identifying signed and read-only `account_keys`.
     pub header: MessageHeader,
     /// List of account public keys
     pub account_keys: Vec<Pubkey>,
     /// The blockhash of a recent block.
-    pub recent_blockhash: [u8; 32],
+    pub recent_blockhash: Blockhash,
     /// Instructions that will be executed in sequence and committed in one atomic transaction if all succeed.
     pub instructions: Vec<CompiledInstruction>,
 }
 
 impl LegacyMessage {
     pub fn serialize_for_signing(&self) -> Result<Vec<u8>, String> {
-        serialize_message_body(
+        let mut bytes = Vec::with_capacity(self.serialized_size());
+        self.write_for_signing(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Append the [`Self::serialize_for_signing`] bytes to `out`
+    pub(crate) fn write_for_signing(&self, out: &mut Vec<u8>) -> Result<(), String> {
+        write_message_body(
+            out,
             &self.header,
             &self.account_keys,
             &self.recent_blockhash,
             &self.instructions,
         )
     }
+
+    /// Size of [`Self::serialize_for_signing`] output, computed without serializing
+    pub fn serialized_size(&self) -> usize {
+        message_body_size(
+            self.account_keys.len(),
+            instruction_sizes(&self.instructions),
+        )
+    }
+
+    /// Build a [`KeyIndex`] over the account keys
+    pub fn key_index(&self) -> KeyIndex<'_> {
+        KeyIndex::new(&self.account_keys)
+    }
+
+    /// Check the header, account keys and instruction indexes as validators do
+    pub fn sanitize(&self) -> Result<(), SanitizeError> {
+        sanitize_message(&self.header, &self.account_keys, &self.instructions, &[])
+    }
 }
 
 /// Versioned message format V0
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct VersionedMessageV0 {
     /// The message header, identifying signed and read-only `account_keys`.
     pub header: MessageHeader,
     /// List of account public keys
     pub account_keys: Vec<Pubkey>,
     /// The blockhash of a recent block.
-    pub recent_blockhash: [u8; 32],
+    pub recent_blockhash: Blockhash,
     /// Instructions that will be executed in sequence and committed in one atomic transaction if all succeed.
     pub instructions: Vec<CompiledInstruction>,
     /// List of address lookup table references
@@ -101,45 +170,134 @@ impl VersionedMessageV0 {
     ///
     /// Format: `[0x80]` version prefix + header + account keys + blockhash + instructions + address table lookups
     pub fn serialize_for_signing(&self) -> Result<Vec<u8>, String> {
-        let mut bytes = Vec::new();
+        let mut bytes = Vec::with_capacity(self.serialized_size());
+        self.write_for_signing(&mut bytes)?;
+        Ok(bytes)
+    }
 
+    /// Append the [`Self::serialize_for_signing`] bytes to `out`
+    pub(crate) fn write_for_signing(&self, out: &mut Vec<u8>) -> Result<(), String> {
         // V0 version prefix
-        bytes.push(0x80);
+        out.push(0x80);
 
         // Message body (same as legacy)
-        let body = serialize_message_body(
+        write_message_body(
+            out,
             &self.header,
             &self.account_keys,
             &self.recent_blockhash,
             &self.instructions,
         )?;
-        bytes.extend_from_slice(&body);
 
         // Address table lookups
-        let lookup_len =
-            crate::encode_length_to_compact_u16_bytes(self.address_table_lookups.len())?;
-        bytes.extend_from_slice(&lookup_len);
-
+        write_compact_u16(out, self.address_table_lookups.len())?;
         for lookup in &self.address_table_lookups {
-            bytes.extend_from_slice(lookup.account_key.as_bytes());
+            out.extend_from_slice(lookup.account_key.as_bytes());
 
-            let writable_len =
-                crate::encode_length_to_compact_u16_bytes(lookup.writable_indexes.len())?;
-            bytes.extend_from_slice(&writable_len);
-            bytes.extend_from_slice(&lookup.writable_indexes);
+            write_compact_u16(out, lookup.writable_indexes.len())?;
+            out.extend_from_slice(&lookup.writable_indexes);
 
-            let readonly_len =
-                crate::encode_length_to_compact_u16_bytes(lookup.readonly_indexes.len())?;
-            bytes.extend_from_slice(&readonly_len);
-            bytes.extend_from_slice(&lookup.readonly_indexes);
+            write_compact_u16(out, lookup.readonly_indexes.len())?;
+            out.extend_from_slice(&lookup.readonly_indexes);
         }
 
-        Ok(bytes)
+        Ok(())
+    }
+
+    /// Size of [`Self::serialize_for_signing`] output, computed without serializing
+    pub fn serialized_size(&self) -> usize {
+        let lookups_size: usize = self
+            .address_table_lookups
+            .iter()
+            .map(|lookup| {
+                32 + compact_u16_len(lookup.writable_indexes.len())
+                    + lookup.writable_indexes.len()
+                    + compact_u16_len(lookup.readonly_indexes.len())
+                    + lookup.readonly_indexes.len()
+            })
+            .sum();
+        1 + message_body_size(
+            self.account_keys.len(),
+            instruction_sizes(&self.instructions),
+        ) + compact_u16_len(self.address_table_lookups.len())
+            + lookups_size
+    }
+
+    /// Build a [`KeyIndex`] over the account keys
+    pub fn key_index(&self) -> KeyIndex<'_> {
+        KeyIndex::new(&self.account_keys)
+    }
+
+    /// Check the header, account keys, lookups and instruction indexes as validators do.
+    ///
+    /// Duplicates among looked-up addresses can only be detected once the tables are loaded.
+    pub fn sanitize(&self) -> Result<(), SanitizeError> {
+        sanitize_message(
+            &self.header,
+            &self.account_keys,
+            &self.instructions,
+            &self.address_table_lookups,
+        )
     }
+
+    /// Resolve the full account list that instruction account indexes refer to.
+    ///
+    /// Static keys come first, then every looked-up writable address, then every looked-up
+    /// readonly address, each in lookup order, matching how the runtime loads them.
+    pub fn resolve_addresses(
+        &self,
+        address_lookup_tables: &[AddressLookupTableAccount],
+    ) -> crate::Result<Vec<Pubkey>> {
+        let tables = self
+            .address_table_lookups
+            .iter()
+            .map(|lookup| {
+                address_lookup_tables
+                    .iter()
+                    .find(|table| table.key == lookup.account_key)
+                    .ok_or_else(|| {
+                        SolanaError::GenericError(format!(
+                            "missing address lookup table {}",
+                            lookup.account_key
+                        ))
+                    })
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let mut addresses = self.account_keys.clone();
+        let lookups = self.address_table_lookups.iter().zip(&tables);
+        for (lookup, table) in lookups.clone() {
+            resolve_indexes(table, &lookup.writable_indexes, &mut addresses)?;
+        }
+        for (lookup, table) in lookups {
+            resolve_indexes(table, &lookup.readonly_indexes, &mut addresses)?;
+        }
+        Ok(addresses)
+    }
+}
+
+fn resolve_indexes(
+    table: &AddressLookupTableAccount,
+    indexes: &[u8],
+    addresses: &mut Vec<Pubkey>,
+) -> crate::Result<()> {
+    for index in indexes {
+        let address = table.get(*index as usize).ok_or_else(|| {
+            SolanaError::GenericError(format!(
+                "lookup index {} out of range for table {} with {} addresses",
+                index,
+                table.key,
+                table.len()
+            ))
+        })?;
+        addresses.push(*address);
+    }
+    Ok(())
 }
 
 /// Versioned message format
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum VersionedMessage {
     /// Legacy message format (pre-versioned transactions)
     Legacy(LegacyMessage),
@@ -149,13 +307,14 @@ pub enum VersionedMessage {
 
 /// A Solana transaction message
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Message {
     /// The message header, identifying signed and read-only `account_keys`.
     pub header: MessageHeader,
     /// List of account public keys
     pub account_keys: Vec<Pubkey>,
     /// The blockhash of a recent block.
-    pub recent_blockhash: [u8; 32],
+    pub recent_blockhash: Blockhash,
     /// Instructions that will be executed in sequence and committed in one atomic transaction if all succeed.
     pub instructions: Vec<CompiledInstruction>,
 }
@@ -165,13 +324,13 @@ impl Message {
     pub fn new(
         header: MessageHeader,
         account_keys: Vec<Pubkey>,
-        recent_blockhash: [u8; 32],
+        recent_blockhash: impl Into<Blockhash>,
         instructions: Vec<CompiledInstruction>,
     ) -> Self {
         Self {
             header,
             account_keys,
-            recent_blockhash,
+            recent_blockhash: recent_blockhash.into(),
             instructions,
         }
     }
@@ -194,13 +353,57 @@ impl Message {
     /// Serializes the message into the byte format required for signing
     /// and for the legacy transaction wire format.
     pub fn serialize_for_signing(&self) -> Result<Vec<u8>, String> {
-        serialize_message_body(
+        let mut bytes = Vec::with_capacity(self.serialized_size());
+        self.write_for_signing(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Append the [`Self::serialize_for_signing`] bytes to `out`
+    pub(crate) fn write_for_signing(&self, out: &mut Vec<u8>) -> Result<(), String> {
+        write_message_body(
+            out,
             &self.header,
             &self.account_keys,
             &self.recent_blockhash,
             &self.instructions,
         )
     }
+
+    /// Size of [`Self::serialize_for_signing`] output, computed without serializing
+    pub fn serialized_size(&self) -> usize {
+        message_body_size(
+            self.account_keys.len(),
+            instruction_sizes(&self.instructions),
+        )
+    }
+
+    /// Build a [`KeyIndex`] over the account keys
+    pub fn key_index(&self) -> KeyIndex<'_> {
+        KeyIndex::new(&self.account_keys)
+    }
+
+    /// Check the header, account keys and instruction indexes as validators do
+    pub fn sanitize(&self) -> Result<(), SanitizeError> {
+        sanitize_message(&self.header, &self.account_keys, &self.instructions, &[])
+    }
+
+    /// Hash of the serialized message, as the runtime computes it for status caches
+    #[cfg(feature = "blake3")]
+    pub fn hash(&self) -> crate::Result<Hash> {
+        let bytes = self
+            .serialize_for_signing()
+            .map_err(SolanaError::SerializationError)?;
+        Ok(Self::hash_raw_message(&bytes))
+    }
+
+    /// Hash serialized message bytes of any version: blake3 over a domain prefix and the bytes
+    #[cfg(feature = "blake3")]
+    pub fn hash_raw_message(message_bytes: &[u8]) -> Hash {
+        let mut data = Vec::with_capacity(MESSAGE_HASH_PREFIX.len() + message_bytes.len());
+        data.extend_from_slice(MESSAGE_HASH_PREFIX);
+        data.extend_from_slice(message_bytes);
+        Hash::from(blake3(&data))
+    }
 }
 
 #[cfg(test)]
@@ -216,7 +419,7 @@ mod tests {
             num_readonly_unsigned_accounts: 1,
         };
         let account_keys = vec![Pubkey::new([0; 32]), Pubkey::new([1; 32])];
-        let recent_blockhash = [0u8; 32];
+        let recent_blockhash = Blockhash::default();
         let instructions = vec![CompiledInstruction {
             program_id_index: 1,
             accounts: vec![0],
@@ -238,7 +441,7 @@ mod tests {
             num_readonly_unsigned_accounts: 1,
         };
         let account_keys = vec![Pubkey::new([0; 32]), Pubkey::new([1; 32])];
-        let recent_blockhash = [0u8; 32];
+        let recent_blockhash = Blockhash::default();
         let instructions = vec![CompiledInstruction {
             program_id_index: 1,
             accounts: vec![0],
@@ -280,4 +483,120 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_resolve_addresses() {
+        let key = |n: u8| Pubkey::new([n; 32]);
+        let message = VersionedMessageV0 {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 0,
+            },
+            account_keys: vec![key(1), key(2)],
+            recent_blockhash: Blockhash::default(),
+            instructions: vec![],
+            address_table_lookups: vec![
+                MessageAddressTableLookup::new(key(10), vec![1], vec![0]),
+                MessageAddressTableLookup::new(key(20), vec![0], vec![2]),
+            ],
+        };
+        let tables = [
+            AddressLookupTableAccount::new(key(20), vec![key(21), key(22), key(23)]),
+            AddressLookupTableAccount::new(key(10), vec![key(11), key(12)]),
+        ];
+
+        assert_eq!(
+            message.resolve_addresses(&tables).unwrap(),
+            vec![key(1), key(2), key(12), key(21), key(11), key(23)]
+        );
+        assert!(message.resolve_addresses(&tables[..1]).is_err());
+
+        let short_tables = [
+            AddressLookupTableAccount::new(key(20), vec![key(21)]),
+            tables[1].clone(),
+        ];
+        assert!(message.resolve_addresses(&short_tables).is_err());
+    }
+
+    #[test]
+    fn test_sanitize() {
+        let key = |n: u8| Pubkey::new([n; 32]);
+        let valid = LegacyMessage {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![key(1), key(2), key(3)],
+            recent_blockhash: Blockhash::default(),
+            instructions: vec![CompiledInstruction {
+                program_id_index: 2,
+                accounts: vec![0, 1],
+                data: vec![],
+            }],
+        };
+        assert_eq!(valid.sanitize(), Ok(()));
+
+        let mut message = valid.clone();
+        message.header.num_readonly_signed_accounts = 1;
+        assert_eq!(message.sanitize(), Err(SanitizeError::ReadonlyFeePayer));
+
+        let mut message = valid.clone();
+        message.header.num_readonly_unsigned_accounts = 3;
+        assert_eq!(
+            message.sanitize(),
+            Err(SanitizeError::HeaderCountsExceedKeys(3))
+        );
+
+        let mut message = valid.clone();
+        message.account_keys[2] = key(1);
+        assert_eq!(
+            message.sanitize(),
+            Err(SanitizeError::DuplicateAccountKey(key(1)))
+        );
+
+        let mut message = valid.clone();
+        message.instructions[0].program_id_index = 0;
+        assert_eq!(
+            message.sanitize(),
+            Err(SanitizeError::ProgramIdIsSigner {
+                instruction: 0,
+                index: 0
+            })
+        );
+
+        let mut message = valid.clone();
+        message.instructions[0].accounts.push(3);
+        assert_eq!(
+            message.sanitize(),
+            Err(SanitizeError::AccountIndexOutOfRange {
+                instruction: 0,
+                index: 3
+            })
+        );
+
+        // Lookups extend the range of instruction account indexes but not program ids
+        let mut v0 = VersionedMessageV0 {
+            header: valid.header.clone(),
+            account_keys: valid.account_keys.clone(),
+            recent_blockhash: Blockhash::default(),
+            instructions: message.instructions.clone(),
+            address_table_lookups: vec![MessageAddressTableLookup::new(key(9), vec![0], vec![])],
+        };
+        assert_eq!(v0.sanitize(), Ok(()));
+        v0.instructions[0].program_id_index = 3;
+        assert_eq!(
+            v0.sanitize(),
+            Err(SanitizeError::ProgramIdOutOfRange {
+                instruction: 0,
+                index: 3
+            })
+        );
+        v0.address_table_lookups[0].writable_indexes.clear();
+        assert_eq!(
+            v0.sanitize(),
+            Err(SanitizeError::EmptyAddressLookup(key(9)))
+        );
+    }
 }
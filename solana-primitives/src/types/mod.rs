@@ -1,20 +1,61 @@
+use alloc::format;
+
 mod account;
+mod hash;
 pub mod instruction;
+mod key_index;
 mod message;
 mod pda;
 mod pubkey;
+mod sanitize;
+mod sanitized;
 mod signature;
+mod signing_request;
 mod transaction;
+mod transaction_error;
+mod transaction_request;
+mod units;
 
 pub use crate::error::{Result, SolanaError};
-pub use account::{AddressLookupTableAccount, MessageAddressTableLookup};
+use crate::short_vec::compact_u16_len;
+pub use account::{Account, AddressLookupTableAccount, LookupTableMeta, MessageAddressTableLookup};
+pub use hash::{Blockhash, Hash};
 pub use instruction::{AccountMeta, CompiledInstruction, Instruction};
+pub use key_index::KeyIndex;
+pub(crate) use message::message_body_size;
 pub use message::{LegacyMessage, Message, MessageHeader, VersionedMessage, VersionedMessageV0};
-pub use pda::{create_program_address, find_program_address};
+pub use pda::{create_program_address, find_program_address, find_program_address_many};
 pub use pubkey::Pubkey;
+pub use sanitize::SanitizeError;
+pub use sanitized::SanitizedTransaction;
 pub use signature::SignatureBytes;
-pub use transaction::{Transaction, VersionedTransaction};
+pub use signing_request::SigningRequest;
+pub use transaction::{SignerRequirement, Transaction, VersionedTransaction};
+pub use transaction_error::{InstructionError, TransactionError};
+pub use transaction_request::{TRANSACTION_REQUEST_VERSION, TransactionRequest};
+pub use units::{
+    ComputeUnits, LAMPORTS_PER_SOL, Lamports, MICRO_LAMPORTS_PER_LAMPORT, MicroLamports,
+    PrioritizationFee, SOL_DECIMALS, TokenAmount,
+};
 
 // Constants
 /// Maximum allowed size for a Solana transaction in bytes
 pub const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Maximum number of accounts a transaction may lock
+pub const MAX_TRANSACTION_ACCOUNTS: usize = 64;
+
+/// Wire size of a signature list
+pub(crate) fn signatures_size(num_signatures: usize) -> usize {
+    compact_u16_len(num_signatures) + num_signatures * 64
+}
+
+/// Reject a wire size above [`MAX_TRANSACTION_SIZE`]
+pub(crate) fn check_size(size: usize) -> Result<()> {
+    if size > MAX_TRANSACTION_SIZE {
+        return Err(SolanaError::SerializationError(format!(
+            "Transaction size {size} exceeds maximum of {MAX_TRANSACTION_SIZE} bytes"
+        )));
+    }
+    Ok(())
+}
@@ -1,5 +1,8 @@
 use crate::error::{Result, SolanaError};
 use crate::types::Pubkey;
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
 use ed25519_dalek::VerifyingKey;
 use sha2::{Digest, Sha256};
 
@@ -7,66 +10,68 @@ use sha2::{Digest, Sha256};
 pub const MAX_SEEDS: usize = 16;
 /// Maximum length of a seed in bytes
 pub const MAX_SEED_LEN: usize = 32;
+/// Domain separator appended after the program ID
+const PDA_MARKER: &[u8] = b"ProgramDerivedAddress";
+/// Fewest seed sets per thread before `find_program_address_many` goes parallel
+#[cfg(feature = "std")]
+const MIN_SEEDS_PER_THREAD: usize = 64;
 
 /// Find a program address and bump seed for the given seeds
 pub fn find_program_address(program_id: &Pubkey, seeds: &[&[u8]]) -> Result<(Pubkey, u8)> {
-    // The bump seed occupies one of the MAX_SEEDS slots.
-    if seeds.len() >= MAX_SEEDS {
-        return Err(SolanaError::InvalidPubkey(format!(
-            "too many seeds: {}, max: {}",
-            seeds.len(),
-            MAX_SEEDS
-        )));
-    }
-    for seed in seeds {
-        if seed.len() > MAX_SEED_LEN {
-            return Err(SolanaError::InvalidPubkey(format!(
-                "seed too long: {}, max: {}",
-                seed.len(),
-                MAX_SEED_LEN
-            )));
-        }
-    }
-
-    // Try each bump seed until we find a valid PDA
-    let mut bump = 255;
-    loop {
-        let mut hasher = Sha256::new();
-
-        // Hash all seeds
-        for seed in seeds {
-            hasher.update(seed);
-        }
-
-        // Add bump seed
-        hasher.update([bump]);
-
-        // Add program ID
-        hasher.update(program_id.as_bytes());
-
-        // Add "ProgramDerivedAddress" as a domain separator
-        hasher.update(b"ProgramDerivedAddress");
+    check_seeds(seeds)?;
 
-        // Get the hash result
-        let hash = hasher.finalize();
-
-        // Convert hash to pubkey
-        let mut pubkey_bytes = [0u8; 32];
-        pubkey_bytes.copy_from_slice(&hash[..32]);
-
-        // Check if it's on curve
+    // The seeds are the same for every bump, so hash them once and only
+    // rehash the bump, program ID and marker on each attempt
+    let prefix = seeds_hasher(seeds);
+    for bump in (0..=u8::MAX).rev() {
+        let pubkey_bytes = derive_address(&prefix, bump, program_id);
         if !is_on_curve(&pubkey_bytes) {
-            // Found a valid PDA
             return Ok((Pubkey::new(pubkey_bytes), bump));
         }
+    }
+    Err(SolanaError::InvalidPubkey(
+        "unable to find valid PDA, all bump seeds exhausted".to_string(),
+    ))
+}
 
-        if bump == 0 {
-            return Err(SolanaError::InvalidPubkey(
-                "unable to find valid PDA, all bump seeds exhausted".to_string(),
-            ));
+/// Find program addresses for many seed sets, e.g. the ATAs of thousands of wallets.
+///
+/// Results are in the order of `seeds`. With the `std` feature large batches are split
+/// across the available cores.
+pub fn find_program_address_many<'s, S>(
+    program_id: &Pubkey,
+    seeds: &[S],
+) -> Vec<Result<(Pubkey, u8)>>
+where
+    S: AsRef<[&'s [u8]]> + Sync,
+{
+    let find = |seeds: &S| find_program_address(program_id, seeds.as_ref());
+
+    #[cfg(feature = "std")]
+    {
+        let threads = std::thread::available_parallelism()
+            .map_or(1, usize::from)
+            .min(seeds.len() / MIN_SEEDS_PER_THREAD);
+        if threads > 1 {
+            let chunk_size = seeds.len().div_ceil(threads);
+            return std::thread::scope(|scope| {
+                let handles: Vec<_> = seeds
+                    .chunks(chunk_size)
+                    .map(|chunk| scope.spawn(move || chunk.iter().map(find).collect::<Vec<_>>()))
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                    })
+                    .collect()
+            });
         }
-        bump -= 1;
     }
+
+    seeds.iter().map(find).collect()
 }
 
 /// Create a program address from seeds and a bump seed
@@ -75,6 +80,19 @@ pub fn create_program_address(
     seeds: &[&[u8]],
     bump_seed: u8,
 ) -> Result<Pubkey> {
+    check_seeds(seeds)?;
+
+    let pubkey_bytes = derive_address(&seeds_hasher(seeds), bump_seed, program_id);
+    if is_on_curve(&pubkey_bytes) {
+        return Err(SolanaError::InvalidPubkey(
+            "resulting address is on curve (invalid PDA)".to_string(),
+        ));
+    }
+
+    Ok(Pubkey::new(pubkey_bytes))
+}
+
+fn check_seeds(seeds: &[&[u8]]) -> Result<()> {
     // The bump seed occupies one of the MAX_SEEDS slots.
     if seeds.len() >= MAX_SEEDS {
         return Err(SolanaError::InvalidPubkey(format!(
@@ -92,38 +110,25 @@ pub fn create_program_address(
             )));
         }
     }
+    Ok(())
+}
 
+/// Hasher state after absorbing the seeds
+fn seeds_hasher(seeds: &[&[u8]]) -> Sha256 {
     let mut hasher = Sha256::new();
-
-    // Hash all seeds
     for seed in seeds {
         hasher.update(seed);
     }
+    hasher
+}
 
-    // Add bump seed
-    hasher.update([bump_seed]);
-
-    // Add program ID
+/// SHA-256 of the seeds, bump, program ID and marker, continuing from `prefix`
+fn derive_address(prefix: &Sha256, bump: u8, program_id: &Pubkey) -> [u8; 32] {
+    let mut hasher = prefix.clone();
+    hasher.update([bump]);
     hasher.update(program_id.as_bytes());
-
-    // Add "ProgramDerivedAddress" as a domain separator
-    hasher.update(b"ProgramDerivedAddress");
-
-    // Get the hash result
-    let hash = hasher.finalize();
-
-    // Convert hash to pubkey
-    let mut pubkey_bytes = [0u8; 32];
-    pubkey_bytes.copy_from_slice(&hash[..32]);
-
-    // Check if it's on curve
-    if is_on_curve(&pubkey_bytes) {
-        return Err(SolanaError::InvalidPubkey(
-            "resulting address is on curve (invalid PDA)".to_string(),
-        ));
-    }
-
-    Ok(Pubkey::new(pubkey_bytes))
+    hasher.update(PDA_MARKER);
+    hasher.finalize().into()
 }
 
 /// Check if a public key is on the ed25519 curve
@@ -139,7 +144,7 @@ pub fn is_on_curve(bytes: &[u8; 32]) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::str::FromStr;
+    use core::str::FromStr;
 
     fn create_test_program_id() -> Pubkey {
         crate::instructions::program_ids::system_program()
@@ -222,6 +227,37 @@ mod tests {
         assert!(matches!(result, Err(SolanaError::InvalidPubkey(_))));
     }
 
+    #[test]
+    fn test_find_program_address_many_matches_single() {
+        let program_id = crate::instructions::program_ids::associated_token_program();
+        let token_program = crate::instructions::program_ids::token_program();
+        let wallets: Vec<[u8; 32]> = (0..300u32)
+            .map(|i| {
+                let mut bytes = [0u8; 32];
+                bytes[..4].copy_from_slice(&i.to_le_bytes());
+                bytes
+            })
+            .collect();
+        let mint = [9u8; 32];
+        let mut seeds: Vec<[&[u8]; 3]> = wallets
+            .iter()
+            .map(|wallet| [&wallet[..], token_program.as_bytes(), &mint[..]])
+            .collect();
+        let too_long = [0u8; MAX_SEED_LEN + 1];
+        seeds[150][1] = &too_long;
+
+        let results = find_program_address_many(&program_id, &seeds);
+        assert_eq!(results.len(), seeds.len());
+        for (seeds, result) in seeds.iter().zip(&results) {
+            match find_program_address(&program_id, seeds) {
+                Ok(expected) => assert_eq!(result.as_ref().unwrap(), &expected),
+                Err(_) => assert!(matches!(result, Err(SolanaError::InvalidPubkey(_)))),
+            }
+        }
+        assert!(results[150].is_err());
+        assert!(find_program_address_many::<[&[u8]; 1]>(&program_id, &[]).is_empty());
+    }
+
     #[test]
     fn test_create_program_address() {
         let program_id = create_test_program_id();
@@ -1,7 +1,11 @@
-use crate::{Result, SolanaError};
+use crate::crypto::verify_signature_strict;
+use crate::types::SignatureBytes;
+use crate::{Result, SolanaError, base58};
+use alloc::format;
+use alloc::string::{String, ToString};
 use borsh::{BorshDeserialize, BorshSerialize};
+use core::str::FromStr;
 use serde::{Deserialize, Serialize};
-use std::str::FromStr;
 
 /// A Solana public key (32 bytes)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize)]
@@ -16,19 +20,19 @@ impl FromStr for Pubkey {
 }
 
 impl Ord for Pubkey {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         self.0.cmp(&other.0)
     }
 }
 
 impl PartialOrd for Pubkey {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
 impl Serialize for Pubkey {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
@@ -37,7 +41,7 @@ impl Serialize for Pubkey {
 }
 
 impl<'de> Deserialize<'de> for Pubkey {
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
@@ -46,37 +50,84 @@ impl<'de> Deserialize<'de> for Pubkey {
     }
 }
 
-impl std::fmt::Display for Pubkey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Pubkey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.to_base58())
     }
 }
 
-impl Pubkey {
-    pub fn new(bytes: [u8; 32]) -> Self {
+impl From<[u8; 32]> for Pubkey {
+    fn from(bytes: [u8; 32]) -> Self {
         Self(bytes)
     }
+}
 
-    pub fn from_base58(s: &str) -> Result<Self> {
-        let bytes = bs58::decode(s)
-            .into_vec()
-            .map_err(|_| SolanaError::InvalidPubkey(format!("failed to decode base58: {}", s)))?;
+impl From<Pubkey> for [u8; 32] {
+    fn from(pubkey: Pubkey) -> Self {
+        pubkey.0
+    }
+}
+
+impl TryFrom<&[u8]> for Pubkey {
+    type Error = SolanaError;
 
-        if bytes.len() != 32 {
-            return Err(SolanaError::InvalidPubkey(format!(
-                "invalid length: {}, expected: 32",
-                bytes.len()
-            )));
-        }
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        bytes.try_into().map(Self).map_err(|_| {
+            SolanaError::InvalidPubkey(format!("invalid length: {}, expected: 32", bytes.len()))
+        })
+    }
+}
 
-        Ok(Self(bytes.try_into().unwrap()))
+impl AsRef<[u8]> for Pubkey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Declare a [`Pubkey`] from a base58 literal, decoded at compile time.
+///
+/// ```
+/// use solana_primitives::{Pubkey, pubkey};
+///
+/// const TOKEN_PROGRAM: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+/// assert_eq!(TOKEN_PROGRAM.to_base58(), "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+/// ```
+#[macro_export]
+macro_rules! pubkey {
+    ($address:expr) => {
+        const { $crate::Pubkey::from_str_const($address) }
+    };
+}
+
+impl Pubkey {
+    pub const fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Decode a base58 pubkey in a const context, panicking if it is invalid.
+    ///
+    /// Prefer [`pubkey!`](crate::pubkey!), which guarantees the decoding happens at compile time.
+    pub const fn from_str_const(s: &str) -> Self {
+        Self(base58::decode_array_const(s))
+    }
+
+    pub fn from_base58(s: &str) -> Result<Self> {
+        base58::decode_array(s)
+            .map(Self)
+            .map_err(|e| SolanaError::InvalidPubkey(e.to_string()))
     }
 
     pub fn to_base58(&self) -> String {
         bs58::encode(&self.0).into_string()
     }
 
-    pub fn as_bytes(&self) -> &[u8; 32] {
+    pub const fn as_bytes(&self) -> &[u8; 32] {
         &self.0
     }
+
+    /// Check `signature` is a valid signature of `message` by this key, rejecting
+    /// small-order keys and signature points as the runtime does
+    pub fn verify_strict(&self, message: &[u8], signature: &SignatureBytes) -> bool {
+        verify_signature_strict(self, message, signature)
+    }
 }
@@ -0,0 +1,91 @@
+use crate::types::{CompiledInstruction, MessageAddressTableLookup, MessageHeader, Pubkey};
+use alloc::collections::BTreeSet;
+use thiserror::Error;
+
+/// Why a message would be rejected by validators before execution
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SanitizeError {
+    #[error("message has no signers; the fee payer must sign")]
+    NoFeePayer,
+    #[error("fee payer is marked read-only")]
+    ReadonlyFeePayer,
+    #[error("header counts exceed the {0} account keys")]
+    HeaderCountsExceedKeys(usize),
+    #[error("message references {0} accounts, more than 256")]
+    TooManyAccounts(usize),
+    #[error("account key {0} appears more than once")]
+    DuplicateAccountKey(Pubkey),
+    #[error("address lookup for table {0} has no indexes")]
+    EmptyAddressLookup(Pubkey),
+    #[error("instruction {instruction} program id index {index} is out of range")]
+    ProgramIdOutOfRange { instruction: usize, index: u8 },
+    #[error("instruction {instruction} program id index {index} points at a signer")]
+    ProgramIdIsSigner { instruction: usize, index: u8 },
+    #[error("instruction {instruction} account index {index} is out of range")]
+    AccountIndexOutOfRange { instruction: usize, index: u8 },
+}
+
+/// Validate a message body the way validators do before loading accounts.
+///
+/// Program ids must be static keys; instruction accounts may also refer to addresses loaded
+/// through `address_table_lookups`.
+pub(crate) fn sanitize_message(
+    header: &MessageHeader,
+    account_keys: &[Pubkey],
+    instructions: &[CompiledInstruction],
+    address_table_lookups: &[MessageAddressTableLookup],
+) -> Result<(), SanitizeError> {
+    let num_signers = header.num_required_signatures as usize;
+    if num_signers == 0 {
+        return Err(SanitizeError::NoFeePayer);
+    }
+    if header.num_readonly_signed_accounts >= header.num_required_signatures {
+        return Err(SanitizeError::ReadonlyFeePayer);
+    }
+    if num_signers + header.num_readonly_unsigned_accounts as usize > account_keys.len() {
+        return Err(SanitizeError::HeaderCountsExceedKeys(account_keys.len()));
+    }
+
+    let mut num_accounts = account_keys.len();
+    for lookup in address_table_lookups {
+        if lookup.writable_indexes.is_empty() && lookup.readonly_indexes.is_empty() {
+            return Err(SanitizeError::EmptyAddressLookup(lookup.account_key));
+        }
+        num_accounts += lookup.writable_indexes.len() + lookup.readonly_indexes.len();
+    }
+    if num_accounts > u8::MAX as usize + 1 {
+        return Err(SanitizeError::TooManyAccounts(num_accounts));
+    }
+
+    let mut seen = BTreeSet::new();
+    if let Some(duplicate) = account_keys.iter().find(|key| !seen.insert(**key)) {
+        return Err(SanitizeError::DuplicateAccountKey(*duplicate));
+    }
+
+    for (instruction_index, instruction) in instructions.iter().enumerate() {
+        let program_id_index = instruction.program_id_index;
+        if program_id_index as usize >= account_keys.len() {
+            return Err(SanitizeError::ProgramIdOutOfRange {
+                instruction: instruction_index,
+                index: program_id_index,
+            });
+        }
+        if (program_id_index as usize) < num_signers {
+            return Err(SanitizeError::ProgramIdIsSigner {
+                instruction: instruction_index,
+                index: program_id_index,
+            });
+        }
+        if let Some(index) = instruction
+            .accounts
+            .iter()
+            .find(|index| **index as usize >= num_accounts)
+        {
+            return Err(SanitizeError::AccountIndexOutOfRange {
+                instruction: instruction_index,
+                index: *index,
+            });
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use crate::crypto::verify_transaction;
+use crate::types::{Message, Pubkey, SignatureBytes, Transaction};
+use crate::{Result, SolanaError};
+
+/// A transaction whose message passed sanitization and whose signatures all verified.
+///
+/// Only produced by [`Transaction::verify`], so code taking a `SanitizedTransaction` can skip
+/// both checks before simulating or replaying it.
+#[derive(Debug, Clone)]
+pub struct SanitizedTransaction {
+    transaction: Transaction,
+}
+
+impl SanitizedTransaction {
+    pub(crate) fn try_new(transaction: Transaction) -> Result<Self> {
+        transaction.message.sanitize()?;
+        verify_transaction(&transaction)?;
+        Ok(Self { transaction })
+    }
+
+    /// The verified transaction
+    pub fn transaction(&self) -> &Transaction {
+        &self.transaction
+    }
+
+    /// Give up the guarantees and take the transaction back
+    pub fn into_transaction(self) -> Transaction {
+        self.transaction
+    }
+
+    /// The sanitized message
+    pub fn message(&self) -> &Message {
+        &self.transaction.message
+    }
+
+    /// The fee payer's signature, which identifies the transaction
+    pub fn signature(&self) -> &SignatureBytes {
+        &self.transaction.signatures[0]
+    }
+
+    /// The fee payer, the first account key
+    pub fn fee_payer(&self) -> &Pubkey {
+        &self.transaction.message.account_keys[0]
+    }
+}
+
+impl AsRef<Transaction> for SanitizedTransaction {
+    fn as_ref(&self) -> &Transaction {
+        &self.transaction
+    }
+}
+
+impl From<SanitizedTransaction> for Transaction {
+    fn from(sanitized: SanitizedTransaction) -> Self {
+        sanitized.transaction
+    }
+}
+
+impl TryFrom<Transaction> for SanitizedTransaction {
+    type Error = SolanaError;
+
+    fn try_from(transaction: Transaction) -> Result<Self> {
+        Self::try_new(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::Keypair;
+    use crate::instructions::system::transfer;
+    use crate::types::{Pubkey, SanitizeError, Transaction};
+    use crate::{SolanaError, TransactionBuilder};
+
+    fn signed_transfer(payer: &Keypair) -> Transaction {
+        let mut builder = TransactionBuilder::new(payer.pubkey(), [7u8; 32]);
+        builder.add_instruction(transfer(&payer.pubkey(), &Pubkey::new([2u8; 32]), 1));
+        let mut transaction = builder.build().unwrap();
+        transaction.sign(&[payer]).unwrap();
+        transaction
+    }
+
+    #[test]
+    fn test_verify_produces_sanitized_transaction() {
+        let payer = Keypair::from_seed(&[1u8; 32]).unwrap();
+        let transaction = signed_transfer(&payer);
+        let signature = transaction.signatures[0];
+
+        let sanitized = transaction.verify().unwrap();
+        assert_eq!(sanitized.fee_payer(), &payer.pubkey());
+        assert_eq!(sanitized.signature(), &signature);
+        assert_eq!(sanitized.message().instructions.len(), 1);
+        assert_eq!(sanitized.into_transaction().signatures, vec![signature]);
+    }
+
+    #[test]
+    fn test_verify_rejects_bad_signature_and_message() {
+        let payer = Keypair::from_seed(&[1u8; 32]).unwrap();
+        let mut transaction = signed_transfer(&payer);
+        transaction.message.recent_blockhash = [8u8; 32].into();
+        assert!(matches!(
+            transaction.verify(),
+            Err(SolanaError::InvalidSignature(_))
+        ));
+
+        let mut transaction = signed_transfer(&payer);
+        transaction.message.header.num_readonly_signed_accounts = 1;
+        assert!(matches!(
+            transaction.verify(),
+            Err(SolanaError::SanitizeError(SanitizeError::ReadonlyFeePayer))
+        ));
+
+        let unsigned = Transaction::new(signed_transfer(&payer).message);
+        assert!(unsigned.verify().is_err());
+    }
+}
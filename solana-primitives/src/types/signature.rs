@@ -1,5 +1,12 @@
+use crate::base58;
+use crate::crypto::verify_signature_strict;
 use crate::error::{Result, SolanaError};
+use crate::types::Pubkey;
+use alloc::format;
+use alloc::string::{String, ToString};
 use borsh::{BorshDeserialize, BorshSerialize};
+use core::fmt;
+use core::str::FromStr;
 use serde::{Deserialize, Serialize};
 
 /// A 64-byte signature
@@ -20,18 +27,9 @@ impl SignatureBytes {
 
     /// Create a signature from a base58 string
     pub fn from_base58(s: &str) -> Result<Self> {
-        let bytes = bs58::decode(s).into_vec().map_err(|_| {
-            SolanaError::InvalidSignature(format!("failed to decode base58: {}", s))
-        })?;
-        if bytes.len() != 64 {
-            return Err(SolanaError::InvalidSignature(format!(
-                "invalid length: {}, expected: 64",
-                bytes.len()
-            )));
-        }
-        let mut result = [0; 64];
-        result.copy_from_slice(&bytes);
-        Ok(Self(result))
+        base58::decode_array(s)
+            .map(Self)
+            .map_err(|e| SolanaError::InvalidSignature(e.to_string()))
     }
 
     /// Convert the signature to a base58 string
@@ -43,10 +41,57 @@ impl SignatureBytes {
     pub fn as_bytes(&self) -> &[u8; 64] {
         &self.0
     }
+
+    /// Check this is a valid signature of `message` by `pubkey`, with the runtime's strict rules
+    pub fn verify(&self, pubkey: &Pubkey, message: &[u8]) -> bool {
+        verify_signature_strict(pubkey, message, self)
+    }
+}
+
+impl fmt::Display for SignatureBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_base58())
+    }
+}
+
+impl FromStr for SignatureBytes {
+    type Err = SolanaError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_base58(s)
+    }
+}
+
+impl From<[u8; 64]> for SignatureBytes {
+    fn from(bytes: [u8; 64]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<SignatureBytes> for [u8; 64] {
+    fn from(signature: SignatureBytes) -> Self {
+        signature.0
+    }
+}
+
+impl TryFrom<&[u8]> for SignatureBytes {
+    type Error = SolanaError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        bytes.try_into().map(Self).map_err(|_| {
+            SolanaError::InvalidSignature(format!("invalid length: {}, expected: 64", bytes.len()))
+        })
+    }
+}
+
+impl AsRef<[u8]> for SignatureBytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
 }
 
 impl Serialize for SignatureBytes {
-    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
@@ -55,7 +100,7 @@ impl Serialize for SignatureBytes {
 }
 
 impl<'de> Deserialize<'de> for SignatureBytes {
-    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
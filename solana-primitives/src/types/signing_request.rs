@@ -0,0 +1,127 @@
+use crate::crypto::Keypair;
+use crate::error::{Result, SolanaError};
+use crate::types::{Pubkey, SignatureBytes, Transaction, VersionedTransaction};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::{Deserialize, Serialize};
+
+/// The bytes an offline signer has to sign, separated from the transaction.
+///
+/// Export it as JSON to an airgapped machine, sign [`SigningRequest::message_bytes`] there
+/// with [`sign_message`](crate::sign_message) or [`SigningRequest::sign`], and bring each
+/// signature back with [`Transaction::add_signature_for`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SigningRequest {
+    /// Base64-encoded serialized message
+    pub message: String,
+    /// Signers the message requires, in signature slot order
+    pub required_signers: Vec<Pubkey>,
+}
+
+impl SigningRequest {
+    /// Create a request from serialized message bytes and the required signers
+    pub fn new(message: &[u8], required_signers: Vec<Pubkey>) -> Self {
+        Self {
+            message: STANDARD.encode(message),
+            required_signers,
+        }
+    }
+
+    /// Create a request for a legacy transaction
+    pub fn from_transaction(transaction: &Transaction) -> Result<Self> {
+        let message = transaction
+            .message
+            .serialize_for_signing()
+            .map_err(SolanaError::SerializationError)?;
+        let required = transaction.num_required_signatures() as usize;
+        let signers = transaction
+            .account_keys()
+            .get(..required)
+            .ok_or(SolanaError::InvalidTransaction)?;
+        Ok(Self::new(&message, signers.to_vec()))
+    }
+
+    /// Create a request for a versioned transaction
+    pub fn from_versioned(transaction: &VersionedTransaction) -> Result<Self> {
+        let required = transaction.num_required_signatures() as usize;
+        let signers = transaction
+            .account_keys()
+            .get(..required)
+            .ok_or(SolanaError::InvalidTransaction)?;
+        Ok(Self::new(
+            &transaction.serialize_message()?,
+            signers.to_vec(),
+        ))
+    }
+
+    /// Decode the message bytes to sign
+    pub fn message_bytes(&self) -> Result<Vec<u8>> {
+        STANDARD
+            .decode(&self.message)
+            .map_err(|e| SolanaError::DeserializationError(e.to_string()))
+    }
+
+    /// Sign the message, checking that the keypair is one of the required signers
+    pub fn sign(&self, keypair: &Keypair) -> Result<SignatureBytes> {
+        let pubkey = keypair.pubkey();
+        if !self.required_signers.contains(&pubkey) {
+            return Err(SolanaError::InvalidSignature(format!(
+                "{pubkey} is not a required signer"
+            )));
+        }
+        Ok(keypair.sign_message(&self.message_bytes()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::TransactionBuilder;
+    use crate::instructions::system::transfer;
+
+    #[test]
+    fn test_signing_request_roundtrip() {
+        let payer = Keypair::from_secret_key(&[1u8; 32]);
+        let cosigner = Keypair::from_secret_key(&[2u8; 32]);
+        let mut builder = TransactionBuilder::new(payer.pubkey(), [3u8; 32]);
+        builder.add_instruction(transfer(&payer.pubkey(), &Pubkey::new([4u8; 32]), 1));
+        builder.add_instruction(transfer(&cosigner.pubkey(), &Pubkey::new([4u8; 32]), 1));
+        let mut transaction = builder.build().unwrap();
+
+        let request = SigningRequest::from_transaction(&transaction).unwrap();
+        let json = serde_json::to_string(&request).unwrap();
+
+        // Airgapped side
+        let offline: SigningRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(offline.required_signers.len(), 2);
+        let cosigner_signature = offline.sign(&cosigner).unwrap();
+        let payer_signature = offline.sign(&payer).unwrap();
+        assert!(offline.sign(&Keypair::from_secret_key(&[9u8; 32])).is_err());
+
+        transaction
+            .add_signature_for(&cosigner.pubkey(), cosigner_signature)
+            .unwrap();
+        assert!(!transaction.is_signed());
+        transaction
+            .add_signature_for(&payer.pubkey(), payer_signature)
+            .unwrap();
+        assert!(transaction.is_signed());
+        assert!(crate::verify_transaction(&transaction).is_ok());
+
+        // Signatures must be over this message, by this signer
+        assert!(
+            transaction
+                .add_signature_for(&payer.pubkey(), cosigner_signature)
+                .is_err()
+        );
+        assert!(
+            transaction
+                .add_signature_for(&Pubkey::new([4u8; 32]), payer_signature)
+                .is_err()
+        );
+    }
+}
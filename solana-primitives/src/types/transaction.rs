@@ -1,16 +1,32 @@
 use crate::Result;
-use crate::crypto::sign_message;
+use crate::crypto::{Signer, verify_signature_strict, verify_signers};
+use crate::decode::{ParsedInstruction, decode_instruction};
 use crate::error::SolanaError;
-use crate::instructions::program_ids::COMPUTE_BUDGET_PROGRAM_ID;
+use crate::fee::calculate_fee;
+use crate::instructions::compute_budget::{
+    COMPUTE_UNIT_LIMIT_FIELD, COMPUTE_UNIT_PRICE_FIELD, parse_compute_unit_limit_data,
+    parse_compute_unit_price_data, set_compute_unit_limit, set_compute_unit_price,
+};
+use crate::instructions::program_ids::compute_budget_program;
+use crate::instructions::system::is_advance_nonce;
+use crate::short_vec::write_compact_u16;
+#[cfg(feature = "blake3")]
+use crate::types::Hash;
 use crate::types::{
-    CompiledInstruction, Instruction, LegacyMessage, MAX_TRANSACTION_SIZE, Message,
-    MessageAddressTableLookup, Pubkey, SignatureBytes, VersionedMessage, VersionedMessageV0,
+    AddressLookupTableAccount, Blockhash, CompiledInstruction, ComputeUnits, Instruction, KeyIndex,
+    LegacyMessage, Message, MessageAddressTableLookup, MicroLamports, Pubkey, SanitizedTransaction,
+    SignatureBytes, VersionedMessage, VersionedMessageV0, check_size, signatures_size,
 };
+use crate::wire::WireCursor;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::{format, vec};
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
 /// A Solana transaction
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Transaction {
     /// The signatures
     pub signatures: Vec<SignatureBytes>,
@@ -53,15 +69,42 @@ impl Transaction {
     }
 
     /// Get the recent blockhash
-    pub fn recent_blockhash(&self) -> &[u8; 32] {
+    pub fn recent_blockhash(&self) -> &Blockhash {
         &self.message.recent_blockhash
     }
 
+    /// Copy the transaction onto a new blockhash with every signature slot cleared
+    pub fn rebase(&self, recent_blockhash: impl Into<Blockhash>) -> Self {
+        let mut message = self.message.clone();
+        message.recent_blockhash = recent_blockhash.into();
+        let num_required = message.header.num_required_signatures as usize;
+        Self {
+            signatures: vec![SignatureBytes::new([0u8; 64]); num_required],
+            message,
+        }
+    }
+
+    /// Build a [`KeyIndex`] over the account keys
+    pub fn key_index(&self) -> KeyIndex<'_> {
+        self.message.key_index()
+    }
+
     /// Get the instructions
     pub fn instructions(&self) -> &[CompiledInstruction] {
         &self.message.instructions
     }
 
+    /// Total fee in lamports, including the compute budget priority fee
+    pub fn fee(&self, lamports_per_signature: u64) -> u64 {
+        calculate_fee(
+            self.account_keys(),
+            self.num_required_signatures(),
+            self.instructions(),
+            lamports_per_signature,
+        )
+        .total()
+    }
+
     /// Deserialize a transaction from bytes
     pub fn deserialize_with_version(bytes: &[u8]) -> Result<Self> {
         if bytes.is_empty() {
@@ -70,41 +113,11 @@ impl Transaction {
             ));
         }
 
-        // Signature count is shortvec-encoded
-        let (num_signatures, len_bytes_consumed) = crate::decode_compact_u16_len(bytes)
-            .map_err(|e| SolanaError::DeserializationError(e.to_string()))?;
-
-        // Check if there are enough bytes for signatures
-        if bytes.len() < len_bytes_consumed + (num_signatures * 64) {
-            return Err(SolanaError::DeserializationError(
-                "Not enough bytes for signatures".to_string(),
-            ));
-        }
-
-        // Extract signatures
-        let mut signatures = Vec::with_capacity(num_signatures);
-        let mut offset = len_bytes_consumed; // Skip shortvec length bytes
-
-        for _ in 0..num_signatures {
-            if offset + 64 > bytes.len() {
-                return Err(SolanaError::DeserializationError(
-                    "Invalid signature data".to_string(),
-                ));
-            }
-
-            let sig_bytes: [u8; 64] = bytes[offset..offset + 64].try_into().map_err(|_| {
-                SolanaError::DeserializationError("Failed to convert signature bytes".to_string())
-            })?;
-
-            signatures.push(SignatureBytes::new(sig_bytes));
-            offset += 64;
-        }
-
-        // The rest is the message
-        let message_bytes = &bytes[offset..];
+        let mut cursor = WireCursor::new(bytes);
+        let signatures = manual_decode::decode_signatures(&mut cursor)?;
 
         // Use our manual decoder to decode the legacy message
-        match manual_decode::decode_legacy_message(message_bytes, Vec::new()) {
+        match manual_decode::decode_legacy_message(&mut cursor, Vec::new()) {
             Ok(VersionedTransaction::Legacy { message, .. }) => {
                 // We know this is a legacy message, convert to regular Message
                 let regular_message = Message {
@@ -127,11 +140,10 @@ impl Transaction {
 
     /// Serializes the full transaction into the Solana legacy wire format.
     pub fn serialize_legacy(&self) -> Result<Vec<u8>> {
-        let mut tx_wire_bytes: Vec<u8> = Vec::new();
+        let mut tx_wire_bytes = Vec::with_capacity(self.serialized_size());
 
         // 1. Number of signatures (Compact-U16 encoded)
-        let sig_len_bytes = crate::encode_length_to_compact_u16_bytes(self.signatures.len())?;
-        tx_wire_bytes.extend_from_slice(&sig_len_bytes);
+        write_compact_u16(&mut tx_wire_bytes, self.signatures.len())?;
 
         // 2. Signatures
         for sig_bytes_wrapper in &self.signatures {
@@ -139,89 +151,61 @@ impl Transaction {
         }
 
         // 3. Serialized Message
-        // The `serialize_for_signing` method in `Message` returns Result<Vec<u8>, String>
-        let serialized_message = self
-            .message
-            .serialize_for_signing()
+        self.message
+            .write_for_signing(&mut tx_wire_bytes)
             .map_err(SolanaError::SerializationError)?;
-        tx_wire_bytes.extend_from_slice(&serialized_message);
 
         Ok(tx_wire_bytes)
     }
 
-    /// Sign the transaction with one or more private keys
-    /// The private keys must correspond to the signing accounts in the same order
-    pub fn sign(&mut self, private_keys: &[&[u8]]) -> Result<()> {
-        // Get message bytes for signing
-        let message_bytes = self
-            .message
-            .serialize_for_signing()
-            .map_err(SolanaError::SerializationError)?;
-
-        // Clear existing signatures
+    /// Sign the transaction with every required signer, in any order.
+    ///
+    /// Fails when a required signer is missing or a signer is not required.
+    pub fn sign(&mut self, signers: &[&dyn Signer]) -> Result<()> {
         self.signatures.clear();
-
-        // Get number of required signatures
-        let num_required_sigs = self.message.header.num_required_signatures as usize;
-
-        // Validate we have enough private keys
-        if private_keys.len() < num_required_sigs {
+        self.partial_sign(signers)?;
+        if !self.is_signed() {
             return Err(SolanaError::InvalidSignature(format!(
-                "insufficient private keys: {}, required: {}",
-                private_keys.len(),
-                num_required_sigs
+                "insufficient signers: {}, required: {}",
+                signers.len(),
+                self.num_required_signatures()
             )));
         }
-
-        // Sign with each private key
-        for private_key in private_keys.iter().take(num_required_sigs) {
-            let signature = sign_message(private_key, &message_bytes)?;
-            self.signatures.push(signature);
-        }
-
         Ok(())
     }
 
-    /// Partially sign the transaction with specific private keys
-    /// Updates only the signatures for the provided keys based on their public key positions
-    pub fn partial_sign(&mut self, private_keys: &[&[u8]], public_keys: &[Pubkey]) -> Result<()> {
-        if private_keys.len() != public_keys.len() {
-            return Err(SolanaError::InvalidSignature(format!(
-                "private keys count ({}) does not match public keys count ({})",
-                private_keys.len(),
-                public_keys.len()
-            )));
-        }
-
-        // Get message bytes for signing
+    /// Sign with a subset of the required signers, keeping other signature slots
+    pub fn partial_sign(&mut self, signers: &[&dyn Signer]) -> Result<()> {
         let message_bytes = self
             .message
             .serialize_for_signing()
             .map_err(SolanaError::SerializationError)?;
+        let num_required = self.num_required_signatures() as usize;
+        sign_slots(
+            &mut self.signatures,
+            &self.message.account_keys[..num_required.min(self.message.account_keys.len())],
+            &message_bytes,
+            signers,
+        )
+    }
 
-        // Ensure we have enough signature slots
-        let num_required_sigs = self.message.header.num_required_signatures as usize;
-        if self.signatures.len() < num_required_sigs {
-            self.signatures
-                .resize(num_required_sigs, SignatureBytes::new([0u8; 64]));
-        }
-
-        // Sign with each private key and place signature at correct index
-        for (private_key, public_key) in private_keys.iter().zip(public_keys.iter()) {
-            // Find the index of this public key in account_keys
-            if let Some(index) = self
-                .message
-                .account_keys
-                .iter()
-                .position(|k| k == public_key)
-                && index < num_required_sigs
-            {
-                let signature = sign_message(private_key, &message_bytes)?;
-                self.signatures[index] = signature;
-            }
-        }
-
-        Ok(())
+    /// Place a signature produced elsewhere, e.g. from a
+    /// [`SigningRequest`](crate::SigningRequest), in the slot of `pubkey`.
+    ///
+    /// Fails when `pubkey` is not a required signer or the signature does not verify.
+    pub fn add_signature_for(&mut self, pubkey: &Pubkey, signature: SignatureBytes) -> Result<()> {
+        let message_bytes = self
+            .message
+            .serialize_for_signing()
+            .map_err(SolanaError::SerializationError)?;
+        let num_required = self.num_required_signatures() as usize;
+        place_signature(
+            &mut self.signatures,
+            &self.message.account_keys[..num_required.min(self.message.account_keys.len())],
+            &message_bytes,
+            pubkey,
+            signature,
+        )
     }
 
     /// Check if the transaction has been signed by all required signers
@@ -241,24 +225,123 @@ impl Transaction {
         true
     }
 
+    /// Size of the legacy wire format, computed without serializing
+    pub fn serialized_size(&self) -> usize {
+        signatures_size(self.signatures.len()) + self.message.serialized_size()
+    }
+
     /// Validate transaction size is within limits (1232 bytes)
     pub fn validate_size(&self) -> Result<()> {
-        let serialized = self.serialize_legacy()?;
+        check_size(self.serialized_size())
+    }
 
-        if serialized.len() > MAX_TRANSACTION_SIZE {
-            return Err(SolanaError::SerializationError(format!(
-                "Transaction size {} exceeds maximum of {} bytes",
-                serialized.len(),
-                MAX_TRANSACTION_SIZE
-            )));
-        }
+    /// Sanitize the message and verify every signature
+    pub fn verify(self) -> Result<SanitizedTransaction> {
+        SanitizedTransaction::try_new(self)
+    }
 
-        Ok(())
+    /// Hash of the message, identical for every signing of the same message
+    #[cfg(feature = "blake3")]
+    pub fn message_hash(&self) -> Result<Hash> {
+        self.message.hash()
+    }
+
+    /// The fee payer's signature, which identifies the transaction on chain.
+    ///
+    /// `None` until the fee payer has signed.
+    pub fn id(&self) -> Option<&SignatureBytes> {
+        first_signature(&self.signatures)
+    }
+}
+
+fn first_signature(signatures: &[SignatureBytes]) -> Option<&SignatureBytes> {
+    signatures
+        .first()
+        .filter(|signature| signature.as_bytes().iter().any(|&b| b != 0))
+}
+
+/// A required signer slot of a transaction, for rendering signing prompts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignerRequirement {
+    /// Index of the signature slot
+    pub index: usize,
+    /// The signer's public key
+    pub pubkey: Pubkey,
+    /// Whether the signer's account is writable
+    pub is_writable: bool,
+    /// Whether a valid signature from this signer is already present
+    pub signed: bool,
+    /// Indexes of the instructions that reference this signer
+    pub instructions: Vec<usize>,
+}
+
+impl SignerRequirement {
+    /// Whether this signer pays the transaction fee
+    pub fn is_fee_payer(&self) -> bool {
+        self.index == 0
     }
 }
 
+/// Sign `message_bytes` with each signer into its slot among `signer_slots`, growing
+/// `signatures` to one slot per required signer
+fn sign_slots(
+    signatures: &mut Vec<SignatureBytes>,
+    signer_slots: &[Pubkey],
+    message_bytes: &[u8],
+    signers: &[&dyn Signer],
+) -> Result<()> {
+    let positions = signers
+        .iter()
+        .map(|signer| {
+            let pubkey = signer.pubkey();
+            signer_slots
+                .iter()
+                .position(|slot| *slot == pubkey)
+                .ok_or_else(|| {
+                    SolanaError::InvalidSignature(format!("{pubkey} is not a required signer"))
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if signatures.len() < signer_slots.len() {
+        signatures.resize(signer_slots.len(), SignatureBytes::new([0u8; 64]));
+    }
+    for (signer, index) in signers.iter().zip(positions) {
+        signatures[index] = signer.try_sign_message(message_bytes)?;
+    }
+    Ok(())
+}
+
+/// Verify `signature` over `message_bytes` and store it in the slot of `pubkey` among `signers`,
+/// growing `signatures` to one slot per signer
+fn place_signature(
+    signatures: &mut Vec<SignatureBytes>,
+    signers: &[Pubkey],
+    message_bytes: &[u8],
+    pubkey: &Pubkey,
+    signature: SignatureBytes,
+) -> Result<()> {
+    let index = signers
+        .iter()
+        .position(|signer| signer == pubkey)
+        .ok_or_else(|| {
+            SolanaError::InvalidSignature(format!("{pubkey} is not a required signer"))
+        })?;
+    if !verify_signature_strict(pubkey, message_bytes, &signature) {
+        return Err(SolanaError::InvalidSignature(format!(
+            "signature verification failed for {pubkey}"
+        )));
+    }
+    if signatures.len() < signers.len() {
+        signatures.resize(signers.len(), SignatureBytes::new([0u8; 64]));
+    }
+    signatures[index] = signature;
+    Ok(())
+}
+
 /// Versioned transaction format
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum VersionedTransaction {
     /// Legacy transaction format (pre-versioned transactions)
     Legacy {
@@ -331,14 +414,43 @@ impl VersionedTransaction {
         }
     }
 
+    /// Build a new [`KeyIndex`] over the static account keys
+    pub fn key_index(&self) -> KeyIndex<'_> {
+        KeyIndex::new(self.account_keys())
+    }
+
     /// Get the recent blockhash
-    pub fn recent_blockhash(&self) -> &[u8; 32] {
+    pub fn recent_blockhash(&self) -> &Blockhash {
         match self {
             Self::Legacy { message, .. } => &message.recent_blockhash,
             Self::V0 { message, .. } => &message.recent_blockhash,
         }
     }
 
+    /// Resolve the full account list that instruction account indexes refer to
+    pub fn resolve_addresses(
+        &self,
+        address_lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<Vec<Pubkey>> {
+        match self {
+            Self::Legacy { message, .. } => Ok(message.account_keys.clone()),
+            Self::V0 { message, .. } => message.resolve_addresses(address_lookup_tables),
+        }
+    }
+
+    /// Copy the transaction onto a new blockhash with every signature slot cleared
+    pub fn rebase(&self, recent_blockhash: impl Into<Blockhash>) -> Self {
+        let recent_blockhash = recent_blockhash.into();
+        let mut rebased = self.clone();
+        match &mut rebased {
+            Self::Legacy { message, .. } => message.recent_blockhash = recent_blockhash,
+            Self::V0 { message, .. } => message.recent_blockhash = recent_blockhash,
+        }
+        let num_required = rebased.num_required_signatures() as usize;
+        *rebased.signatures_mut() = vec![SignatureBytes::new([0u8; 64]); num_required];
+        rebased
+    }
+
     /// Get the instructions
     pub fn instructions(&self) -> &[CompiledInstruction] {
         match self {
@@ -347,6 +459,31 @@ impl VersionedTransaction {
         }
     }
 
+    /// Total fee in lamports, including the compute budget priority fee
+    pub fn fee(&self, lamports_per_signature: u64) -> u64 {
+        calculate_fee(
+            self.account_keys(),
+            self.num_required_signatures(),
+            self.instructions(),
+            lamports_per_signature,
+        )
+        .total()
+    }
+
+    /// Decode every instruction, with raw data for programs without a built-in parser
+    pub fn decode_instructions(&self) -> Result<Vec<ParsedInstruction>> {
+        let account_keys = self.account_keys();
+        self.instructions()
+            .iter()
+            .map(|instruction| {
+                let program_id = account_keys
+                    .get(instruction.program_id_index as usize)
+                    .ok_or(SolanaError::InvalidMessage)?;
+                Ok(decode_instruction(program_id, &instruction.data))
+            })
+            .collect()
+    }
+
     pub fn signatures(&self) -> &[SignatureBytes] {
         match self {
             Self::Legacy { signatures, .. } => signatures,
@@ -368,29 +505,131 @@ impl VersionedTransaction {
         }
     }
 
+    /// Sign the transaction with every required signer, in any order.
+    ///
+    /// Fails when a required signer is missing or a signer is not required.
+    pub fn sign(&mut self, signers: &[&dyn Signer]) -> Result<()> {
+        self.signatures_mut().clear();
+        self.partial_sign(signers)?;
+        if !self.is_signed() {
+            return Err(SolanaError::InvalidSignature(format!(
+                "insufficient signers: {}, required: {}",
+                signers.len(),
+                self.num_required_signatures()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sign with a subset of the required signers, keeping other signature slots
+    pub fn partial_sign(&mut self, signers: &[&dyn Signer]) -> Result<()> {
+        let message_bytes = self.serialize_message()?;
+        let num_required = self.num_required_signatures() as usize;
+        let slots = self.account_keys()[..num_required.min(self.account_keys().len())].to_vec();
+        sign_slots(self.signatures_mut(), &slots, &message_bytes, signers)
+    }
+
+    /// Check if the transaction has a non-empty signature for every required signer
+    pub fn is_signed(&self) -> bool {
+        let num_required = self.num_required_signatures() as usize;
+        let signatures = self.signatures();
+        signatures.len() >= num_required
+            && signatures[..num_required]
+                .iter()
+                .all(|signature| signature.as_bytes().iter().any(|&b| b != 0))
+    }
+
+    /// Place a signature produced elsewhere, e.g. from a
+    /// [`SigningRequest`](crate::SigningRequest), in the slot of `pubkey`.
+    ///
+    /// Fails when `pubkey` is not a required signer or the signature does not verify.
+    pub fn add_signature_for(&mut self, pubkey: &Pubkey, signature: SignatureBytes) -> Result<()> {
+        let message_bytes = self.serialize_message()?;
+        let num_required = self.num_required_signatures() as usize;
+        let signers = self.account_keys()[..num_required.min(self.account_keys().len())].to_vec();
+        place_signature(
+            self.signatures_mut(),
+            &signers,
+            &message_bytes,
+            pubkey,
+            signature,
+        )
+    }
+
+    /// Verify that every required signature is present and valid
+    pub fn verify_signatures(&self) -> Result<()> {
+        verify_signers(
+            self.signatures(),
+            self.account_keys(),
+            self.num_required_signatures() as usize,
+            &self.serialize_message()?,
+        )
+    }
+
+    /// Verify every signature, then resolve the full account list against the lookup tables
+    pub fn verify_and_resolve(
+        &self,
+        address_lookup_tables: &[AddressLookupTableAccount],
+    ) -> Result<Vec<Pubkey>> {
+        self.verify_signatures()?;
+        self.resolve_addresses(address_lookup_tables)
+    }
+
+    /// List every required signer slot with its signature status and referencing instructions
+    pub fn signing_requirements(&self) -> Result<Vec<SignerRequirement>> {
+        let message_bytes = self.serialize_message()?;
+        let required = self.num_required_signatures() as usize;
+        let writable_signers =
+            required.saturating_sub(self.num_readonly_signed_accounts() as usize);
+        let signatures = self.signatures();
+
+        Ok(self
+            .account_keys()
+            .iter()
+            .take(required)
+            .enumerate()
+            .map(|(index, pubkey)| SignerRequirement {
+                index,
+                pubkey: *pubkey,
+                is_writable: index < writable_signers,
+                signed: signatures.get(index).is_some_and(|signature| {
+                    verify_signature_strict(pubkey, &message_bytes, signature)
+                }),
+                instructions: self
+                    .instructions()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, ix)| {
+                        ix.program_id_index as usize == index
+                            || ix.accounts.iter().any(|account| *account as usize == index)
+                    })
+                    .map(|(position, _)| position)
+                    .collect(),
+            })
+            .collect())
+    }
+
     fn compute_budget_program_index(&self) -> Option<u8> {
-        let cb_pubkey = Pubkey::from_base58(COMPUTE_BUDGET_PROGRAM_ID).ok()?;
+        let cb_pubkey = compute_budget_program();
         self.account_keys()
             .iter()
             .position(|k| *k == cb_pubkey)
             .map(|i| i as u8)
     }
 
-    pub fn get_compute_unit_price(&self) -> Option<u64> {
+    pub fn get_compute_unit_price(&self) -> Option<MicroLamports> {
         let idx = self.compute_budget_program_index()?;
-        for ix in self.instructions() {
-            if ix.program_id_index == idx && ix.data.len() == 9 && ix.data[0] == 3 {
-                return Some(u64::from_le_bytes(ix.data[1..9].try_into().ok()?));
-            }
-        }
-        None
+        self.instructions()
+            .iter()
+            .filter(|ix| ix.program_id_index == idx)
+            .find_map(|ix| parse_compute_unit_price_data(&ix.data))
     }
 
-    pub fn set_compute_unit_price(&mut self, micro_lamports: u64) -> Result<bool> {
+    pub fn set_compute_unit_price(&mut self, price: MicroLamports) -> Result<bool> {
         if let Some(idx) = self.compute_budget_program_index() {
             for ix in self.instructions_mut() {
-                if ix.program_id_index == idx && ix.data.len() == 9 && ix.data[0] == 3 {
-                    ix.data[1..9].copy_from_slice(&micro_lamports.to_le_bytes());
+                if ix.program_id_index == idx && parse_compute_unit_price_data(&ix.data).is_some() {
+                    COMPUTE_UNIT_PRICE_FIELD.write(&mut ix.data, price.get())?;
                     return Ok(true);
                 }
             }
@@ -398,21 +637,19 @@ impl VersionedTransaction {
         Ok(false)
     }
 
-    pub fn get_compute_unit_limit(&self) -> Option<u32> {
+    pub fn get_compute_unit_limit(&self) -> Option<ComputeUnits> {
         let idx = self.compute_budget_program_index()?;
-        for ix in self.instructions() {
-            if ix.program_id_index == idx && ix.data.len() == 5 && ix.data[0] == 2 {
-                return Some(u32::from_le_bytes(ix.data[1..5].try_into().ok()?));
-            }
-        }
-        None
+        self.instructions()
+            .iter()
+            .filter(|ix| ix.program_id_index == idx)
+            .find_map(|ix| parse_compute_unit_limit_data(&ix.data))
     }
 
-    pub fn set_compute_unit_limit(&mut self, units: u32) -> Result<bool> {
+    pub fn set_compute_unit_limit(&mut self, units: ComputeUnits) -> Result<bool> {
         if let Some(idx) = self.compute_budget_program_index() {
             for ix in self.instructions_mut() {
-                if ix.program_id_index == idx && ix.data.len() == 5 && ix.data[0] == 2 {
-                    ix.data[1..5].copy_from_slice(&units.to_le_bytes());
+                if ix.program_id_index == idx && parse_compute_unit_limit_data(&ix.data).is_some() {
+                    COMPUTE_UNIT_LIMIT_FIELD.write(&mut ix.data, units.get())?;
                     return Ok(true);
                 }
             }
@@ -420,6 +657,110 @@ impl VersionedTransaction {
         Ok(false)
     }
 
+    /// Set the compute unit price, inserting a Compute Budget instruction when none exists.
+    ///
+    /// Returns `true` when a new instruction was inserted. Existing signatures are not
+    /// cleared and must be refreshed after this call.
+    pub fn upsert_compute_unit_price(&mut self, price: MicroLamports) -> Result<bool> {
+        if self.set_compute_unit_price(price)? {
+            return Ok(false);
+        }
+        self.insert_compute_budget_instruction(set_compute_unit_price(price).data)?;
+        Ok(true)
+    }
+
+    /// Set the compute unit limit, inserting a Compute Budget instruction when none exists.
+    ///
+    /// Returns `true` when a new instruction was inserted. Existing signatures are not
+    /// cleared and must be refreshed after this call.
+    pub fn upsert_compute_unit_limit(&mut self, units: ComputeUnits) -> Result<bool> {
+        if self.set_compute_unit_limit(units)? {
+            return Ok(false);
+        }
+        self.insert_compute_budget_instruction(set_compute_unit_limit(units).data)?;
+        Ok(true)
+    }
+
+    /// Prepend a Compute Budget instruction, after `AdvanceNonceAccount` in durable nonce
+    /// transactions, adding the program as a read-only static key when missing. Indexes into
+    /// lookup-table addresses shift past the new static key.
+    fn insert_compute_budget_instruction(&mut self, data: Vec<u8>) -> Result<()> {
+        let program_id_index = match self.compute_budget_program_index() {
+            Some(index) => index,
+            None => {
+                let cb_pubkey = compute_budget_program();
+                let (header, account_keys, instructions, num_loaded) = match self {
+                    Self::Legacy { message, .. } => (
+                        &mut message.header,
+                        &mut message.account_keys,
+                        &mut message.instructions,
+                        0,
+                    ),
+                    Self::V0 { message, .. } => (
+                        &mut message.header,
+                        &mut message.account_keys,
+                        &mut message.instructions,
+                        message
+                            .address_table_lookups
+                            .iter()
+                            .map(|lookup| {
+                                lookup.writable_indexes.len() + lookup.readonly_indexes.len()
+                            })
+                            .sum(),
+                    ),
+                };
+                let num_accounts = account_keys.len() + num_loaded;
+                if num_accounts > u8::MAX as usize {
+                    return Err(SolanaError::GenericError(format!(
+                        "cannot add the compute budget program to a message with {num_accounts} accounts, maximum is 256"
+                    )));
+                }
+                if instructions
+                    .iter()
+                    .flat_map(|ix| &ix.accounts)
+                    .any(|&account| account as usize >= num_accounts)
+                {
+                    return Err(SolanaError::InvalidMessage);
+                }
+                let num_readonly_unsigned_accounts = header
+                    .num_readonly_unsigned_accounts
+                    .checked_add(1)
+                    .ok_or_else(|| {
+                        SolanaError::GenericError(
+                            "too many readonly unsigned accounts to add the compute budget program"
+                                .to_string(),
+                        )
+                    })?;
+
+                // Loaded accounts follow the static keys, so their indexes shift by one
+                let index = account_keys.len() as u8;
+                for ix in instructions.iter_mut() {
+                    for account in ix.accounts.iter_mut().filter(|a| **a >= index) {
+                        *account += 1;
+                    }
+                }
+                account_keys.push(cb_pubkey);
+                header.num_readonly_unsigned_accounts = num_readonly_unsigned_accounts;
+                index
+            }
+        };
+        // Durable nonce transactions must keep AdvanceNonceAccount as instruction 0
+        let position = usize::from(
+            self.instructions()
+                .first()
+                .is_some_and(|first| is_advance_nonce(self.account_keys(), first)),
+        );
+        self.instructions_mut().insert(
+            position,
+            CompiledInstruction {
+                program_id_index,
+                accounts: Vec::new(),
+                data,
+            },
+        );
+        Ok(())
+    }
+
     pub fn add_instruction(&mut self, instruction: Instruction) -> Result<()> {
         let message = match self {
             Self::Legacy { message, .. } => message,
@@ -478,21 +819,12 @@ impl VersionedTransaction {
         }
         message.header.num_readonly_unsigned_accounts += new_readonly_non_signers.len() as u8;
 
-        let program_id_index = message
-            .account_keys
-            .iter()
-            .position(|k| *k == instruction.program_id)
-            .unwrap() as u8;
+        let key_index = message.key_index();
+        let program_id_index = key_index.position(&instruction.program_id).unwrap() as u8;
         let accounts: Vec<u8> = instruction
             .accounts
             .iter()
-            .map(|meta| {
-                message
-                    .account_keys
-                    .iter()
-                    .position(|k| *k == meta.pubkey)
-                    .unwrap() as u8
-            })
+            .map(|meta| key_index.position(&meta.pubkey).unwrap() as u8)
             .collect();
 
         message.instructions.push(CompiledInstruction {
@@ -504,6 +836,28 @@ impl VersionedTransaction {
         Ok(())
     }
 
+    /// Size of [`Self::serialize`] output, computed without serializing
+    pub fn serialized_size(&self) -> usize {
+        let message_size = match self {
+            Self::Legacy { message, .. } => message.serialized_size(),
+            Self::V0 { message, .. } => message.serialized_size(),
+        };
+        signatures_size(self.signatures().len()) + message_size
+    }
+
+    /// Hash of the message, identical for every signing of the same message
+    #[cfg(feature = "blake3")]
+    pub fn message_hash(&self) -> Result<Hash> {
+        Ok(Message::hash_raw_message(&self.serialize_message()?))
+    }
+
+    /// The fee payer's signature, which identifies the transaction on chain.
+    ///
+    /// `None` until the fee payer has signed.
+    pub fn id(&self) -> Option<&SignatureBytes> {
+        first_signature(self.signatures())
+    }
+
     pub fn serialize_message(&self) -> Result<Vec<u8>> {
         match self {
             Self::Legacy { message, .. } => message
@@ -516,16 +870,17 @@ impl VersionedTransaction {
     }
 
     pub fn serialize(&self) -> Result<Vec<u8>> {
-        let mut bytes = Vec::new();
+        let mut bytes = Vec::with_capacity(self.serialized_size());
         let signatures = self.signatures();
-        let sig_len = crate::encode_length_to_compact_u16_bytes(signatures.len())
-            .map_err(SolanaError::SerializationError)?;
-        bytes.extend_from_slice(&sig_len);
+        write_compact_u16(&mut bytes, signatures.len()).map_err(SolanaError::SerializationError)?;
         for sig in signatures {
             bytes.extend_from_slice(sig.as_bytes());
         }
-        let message_bytes = self.serialize_message()?;
-        bytes.extend_from_slice(&message_bytes);
+        match self {
+            Self::Legacy { message, .. } => message.write_for_signing(&mut bytes),
+            Self::V0 { message, .. } => message.write_for_signing(&mut bytes),
+        }
+        .map_err(SolanaError::SerializationError)?;
         Ok(bytes)
     }
 
@@ -537,41 +892,9 @@ impl VersionedTransaction {
             ));
         }
 
-        // Signature count is shortvec-encoded
-        let (num_signatures, len_bytes_consumed) = crate::decode_compact_u16_len(bytes)
-            .map_err(|e| SolanaError::DeserializationError(e.to_string()))?;
-
-        // Check if there are enough bytes for signatures
-        if bytes.len() < len_bytes_consumed + (num_signatures * 64) {
-            return Err(SolanaError::DeserializationError(
-                "Not enough bytes for signatures".to_string(),
-            ));
-        }
-
-        // Extract signatures
-        let mut signatures = Vec::with_capacity(num_signatures);
-        let mut offset = len_bytes_consumed; // Skip shortvec length bytes
-
-        for _ in 0..num_signatures {
-            if offset + 64 > bytes.len() {
-                return Err(SolanaError::DeserializationError(
-                    "Invalid signature data".to_string(),
-                ));
-            }
-
-            let sig_bytes: [u8; 64] = bytes[offset..offset + 64].try_into().map_err(|_| {
-                SolanaError::DeserializationError("Failed to convert signature bytes".to_string())
-            })?;
-
-            signatures.push(SignatureBytes::new(sig_bytes));
-            offset += 64;
-        }
-
-        // The rest is the message
-        let message_bytes = &bytes[offset..];
-
-        // Manually decode the message
-        self::manual_decode::decode_message(message_bytes, signatures)
+        let mut cursor = WireCursor::new(bytes);
+        let signatures = manual_decode::decode_signatures(&mut cursor)?;
+        manual_decode::decode_message(&mut cursor, signatures)
     }
 }
 
@@ -579,212 +902,105 @@ impl VersionedTransaction {
 mod manual_decode {
     use super::*;
     use crate::types::MessageHeader;
-
-    /// Validates each header count against its own section, not just the total length.
-    fn validate_header_counts(header: &MessageHeader, account_keys_len: usize) -> Result<()> {
-        let num_required_signatures = header.num_required_signatures as usize;
-        if num_required_signatures > account_keys_len {
-            return Err(SolanaError::DeserializationError(
-                "Message header num_required_signatures exceeds account_keys length".to_string(),
-            ));
-        }
-        if header.num_readonly_signed_accounts as usize > num_required_signatures {
-            return Err(SolanaError::DeserializationError(
-                "Message header num_readonly_signed_accounts exceeds num_required_signatures"
-                    .to_string(),
-            ));
-        }
-        if header.num_readonly_unsigned_accounts as usize
-            > account_keys_len - num_required_signatures
-        {
-            return Err(SolanaError::DeserializationError(
-                "Message header num_readonly_unsigned_accounts exceeds the number of unsigned accounts"
-                    .to_string(),
-            ));
-        }
-        Ok(())
+    use crate::wire::{InstructionField, LookupField, WireCursor, WireField, check_header_counts};
+
+    /// Decode the shortvec-prefixed signature list at the start of a wire transaction.
+    ///
+    /// Validates the total length up front, then copies each signature straight into an
+    /// exactly sized vector.
+    pub fn decode_signatures(cursor: &mut WireCursor<'_>) -> Result<Vec<SignatureBytes>> {
+        let num_signatures = cursor.read_compact_u16(WireField::SignatureCount)?;
+        let signature_bytes = cursor.read_bytes(num_signatures * 64, WireField::Signature(0))?;
+        Ok(signature_bytes
+            .chunks_exact(64)
+            .map(|chunk| SignatureBytes::new(chunk.try_into().unwrap()))
+            .collect())
     }
 
-    /// Decode a message based on the Solana binary format
-    /// The format is:
-    /// 1. If the high bit of the first byte is set, it's a versioned message
-    ///    - The version is in the lower 7 bits
-    ///    - Rest of message follows based on version
-    /// 2. Otherwise, it's a legacy message with format:
-    ///    - 3 bytes header (num_required_signatures, num_readonly_signed, num_readonly_unsigned)
-    ///    - Account keys (1 byte count, then count * 32 bytes)
-    ///    - Recent blockhash (32 bytes)
-    ///    - Instructions (1 byte count, then variable length instructions)
+    /// Decode a message based on the Solana binary format.
+    ///
+    /// If the high bit of the first byte is set, it's a versioned message with the version
+    /// in the lower 7 bits; otherwise it's a legacy message.
     pub fn decode_message(
-        bytes: &[u8],
+        cursor: &mut WireCursor<'_>,
         signatures: Vec<SignatureBytes>,
     ) -> Result<VersionedTransaction> {
-        if bytes.len() < 3 {
-            return Err(SolanaError::DeserializationError(
-                "Message bytes too short, need at least 3 bytes for header".to_string(),
-            ));
-        }
-
-        // Check if this is a versioned message (first bit set)
-        let is_versioned = (bytes[0] & 0x80) != 0;
-
-        if is_versioned {
-            // Extract version from first byte (low 7 bits)
-            let version = bytes[0] & 0x7F;
-
-            // Currently only V0 messages are supported
-            if version == 0 {
-                decode_v0_message(&bytes[1..], signatures)
-            } else {
-                Err(SolanaError::DeserializationError(format!(
-                    "Unsupported message version: {version}"
-                )))
+        match cursor.peek() {
+            Some(prefix) if prefix & 0x80 != 0 => {
+                let version = prefix & 0x7F;
+                // Currently only V0 messages are supported
+                if version != 0 {
+                    return Err(cursor
+                        .error(
+                            WireField::VersionPrefix,
+                            "version 0",
+                            format!("version {version}"),
+                        )
+                        .into());
+                }
+                cursor.read_u8(WireField::VersionPrefix)?;
+                decode_v0_message(cursor, signatures)
             }
-        } else {
-            // Legacy message (no version byte)
-            decode_legacy_message(bytes, signatures)
+            _ => decode_legacy_message(cursor, signatures),
         }
     }
 
-    /// Decode a legacy (non-versioned) message
-    /// The format is:
-    /// 1. Header (3 bytes)
-    ///    - num_required_signatures (1 byte)
-    ///    - num_readonly_signed_accounts (1 byte)
-    ///    - num_readonly_unsigned_accounts (1 byte)
-    /// 2. Account keys
-    ///    - count (1 byte)
-    ///    - public keys (count * 32 bytes)
-    /// 3. Recent blockhash (32 bytes)
-    /// 4. Instructions
-    ///    - count (1 byte)
-    ///    - instructions (variable length)
-    pub fn decode_legacy_message(
-        bytes: &[u8],
-        signatures: Vec<SignatureBytes>,
-    ) -> Result<VersionedTransaction> {
-        if bytes.len() < 3 {
-            return Err(SolanaError::DeserializationError(
-                "Legacy message too short".to_string(),
-            ));
-        }
-
-        // Header: 3 bytes
+    /// Decode the header, account keys, blockhash and instructions shared by every version
+    fn decode_message_body(
+        cursor: &mut WireCursor<'_>,
+    ) -> Result<(
+        MessageHeader,
+        Vec<Pubkey>,
+        Blockhash,
+        Vec<CompiledInstruction>,
+    )> {
+        let header: [u8; 3] = cursor.read_array(WireField::Header)?;
+        let account_count = cursor.read_compact_u16(WireField::AccountKeyCount)?;
+        let key_bytes = cursor.read_bytes(account_count * 32, WireField::AccountKey(0))?;
+        let account_keys: Vec<Pubkey> = key_bytes
+            .chunks_exact(32)
+            .map(|chunk| Pubkey::new(chunk.try_into().unwrap()))
+            .collect();
+        check_header_counts(header, account_keys.len())
+            .map_err(|expected| cursor.error(WireField::Header, expected, format!("{header:?}")))?;
+        let [
+            num_required_signatures,
+            num_readonly_signed_accounts,
+            num_readonly_unsigned_accounts,
+        ] = header;
         let header = MessageHeader {
-            num_required_signatures: bytes[0],
-            num_readonly_signed_accounts: bytes[1],
-            num_readonly_unsigned_accounts: bytes[2],
+            num_required_signatures,
+            num_readonly_signed_accounts,
+            num_readonly_unsigned_accounts,
         };
 
-        let mut offset = 3;
-
-        // Account keys
-        if offset >= bytes.len() {
-            return Err(SolanaError::DeserializationError(
-                "Message too short: no account count".to_string(),
-            ));
-        }
-        let (account_count, len_bytes_consumed) =
-            crate::decode_compact_u16_len(&bytes[offset..]).map_err(SolanaError::from)?;
-        offset += len_bytes_consumed;
-
-        if offset + (account_count * 32) > bytes.len() {
-            return Err(SolanaError::DeserializationError(
-                "Message too short: not enough bytes for accounts".to_string(),
-            ));
-        }
-
-        let mut account_keys = Vec::with_capacity(account_count);
-        for _ in 0..account_count {
-            let mut key = [0u8; 32];
-            key.copy_from_slice(&bytes[offset..offset + 32]);
-            account_keys.push(Pubkey::new(key));
-            offset += 32;
-        }
-
-        validate_header_counts(&header, account_keys.len())?;
-
-        // Recent blockhash (always 32 bytes)
-        if offset + 32 > bytes.len() {
-            return Err(SolanaError::DeserializationError(
-                "Message too short: no recent blockhash".to_string(),
-            ));
-        }
-        let mut recent_blockhash = [0u8; 32];
-        recent_blockhash.copy_from_slice(&bytes[offset..offset + 32]);
-        offset += 32;
-
-        // Instructions
-        if offset >= bytes.len() {
-            return Err(SolanaError::DeserializationError(
-                "Message too short: no instruction count".to_string(),
-            ));
-        }
-        let (instruction_count, len_bytes_consumed) =
-            crate::decode_compact_u16_len(&bytes[offset..]).map_err(SolanaError::from)?;
-        offset += len_bytes_consumed;
+        let recent_blockhash = Blockhash::new(cursor.read_array(WireField::RecentBlockhash)?);
 
+        let instruction_count = cursor.read_compact_u16(WireField::InstructionCount)?;
         // Each instruction needs >= 3 bytes; reject counts that can't fit in what's left.
-        let remaining = bytes.len().saturating_sub(offset);
+        let remaining = cursor.remaining().len();
         if instruction_count.saturating_mul(3) > remaining {
-            return Err(SolanaError::DeserializationError(
-                "Message too short: instruction count exceeds remaining bytes".to_string(),
-            ));
+            return Err(cursor
+                .error(
+                    WireField::InstructionCount,
+                    format!("at most {} instructions", remaining / 3),
+                    instruction_count.to_string(),
+                )
+                .into());
         }
 
         let mut instructions = Vec::with_capacity(instruction_count);
-        for _ in 0..instruction_count {
-            if offset >= bytes.len() {
-                return Err(SolanaError::DeserializationError(
-                    "Message too short: incomplete instruction".to_string(),
-                ));
-            }
-
-            // Program ID index (1 byte)
-            let program_id_index = bytes[offset];
-            offset += 1;
-
-            if offset >= bytes.len() {
-                return Err(SolanaError::DeserializationError(
-                    "Message too short: no account indices count".to_string(),
-                ));
-            }
-
-            // Account indices (compact-u16 length, then count bytes)
-            let (account_indices_count, len_bytes_consumed) =
-                crate::decode_compact_u16_len(&bytes[offset..]).map_err(SolanaError::from)?;
-            offset += len_bytes_consumed;
-
-            if offset + account_indices_count > bytes.len() {
-                return Err(SolanaError::DeserializationError(
-                    "Message too short: not enough account indices".to_string(),
-                ));
-            }
-
-            let accounts = bytes[offset..offset + account_indices_count].to_vec();
-            offset += account_indices_count;
-
-            if offset >= bytes.len() {
-                // This check ensures there's at least one byte for the length itself.
-                return Err(SolanaError::DeserializationError(
-                    "Message too short: no instruction data length".to_string(),
-                ));
-            }
-
-            // Instruction data (compact-u16 length, then length bytes)
-            let (data_length, len_bytes_consumed) =
-                crate::decode_compact_u16_len(&bytes[offset..]).map_err(SolanaError::from)?;
-            offset += len_bytes_consumed;
-
-            if offset + data_length > bytes.len() {
-                return Err(SolanaError::DeserializationError(
-                    "Message too short: not enough instruction data".to_string(),
-                ));
-            }
-
-            let data = bytes[offset..offset + data_length].to_vec();
-            offset += data_length;
-
+        for index in 0..instruction_count {
+            let field = |part| WireField::Instruction(index, part);
+            let program_id_index = cursor.read_u8(field(InstructionField::ProgramIdIndex))?;
+            let account_count = cursor.read_compact_u16(field(InstructionField::AccountCount))?;
+            let accounts = cursor
+                .read_bytes(account_count, field(InstructionField::Accounts))?
+                .to_vec();
+            let data_length = cursor.read_compact_u16(field(InstructionField::DataLength))?;
+            let data = cursor
+                .read_bytes(data_length, field(InstructionField::Data))?
+                .to_vec();
             instructions.push(CompiledInstruction {
                 program_id_index,
                 accounts,
@@ -792,6 +1008,16 @@ mod manual_decode {
             });
         }
 
+        Ok((header, account_keys, recent_blockhash, instructions))
+    }
+
+    /// Decode a legacy (non-versioned) message: header, account keys, recent blockhash and
+    /// instructions.
+    pub fn decode_legacy_message(
+        cursor: &mut WireCursor<'_>,
+        signatures: Vec<SignatureBytes>,
+    ) -> Result<VersionedTransaction> {
+        let (header, account_keys, recent_blockhash, instructions) = decode_message_body(cursor)?;
         Ok(VersionedTransaction::Legacy {
             signatures,
             message: LegacyMessage {
@@ -803,210 +1029,32 @@ mod manual_decode {
         })
     }
 
-    /// Decode a V0 versioned message
-    /// V0 messages support address lookup tables
+    /// Decode a V0 message (after the version prefix), which adds address table lookups
     pub fn decode_v0_message(
-        bytes: &[u8],
+        cursor: &mut WireCursor<'_>,
         signatures: Vec<SignatureBytes>,
     ) -> Result<VersionedTransaction> {
-        if bytes.len() < 3 {
-            return Err(SolanaError::DeserializationError(
-                "V0 message too short".to_string(),
-            ));
-        }
-
-        // Header: 3 bytes
-        let header = MessageHeader {
-            num_required_signatures: bytes[0],
-            num_readonly_signed_accounts: bytes[1],
-            num_readonly_unsigned_accounts: bytes[2],
-        };
-
-        let mut offset = 3;
+        let (header, account_keys, recent_blockhash, instructions) = decode_message_body(cursor)?;
 
-        // Account keys
-        if offset >= bytes.len() {
-            return Err(SolanaError::DeserializationError(
-                "Message too short: no account count".to_string(),
-            ));
-        }
-        let (account_count, len_bytes_consumed) =
-            crate::decode_compact_u16_len(&bytes[offset..]).map_err(SolanaError::from)?;
-        offset += len_bytes_consumed;
-
-        if offset + (account_count * 32) > bytes.len() {
-            return Err(SolanaError::DeserializationError(
-                "Message too short: not enough bytes for accounts".to_string(),
-            ));
-        }
-
-        let mut account_keys = Vec::with_capacity(account_count);
-        for _ in 0..account_count {
-            let mut key = [0u8; 32];
-            key.copy_from_slice(&bytes[offset..offset + 32]);
-            account_keys.push(Pubkey::new(key));
-            offset += 32;
-        }
-
-        validate_header_counts(&header, account_keys.len())?;
-
-        // Recent blockhash (always 32 bytes)
-        if offset + 32 > bytes.len() {
-            return Err(SolanaError::DeserializationError(
-                "Message too short: no recent blockhash".to_string(),
-            ));
-        }
-        let mut recent_blockhash = [0u8; 32];
-        recent_blockhash.copy_from_slice(&bytes[offset..offset + 32]);
-        offset += 32;
-
-        // Instructions
-        if offset >= bytes.len() {
-            return Err(SolanaError::DeserializationError(
-                "Message too short: no instruction count".to_string(),
-            ));
-        }
-        let (instruction_count, len_bytes_consumed) =
-            crate::decode_compact_u16_len(&bytes[offset..]).map_err(SolanaError::from)?;
-        offset += len_bytes_consumed;
-
-        // Each instruction needs >= 3 bytes; reject counts that can't fit in what's left.
-        let remaining = bytes.len().saturating_sub(offset);
-        if instruction_count.saturating_mul(3) > remaining {
-            return Err(SolanaError::DeserializationError(
-                "Message too short: instruction count exceeds remaining bytes".to_string(),
-            ));
-        }
-
-        let mut instructions = Vec::with_capacity(instruction_count);
-        for _ in 0..instruction_count {
-            if offset >= bytes.len() {
-                return Err(SolanaError::DeserializationError(
-                    "Message too short: incomplete instruction".to_string(),
-                ));
-            }
-
-            // Program ID index (1 byte)
-            let program_id_index = bytes[offset];
-            offset += 1;
-
-            if offset >= bytes.len() {
-                return Err(SolanaError::DeserializationError(
-                    "Message too short: no account indices count".to_string(),
-                ));
-            }
-
-            // Account indices (compact-u16 length, then count bytes)
-            let (account_indices_count, len_bytes_consumed) =
-                crate::decode_compact_u16_len(&bytes[offset..]).map_err(SolanaError::from)?;
-            offset += len_bytes_consumed;
-
-            if offset + account_indices_count > bytes.len() {
-                return Err(SolanaError::DeserializationError(
-                    "Message too short: not enough account indices".to_string(),
-                ));
-            }
-
-            let accounts = bytes[offset..offset + account_indices_count].to_vec();
-            offset += account_indices_count;
-
-            if offset >= bytes.len() {
-                // This check ensures there's at least one byte for the length itself.
-                return Err(SolanaError::DeserializationError(
-                    "Message too short: no instruction data length".to_string(),
-                ));
-            }
-
-            // Instruction data (compact-u16 length, then length bytes)
-            let (data_length, len_bytes_consumed) =
-                crate::decode_compact_u16_len(&bytes[offset..]).map_err(SolanaError::from)?;
-            offset += len_bytes_consumed;
-
-            if offset + data_length > bytes.len() {
-                return Err(SolanaError::DeserializationError(
-                    "Message too short: not enough instruction data".to_string(),
-                ));
-            }
-
-            let data = bytes[offset..offset + data_length].to_vec();
-            offset += data_length;
-
-            instructions.push(CompiledInstruction {
-                program_id_index,
-                accounts,
-                data,
-            });
-        }
-
-        // Address table lookups (new in V0)
+        // The lookup section is required, even when empty, as in the runtime
         let mut address_table_lookups = Vec::new();
-
-        // Check if we have more data (for address table lookups)
-        if offset < bytes.len() {
-            let (lookup_table_count, len_bytes_consumed) =
-                crate::decode_compact_u16_len(&bytes[offset..])
-                    .map_err(|e| SolanaError::DeserializationError(e.to_string()))?;
-            offset += len_bytes_consumed;
-
-            for _ in 0..lookup_table_count {
-                if offset + 32 > bytes.len() {
-                    return Err(SolanaError::DeserializationError(
-                        "Message too short: incomplete address lookup table".to_string(),
-                    ));
-                }
-
-                // Lookup table account key
-                let mut key = [0u8; 32];
-                key.copy_from_slice(&bytes[offset..offset + 32]);
-                let lookup_table_key = Pubkey::new(key);
-                offset += 32;
-
-                // Writable indexes
-                if offset >= bytes.len() {
-                    return Err(SolanaError::DeserializationError(
-                        "Message too short: no writable indexes count".to_string(),
-                    ));
-                }
-                let (writable_indexes_count, len_bytes_consumed) =
-                    crate::decode_compact_u16_len(&bytes[offset..])
-                        .map_err(|e| SolanaError::DeserializationError(e.to_string()))?;
-                offset += len_bytes_consumed;
-
-                if offset + writable_indexes_count > bytes.len() {
-                    return Err(SolanaError::DeserializationError(
-                        "Message too short: not enough writable indexes".to_string(),
-                    ));
-                }
-
-                let writable_indexes = bytes[offset..offset + writable_indexes_count].to_vec();
-                offset += writable_indexes_count;
-
-                // Readonly indexes
-                if offset >= bytes.len() {
-                    return Err(SolanaError::DeserializationError(
-                        "Message too short: no readonly indexes count".to_string(),
-                    ));
-                }
-                let (readonly_indexes_count, len_bytes_consumed) =
-                    crate::decode_compact_u16_len(&bytes[offset..])
-                        .map_err(|e| SolanaError::DeserializationError(e.to_string()))?;
-                offset += len_bytes_consumed;
-
-                if offset + readonly_indexes_count > bytes.len() {
-                    return Err(SolanaError::DeserializationError(
-                        "Message too short: not enough readonly indexes".to_string(),
-                    ));
-                }
-
-                let readonly_indexes = bytes[offset..offset + readonly_indexes_count].to_vec();
-                offset += readonly_indexes_count;
-
-                address_table_lookups.push(MessageAddressTableLookup {
-                    account_key: lookup_table_key,
-                    writable_indexes,
-                    readonly_indexes,
-                });
-            }
+        let lookup_count = cursor.read_compact_u16(WireField::LookupCount)?;
+        for index in 0..lookup_count {
+            let field = |part| WireField::Lookup(index, part);
+            let account_key = Pubkey::new(cursor.read_array(field(LookupField::AccountKey))?);
+            let writable_count = cursor.read_compact_u16(field(LookupField::WritableCount))?;
+            let writable_indexes = cursor
+                .read_bytes(writable_count, field(LookupField::WritableIndexes))?
+                .to_vec();
+            let readonly_count = cursor.read_compact_u16(field(LookupField::ReadonlyCount))?;
+            let readonly_indexes = cursor
+                .read_bytes(readonly_count, field(LookupField::ReadonlyIndexes))?
+                .to_vec();
+            address_table_lookups.push(MessageAddressTableLookup {
+                account_key,
+                writable_indexes,
+                readonly_indexes,
+            });
         }
 
         Ok(VersionedTransaction::V0 {
@@ -1026,8 +1074,9 @@ mod manual_decode {
 mod tests {
     use super::*;
     use crate::{
+        crypto::{Keypair, sign_message},
         instructions::system,
-        types::{Pubkey, SignatureBytes},
+        types::{MessageHeader, Pubkey, SignatureBytes},
     };
     use base64::{Engine, engine::general_purpose::STANDARD};
 
@@ -1063,6 +1112,21 @@ mod tests {
         assert_eq!(tx.signatures().len(), 1);
     }
 
+    #[test]
+    fn decode_instructions_of_legacy() {
+        let parsed = decode_legacy_tx().decode_instructions().unwrap();
+        assert_eq!(parsed.len(), 7);
+        assert_eq!(
+            parsed[1],
+            ParsedInstruction::ComputeBudget(
+                crate::instructions::compute_budget::ComputeBudgetInstruction::SetComputeUnitPrice {
+                    micro_lamports: 70_000
+                }
+            )
+        );
+        assert!(parsed.iter().any(|ix| !ix.is_known()));
+    }
+
     #[test]
     fn signatures_accessors() {
         let mut tx = decode_legacy_tx();
@@ -1075,36 +1139,161 @@ mod tests {
 
     #[test]
     fn get_compute_unit_price_from_legacy() {
-        assert_eq!(decode_legacy_tx().get_compute_unit_price(), Some(70_000));
+        assert_eq!(
+            decode_legacy_tx().get_compute_unit_price(),
+            Some(MicroLamports(70_000))
+        );
     }
 
     #[test]
     fn get_compute_unit_price_from_v0() {
-        assert_eq!(decode_mayan_tx().get_compute_unit_price(), Some(71_428));
+        assert_eq!(
+            decode_mayan_tx().get_compute_unit_price(),
+            Some(MicroLamports(71_428))
+        );
     }
 
     #[test]
     fn set_compute_unit_price_legacy() {
         let mut tx = decode_legacy_tx();
-        assert!(tx.set_compute_unit_price(999_999).unwrap());
-        assert_eq!(tx.get_compute_unit_price(), Some(999_999));
+        assert!(tx.set_compute_unit_price(MicroLamports(999_999)).unwrap());
+        assert_eq!(tx.get_compute_unit_price(), Some(MicroLamports(999_999)));
     }
 
     #[test]
     fn get_compute_unit_limit_from_legacy() {
-        assert_eq!(decode_legacy_tx().get_compute_unit_limit(), Some(420_000));
+        assert_eq!(
+            decode_legacy_tx().get_compute_unit_limit(),
+            Some(ComputeUnits(420_000))
+        );
     }
 
     #[test]
     fn get_compute_unit_limit_from_v0() {
-        assert_eq!(decode_mayan_tx().get_compute_unit_limit(), Some(475_676));
+        assert_eq!(
+            decode_mayan_tx().get_compute_unit_limit(),
+            Some(ComputeUnits(475_676))
+        );
     }
 
     #[test]
     fn set_compute_unit_limit_legacy() {
         let mut tx = decode_legacy_tx();
-        assert!(tx.set_compute_unit_limit(500_000).unwrap());
-        assert_eq!(tx.get_compute_unit_limit(), Some(500_000));
+        assert!(tx.set_compute_unit_limit(ComputeUnits(500_000)).unwrap());
+        assert_eq!(tx.get_compute_unit_limit(), Some(ComputeUnits(500_000)));
+    }
+
+    #[test]
+    fn upsert_compute_unit_price_updates_existing() {
+        let mut tx = decode_legacy_tx();
+        let count = tx.instructions().len();
+        assert!(!tx.upsert_compute_unit_price(MicroLamports(5)).unwrap());
+        assert_eq!(tx.instructions().len(), count);
+        assert_eq!(tx.get_compute_unit_price(), Some(MicroLamports(5)));
+    }
+
+    #[test]
+    fn upsert_compute_budget_inserts_into_legacy() {
+        let payer = Pubkey::new([1; 32]);
+        let to = Pubkey::new([2; 32]);
+        let mut builder = crate::builder::TransactionBuilder::new(payer, [0; 32]);
+        builder.add_instruction(system::transfer(&payer, &to, 1));
+        let legacy = builder.build().unwrap().serialize_legacy().unwrap();
+        let mut tx = VersionedTransaction::deserialize_with_version(&legacy).unwrap();
+
+        assert!(tx.upsert_compute_unit_price(MicroLamports(7)).unwrap());
+        assert!(tx.upsert_compute_unit_limit(ComputeUnits(300)).unwrap());
+        assert_eq!(tx.get_compute_unit_price(), Some(MicroLamports(7)));
+        assert_eq!(tx.get_compute_unit_limit(), Some(ComputeUnits(300)));
+        assert_eq!(tx.instructions().len(), 3);
+        assert_eq!(tx.num_readonly_unsigned_accounts(), 2);
+
+        let decoded = VersionedTransaction::deserialize_with_version(&tx.serialize().unwrap());
+        assert_eq!(decoded.unwrap().instructions().len(), 3);
+    }
+
+    #[test]
+    fn upsert_compute_budget_keeps_nonce_instruction_first() {
+        let payer = Pubkey::new([1u8; 32]);
+        let mut builder = crate::builder::TransactionBuilder::new(payer, [0u8; 32]);
+        builder.add_instruction(system::transfer(&payer, &Pubkey::new([2u8; 32]), 1));
+        builder.with_durable_nonce(Pubkey::new([3u8; 32]), payer, [4u8; 32]);
+        let mut tx = builder.build_v0(&[]).unwrap();
+
+        assert!(tx.upsert_compute_unit_price(MicroLamports(10)).unwrap());
+        assert!(tx.upsert_compute_unit_limit(ComputeUnits(50_000)).unwrap());
+        assert!(is_advance_nonce(tx.account_keys(), &tx.instructions()[0]));
+        let compute_budget = tx.compute_budget_program_index();
+        assert!(
+            tx.instructions()[1..3]
+                .iter()
+                .all(|ix| Some(ix.program_id_index) == compute_budget)
+        );
+        assert_eq!(tx.get_compute_unit_price(), Some(MicroLamports(10)));
+        assert_eq!(tx.get_compute_unit_limit(), Some(ComputeUnits(50_000)));
+        if let VersionedTransaction::V0 { message, .. } = &tx {
+            assert!(message.sanitize().is_ok());
+        }
+    }
+
+    #[test]
+    fn upsert_compute_budget_rejects_full_messages() {
+        let full =
+            |num_keys: usize, num_readonly_unsigned_accounts: u8| VersionedTransaction::Legacy {
+                signatures: vec![SignatureBytes::default()],
+                message: LegacyMessage {
+                    header: MessageHeader {
+                        num_required_signatures: 1,
+                        num_readonly_signed_accounts: 0,
+                        num_readonly_unsigned_accounts,
+                    },
+                    account_keys: (0..num_keys)
+                        .map(|i| {
+                            let mut key = [0u8; 32];
+                            key[..2].copy_from_slice(&(i as u16).to_le_bytes());
+                            Pubkey::new(key)
+                        })
+                        .collect(),
+                    recent_blockhash: Blockhash::default(),
+                    instructions: vec![],
+                },
+            };
+
+        let mut tx = full(256, 0);
+        assert!(tx.upsert_compute_unit_limit(ComputeUnits(1_000)).is_err());
+        assert_eq!(tx.account_keys().len(), 256);
+        assert!(tx.instructions().is_empty());
+
+        // A malformed header can't overflow its readonly count either
+        let mut tx = full(10, 255);
+        assert!(tx.upsert_compute_unit_price(MicroLamports(1)).is_err());
+        let mut tx = full(100, 99);
+        assert!(tx.upsert_compute_unit_price(MicroLamports(1)).is_ok());
+        assert_eq!(tx.num_readonly_unsigned_accounts(), 100);
+    }
+
+    #[test]
+    fn upsert_compute_budget_shifts_lookup_indexes_in_v0() {
+        let payer = Pubkey::new([1; 32]);
+        let looked_up = Pubkey::new([3; 32]);
+        let table = AddressLookupTableAccount::new(Pubkey::new([4; 32]), vec![looked_up]);
+        let mut builder = crate::builder::TransactionBuilder::new(payer, [0; 32]);
+        builder.add_instruction(system::transfer(&payer, &looked_up, 1));
+        let mut tx = builder.build_v0(core::slice::from_ref(&table)).unwrap();
+        let resolve_transfer = |tx: &VersionedTransaction| -> Vec<Pubkey> {
+            let keys = tx.resolve_addresses(core::slice::from_ref(&table)).unwrap();
+            let transfer = tx.instructions().last().unwrap();
+            transfer
+                .accounts
+                .iter()
+                .map(|a| keys[*a as usize])
+                .collect()
+        };
+        assert_eq!(resolve_transfer(&tx), vec![payer, looked_up]);
+
+        assert!(tx.upsert_compute_unit_limit(ComputeUnits(1_000)).unwrap());
+        assert_eq!(resolve_transfer(&tx), vec![payer, looked_up]);
+        assert_eq!(tx.get_compute_unit_limit(), Some(ComputeUnits(1_000)));
     }
 
     #[test]
@@ -1138,17 +1327,36 @@ mod tests {
     fn legacy_message_prefix(header: [u8; 3], num_accounts: u8) -> Vec<u8> {
         let mut bytes = header.to_vec();
         bytes.push(num_accounts);
-        bytes.extend(std::iter::repeat_n(0u8, 32 * num_accounts as usize));
+        bytes.extend(core::iter::repeat_n(0u8, 32 * num_accounts as usize));
         bytes.extend_from_slice(&[0u8; 32]);
         bytes
     }
 
+    #[test]
+    fn decode_signatures_validates_length_up_front() {
+        let mut bytes = vec![2u8];
+        bytes.extend_from_slice(&[1u8; 64]);
+        bytes.extend_from_slice(&[2u8; 64]);
+        bytes.push(0xFF);
+
+        let mut cursor = WireCursor::new(&bytes);
+        let signatures = manual_decode::decode_signatures(&mut cursor).unwrap();
+        assert_eq!(signatures.len(), 2);
+        assert_eq!(signatures.capacity(), 2);
+        assert_eq!(signatures[1], SignatureBytes::new([2u8; 64]));
+        assert_eq!(cursor.offset(), 129);
+
+        assert!(manual_decode::decode_signatures(&mut WireCursor::new(&bytes[..128])).is_err());
+        assert!(VersionedTransaction::deserialize_with_version(&bytes[..128]).is_err());
+        assert!(Transaction::deserialize_with_version(&bytes[..128]).is_err());
+    }
+
     #[test]
     fn decode_legacy_message_rejects_huge_instruction_count() {
         let mut bytes = legacy_message_prefix([1, 0, 0], 1);
         bytes.extend_from_slice(&crate::encode_length_to_compact_u16_bytes(60_000).unwrap());
 
-        let result = manual_decode::decode_legacy_message(&bytes, Vec::new());
+        let result = manual_decode::decode_legacy_message(&mut WireCursor::new(&bytes), Vec::new());
         assert!(
             result.is_err(),
             "huge instruction count exceeding remaining bytes must be rejected"
@@ -1166,7 +1374,7 @@ mod tests {
         let mut bytes = legacy_message_prefix([1, 0, 5], 1);
         bytes.push(0); // instruction count = 0
 
-        let result = manual_decode::decode_legacy_message(&bytes, Vec::new());
+        let result = manual_decode::decode_legacy_message(&mut WireCursor::new(&bytes), Vec::new());
         assert!(
             result.is_err(),
             "header counts inconsistent with account_keys length must be rejected at parse time"
@@ -1179,7 +1387,7 @@ mod tests {
         let mut bytes = legacy_message_prefix([1, 0, 2], 2);
         bytes.push(0); // instruction count = 0
 
-        let result = manual_decode::decode_legacy_message(&bytes, Vec::new());
+        let result = manual_decode::decode_legacy_message(&mut WireCursor::new(&bytes), Vec::new());
         assert!(
             result.is_err(),
             "num_readonly_unsigned_accounts exceeding the unsigned section must be rejected"
@@ -1192,7 +1400,7 @@ mod tests {
         let mut bytes = legacy_message_prefix([1, 2, 0], 2);
         bytes.push(0); // instruction count = 0
 
-        let result = manual_decode::decode_legacy_message(&bytes, Vec::new());
+        let result = manual_decode::decode_legacy_message(&mut WireCursor::new(&bytes), Vec::new());
         assert!(
             result.is_err(),
             "num_readonly_signed_accounts exceeding num_required_signatures must be rejected"
@@ -1206,13 +1414,45 @@ mod tests {
         bytes.push(0); // instruction count = 0
         bytes.push(0); // address table lookup count = 0
 
-        let result = manual_decode::decode_v0_message(&bytes, Vec::new());
+        let result = manual_decode::decode_v0_message(&mut WireCursor::new(&bytes), Vec::new());
         assert!(
             result.is_err(),
             "num_readonly_unsigned_accounts exceeding the unsigned section must be rejected in V0 too"
         );
     }
 
+    #[test]
+    fn decode_v0_message_requires_lookup_section() {
+        let mut bytes = legacy_message_prefix([1, 0, 0], 1);
+        bytes.push(0); // instruction count = 0
+        let result = manual_decode::decode_v0_message(&mut WireCursor::new(&bytes), Vec::new());
+        assert!(
+            result.is_err(),
+            "the lookup count is required, as in the runtime"
+        );
+
+        bytes.push(0); // address table lookup count = 0
+        let result = manual_decode::decode_v0_message(&mut WireCursor::new(&bytes), Vec::new());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn deserialize_reports_offset_of_malformed_field() {
+        // No signatures, then a legacy message cut off inside the recent blockhash
+        let mut bytes = vec![0u8];
+        bytes.extend_from_slice(&legacy_message_prefix([1, 0, 0], 1)[..40]);
+
+        let Err(SolanaError::WireError(error)) =
+            VersionedTransaction::deserialize_with_version(&bytes)
+        else {
+            panic!("expected a wire error");
+        };
+        assert_eq!(error.offset, 37);
+        assert_eq!(error.field, crate::wire::WireField::RecentBlockhash);
+        assert_eq!(error.expected, "32 bytes");
+        assert_eq!(error.found, "4 bytes");
+    }
+
     #[test]
     fn serialize_roundtrip_legacy() {
         let data = STANDARD.decode(LEGACY_TX).unwrap();
@@ -1223,8 +1463,8 @@ mod tests {
 
         let tx2 = VersionedTransaction::deserialize_with_version(&reserialized).unwrap();
         assert!(matches!(tx2, VersionedTransaction::Legacy { .. }));
-        assert_eq!(tx2.get_compute_unit_price(), Some(70_000));
-        assert_eq!(tx2.get_compute_unit_limit(), Some(420_000));
+        assert_eq!(tx2.get_compute_unit_price(), Some(MicroLamports(70_000)));
+        assert_eq!(tx2.get_compute_unit_limit(), Some(ComputeUnits(420_000)));
     }
 
     #[test]
@@ -1237,8 +1477,8 @@ mod tests {
 
         let tx2 = VersionedTransaction::deserialize_with_version(&reserialized).unwrap();
         assert!(matches!(tx2, VersionedTransaction::V0 { .. }));
-        assert_eq!(tx2.get_compute_unit_price(), Some(71_428));
-        assert_eq!(tx2.get_compute_unit_limit(), Some(475_676));
+        assert_eq!(tx2.get_compute_unit_price(), Some(MicroLamports(71_428)));
+        assert_eq!(tx2.get_compute_unit_limit(), Some(ComputeUnits(475_676)));
     }
 
     #[test]
@@ -1255,4 +1495,165 @@ mod tests {
         assert_eq!(deserialized.signatures()[0], sig);
         assert_ne!(deserialized.signatures()[0], SignatureBytes::default());
     }
+
+    #[test]
+    fn signing_requirements_report_status_and_instructions() {
+        let payer = Keypair::from_secret_key(&[1u8; 32]);
+        let owner = Keypair::from_secret_key(&[2u8; 32]);
+        let recipient = Pubkey::new([3u8; 32]);
+
+        let mut builder = crate::builder::TransactionBuilder::new(payer.pubkey(), [4u8; 32]);
+        builder.add_instruction(system::transfer(&payer.pubkey(), &recipient, 10));
+        builder.add_instruction(system::transfer(&owner.pubkey(), &recipient, 20));
+        let mut tx = builder.build_v0(&[]).unwrap();
+
+        let message_bytes = tx.serialize_message().unwrap();
+        tx.signatures_mut()[1] = owner.sign_message(&message_bytes);
+
+        let requirements = tx.signing_requirements().unwrap();
+        assert_eq!(requirements.len(), 2);
+
+        assert_eq!(requirements[0].pubkey, payer.pubkey());
+        assert!(requirements[0].is_fee_payer());
+        assert!(requirements[0].is_writable);
+        assert!(!requirements[0].signed);
+        assert_eq!(requirements[0].instructions, vec![0]);
+
+        assert_eq!(requirements[1].pubkey, owner.pubkey());
+        assert!(!requirements[1].is_fee_payer());
+        assert!(requirements[1].signed);
+        assert_eq!(requirements[1].instructions, vec![1]);
+
+        // A signature over different bytes is not counted
+        tx.signatures_mut()[0] = payer.sign_message(b"something else");
+        assert!(!tx.signing_requirements().unwrap()[0].signed);
+    }
+
+    #[test]
+    fn sign_parity_for_legacy_and_v0() {
+        let payer = Keypair::from_secret_key(&[1u8; 32]);
+        let owner = Keypair::from_secret_key(&[2u8; 32]);
+        let recipient = Pubkey::new([3u8; 32]);
+
+        let mut builder = crate::builder::TransactionBuilder::new(payer.pubkey(), [4u8; 32]);
+        builder.add_instruction(system::transfer(&owner.pubkey(), &recipient, 20));
+        let v0 = builder.build_v0(&[]).unwrap();
+        let VersionedTransaction::V0 { message, .. } = &v0 else {
+            unreachable!()
+        };
+        let legacy = VersionedTransaction::new(VersionedMessage::Legacy(LegacyMessage {
+            header: message.header.clone(),
+            account_keys: message.account_keys.clone(),
+            recent_blockhash: message.recent_blockhash,
+            instructions: message.instructions.clone(),
+        }));
+
+        for mut tx in [legacy, v0] {
+            assert!(!tx.is_signed());
+            assert!(tx.verify_signatures().is_err());
+
+            tx.partial_sign(&[&owner]).unwrap();
+            assert_eq!(tx.signatures().len(), 2);
+            assert!(!tx.is_signed());
+            assert!(tx.signing_requirements().unwrap()[1].signed);
+
+            tx.partial_sign(&[&payer]).unwrap();
+            assert!(tx.is_signed());
+            tx.verify_signatures().unwrap();
+
+            tx.sign(&[&payer, &owner]).unwrap();
+            tx.verify_signatures().unwrap();
+
+            // Signers are placed by pubkey, not by argument order
+            tx.sign(&[&owner, &payer]).unwrap();
+            tx.verify_signatures().unwrap();
+
+            assert!(tx.sign(&[&payer]).is_err());
+            assert!(
+                tx.partial_sign(&[&Keypair::from_secret_key(&[9u8; 32])])
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn rebase_clears_signatures_and_keeps_message() {
+        let mut tx = decode_mayan_tx();
+        tx.signatures_mut()[0] = SignatureBytes::new([1u8; 64]);
+        assert!(tx.is_signed());
+
+        let rebased = tx.rebase([7u8; 32]);
+        assert_eq!(rebased.recent_blockhash(), &Blockhash::new([7u8; 32]));
+        assert_eq!(rebased.signatures(), &[SignatureBytes::new([0u8; 64])]);
+        assert!(!rebased.is_signed());
+        assert_eq!(rebased.account_keys(), tx.account_keys());
+        assert_eq!(rebased.instructions().len(), tx.instructions().len());
+        assert_eq!(
+            rebased.get_compute_unit_price(),
+            tx.get_compute_unit_price()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "blake3")]
+    fn message_hash_ignores_signatures_and_id_is_first_signature() {
+        let mayan = decode_mayan_tx();
+        assert_eq!(
+            mayan.id().unwrap().to_base58(),
+            "2DZEgrPpdwCu2JcQZJCFivcmLSNMHMmDth9ujqFFZ8UeaEX6EqJmFTfZ43c7LgWqu85wiFhqo2h8PukruvpS4g4u"
+        );
+
+        let data = STANDARD.decode(LEGACY_TX).unwrap();
+        let legacy = Transaction::deserialize_with_version(&data).unwrap();
+        let hash = legacy.message_hash().unwrap();
+        assert_eq!(hash, decode_legacy_tx().message_hash().unwrap());
+        assert_eq!(legacy.id(), None);
+
+        let mut signed = legacy.clone();
+        signed.signatures[0] = SignatureBytes::new([1u8; 64]);
+        assert_eq!(signed.message_hash().unwrap(), hash);
+        assert_eq!(signed.id(), Some(&SignatureBytes::new([1u8; 64])));
+        assert_ne!(legacy.rebase([7u8; 32]).message_hash().unwrap(), hash);
+    }
+
+    #[test]
+    fn verify_mainnet_v0_against_resolved_accounts() {
+        let tx = decode_mayan_tx();
+        tx.verify_signatures().unwrap();
+        crate::crypto::verify_versioned_transaction(&tx).unwrap();
+
+        let VersionedTransaction::V0 { message, .. } = &tx else {
+            panic!("expected a V0 transaction");
+        };
+        let tables: Vec<AddressLookupTableAccount> = message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| {
+                let len = lookup
+                    .writable_indexes
+                    .iter()
+                    .chain(&lookup.readonly_indexes)
+                    .max()
+                    .map_or(0, |index| *index as usize + 1);
+                let addresses = (0..len).map(|i| Pubkey::new([i as u8; 32])).collect();
+                AddressLookupTableAccount::new(lookup.account_key, addresses)
+            })
+            .collect();
+        let loaded: usize = message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+            .sum();
+        let accounts = tx.verify_and_resolve(&tables).unwrap();
+        assert_eq!(accounts.len(), tx.account_keys().len() + loaded);
+        assert!(tx.verify_and_resolve(&[]).is_err());
+
+        // Flipping any byte of the signed message invalidates the fee payer's signature
+        let mut bytes = STANDARD.decode(MAYAN_V0_TX).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        let tampered = VersionedTransaction::deserialize_with_version(&bytes).unwrap();
+        assert!(tampered.verify_signatures().is_err());
+        assert!(tampered.verify_and_resolve(&tables).is_err());
+    }
 }
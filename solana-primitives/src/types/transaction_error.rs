@@ -0,0 +1,359 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+use serde::de::{self, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use thiserror::Error;
+
+/// Why the runtime rejected or failed a transaction.
+///
+/// Serializes like the `err` field of RPC responses, e.g. `"BlockhashNotFound"` or
+/// `{"InstructionError":[0,{"Custom":1}]}`. Variants added to the runtime after this version
+/// deserialize as [`TransactionError::Unknown`].
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+pub enum TransactionError {
+    #[error("Account in use")]
+    AccountInUse,
+    #[error("Account loaded twice")]
+    AccountLoadedTwice,
+    #[error("Attempt to debit an account but found no record of a prior credit.")]
+    AccountNotFound,
+    #[error("Attempt to load a program that does not exist")]
+    ProgramAccountNotFound,
+    #[error("Insufficient funds for fee")]
+    InsufficientFundsForFee,
+    #[error("This account may not be used to pay transaction fees")]
+    InvalidAccountForFee,
+    #[error("This transaction has already been processed")]
+    AlreadyProcessed,
+    #[error("Blockhash not found")]
+    BlockhashNotFound,
+    #[error("Error processing Instruction {0}: {1}")]
+    InstructionError(u8, InstructionError),
+    #[error("Loader call chain is too deep")]
+    CallChainTooDeep,
+    #[error("Transaction requires a fee but has no signature present")]
+    MissingSignatureForFee,
+    #[error("Transaction contains an invalid account reference")]
+    InvalidAccountIndex,
+    #[error("Transaction did not pass signature verification")]
+    SignatureFailure,
+    #[error("This program may not be used for executing instructions")]
+    InvalidProgramForExecution,
+    #[error("Transaction failed to sanitize accounts offsets correctly")]
+    SanitizeFailure,
+    #[error("Transactions are currently disabled due to cluster maintenance")]
+    ClusterMaintenance,
+    #[error("Transaction processing left an account with an outstanding borrowed reference")]
+    AccountBorrowOutstanding,
+    #[error("Transaction would exceed max Block Cost Limit")]
+    WouldExceedMaxBlockCostLimit,
+    #[error("Transaction version is unsupported")]
+    UnsupportedVersion,
+    #[error("Transaction loads a writable account that cannot be written")]
+    InvalidWritableAccount,
+    #[error("Transaction would exceed max account limit within the block")]
+    WouldExceedMaxAccountCostLimit,
+    #[error("Transaction would exceed account data limit within the block")]
+    WouldExceedAccountDataBlockLimit,
+    #[error("Transaction locked too many accounts")]
+    TooManyAccountLocks,
+    #[error("Transaction loads an address table account that doesn't exist")]
+    AddressLookupTableNotFound,
+    #[error("Transaction loads an address table account with an invalid owner")]
+    InvalidAddressLookupTableOwner,
+    #[error("Transaction loads an address table account with invalid data")]
+    InvalidAddressLookupTableData,
+    #[error("Transaction address table lookup uses an invalid index")]
+    InvalidAddressLookupTableIndex,
+    #[error("Transaction leaves an account with a lower balance than rent-exempt minimum")]
+    InvalidRentPayingAccount,
+    #[error("Transaction would exceed max Vote Cost Limit")]
+    WouldExceedMaxVoteCostLimit,
+    #[error("Transaction would exceed total account data limit")]
+    WouldExceedAccountDataTotalLimit,
+    #[error("Transaction contains a duplicate instruction ({0}) that is not allowed")]
+    DuplicateInstruction(u8),
+    #[error("Transaction results in an account ({account_index}) with insufficient funds for rent")]
+    InsufficientFundsForRent { account_index: u8 },
+    #[error("Transaction exceeded max loaded accounts data size cap")]
+    MaxLoadedAccountsDataSizeExceeded,
+    #[error("LoadedAccountsDataSizeLimit set for transaction must be greater than 0.")]
+    InvalidLoadedAccountsDataSizeLimit,
+    #[error("ResanitizationNeeded")]
+    ResanitizationNeeded,
+    #[error(
+        "Execution of the program referenced by account at index {account_index} is temporarily restricted."
+    )]
+    ProgramExecutionTemporarilyRestricted { account_index: u8 },
+    #[error("Sum of account balances before and after transaction do not match")]
+    UnbalancedTransaction,
+    #[error("Program cache hit max limit")]
+    ProgramCacheHitMaxLimit,
+    #[error("CommitCancelled")]
+    CommitCancelled,
+    /// A variant this version doesn't know, keeping only its name
+    #[error("Unknown transaction error: {0}")]
+    #[serde(untagged, deserialize_with = "variant_name")]
+    Unknown(String),
+}
+
+impl TransactionError {
+    /// The index and error of the failing instruction
+    pub fn instruction_error(&self) -> Option<(u8, &InstructionError)> {
+        match self {
+            Self::InstructionError(index, error) => Some((*index, error)),
+            _ => None,
+        }
+    }
+
+    /// The code of a program's custom error
+    pub fn custom_error(&self) -> Option<u32> {
+        match self.instruction_error()? {
+            (_, InstructionError::Custom(code)) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+/// Why an instruction failed, as reported inside [`TransactionError::InstructionError`].
+///
+/// Variants added to the runtime after this version deserialize as
+/// [`InstructionError::Unknown`].
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+pub enum InstructionError {
+    #[error("generic instruction error")]
+    GenericError,
+    #[error("invalid program argument")]
+    InvalidArgument,
+    #[error("invalid instruction data")]
+    InvalidInstructionData,
+    #[error("invalid account data for instruction")]
+    InvalidAccountData,
+    #[error("account data too small for instruction")]
+    AccountDataTooSmall,
+    #[error("insufficient funds for instruction")]
+    InsufficientFunds,
+    #[error("incorrect program id for instruction")]
+    IncorrectProgramId,
+    #[error("missing required signature for instruction")]
+    MissingRequiredSignature,
+    #[error("instruction requires an uninitialized account")]
+    AccountAlreadyInitialized,
+    #[error("instruction requires an initialized account")]
+    UninitializedAccount,
+    #[error("sum of account balances before and after instruction do not match")]
+    UnbalancedInstruction,
+    #[error("instruction illegally modified the program id of an account")]
+    ModifiedProgramId,
+    #[error("instruction spent from the balance of an account it does not own")]
+    ExternalAccountLamportSpend,
+    #[error("instruction modified data of an account it does not own")]
+    ExternalAccountDataModified,
+    #[error("instruction changed the balance of a read-only account")]
+    ReadonlyLamportChange,
+    #[error("instruction modified data of a read-only account")]
+    ReadonlyDataModified,
+    #[error("instruction contains duplicate accounts")]
+    DuplicateAccountIndex,
+    #[error("instruction changed executable bit of an account")]
+    ExecutableModified,
+    #[error("instruction modified rent epoch of an account")]
+    RentEpochModified,
+    #[error("insufficient account keys for instruction")]
+    NotEnoughAccountKeys,
+    #[error("program other than the account's owner changed the size of the account data")]
+    AccountDataSizeChanged,
+    #[error("instruction expected an executable account")]
+    AccountNotExecutable,
+    #[error("instruction tries to borrow reference for an account which is already borrowed")]
+    AccountBorrowFailed,
+    #[error("instruction left account with an outstanding borrowed reference")]
+    AccountBorrowOutstanding,
+    #[error("instruction modifications of multiply-passed account differ")]
+    DuplicateAccountOutOfSync,
+    #[error("custom program error: {0:#x}")]
+    Custom(u32),
+    #[error("program returned invalid error code")]
+    InvalidError,
+    #[error("instruction changed executable accounts data")]
+    ExecutableDataModified,
+    #[error("instruction changed the balance of an executable account")]
+    ExecutableLamportChange,
+    #[error("executable accounts must be rent exempt")]
+    ExecutableAccountNotRentExempt,
+    #[error("Unsupported program id")]
+    UnsupportedProgramId,
+    #[error("Cross-program invocation call depth too deep")]
+    CallDepth,
+    #[error("An account required by the instruction is missing")]
+    MissingAccount,
+    #[error("Cross-program invocation reentrancy not allowed for this instruction")]
+    ReentrancyNotAllowed,
+    #[error("Length of the seed is too long for address generation")]
+    MaxSeedLengthExceeded,
+    #[error("Provided seeds do not result in a valid address")]
+    InvalidSeeds,
+    #[error("Failed to reallocate account data")]
+    InvalidRealloc,
+    #[error("Computational budget exceeded")]
+    ComputationalBudgetExceeded,
+    #[error("Cross-program invocation with unauthorized signer or writable account")]
+    PrivilegeEscalation,
+    #[error("Failed to create program execution environment")]
+    ProgramEnvironmentSetupFailure,
+    #[error("Program failed to complete")]
+    ProgramFailedToComplete,
+    #[error("Program failed to compile")]
+    ProgramFailedToCompile,
+    #[error("Account is immutable")]
+    Immutable,
+    #[error("Incorrect authority provided")]
+    IncorrectAuthority,
+    #[error("Failed to serialize or deserialize account data")]
+    BorshIoError,
+    #[error("An account does not have enough lamports to be rent-exempt")]
+    AccountNotRentExempt,
+    #[error("Invalid account owner")]
+    InvalidAccountOwner,
+    #[error("Program arithmetic overflowed")]
+    ArithmeticOverflow,
+    #[error("Unsupported sysvar")]
+    UnsupportedSysvar,
+    #[error("Provided owner is not allowed")]
+    IllegalOwner,
+    #[error("Accounts data allocations exceeded the maximum allowed per transaction")]
+    MaxAccountsDataAllocationsExceeded,
+    #[error("Max accounts exceeded")]
+    MaxAccountsExceeded,
+    #[error("Max instruction trace length exceeded")]
+    MaxInstructionTraceLengthExceeded,
+    #[error("Builtin programs must consume compute units")]
+    BuiltinProgramsMustConsumeComputeUnits,
+    /// A variant this version doesn't know, keeping only its name
+    #[error("Unknown instruction error: {0}")]
+    #[serde(untagged, deserialize_with = "variant_name")]
+    Unknown(String),
+}
+
+/// Deserialize the name of an enum variant given as `"Name"` or `{"Name": payload}`,
+/// discarding the payload
+fn variant_name<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(VariantNameVisitor)
+}
+
+struct VariantNameVisitor;
+
+impl<'de> Visitor<'de> for VariantNameVisitor {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a variant name or a map with a single variant name key")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<String, E> {
+        Ok(name.to_string())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<String, A::Error> {
+        let name = map
+            .next_key::<String>()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        map.next_value::<IgnoredAny>()?;
+        if map.next_key::<IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(2, &self));
+        }
+        Ok(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_rpc_json_representation() {
+        let cases = [
+            (
+                r#""BlockhashNotFound""#,
+                TransactionError::BlockhashNotFound,
+            ),
+            (
+                r#"{"InstructionError":[2,{"Custom":6001}]}"#,
+                TransactionError::InstructionError(2, InstructionError::Custom(6001)),
+            ),
+            (
+                r#"{"InstructionError":[0,"InsufficientFunds"]}"#,
+                TransactionError::InstructionError(0, InstructionError::InsufficientFunds),
+            ),
+            (
+                r#"{"InsufficientFundsForRent":{"account_index":1}}"#,
+                TransactionError::InsufficientFundsForRent { account_index: 1 },
+            ),
+            (
+                r#"{"DuplicateInstruction":3}"#,
+                TransactionError::DuplicateInstruction(3),
+            ),
+        ];
+        for (json, error) in cases {
+            assert_eq!(
+                serde_json::from_str::<TransactionError>(json).unwrap(),
+                error
+            );
+            assert_eq!(serde_json::to_string(&error).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn test_unknown_variants() {
+        let cases = [
+            (
+                r#""SomeFutureError""#,
+                TransactionError::Unknown("SomeFutureError".to_string()),
+            ),
+            (
+                r#"{"SomeFutureError":{"account_index":2}}"#,
+                TransactionError::Unknown("SomeFutureError".to_string()),
+            ),
+            (
+                r#"{"InstructionError":[1,"SomeFutureError"]}"#,
+                TransactionError::InstructionError(
+                    1,
+                    InstructionError::Unknown("SomeFutureError".to_string()),
+                ),
+            ),
+            (
+                r#"{"InstructionError":[0,"BorshIoError"]}"#,
+                TransactionError::InstructionError(0, InstructionError::BorshIoError),
+            ),
+        ];
+        for (json, error) in cases {
+            assert_eq!(
+                serde_json::from_str::<TransactionError>(json).unwrap(),
+                error
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&TransactionError::Unknown("SomeFutureError".to_string()))
+                .unwrap(),
+            r#""SomeFutureError""#
+        );
+        assert!(serde_json::from_str::<TransactionError>("7").is_err());
+        assert!(serde_json::from_str::<TransactionError>(r#"{"A":1,"B":2}"#).is_err());
+    }
+
+    #[test]
+    fn test_custom_error_and_display() {
+        let error = TransactionError::InstructionError(1, InstructionError::Custom(0x1771));
+        assert_eq!(error.custom_error(), Some(0x1771));
+        assert_eq!(
+            error.to_string(),
+            "Error processing Instruction 1: custom program error: 0x1771"
+        );
+        assert_eq!(TransactionError::AccountInUse.custom_error(), None);
+        assert_eq!(TransactionError::AccountInUse.instruction_error(), None);
+    }
+}
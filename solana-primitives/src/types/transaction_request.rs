@@ -0,0 +1,201 @@
+use crate::error::{Result, SolanaError};
+use crate::types::{Pubkey, VersionedTransaction};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::{Deserialize, Serialize};
+
+/// Current schema version of [`TransactionRequest`].
+pub const TRANSACTION_REQUEST_VERSION: u8 = 1;
+
+/// An unsigned (or partially signed) transaction handed from a backend to a wallet.
+///
+/// Mirrors the shape dApps send wallets: the wire-format transaction as base64, optional
+/// display metadata, the signers the wallet is expected to provide, and an expiry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionRequest {
+    /// Schema version of this envelope
+    pub version: u8,
+    /// Base64-encoded transaction in wire format
+    pub transaction: String,
+    /// Short label describing the requester
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Human-readable description of what the transaction does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// URL of an icon representing the requester
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Signers the transaction requires, in signature slot order
+    pub required_signers: Vec<Pubkey>,
+    /// Unix timestamp (seconds) after which the request must not be signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+}
+
+impl TransactionRequest {
+    /// Create a request for a transaction, listing its required signers
+    pub fn new(transaction: &VersionedTransaction) -> Result<Self> {
+        let required = transaction.num_required_signatures() as usize;
+        let required_signers = transaction
+            .account_keys()
+            .get(..required)
+            .ok_or(SolanaError::InvalidTransaction)?
+            .to_vec();
+
+        Ok(Self {
+            version: TRANSACTION_REQUEST_VERSION,
+            transaction: STANDARD.encode(transaction.serialize()?),
+            label: None,
+            message: None,
+            icon: None,
+            required_signers,
+            expires_at: None,
+        })
+    }
+
+    /// Set the requester label
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set the description shown to the user
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Set the requester icon URL
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Set the expiry as a unix timestamp in seconds
+    pub fn with_expiry(mut self, expires_at: u64) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Check whether the request has expired at the given unix timestamp
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    /// Decode the embedded transaction without validating the envelope
+    pub fn decode_transaction(&self) -> Result<VersionedTransaction> {
+        let bytes = STANDARD
+            .decode(&self.transaction)
+            .map_err(|e| SolanaError::DeserializationError(e.to_string()))?;
+        VersionedTransaction::deserialize_with_version(&bytes)
+    }
+
+    /// Validate the envelope at the given unix timestamp and return the decoded transaction.
+    ///
+    /// Checks the schema version, expiry, and that `required_signers` matches the signer
+    /// slots of the transaction's message.
+    pub fn validate(&self, now: u64) -> Result<VersionedTransaction> {
+        if self.version != TRANSACTION_REQUEST_VERSION {
+            return Err(SolanaError::GenericError(format!(
+                "unsupported transaction request version: {}, expected: {}",
+                self.version, TRANSACTION_REQUEST_VERSION
+            )));
+        }
+        if self.is_expired(now) {
+            return Err(SolanaError::GenericError(format!(
+                "transaction request expired at {}",
+                self.expires_at.unwrap_or_default()
+            )));
+        }
+
+        let transaction = self.decode_transaction()?;
+        let required = transaction.num_required_signatures() as usize;
+        if transaction.signatures().len() != required {
+            return Err(SolanaError::InvalidSignature(format!(
+                "signature count mismatch: found {}, required {}",
+                transaction.signatures().len(),
+                required
+            )));
+        }
+        if transaction.account_keys().get(..required) != Some(self.required_signers.as_slice()) {
+            return Err(SolanaError::GenericError(
+                "required signers do not match the transaction's signer slots".to_string(),
+            ));
+        }
+
+        Ok(transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::TransactionBuilder;
+    use crate::instructions::system::transfer;
+
+    fn unsigned_transaction() -> VersionedTransaction {
+        let payer = Pubkey::new([1u8; 32]);
+        let recipient = Pubkey::new([2u8; 32]);
+        let mut builder = TransactionBuilder::new(payer, [3u8; 32]);
+        builder.add_instruction(transfer(&payer, &recipient, 1_000));
+        builder.build_v0(&[]).unwrap()
+    }
+
+    #[test]
+    fn test_transaction_request_roundtrip() {
+        let transaction = unsigned_transaction();
+        let request = TransactionRequest::new(&transaction)
+            .unwrap()
+            .with_label("Example Shop")
+            .with_message("Pay for order #42")
+            .with_expiry(1_700_000_000);
+
+        assert_eq!(request.required_signers, vec![Pubkey::new([1u8; 32])]);
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["version"], 1);
+        assert_eq!(json["expiresAt"], 1_700_000_000u64);
+        assert!(json.get("icon").is_none());
+
+        let parsed: TransactionRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, request);
+
+        let decoded = parsed.validate(1_600_000_000).unwrap();
+        assert_eq!(
+            decoded.serialize().unwrap(),
+            transaction.serialize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_transaction_request_rejects_expired() {
+        let request = TransactionRequest::new(&unsigned_transaction())
+            .unwrap()
+            .with_expiry(100);
+
+        assert!(!request.is_expired(99));
+        assert!(request.is_expired(100));
+        assert!(request.validate(100).is_err());
+    }
+
+    #[test]
+    fn test_transaction_request_rejects_signer_mismatch() {
+        let mut request = TransactionRequest::new(&unsigned_transaction()).unwrap();
+        request.required_signers = vec![Pubkey::new([9u8; 32])];
+
+        assert!(request.validate(0).is_err());
+    }
+
+    #[test]
+    fn test_transaction_request_rejects_unknown_version() {
+        let mut request = TransactionRequest::new(&unsigned_transaction()).unwrap();
+        request.version = TRANSACTION_REQUEST_VERSION + 1;
+
+        assert!(request.validate(0).is_err());
+    }
+}
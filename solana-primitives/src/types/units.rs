@@ -0,0 +1,332 @@
+use crate::error::{Result, SolanaError};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// An amount of lamports, the smallest unit of SOL
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct Lamports(pub u64);
+
+/// A token amount in base units together with the mint's decimals.
+///
+/// Formats and parses exact decimal strings, e.g. `1500000` with 6 decimals is `"1.5"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TokenAmount {
+    /// Amount in base units
+    pub amount: u64,
+    /// Number of base 10 digits to the right of the decimal place
+    pub decimals: u8,
+}
+
+/// Micro-lamports per compute unit, the unit of the compute unit price.
+///
+/// One lamport is 1,000,000 micro-lamports, so a price is not a fee on its own; multiply by
+/// the compute unit limit with [`MicroLamports::fee_for`] to get lamports.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct MicroLamports(pub u64);
+
+/// A number of compute units
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct ComputeUnits(pub u32);
+
+/// Prioritization fee paid in a recent slot, as returned by `getRecentPrioritizationFees`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct PrioritizationFee {
+    /// The slot
+    pub slot: u64,
+    /// Minimum compute unit price paid by a transaction that landed in the slot
+    pub prioritization_fee: MicroLamports,
+}
+
+/// Micro-lamports in one lamport
+pub const MICRO_LAMPORTS_PER_LAMPORT: u64 = 1_000_000;
+
+/// Lamports in one SOL
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Decimals of SOL
+pub const SOL_DECIMALS: u8 = 9;
+
+impl Lamports {
+    /// Zero lamports
+    pub const ZERO: Self = Self(0);
+
+    /// Create an amount of lamports
+    pub const fn new(lamports: u64) -> Self {
+        Self(lamports)
+    }
+
+    /// Get the raw value
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Parse an exact decimal SOL amount such as `"0.001"`
+    pub fn from_sol(sol: &str) -> Result<Self> {
+        TokenAmount::parse(sol, SOL_DECIMALS).map(|amount| Self(amount.amount))
+    }
+
+    /// Format as an exact decimal SOL amount, e.g. `"0.001"`
+    pub fn to_sol(self) -> String {
+        TokenAmount::new(self.0, SOL_DECIMALS).to_string()
+    }
+
+    /// Add, returning `None` on overflow
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    /// Subtract, returning `None` on underflow
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    /// Multiply, returning `None` on overflow
+    pub fn checked_mul(self, factor: u64) -> Option<Self> {
+        self.0.checked_mul(factor).map(Self)
+    }
+}
+
+impl From<u64> for Lamports {
+    fn from(lamports: u64) -> Self {
+        Self(lamports)
+    }
+}
+
+impl TokenAmount {
+    /// Create an amount from base units
+    pub const fn new(amount: u64, decimals: u8) -> Self {
+        Self { amount, decimals }
+    }
+
+    /// Parse an exact decimal string into base units.
+    ///
+    /// Rejects more fractional digits than `decimals` instead of rounding.
+    pub fn parse(value: &str, decimals: u8) -> Result<Self> {
+        let invalid = || SolanaError::GenericError(format!("invalid amount: {value:?}"));
+        let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+        if whole.is_empty() && fraction.is_empty()
+            || fraction.len() > decimals as usize
+            || !whole
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let mut amount: u64 = 0;
+        let padding = decimals as usize - fraction.len();
+        for digit in whole
+            .bytes()
+            .chain(fraction.bytes())
+            .chain(core::iter::repeat_n(b'0', padding))
+        {
+            amount = amount
+                .checked_mul(10)
+                .and_then(|amount| amount.checked_add((digit - b'0') as u64))
+                .ok_or_else(invalid)?;
+        }
+        Ok(Self { amount, decimals })
+    }
+}
+
+impl FromStr for Lamports {
+    type Err = SolanaError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.parse()
+            .map(Self)
+            .map_err(|e| SolanaError::GenericError(format!("invalid lamports {s:?}: {e}")))
+    }
+}
+
+impl MicroLamports {
+    /// Zero price
+    pub const ZERO: Self = Self(0);
+
+    /// Create a compute unit price
+    pub const fn new(micro_lamports: u64) -> Self {
+        Self(micro_lamports)
+    }
+
+    /// Get the raw value
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Priority fee in lamports for `units` at this price, rounded up as the runtime does
+    pub fn fee_for(self, units: ComputeUnits) -> u64 {
+        let micro_lamports = self.0 as u128 * units.0 as u128;
+        micro_lamports
+            .div_ceil(MICRO_LAMPORTS_PER_LAMPORT as u128)
+            .min(u64::MAX as u128) as u64
+    }
+
+    /// The `percentile` (0-100, nearest rank) of recent prioritization fees, or zero if empty
+    pub fn percentile(fees: &[PrioritizationFee], percentile: u8) -> Self {
+        let mut prices: Vec<Self> = fees.iter().map(|fee| fee.prioritization_fee).collect();
+        prices.sort_unstable();
+        Self::nearest_rank(&prices, percentile)
+    }
+
+    /// The `percentile` (0-100, nearest rank) of already sorted prices, or zero if empty
+    pub(crate) fn nearest_rank(sorted: &[Self], percentile: u8) -> Self {
+        let rank = (sorted.len() * percentile.min(100) as usize).div_ceil(100);
+        sorted
+            .get(rank.saturating_sub(1))
+            .copied()
+            .unwrap_or(Self::ZERO)
+    }
+}
+
+impl ComputeUnits {
+    /// Maximum compute units a transaction may request
+    pub const MAX: Self = Self(1_400_000);
+    /// Default compute units allotted per non-builtin instruction
+    pub const DEFAULT_PER_INSTRUCTION: Self = Self(200_000);
+
+    /// Create a compute unit count
+    pub const fn new(units: u32) -> Self {
+        Self(units)
+    }
+
+    /// Get the raw value
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for MicroLamports {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} micro-lamports/CU", self.0)
+    }
+}
+
+impl fmt::Display for Lamports {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} lamports", self.0)
+    }
+}
+
+/// Formats the exact decimal value without trailing zeros, e.g. `"1.5"` or `"42"`
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = format!(
+            "{:0>width$}",
+            self.amount,
+            width = self.decimals as usize + 1
+        );
+        let (whole, fraction) = digits.split_at(digits.len() - self.decimals as usize);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            write!(f, "{whole}")
+        } else {
+            write!(f, "{whole}.{fraction}")
+        }
+    }
+}
+
+impl fmt::Display for ComputeUnits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} CU", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_for_rounds_up() {
+        assert_eq!(MicroLamports(1).fee_for(ComputeUnits(1)), 1);
+        assert_eq!(MicroLamports(1_000).fee_for(ComputeUnits(200_000)), 200);
+        assert_eq!(MicroLamports(1_000).fee_for(ComputeUnits(200_001)), 201);
+        assert_eq!(MicroLamports::ZERO.fee_for(ComputeUnits::MAX), 0);
+        assert_eq!(
+            MicroLamports(u64::MAX).fee_for(ComputeUnits(u32::MAX)),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn test_percentile() {
+        let fees: Vec<_> = [0u64, 5_000, 100, 20_000]
+            .into_iter()
+            .enumerate()
+            .map(|(slot, fee)| PrioritizationFee {
+                slot: slot as u64,
+                prioritization_fee: MicroLamports(fee),
+            })
+            .collect();
+
+        assert_eq!(MicroLamports::percentile(&fees, 0), MicroLamports(0));
+        assert_eq!(MicroLamports::percentile(&fees, 50), MicroLamports(100));
+        assert_eq!(MicroLamports::percentile(&fees, 75), MicroLamports(5_000));
+        assert_eq!(MicroLamports::percentile(&fees, 100), MicroLamports(20_000));
+        assert_eq!(MicroLamports::percentile(&[], 90), MicroLamports::ZERO);
+
+        let fee: PrioritizationFee =
+            serde_json::from_str(r#"{"slot":348125,"prioritizationFee":1234}"#).unwrap();
+        assert_eq!(fee.prioritization_fee, MicroLamports(1234));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(MicroLamports(5_000).to_string(), "5000 micro-lamports/CU");
+        assert_eq!(ComputeUnits(200_000).to_string(), "200000 CU");
+        assert_eq!(serde_json::to_string(&ComputeUnits(7)).unwrap(), "7");
+    }
+
+    #[test]
+    fn test_lamports() {
+        assert_eq!(Lamports::from_sol("1").unwrap(), Lamports(LAMPORTS_PER_SOL));
+        assert_eq!(Lamports::from_sol("0.001").unwrap(), Lamports(1_000_000));
+        assert_eq!(Lamports::from_sol("0.000000001").unwrap(), Lamports(1));
+        assert!(Lamports::from_sol("0.0000000001").is_err());
+        assert!(Lamports::from_sol("18446744074").is_err());
+        assert_eq!(Lamports(1_500_000_000).to_sol(), "1.5");
+        assert_eq!(Lamports(1).to_sol(), "0.000000001");
+
+        assert_eq!(Lamports(u64::MAX).checked_add(Lamports(1)), None);
+        assert_eq!(Lamports(1).checked_sub(Lamports(2)), None);
+        assert_eq!(Lamports(5).checked_mul(3), Some(Lamports(15)));
+        assert_eq!("42".parse::<Lamports>().unwrap(), Lamports(42));
+        assert_eq!(Lamports(42).to_string(), "42 lamports");
+    }
+
+    #[test]
+    fn test_token_amount() {
+        assert_eq!(TokenAmount::new(1_500_000, 6).to_string(), "1.5");
+        assert_eq!(TokenAmount::new(42, 0).to_string(), "42");
+        assert_eq!(TokenAmount::new(0, 6).to_string(), "0");
+        assert_eq!(TokenAmount::new(7, 3).to_string(), "0.007");
+
+        assert_eq!(
+            TokenAmount::parse("1.5", 6).unwrap(),
+            TokenAmount::new(1_500_000, 6)
+        );
+        assert_eq!(TokenAmount::parse(".5", 1).unwrap().amount, 5);
+        assert_eq!(TokenAmount::parse("3.", 2).unwrap().amount, 300);
+        for invalid in ["", ".", "1.2.3", "-1", "1e3", "1.234"] {
+            assert!(TokenAmount::parse(invalid, 2).is_err(), "{invalid}");
+        }
+    }
+}
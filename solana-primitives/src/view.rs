@@ -0,0 +1,454 @@
+//! Zero-copy views over wire-format transactions.
+//!
+//! [`TransactionView::parse`] validates the layout once and records where each section
+//! starts; field accessors then borrow from the input without allocating.
+
+use crate::error::{Result, SolanaError};
+use crate::types::{Blockhash, MessageHeader, Pubkey, SignatureBytes, VersionedTransaction};
+use crate::wire::{
+    InstructionField, LookupField, WireCursor, WireError, WireField, check_header_counts,
+};
+use alloc::format;
+
+/// A borrowed, validated wire-format transaction
+#[derive(Debug, Clone, Copy)]
+pub struct TransactionView<'a> {
+    bytes: &'a [u8],
+    signatures: &'a [u8],
+    message: MessageView<'a>,
+}
+
+/// A borrowed, validated wire-format message
+#[derive(Debug, Clone, Copy)]
+pub struct MessageView<'a> {
+    bytes: &'a [u8],
+    version: Option<u8>,
+    header: [u8; 3],
+    account_keys: &'a [u8],
+    recent_blockhash: &'a [u8],
+    num_instructions: usize,
+    instructions: &'a [u8],
+    num_lookups: usize,
+    lookups: &'a [u8],
+}
+
+/// A borrowed compiled instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstructionView<'a> {
+    /// Index of the program in the account keys
+    pub program_id_index: u8,
+    /// Account indexes
+    pub accounts: &'a [u8],
+    /// Instruction data
+    pub data: &'a [u8],
+}
+
+/// A borrowed address table lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LookupView<'a> {
+    /// Address of the lookup table
+    pub account_key: Pubkey,
+    /// Indexes of writable addresses in the table
+    pub writable_indexes: &'a [u8],
+    /// Indexes of readonly addresses in the table
+    pub readonly_indexes: &'a [u8],
+}
+
+/// Iterator over the instructions of a [`MessageView`]
+#[derive(Debug, Clone)]
+pub struct Instructions<'a> {
+    cursor: WireCursor<'a>,
+    index: usize,
+    len: usize,
+}
+
+/// Iterator over the address table lookups of a [`MessageView`]
+#[derive(Debug, Clone)]
+pub struct Lookups<'a> {
+    cursor: WireCursor<'a>,
+    index: usize,
+    len: usize,
+}
+
+fn read_instruction<'a>(
+    cursor: &mut WireCursor<'a>,
+    index: usize,
+) -> core::result::Result<InstructionView<'a>, WireError> {
+    let field = |part| WireField::Instruction(index, part);
+    let program_id_index = cursor.read_u8(field(InstructionField::ProgramIdIndex))?;
+    let num_accounts = cursor.read_compact_u16(field(InstructionField::AccountCount))?;
+    let accounts = cursor.read_bytes(num_accounts, field(InstructionField::Accounts))?;
+    let data_len = cursor.read_compact_u16(field(InstructionField::DataLength))?;
+    let data = cursor.read_bytes(data_len, field(InstructionField::Data))?;
+    Ok(InstructionView {
+        program_id_index,
+        accounts,
+        data,
+    })
+}
+
+fn read_lookup<'a>(
+    cursor: &mut WireCursor<'a>,
+    index: usize,
+) -> core::result::Result<LookupView<'a>, WireError> {
+    let field = |part| WireField::Lookup(index, part);
+    let account_key = Pubkey::new(cursor.read_array(field(LookupField::AccountKey))?);
+    let num_writable = cursor.read_compact_u16(field(LookupField::WritableCount))?;
+    let writable_indexes = cursor.read_bytes(num_writable, field(LookupField::WritableIndexes))?;
+    let num_readonly = cursor.read_compact_u16(field(LookupField::ReadonlyCount))?;
+    let readonly_indexes = cursor.read_bytes(num_readonly, field(LookupField::ReadonlyIndexes))?;
+    Ok(LookupView {
+        account_key,
+        writable_indexes,
+        readonly_indexes,
+    })
+}
+
+fn reject_trailing(cursor: &WireCursor<'_>) -> Result<()> {
+    match cursor.remaining().len() {
+        0 => Ok(()),
+        len => Err(cursor
+            .error(WireField::Trailing, "end of input", format!("{len} bytes"))
+            .into()),
+    }
+}
+
+impl<'a> TransactionView<'a> {
+    /// Validate a wire-format transaction and borrow its fields.
+    ///
+    /// Rejects trailing bytes, unsupported message versions and header counts that don't
+    /// fit the account keys.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self> {
+        let mut cursor = WireCursor::new(bytes);
+        let num_signatures = cursor.read_compact_u16(WireField::SignatureCount)?;
+        let signatures = cursor.read_bytes(num_signatures * 64, WireField::Signature(0))?;
+        let message = MessageView::read(&mut cursor)?;
+        reject_trailing(&cursor)?;
+        if num_signatures != message.header().num_required_signatures as usize {
+            return Err(SolanaError::DeserializationError(format!(
+                "{num_signatures} signatures for {} required signers",
+                message.header().num_required_signatures
+            )));
+        }
+        Ok(Self {
+            bytes,
+            signatures,
+            message,
+        })
+    }
+
+    /// The full wire bytes
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Number of signatures
+    pub fn num_signatures(&self) -> usize {
+        self.signatures.len() / 64
+    }
+
+    /// Signature at `index`
+    pub fn signature(&self, index: usize) -> Option<&'a [u8; 64]> {
+        self.signatures
+            .get(index * 64..(index + 1) * 64)
+            .map(|bytes| bytes.try_into().unwrap())
+    }
+
+    /// Signatures in order
+    pub fn signatures(&self) -> impl ExactSizeIterator<Item = &'a [u8; 64]> + 'a {
+        self.signatures
+            .chunks_exact(64)
+            .map(|bytes| bytes.try_into().unwrap())
+    }
+
+    /// The first signature, which identifies the transaction
+    pub fn id(&self) -> Option<SignatureBytes> {
+        self.signature(0).copied().map(SignatureBytes::new)
+    }
+
+    /// The message
+    pub fn message(&self) -> &MessageView<'a> {
+        &self.message
+    }
+
+    /// Decode into an owned transaction
+    pub fn to_transaction(&self) -> Result<VersionedTransaction> {
+        VersionedTransaction::deserialize_with_version(self.bytes)
+    }
+}
+
+impl<'a> MessageView<'a> {
+    /// Validate a wire-format message and borrow its fields
+    pub fn parse(bytes: &'a [u8]) -> Result<Self> {
+        let mut cursor = WireCursor::new(bytes);
+        let message = Self::read(&mut cursor)?;
+        reject_trailing(&cursor)?;
+        Ok(message)
+    }
+
+    fn read(cursor: &mut WireCursor<'a>) -> Result<Self> {
+        let bytes = cursor.remaining();
+        let start = cursor.offset();
+        let version = match cursor.peek() {
+            Some(prefix) if prefix & 0x80 != 0 => match prefix & 0x7F {
+                0 => Some(cursor.read_u8(WireField::VersionPrefix)? & 0x7F),
+                version => {
+                    return Err(cursor
+                        .error(
+                            WireField::VersionPrefix,
+                            "version 0",
+                            format!("version {version}"),
+                        )
+                        .into());
+                }
+            },
+            _ => None,
+        };
+
+        let header: [u8; 3] = cursor.read_array(WireField::Header)?;
+        let num_account_keys = cursor.read_compact_u16(WireField::AccountKeyCount)?;
+        let account_keys = cursor.read_bytes(num_account_keys * 32, WireField::AccountKey(0))?;
+        check_header_counts(header, num_account_keys)
+            .map_err(|expected| cursor.error(WireField::Header, expected, format!("{header:?}")))?;
+        let recent_blockhash = cursor.read_bytes(32, WireField::RecentBlockhash)?;
+
+        let num_instructions = cursor.read_compact_u16(WireField::InstructionCount)?;
+        let instructions = cursor.remaining();
+        let instructions_start = cursor.offset();
+        for index in 0..num_instructions {
+            read_instruction(cursor, index)?;
+        }
+        let instructions = &instructions[..cursor.offset() - instructions_start];
+
+        let (num_lookups, lookups) = if version.is_some() {
+            let num_lookups = cursor.read_compact_u16(WireField::LookupCount)?;
+            let lookups = cursor.remaining();
+            let lookups_start = cursor.offset();
+            for index in 0..num_lookups {
+                read_lookup(cursor, index)?;
+            }
+            (num_lookups, &lookups[..cursor.offset() - lookups_start])
+        } else {
+            (0, &[][..])
+        };
+
+        Ok(Self {
+            bytes: &bytes[..cursor.offset() - start],
+            version,
+            header,
+            account_keys,
+            recent_blockhash,
+            num_instructions,
+            instructions,
+            num_lookups,
+            lookups,
+        })
+    }
+
+    /// The serialized message, as signed
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Message version, `None` for legacy messages
+    pub fn version(&self) -> Option<u8> {
+        self.version
+    }
+
+    /// The message header
+    pub fn header(&self) -> MessageHeader {
+        MessageHeader {
+            num_required_signatures: self.header[0],
+            num_readonly_signed_accounts: self.header[1],
+            num_readonly_unsigned_accounts: self.header[2],
+        }
+    }
+
+    /// Number of static account keys
+    pub fn num_account_keys(&self) -> usize {
+        self.account_keys.len() / 32
+    }
+
+    /// Static account key at `index`
+    pub fn account_key(&self, index: usize) -> Option<Pubkey> {
+        self.account_keys
+            .get(index * 32..(index + 1) * 32)
+            .map(|bytes| Pubkey::new(bytes.try_into().unwrap()))
+    }
+
+    /// Static account keys in order
+    pub fn account_keys(&self) -> impl ExactSizeIterator<Item = Pubkey> + 'a {
+        self.account_keys
+            .chunks_exact(32)
+            .map(|bytes| Pubkey::new(bytes.try_into().unwrap()))
+    }
+
+    /// The fee payer, if the message has any account keys
+    pub fn fee_payer(&self) -> Option<Pubkey> {
+        self.account_key(0)
+    }
+
+    /// The recent blockhash or durable nonce
+    pub fn recent_blockhash(&self) -> Blockhash {
+        Blockhash::try_from(self.recent_blockhash).unwrap()
+    }
+
+    /// Number of instructions
+    pub fn num_instructions(&self) -> usize {
+        self.num_instructions
+    }
+
+    /// Instructions in order
+    pub fn instructions(&self) -> Instructions<'a> {
+        Instructions {
+            cursor: WireCursor::new(self.instructions),
+            index: 0,
+            len: self.num_instructions,
+        }
+    }
+
+    /// Number of address table lookups, always zero for legacy messages
+    pub fn num_address_table_lookups(&self) -> usize {
+        self.num_lookups
+    }
+
+    /// Address table lookups in order
+    pub fn address_table_lookups(&self) -> Lookups<'a> {
+        Lookups {
+            cursor: WireCursor::new(self.lookups),
+            index: 0,
+            len: self.num_lookups,
+        }
+    }
+}
+
+impl<'a> Iterator for Instructions<'a> {
+    type Item = InstructionView<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.len {
+            return None;
+        }
+        // The section was validated by `MessageView::parse`
+        let item = read_instruction(&mut self.cursor, self.index).ok();
+        self.index += 1;
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Instructions<'_> {}
+
+impl<'a> Iterator for Lookups<'a> {
+    type Item = LookupView<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.len {
+            return None;
+        }
+        // The section was validated by `MessageView::parse`
+        let item = read_lookup(&mut self.cursor, self.index).ok();
+        self.index += 1;
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Lookups<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::TransactionBuilder;
+    use crate::crypto::Keypair;
+    use crate::instructions::system::transfer;
+    use crate::types::AddressLookupTableAccount;
+
+    fn sample(v0: bool) -> (VersionedTransaction, Vec<u8>) {
+        let payer = Keypair::from_secret_key(&[1u8; 32]);
+        let recipient = Pubkey::new([2u8; 32]);
+        let mut builder = TransactionBuilder::new(payer.pubkey(), [3u8; 32]);
+        builder.add_instruction(transfer(&payer.pubkey(), &recipient, 1_000));
+        let bytes = if v0 {
+            let table = AddressLookupTableAccount::new(Pubkey::new([4u8; 32]), vec![recipient]);
+            let mut transaction = builder.build_v0(&[table]).unwrap();
+            transaction.sign(&[&payer]).unwrap();
+            transaction.serialize().unwrap()
+        } else {
+            let mut transaction = builder.build().unwrap();
+            transaction.sign(&[&payer]).unwrap();
+            transaction.serialize_legacy().unwrap()
+        };
+        let transaction = VersionedTransaction::deserialize_with_version(&bytes).unwrap();
+        (transaction, bytes)
+    }
+
+    #[test]
+    fn test_view_matches_owned_decode() {
+        for v0 in [false, true] {
+            let (transaction, bytes) = sample(v0);
+            let view = TransactionView::parse(&bytes).unwrap();
+            let message = view.message();
+
+            assert_eq!(view.num_signatures(), 1);
+            assert_eq!(view.id(), Some(transaction.signatures()[0]));
+            assert_eq!(message.version(), v0.then_some(0));
+            assert_eq!(
+                message.header().num_required_signatures,
+                transaction.num_required_signatures()
+            );
+            assert_eq!(
+                message.account_keys().collect::<Vec<_>>(),
+                transaction.account_keys()
+            );
+            assert_eq!(&message.recent_blockhash(), transaction.recent_blockhash());
+            assert_eq!(
+                message.as_bytes(),
+                transaction.serialize_message().unwrap().as_slice()
+            );
+
+            let instructions: Vec<_> = message.instructions().collect();
+            assert_eq!(instructions.len(), transaction.instructions().len());
+            for (view, owned) in instructions.iter().zip(transaction.instructions()) {
+                assert_eq!(view.program_id_index, owned.program_id_index);
+                assert_eq!(view.accounts, owned.accounts.as_slice());
+                assert_eq!(view.data, owned.data.as_slice());
+            }
+            assert_eq!(message.num_address_table_lookups(), v0 as usize);
+            if let Some(lookup) = message.address_table_lookups().next() {
+                assert_eq!(lookup.account_key, Pubkey::new([4u8; 32]));
+                assert_eq!(lookup.writable_indexes, &[0]);
+            }
+            assert!(view.to_transaction().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_view_rejects_malformed_input() {
+        let (_, bytes) = sample(true);
+        assert!(TransactionView::parse(&[]).is_err());
+        for len in 0..bytes.len() {
+            assert!(TransactionView::parse(&bytes[..len]).is_err(), "{len}");
+        }
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(TransactionView::parse(&trailing).is_err());
+
+        let mut version = bytes.clone();
+        version[65] = 0x81;
+        assert!(TransactionView::parse(&version).is_err());
+
+        let mut header = bytes;
+        header[66] = 9;
+        assert!(TransactionView::parse(&header).is_err());
+    }
+}
@@ -0,0 +1,473 @@
+//! Wire-format reading and diagnostics.
+
+use crate::error::Result;
+use crate::short_vec::{decode_compact_u16_len, encode_length_to_compact_u16_bytes};
+use crate::types::VersionedTransaction;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+use thiserror::Error;
+
+/// A field of the transaction wire format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireField {
+    /// Compact-u16 signature count
+    SignatureCount,
+    /// Signature at the given index
+    Signature(usize),
+    /// Message version prefix (V0 only)
+    VersionPrefix,
+    /// 3-byte message header
+    Header,
+    /// Compact-u16 account key count
+    AccountKeyCount,
+    /// Account key at the given index
+    AccountKey(usize),
+    /// Recent blockhash
+    RecentBlockhash,
+    /// Compact-u16 instruction count
+    InstructionCount,
+    /// Part of the instruction at the given index
+    Instruction(usize, InstructionField),
+    /// Compact-u16 address table lookup count
+    LookupCount,
+    /// Part of the address table lookup at the given index
+    Lookup(usize, LookupField),
+    /// Bytes past the end of the re-serialized transaction
+    Trailing,
+}
+
+/// A field of a compiled instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstructionField {
+    /// Index of the program id in the account keys
+    ProgramIdIndex,
+    /// Compact-u16 account index count
+    AccountCount,
+    /// Account indexes into the account keys
+    Accounts,
+    /// Compact-u16 data length
+    DataLength,
+    /// Instruction data
+    Data,
+}
+
+/// A field of an address table lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LookupField {
+    /// Address of the lookup table account
+    AccountKey,
+    /// Compact-u16 writable index count
+    WritableCount,
+    /// Indexes of writable addresses in the table
+    WritableIndexes,
+    /// Compact-u16 read-only index count
+    ReadonlyCount,
+    /// Indexes of read-only addresses in the table
+    ReadonlyIndexes,
+}
+
+impl fmt::Display for WireField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Signature(index) => write!(f, "signatures[{index}]"),
+            Self::AccountKey(index) => write!(f, "account_keys[{index}]"),
+            Self::Instruction(index, field) => write!(f, "instructions[{index}].{field:?}"),
+            Self::Lookup(index, field) => write!(f, "address_table_lookups[{index}].{field:?}"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+/// A malformed wire-format field, located by byte offset
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("at offset {offset} in {field}: expected {expected}, found {found}")]
+pub struct WireError {
+    /// Offset of the field within the decoded bytes
+    pub offset: usize,
+    /// Field being decoded
+    pub field: WireField,
+    /// What the decoder needed
+    pub expected: String,
+    /// What the input contained
+    pub found: String,
+}
+
+/// Sequential reader over wire-format bytes that reports failures as [`WireError`]s
+#[derive(Debug, Clone)]
+pub struct WireCursor<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> WireCursor<'a> {
+    /// Start reading at the beginning of `bytes`
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    /// Bytes consumed so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Bytes not yet consumed
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.offset..]
+    }
+
+    /// Peek at the next byte without consuming it
+    pub fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.offset).copied()
+    }
+
+    /// Build an error for `field` at the current offset
+    pub fn error(
+        &self,
+        field: WireField,
+        expected: impl Into<String>,
+        found: impl Into<String>,
+    ) -> WireError {
+        WireError {
+            offset: self.offset,
+            field,
+            expected: expected.into(),
+            found: found.into(),
+        }
+    }
+
+    /// Consume the next `len` bytes of `field`
+    pub fn read_bytes(
+        &mut self,
+        len: usize,
+        field: WireField,
+    ) -> core::result::Result<&'a [u8], WireError> {
+        let remaining = self.remaining();
+        let bytes = remaining.get(..len).ok_or_else(|| {
+            self.error(
+                field,
+                format!("{len} bytes"),
+                format!("{} bytes", remaining.len()),
+            )
+        })?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    /// Consume a single byte of `field`
+    pub fn read_u8(&mut self, field: WireField) -> core::result::Result<u8, WireError> {
+        self.read_bytes(1, field).map(|bytes| bytes[0])
+    }
+
+    /// Consume exactly `N` bytes of `field`
+    pub fn read_array<const N: usize>(
+        &mut self,
+        field: WireField,
+    ) -> core::result::Result<[u8; N], WireError> {
+        self.read_bytes(N, field)
+            .map(|bytes| bytes.try_into().unwrap())
+    }
+
+    /// Consume a compact-u16 length prefix for `field`
+    pub fn read_compact_u16(&mut self, field: WireField) -> core::result::Result<usize, WireError> {
+        let remaining = self.remaining();
+        let (len, consumed) = decode_compact_u16_len(remaining).map_err(|_| {
+            let prefix = &remaining[..remaining.len().min(3)];
+            self.error(field, "compact-u16 length", format!("{prefix:02x?}"))
+        })?;
+        self.offset += consumed;
+        Ok(len)
+    }
+}
+
+/// Check that a message header's signer and read-only counts fit its account keys.
+///
+/// Every decoder runs this right after the account keys so they reject the same headers;
+/// the error describes what the header should have held.
+pub(crate) fn check_header_counts(
+    header: [u8; 3],
+    num_account_keys: usize,
+) -> core::result::Result<(), String> {
+    let [
+        num_required_signatures,
+        num_readonly_signed,
+        num_readonly_unsigned,
+    ] = header.map(usize::from);
+    if num_required_signatures > num_account_keys {
+        return Err(format!("at most {num_account_keys} required signatures"));
+    }
+    if num_readonly_signed > num_required_signatures {
+        return Err(format!(
+            "at most {num_required_signatures} readonly signed accounts"
+        ));
+    }
+    let num_unsigned = num_account_keys - num_required_signatures;
+    if num_readonly_unsigned > num_unsigned {
+        return Err(format!("at most {num_unsigned} readonly unsigned accounts"));
+    }
+    Ok(())
+}
+
+/// First point where re-serialized bytes differ from the original
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// Offset of the first differing byte
+    pub offset: usize,
+    /// Field of the re-serialized layout containing that offset
+    pub field: WireField,
+    /// Original byte at the offset, if any
+    pub original: Option<u8>,
+    /// Re-serialized byte at the offset, if any
+    pub reserialized: Option<u8>,
+    /// Length of the original bytes
+    pub original_len: usize,
+    /// Length of the re-serialized bytes
+    pub reserialized_len: usize,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "byte-exact roundtrip failed at offset {} in {}: original {:02x?}, re-serialized {:02x?} (lengths {} vs {})",
+            self.offset,
+            self.field,
+            self.original,
+            self.reserialized,
+            self.original_len,
+            self.reserialized_len
+        )
+    }
+}
+
+/// Outcome of [`roundtrip_check`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Roundtrip {
+    /// Re-serializing reproduced the input exactly
+    ByteExact,
+    /// Re-serializing produced different bytes
+    Divergent(Divergence),
+}
+
+impl Roundtrip {
+    /// Check whether the roundtrip was byte-exact
+    pub fn is_byte_exact(&self) -> bool {
+        matches!(self, Self::ByteExact)
+    }
+}
+
+/// Deserialize a wire transaction, re-serialize it, and report where the bytes diverge.
+///
+/// Non-canonical encodings the decoder tolerates (such as over-long compact-u16 lengths) or
+/// trailing bytes show up as a [`Divergence`] naming the offending field. Fails only if the
+/// input can't be deserialized at all.
+pub fn roundtrip_check(bytes: &[u8]) -> Result<Roundtrip> {
+    let transaction = VersionedTransaction::deserialize_with_version(bytes)?;
+    let reserialized = transaction.serialize()?;
+
+    let Some(offset) = bytes
+        .iter()
+        .zip(&reserialized)
+        .position(|(a, b)| a != b)
+        .or_else(|| {
+            (bytes.len() != reserialized.len()).then(|| bytes.len().min(reserialized.len()))
+        })
+    else {
+        return Ok(Roundtrip::ByteExact);
+    };
+
+    let field = field_spans(&transaction)?
+        .into_iter()
+        .find(|(range, _)| range.contains(&offset))
+        .map_or(WireField::Trailing, |(_, field)| field);
+
+    Ok(Roundtrip::Divergent(Divergence {
+        offset,
+        field,
+        original: bytes.get(offset).copied(),
+        reserialized: reserialized.get(offset).copied(),
+        original_len: bytes.len(),
+        reserialized_len: reserialized.len(),
+    }))
+}
+
+/// Byte ranges of each field in the canonical serialization of `transaction`
+fn field_spans(transaction: &VersionedTransaction) -> Result<Vec<(Range<usize>, WireField)>> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    let mut push = |len: usize, field: WireField| {
+        spans.push((offset..offset + len, field));
+        offset += len;
+    };
+    let compact_len =
+        |n: usize| -> Result<usize> { Ok(encode_length_to_compact_u16_bytes(n)?.len()) };
+
+    push(
+        compact_len(transaction.signatures().len())?,
+        WireField::SignatureCount,
+    );
+    for index in 0..transaction.signatures().len() {
+        push(64, WireField::Signature(index));
+    }
+    if matches!(transaction, VersionedTransaction::V0 { .. }) {
+        push(1, WireField::VersionPrefix);
+    }
+    push(3, WireField::Header);
+    push(
+        compact_len(transaction.account_keys().len())?,
+        WireField::AccountKeyCount,
+    );
+    for index in 0..transaction.account_keys().len() {
+        push(32, WireField::AccountKey(index));
+    }
+    push(32, WireField::RecentBlockhash);
+    push(
+        compact_len(transaction.instructions().len())?,
+        WireField::InstructionCount,
+    );
+    for (index, instruction) in transaction.instructions().iter().enumerate() {
+        let field = |part| WireField::Instruction(index, part);
+        push(1, field(InstructionField::ProgramIdIndex));
+        push(
+            compact_len(instruction.accounts.len())?,
+            field(InstructionField::AccountCount),
+        );
+        push(
+            instruction.accounts.len(),
+            field(InstructionField::Accounts),
+        );
+        push(
+            compact_len(instruction.data.len())?,
+            field(InstructionField::DataLength),
+        );
+        push(instruction.data.len(), field(InstructionField::Data));
+    }
+
+    if let VersionedTransaction::V0 { message, .. } = transaction {
+        push(
+            compact_len(message.address_table_lookups.len())?,
+            WireField::LookupCount,
+        );
+        for (index, lookup) in message.address_table_lookups.iter().enumerate() {
+            let field = |part| WireField::Lookup(index, part);
+            push(32, field(LookupField::AccountKey));
+            push(
+                compact_len(lookup.writable_indexes.len())?,
+                field(LookupField::WritableCount),
+            );
+            push(
+                lookup.writable_indexes.len(),
+                field(LookupField::WritableIndexes),
+            );
+            push(
+                compact_len(lookup.readonly_indexes.len())?,
+                field(LookupField::ReadonlyCount),
+            );
+            push(
+                lookup.readonly_indexes.len(),
+                field(LookupField::ReadonlyIndexes),
+            );
+        }
+    }
+
+    Ok(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::TransactionBuilder;
+    use crate::instructions::system::transfer;
+    use crate::types::{AddressLookupTableAccount, Pubkey};
+
+    fn sample_v0() -> Vec<u8> {
+        let payer = Pubkey::new([1u8; 32]);
+        let recipient = Pubkey::new([2u8; 32]);
+        let mut builder = TransactionBuilder::new(payer, [3u8; 32]);
+        builder.add_instruction(transfer(&payer, &recipient, 1_000));
+        let table = AddressLookupTableAccount::new(Pubkey::new([4u8; 32]), vec![recipient]);
+        builder.build_v0(&[table]).unwrap().serialize().unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip_check_byte_exact() {
+        assert!(roundtrip_check(&sample_v0()).unwrap().is_byte_exact());
+    }
+
+    #[test]
+    fn test_roundtrip_check_reports_non_canonical_length() {
+        // Signature count 1 encoded in two bytes
+        let bytes = sample_v0();
+        let mut padded = vec![0x81, 0x00];
+        padded.extend_from_slice(&bytes[1..]);
+
+        let Roundtrip::Divergent(divergence) = roundtrip_check(&padded).unwrap() else {
+            panic!("expected divergence");
+        };
+        assert_eq!(divergence.offset, 0);
+        assert_eq!(divergence.field, WireField::SignatureCount);
+        assert_eq!(divergence.original, Some(0x81));
+        assert_eq!(divergence.reserialized, Some(0x01));
+        assert_eq!(divergence.original_len, divergence.reserialized_len + 1);
+    }
+
+    #[test]
+    fn test_roundtrip_check_reports_trailing_bytes() {
+        let mut bytes = sample_v0();
+        let len = bytes.len();
+        bytes.push(0xEE);
+
+        let Roundtrip::Divergent(divergence) = roundtrip_check(&bytes).unwrap() else {
+            panic!("expected divergence");
+        };
+        assert_eq!(divergence.offset, len);
+        assert_eq!(divergence.field, WireField::Trailing);
+        assert_eq!(divergence.reserialized, None);
+        assert!(divergence.to_string().contains("Trailing"));
+    }
+
+    #[test]
+    fn test_wire_cursor_reports_offsets() {
+        let bytes = [0x02, 0xAA, 0xBB, 0xFF, 0xFF, 0xFF];
+        let mut cursor = WireCursor::new(&bytes);
+        assert_eq!(
+            cursor.read_compact_u16(WireField::AccountKeyCount).unwrap(),
+            2
+        );
+        assert_eq!(
+            cursor.read_array::<2>(WireField::Header).unwrap(),
+            [0xAA, 0xBB]
+        );
+        assert_eq!(cursor.peek(), Some(0xFF));
+
+        let error = cursor
+            .clone()
+            .read_bytes(4, WireField::RecentBlockhash)
+            .unwrap_err();
+        assert_eq!(error.offset, 3);
+        assert_eq!(error.expected, "4 bytes");
+        assert_eq!(error.found, "3 bytes");
+
+        let error = cursor
+            .read_compact_u16(WireField::InstructionCount)
+            .unwrap_err();
+        assert_eq!(error.field, WireField::InstructionCount);
+        assert_eq!(
+            error.to_string(),
+            "at offset 3 in InstructionCount: expected compact-u16 length, found [ff, ff, ff]"
+        );
+    }
+
+    #[test]
+    fn test_field_spans_cover_serialization() {
+        let bytes = sample_v0();
+        let transaction = VersionedTransaction::deserialize_with_version(&bytes).unwrap();
+        let spans = field_spans(&transaction).unwrap();
+
+        assert_eq!(spans.last().unwrap().0.end, bytes.len());
+        assert!(spans.windows(2).all(|w| w[0].0.end == w[1].0.start));
+        assert_eq!(spans[1].1, WireField::Signature(0));
+    }
+}